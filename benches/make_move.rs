@@ -0,0 +1,26 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use chess_game::{best_move, Game};
+
+/// The middlegame position "Kiwipete", rich in captures, castling and pins.
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+/// Every node of a perft makes and unmakes a move, so this measures the cost of keeping the
+/// movement and capture masks up to date.
+fn perft(c: &mut Criterion) {
+    let mut game = Game::from_fen(KIWIPETE).unwrap();
+    c.bench_function("perft 3 kiwipete", |b| b.iter(|| game.perft(3)));
+    let mut game = Game::new();
+    c.bench_function("perft 4 start position", |b| b.iter(|| game.perft(4)));
+}
+
+fn search(c: &mut Criterion) {
+    let game = Game::from_fen(KIWIPETE).unwrap();
+    c.bench_function("best move depth 3 kiwipete", |b| b.iter(|| best_move(&game, 3)));
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = perft, search
+}
+criterion_main!(benches);
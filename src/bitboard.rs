@@ -1,5 +1,6 @@
 use std::{fmt, ops};
 use std::ops::BitAndAssign;
+use crate::square::Square;
 
 #[derive(Copy, Clone, PartialEq)]
 pub(crate) struct BitBoard(u64);
@@ -9,7 +10,7 @@ impl BitBoard{
     ///
     /// # Parameters
     /// - `v`: A `u64` value representing the bitboard, where each bit corresponds to a square
-    ///        on the chessboard. Bit 0 represents `a1`, and bit 63 represents `h8`.
+    ///   on the chessboard. Bit 0 represents `a1`, and bit 63 represents `h8`.
     ///
     /// # Returns
     /// A `BitBoard` initialized with the provided value.
@@ -39,15 +40,106 @@ impl BitBoard{
         self.0 == 0
     }
 
-    /// Clears all bits in the `BitBoard`, effectively resetting it to an empty state.
-    pub fn clear(&mut self) {
-        self.0 = 0;
+    /// Returns the number of set bits (occupied squares) in the `BitBoard`.
+    pub fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Writes the set squares like the placement field of a FEN, with `X` for an occupied
+    /// square, e.g. `8/8/8/3X4/4X3/8/8/8` for `{e4, d5}`. Meant for the messages of the
+    /// consistency checks, which release builds leave out.
+    #[cfg_attr(not(debug_assertions), allow(dead_code))]
+    pub fn to_fen_occupancy(self) -> String {
+        (0..8).rev()
+            .map(|rank| {
+                let mut row = String::new();
+                let mut empty = 0;
+                for file in 0..8 {
+                    if self.0 >> (rank * 8 + file) & 1 == 1 {
+                        if empty > 0 {
+                            row.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        row.push('X');
+                    } else {
+                        empty += 1;
+                    }
+                }
+                if empty > 0 {
+                    row.push_str(&empty.to_string());
+                }
+                row
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+impl From<BitBoard> for u64 {
+    fn from(value: BitBoard) -> Self {
+        value.0
+    }
+}
+
+/// Iterator over the squares of the set bits of a [`BitBoard`], from `a1` up to `h8`.
+pub(crate) struct BitBoardIter(u64);
+
+impl Iterator for BitBoardIter {
+    type Item = Square;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+        let idx = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Square::try_from(idx).ok()
+    }
+}
+
+impl IntoIterator for BitBoard {
+    type Item = Square;
+    type IntoIter = BitBoardIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        BitBoardIter(self.0)
+    }
+}
+
+impl FromIterator<Square> for BitBoard {
+    fn from_iter<I: IntoIterator<Item = Square>>(squares: I) -> Self {
+        squares.into_iter().fold(BitBoard::empty(), |board, square| board | BitBoard::from(square))
+    }
+}
+
+impl From<Vec<Square>> for BitBoard {
+    fn from(squares: Vec<Square>) -> Self {
+        squares.into_iter().collect()
+    }
+}
+
+impl From<BitBoard> for Vec<Square> {
+    /// Lists the set squares from `a1` up to `h8`.
+    fn from(board: BitBoard) -> Self {
+        board.into_iter().collect()
+    }
+}
+
+impl fmt::Display for BitBoard {
+    /// Formats the set squares in algebraic notation, from `a1` up to `h8`, e.g. `{e4, d5}`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let squares = self.into_iter().map(|square| square.to_string()).collect::<Vec<_>>();
+        write!(f, "{{{}}}", squares.join(", "))
     }
 }
 
 impl fmt::Debug for BitBoard{
-    // Formats the `BitBoard` in a human-readable chessboard representation.
+    // Formats the `BitBoard` as its list of squares, e.g. `BitBoard {e4, d5}`, short enough for
+    // assertion failures; `{:#?}` draws the whole chessboard instead.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !f.alternate() {
+            return write!(f, "BitBoard {}", self);
+        }
         writeln!(f, "Chessboard visualization (LSB is a1):")?;
         for rank in (0..8).rev() {
             for file in 0..8 {
@@ -74,7 +166,7 @@ impl ops::BitOr for BitBoard {
     }
 }
 
-impl<'a, 'b> ops::BitOr<&'b BitBoard> for &'a BitBoard {
+impl ops::BitOr<&BitBoard> for &BitBoard {
     type Output = BitBoard;
 
     fn bitor(self, rhs: &BitBoard) -> Self::Output {
@@ -90,7 +182,7 @@ impl ops::BitAnd for BitBoard {
     }
 }
 
-impl<'a, 'b> ops::BitAnd<&'b BitBoard> for &'a BitBoard {
+impl ops::BitAnd<&BitBoard> for &BitBoard {
     type Output = BitBoard;
 
     fn bitand(self, rhs: &BitBoard) -> Self::Output {
@@ -106,7 +198,7 @@ impl ops::BitXor for BitBoard {
     }
 }
 
-impl<'a, 'b> ops::BitXor<&'b BitBoard> for &'a BitBoard {
+impl ops::BitXor<&BitBoard> for &BitBoard {
     type Output = BitBoard;
 
     fn bitxor(self, rhs: &BitBoard) -> Self::Output {
@@ -122,7 +214,7 @@ impl ops::Not for BitBoard {
     }
 }
 
-impl<'a> ops::Not for &'a BitBoard {
+impl ops::Not for &BitBoard {
     type Output = BitBoard;
 
     fn not(self) -> Self::Output {
@@ -138,7 +230,7 @@ impl ops::Shl<u32> for BitBoard {
     }
 }
 
-impl<'a> ops::Shl<u32> for &'a BitBoard {
+impl ops::Shl<u32> for &BitBoard {
     type Output = BitBoard;
 
     fn shl(self, rhs: u32) -> Self::Output {
@@ -154,7 +246,7 @@ impl ops::Shr<u32> for BitBoard {
     }
 }
 
-impl<'a> ops::Shr<u32> for &'a BitBoard {
+impl ops::Shr<u32> for &BitBoard {
     type Output = BitBoard;
 
     fn shr(self, rhs: u32) -> Self::Output {
@@ -209,6 +301,7 @@ impl BitAndAssign for BitBoard {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::square::square;
 
     #[test]
     fn test_bitand() {
@@ -217,7 +310,7 @@ mod tests {
         let expected = BitBoard(0x0000000000000000);
 
         assert_eq!(a & b, expected);
-        assert_eq!(&a & &b, expected);
+        assert_eq!(ops::BitAnd::bitand(&a, &b), expected);
     }
 
     #[test]
@@ -227,7 +320,7 @@ mod tests {
         let expected = BitBoard(0x000000000000FFFF);
 
         assert_eq!(a | b, expected);
-        assert_eq!(&a | &b, expected);
+        assert_eq!(ops::BitOr::bitor(&a, &b), expected);
     }
 
     #[test]
@@ -237,7 +330,7 @@ mod tests {
         let expected = BitBoard(0x000000000000FFFF);
 
         assert_eq!(a ^ b, expected);
-        assert_eq!(&a ^ &b, expected);
+        assert_eq!(ops::BitXor::bitxor(&a, &b), expected);
     }
 
     #[test]
@@ -258,6 +351,28 @@ mod tests {
         assert_eq!(&a << 8, expected);
     }
 
+    #[test]
+    fn test_into_iter_yields_squares_in_order() {
+        let a = BitBoard(0x8000000000000011);
+        let squares = a.into_iter().map(usize::from).collect::<Vec<usize>>();
+
+        assert_eq!(squares, vec![0, 4, 63]);
+        assert_eq!(a.count(), 3);
+    }
+
+    #[test]
+    fn test_formats_and_converts_square_lists() {
+        let squares = ["e4", "d5"].map(square);
+        let board = BitBoard::from(squares.to_vec());
+        assert_eq!(board.to_string(), "{e4, d5}");
+        assert_eq!(format!("{:?}", board), "BitBoard {e4, d5}");
+        assert!(format!("{:#?}", board).starts_with("Chessboard visualization"));
+        assert_eq!(board.to_fen_occupancy(), "8/8/8/3X4/4X3/8/8/8");
+        assert_eq!(BitBoard::new(0x8000000000000001).to_fen_occupancy(), "7X/8/8/8/8/8/8/X7");
+        assert_eq!(Vec::<Square>::from(board), squares.to_vec());
+        assert_eq!(BitBoard::empty().to_string(), "{}");
+    }
+
     #[test]
     fn test_shr() {
         let a = BitBoard(0x000000000000FF00);
@@ -0,0 +1,98 @@
+use std::fs;
+use std::panic;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Snapshot of the running game, written to disk if the program panics.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CrashContext {
+    /// The position in Forsyth–Edwards Notation.
+    pub fen: String,
+    /// Every move played so far, in coordinate notation (e.g. `e2e4`).
+    pub history: Vec<String>,
+    /// The last raw command entered by the user.
+    pub last_command: String,
+}
+
+static CONTEXT: Mutex<Option<CrashContext>> = Mutex::new(None);
+
+/// Installs a panic hook that dumps the latest recorded `CrashContext` into a
+/// `crash-report-<timestamp>.txt` file before delegating to the default hook.
+pub fn install() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let context = CONTEXT.try_lock().ok().and_then(|context| context.clone());
+        if let Some(context) = context {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default();
+            let path = format!("crash-report-{}.txt", timestamp);
+            let report = format!("{}\n{}", info, report(&context));
+            match fs::write(&path, report) {
+                Ok(()) => eprintln!("Crash report written to {}", path),
+                Err(err) => eprintln!("Failed to write crash report {}: {}", path, err),
+            }
+        }
+        default_hook(info);
+    }));
+}
+
+/// Records the latest position so it can be dumped if a panic occurs.
+///
+/// # Arguments
+/// - `fen`: The current position in Forsyth–Edwards Notation.
+/// - `history`: Every move played so far, in coordinate notation.
+pub(crate) fn record_position(fen: String, history: Vec<String>) {
+    if let Ok(mut current) = CONTEXT.lock() {
+        let context = current.get_or_insert_with(CrashContext::default);
+        context.fen = fen;
+        context.history = history;
+    }
+}
+
+/// Records the last raw command entered by the user.
+///
+/// # Arguments
+/// - `command`: The command as typed, before any parsing.
+pub(crate) fn record_command(command: &str) {
+    if let Ok(mut current) = CONTEXT.lock() {
+        current.get_or_insert_with(CrashContext::default).last_command = command.to_string();
+    }
+}
+
+/// Formats the context as a human-readable report.
+///
+/// # Arguments
+/// - `context`: The recorded game state.
+///
+/// # Returns
+/// - A `String` with the FEN, the numbered move list and the last command.
+fn report(context: &CrashContext) -> String {
+    let moves = context.history
+        .chunks(2)
+        .enumerate()
+        .map(|(idx, pair)| format!("{}. {}", idx + 1, pair.join(" ")))
+        .collect::<Vec<String>>()
+        .join(" ");
+    format!(
+        "FEN: {}\nMoves: {}\nLast command: {}\n",
+        context.fen, moves, context.last_command
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_numbers_move_pairs() {
+        let context = CrashContext {
+            fen: "8/8/8/8/8/8/8/8 w - - 0 1".to_string(),
+            history: vec!["e2e4".to_string(), "e7e5".to_string(), "g1f3".to_string()],
+            last_command: "move b8 c6".to_string(),
+        };
+        let expected = "FEN: 8/8/8/8/8/8/8/8 w - - 0 1\nMoves: 1. e2e4 e7e5 2. g1f3\nLast command: move b8 c6\n";
+        assert_eq!(report(&context), expected);
+    }
+}
@@ -0,0 +1,188 @@
+use std::ops::Index;
+use strum::IntoEnumIterator;
+use crate::bitboard::BitBoard;
+use crate::engine::game::{Game, Move, MoveFlag};
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::Square;
+
+/// A snapshot of the pieces on the board, kept both as a mailbox array, so that the content of a
+/// square is one lookup, and as one bitboard per side and piece with the occupancy of each side,
+/// so that attack and move queries get their masks without combining boards.
+///
+/// Indexing by [`Square`] (or by its index from `a1` to `h8`) gives the content of the square.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Board {
+    squares: [Option<(Piece, Color)>; 64],
+    pieces: [[BitBoard; 6]; 2],
+    occupancy: [BitBoard; 2],
+}
+
+impl Board {
+    /// Creates a board without any piece.
+    pub(crate) fn empty() -> Self {
+        Self { squares: [None; 64], pieces: [[BitBoard::empty(); 6]; 2], occupancy: [BitBoard::empty(); 2] }
+    }
+
+    /// Creates a board from the location masks of a game.
+    ///
+    /// # Arguments
+    /// - `pieces`: One bitboard per piece, indexed by side then piece. A square set in two
+    ///   boards keeps the piece of the later one.
+    pub(crate) fn from_bitboards(pieces: &[[BitBoard; 6]; 2]) -> Self {
+        let mut board = Self::empty();
+        for side in Color::iter() {
+            for piece in Piece::iter() {
+                for square in pieces[usize::from(side)][usize::from(piece)] {
+                    board.set(square, Some((piece, side)));
+                }
+            }
+        }
+        board
+    }
+
+    /// Returns the location masks of the pieces, indexed by side then piece.
+    pub(crate) fn into_bitboards(self) -> [[BitBoard; 6]; 2] {
+        self.pieces
+    }
+
+    /// Puts a piece on a square, or empties it with `None`, keeping the array and the bitboards
+    /// in step.
+    pub(crate) fn set(&mut self, square: Square, content: Option<(Piece, Color)>) {
+        let bit = BitBoard::from(square);
+        if let Some((piece, side)) = self.squares[usize::from(square)] {
+            self.pieces[usize::from(side)][usize::from(piece)] &= !bit;
+            self.occupancy[usize::from(side)] &= !bit;
+        }
+        if let Some((piece, side)) = content {
+            self.pieces[usize::from(side)][usize::from(piece)] |= bit;
+            self.occupancy[usize::from(side)] |= bit;
+        }
+        self.squares[usize::from(square)] = content;
+    }
+
+    /// Returns the squares holding a piece of `side`.
+    pub(crate) fn occupancy(&self, side: Color) -> BitBoard {
+        self.occupancy[usize::from(side)]
+    }
+
+    /// Returns the squares holding any piece.
+    pub(crate) fn occupied(&self) -> BitBoard {
+        self.occupancy(Color::White) | self.occupancy(Color::Black)
+    }
+
+    /// Iterates over the content of the squares, from `a1` to `h8`.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Option<(Piece, Color)>> {
+        self.squares.iter()
+    }
+
+    /// Infers the move that turns this position into `other` from the squares whose content
+    /// changed: one piece moving, possibly capturing or promoting, a pawn capturing en passant,
+    /// or the king and a rook castling. The pieces are not checked to move the way they may;
+    /// [`Game::infer_move`] also checks that the move is legal.
+    ///
+    /// # Arguments
+    /// - `other`: The position after the move.
+    ///
+    /// # Returns
+    /// - `Some(Move)`: The move, described as [`Game::legal_move_list`] describes it.
+    /// - `None`: If the changes are not those of a single move.
+    pub(crate) fn diff(&self, other: &Board) -> Option<Move> {
+        let changed = self.pieces.iter().flatten().zip(other.pieces.iter().flatten())
+            .fold(BitBoard::empty(), |changed, (before, after)| changed | (*before ^ *after));
+        let (emptied, filled): (Vec<Square>, Vec<Square>) = changed.into_iter().partition(|square| other[*square].is_none());
+        // the king of a castling moves with its rook, a pawn capturing en passant empties a second square
+        let find = |squares: &[Square], board: &Board, wanted: fn(Piece) -> bool| {
+            squares.iter().copied().find(|square| board[*square].is_some_and(|(piece, _)| wanted(piece)))
+        };
+        let (from, to) = match (emptied.as_slice(), filled.as_slice()) {
+            ([from], [to]) => (*from, *to),
+            ([_, _], [to]) => (emptied.iter().copied().find(|from| self[*from].map(|(_, side)| side) == other[*to].map(|(_, side)| side))?, *to),
+            ([_, _], [_, _]) => (find(&emptied, self, |piece| piece == Piece::King)?, find(&filled, other, |piece| piece == Piece::King)?),
+            _ => return None,
+        };
+        let (piece, side) = self[from]?;
+        let (landed, owner) = other[to]?;
+        let captured = self[to].map(|(captured, _)| captured);
+        if owner != side || self[to].is_some_and(|(_, owner)| owner == side) || (landed != piece && piece != Piece::Pawn) {
+            return None;
+        }
+        let mv = Move { from, to, piece, captured, promotion: (landed != piece).then_some(landed), flag: MoveFlag::Normal };
+        let file_distance = usize::from(from.file()).abs_diff(usize::from(to.file()));
+        let rank_distance = usize::from(from.rank()).abs_diff(usize::from(to.rank()));
+        match emptied.len() + filled.len() {
+            2 if piece == Piece::Pawn && rank_distance == 2 => Some(Move { flag: MoveFlag::DoublePush, ..mv }),
+            2 => Some(mv),
+            3 if captured.is_none() => {
+                let square = Square::new(to.file(), from.rank());
+                let en_passant = piece == Piece::Pawn && file_distance == 1 && emptied.contains(&square)
+                    && self[square] == Some((Piece::Pawn, side.opposite()));
+                en_passant.then_some(Move { captured: Some(Piece::Pawn), flag: MoveFlag::EnPassant, ..mv })
+            }
+            4 if captured.is_none() => {
+                let (rook_from, rook_to) = Game::castling_rook_squares(to);
+                let rook = Some((Piece::Rock, side));
+                let castled = file_distance == 2 && rank_distance == 0 && self[rook_from] == rook && other[rook_to] == rook
+                    && emptied.contains(&rook_from) && filled.contains(&rook_to);
+                castled.then_some(Move { flag: MoveFlag::Castle, ..mv })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Index<Square> for Board {
+    type Output = Option<(Piece, Color)>;
+
+    fn index(&self, square: Square) -> &Self::Output {
+        &self.squares[usize::from(square)]
+    }
+}
+
+impl Index<usize> for Board {
+    type Output = Option<(Piece, Color)>;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.squares[idx]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::{File, Rank};
+
+    #[test]
+    fn test_mailbox_and_bitboards_stay_in_step() {
+        let e4 = Square::new(File::E, Rank::Four);
+        let mut board = Board::empty();
+        board.set(e4, Some((Piece::Pawn, Color::White)));
+        board.set(e4, Some((Piece::Knight, Color::Black)));
+        assert_eq!(board[e4], Some((Piece::Knight, Color::Black)));
+        assert_eq!(board[usize::from(e4)], board[e4]);
+        assert_eq!((board.occupancy(Color::White), board.occupied()), (BitBoard::empty(), BitBoard::from(e4)));
+        assert!(board.into_bitboards()[usize::from(Color::White)][usize::from(Piece::Pawn)].is_empty());
+        assert_eq!(Board::from_bitboards(&board.into_bitboards()), board);
+        board.set(e4, None);
+        assert_eq!(board, Board::empty());
+    }
+
+    #[test]
+    fn test_diff_finds_every_kind_of_move() {
+        let game = Game::from_fen("r3k3/1P6/8/3pP3/8/8/4P3/4K2R w K d6 0 1").unwrap();
+        let before = game.get_all_position();
+        for mv in game.legal_move_list().iter() {
+            let mut after = game.clone();
+            after.make_move(*mv);
+            assert_eq!(before.diff(&after.get_all_position()), Some(*mv), "{}", mv);
+        }
+        assert_eq!(before.diff(&before), None);
+        // two pieces moving at once
+        let mut two_moves = before;
+        two_moves.set(Square::new(File::E, Rank::Two), None);
+        two_moves.set(Square::new(File::H, Rank::One), None);
+        two_moves.set(Square::new(File::E, Rank::Three), Some((Piece::Pawn, Color::White)));
+        two_moves.set(Square::new(File::H, Rank::Two), Some((Piece::Rock, Color::White)));
+        assert_eq!(before.diff(&two_moves), None);
+    }
+}
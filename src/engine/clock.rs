@@ -0,0 +1,201 @@
+use std::fmt;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::pieces::common::Color;
+
+/// What a player gets back for each move, on top of the base time.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ClockBonus {
+    /// Fischer increment: the time is added after every move.
+    Increment(Duration),
+    /// Simple delay: the clock only starts running once the delay has passed.
+    Delay(Duration),
+    /// Bronstein delay: the time used on the move is given back, up to the delay.
+    Bronstein(Duration),
+}
+
+/// The time budget of each player: a base time and a bonus per move.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimeControl {
+    pub base: Duration,
+    pub bonus: ClockBonus,
+}
+
+impl TimeControl {
+    /// Parses a time control: the base time in minutes, optionally followed by the bonus per
+    /// move in seconds, e.g. `5` (no bonus), `5+3` (increment), `5d3` (simple delay) or `5b3`
+    /// (Bronstein delay).
+    ///
+    /// # Arguments
+    /// - `spec`: The time control.
+    ///
+    /// # Returns
+    /// - `Ok(TimeControl)`: The parsed time control.
+    /// - `Err(String)`: If the time control is malformed or the base time is not positive.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        let (base, bonus) = match spec.find(['+', 'd', 'b']) {
+            Some(idx) => (&spec[..idx], Some((&spec[idx..idx + 1], &spec[idx + 1..]))),
+            None => (spec, None),
+        };
+        let invalid = || format!("Invalid time control '{}', expected e.g. 5, 5+3, 5d3 or 5b3.", spec);
+        let minutes = base.parse::<f64>().ok().filter(|minutes| minutes.is_finite() && *minutes > 0.0).ok_or_else(invalid)?;
+        let bonus = match bonus {
+            None => ClockBonus::Increment(Duration::ZERO),
+            Some((kind, seconds)) => {
+                let seconds = seconds.parse::<u64>().map_err(|_| invalid())?;
+                let seconds = Duration::from_secs(seconds);
+                match kind {
+                    "+" => ClockBonus::Increment(seconds),
+                    "d" => ClockBonus::Delay(seconds),
+                    _ => ClockBonus::Bronstein(seconds),
+                }
+            }
+        };
+        Ok(Self { base: Duration::from_secs_f64(minutes * 60.0), bonus })
+    }
+}
+
+/// The time left to each player under a time control.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Clock {
+    control: TimeControl,
+    remaining: [Duration; 2],
+}
+
+impl Clock {
+    /// Creates a clock giving both players the base time of the control.
+    pub(crate) fn new(control: TimeControl) -> Self {
+        Self { control, remaining: [control.base; 2] }
+    }
+
+    /// Returns the time left to `side` after thinking `elapsed` on its current move, without
+    /// charging it.
+    pub(crate) fn remaining(&self, side: Color, elapsed: Duration) -> Duration {
+        let charged = match self.control.bonus {
+            ClockBonus::Delay(delay) => elapsed.saturating_sub(delay),
+            ClockBonus::Increment(_) | ClockBonus::Bronstein(_) => elapsed,
+        };
+        self.remaining[usize::from(side)].saturating_sub(charged)
+    }
+
+    /// Returns how long `side` may think on its current move before its flag falls, the delay
+    /// of a simple delay control included.
+    pub(crate) fn flag_time(&self, side: Color) -> Duration {
+        let delay = match self.control.bonus {
+            ClockBonus::Delay(delay) => delay,
+            ClockBonus::Increment(_) | ClockBonus::Bronstein(_) => Duration::ZERO,
+        };
+        self.remaining[usize::from(side)] + delay
+    }
+
+    /// Charges a finished move to the clock of `side` and adds the bonus it earns.
+    ///
+    /// # Arguments
+    /// - `side`: The side that moved.
+    /// - `elapsed`: The time spent on the move.
+    ///
+    /// # Returns
+    /// - `true`: If the side still had time left when the move was made.
+    /// - `false`: If its flag fell; the clock then shows no time left.
+    pub(crate) fn punch(&mut self, side: Color, elapsed: Duration) -> bool {
+        let left = self.remaining(side, elapsed);
+        if left.is_zero() {
+            self.remaining[usize::from(side)] = Duration::ZERO;
+            return false;
+        }
+        self.remaining[usize::from(side)] = match self.control.bonus {
+            ClockBonus::Increment(increment) => left + increment,
+            ClockBonus::Delay(_) => left,
+            ClockBonus::Bronstein(delay) => left + elapsed.min(delay),
+        };
+        true
+    }
+
+    /// Returns a display of both clocks, with `elapsed` already running on the side to move.
+    pub(crate) fn display(&self, turn: Color, elapsed: Duration) -> String {
+        let shown = |side: Color| {
+            let left = if side == turn { self.remaining(side, elapsed) } else { self.remaining[usize::from(side)] };
+            ClockTime(left)
+        };
+        format!("[White {} | Black {}]", shown(Color::White), shown(Color::Black))
+    }
+}
+
+/// How long a move took, and the clock of the mover once it was punched in a timed game.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct MoveTiming {
+    pub(crate) side: Color,
+    pub(crate) elapsed: Duration,
+    pub(crate) clock: Option<Duration>,
+}
+
+/// A duration as written in PGN clock comments, `H:MM:SS`, e.g. `[%clk 0:04:59]`.
+pub(crate) struct PgnTime(pub(crate) Duration);
+
+impl fmt::Display for PgnTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.0.as_secs();
+        write!(f, "{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    }
+}
+
+/// Time left on a clock, shown as minutes and seconds rounded up, with tenths in the last ten
+/// seconds.
+struct ClockTime(Duration);
+
+impl fmt::Display for ClockTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 < Duration::from_secs(10) {
+            return write!(f, "0:0{}.{}", self.0.as_secs(), self.0.subsec_millis() / 100);
+        }
+        let seconds = self.0.as_millis().div_ceil(1000);
+        write!(f, "{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(seconds: u64) -> Duration {
+        Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn test_parse_time_controls() {
+        assert_eq!(TimeControl::parse("5+3"), Ok(TimeControl { base: secs(300), bonus: ClockBonus::Increment(secs(3)) }));
+        assert_eq!(TimeControl::parse("0.5").unwrap().base, secs(30));
+        assert_eq!(TimeControl::parse("10d5").unwrap().bonus, ClockBonus::Delay(secs(5)));
+        assert_eq!(TimeControl::parse("10b5").unwrap().bonus, ClockBonus::Bronstein(secs(5)));
+        for spec in ["", "0", "-1", "5+", "5+x", "five"] {
+            assert!(TimeControl::parse(spec).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn test_bonus_modes() {
+        let mut clock = Clock::new(TimeControl::parse("1+2").unwrap());
+        assert!(clock.punch(Color::White, secs(10)));
+        assert_eq!(clock.remaining(Color::White, Duration::ZERO), secs(52));
+        let mut clock = Clock::new(TimeControl::parse("1d5").unwrap());
+        clock.punch(Color::White, secs(3));
+        clock.punch(Color::Black, secs(10));
+        assert_eq!((clock.remaining(Color::White, Duration::ZERO), clock.remaining(Color::Black, Duration::ZERO)), (secs(60), secs(55)));
+        let mut clock = Clock::new(TimeControl::parse("1b5").unwrap());
+        clock.punch(Color::White, secs(3));
+        clock.punch(Color::Black, secs(10));
+        assert_eq!((clock.remaining(Color::White, Duration::ZERO), clock.remaining(Color::Black, Duration::ZERO)), (secs(60), secs(55)));
+        assert_eq!(clock.display(Color::White, secs(51)), "[White 0:09.0 | Black 0:55]");
+    }
+
+    #[test]
+    fn test_flag_falls_when_time_runs_out() {
+        let mut clock = Clock::new(TimeControl::parse("1+30").unwrap());
+        assert!(!clock.punch(Color::White, secs(60)));
+        assert_eq!(clock.remaining(Color::White, Duration::ZERO), Duration::ZERO);
+        let clock = Clock::new(TimeControl::parse("1d5").unwrap());
+        assert_eq!(clock.flag_time(Color::Black), secs(65));
+        assert_eq!(clock.remaining(Color::Black, clock.flag_time(Color::Black)), Duration::ZERO);
+    }
+}
@@ -0,0 +1,168 @@
+use std::time::Duration;
+use strum::IntoEnumIterator;
+use crate::engine::eval::GamePhase;
+use crate::engine::game::GameResult;
+use crate::engine::observer::{MoveEvent, MoveObserver};
+use crate::pieces::common::Color;
+
+/// Number of opening moves a side must have played before its opening pace is judged.
+const OPENING_SAMPLE: usize = 3;
+
+/// Average think time per opening move above which the coach warns.
+const OPENING_AVERAGE_LIMIT: Duration = Duration::from_secs(20);
+
+/// A single move taking this many times the side's average so far is worth a warning.
+const SLOW_MOVE_FACTOR: u32 = 4;
+
+/// Moves shorter than this are never reported as slow, whatever the average.
+const SLOW_MOVE_MIN: Duration = Duration::from_secs(30);
+
+/// Watches the time each coached side spends per move and per game phase.
+///
+/// During the game it warns about disproportionate time usage: a slow opening, or a single
+/// move taking far longer than usual. When the game ends it reports the average think time
+/// of each side in each phase.
+pub struct TimeCoach {
+    sides: Vec<Color>,
+    // total time and move count, indexed by side, then phase
+    totals: [[(Duration, u32); 3]; 2],
+    opening_warned: [bool; 2],
+}
+
+impl TimeCoach {
+    /// Creates a coach for the given sides, typically the human players.
+    pub fn new(sides: Vec<Color>) -> Self {
+        Self { sides, totals: [[(Duration::ZERO, 0); 3]; 2], opening_warned: [false; 2] }
+    }
+
+    /// Records a move and returns the warnings it triggers, if any.
+    ///
+    /// # Returns
+    /// - `Vec<String>`: One message per warning; empty if the side is not coached.
+    pub fn record(&mut self, event: &MoveEvent) -> Vec<String> {
+        if !self.sides.contains(&event.side) {
+            return Vec::new();
+        }
+        let mut warnings = Vec::new();
+        let side_idx = usize::from(event.side);
+        let (played, total) = self.side_totals(event.side);
+        if played > 0 {
+            let average = total / played;
+            if event.elapsed >= SLOW_MOVE_MIN && event.elapsed > average * SLOW_MOVE_FACTOR {
+                warnings.push(format!(
+                    "{:?} spent {:.0}s on {}, more than {} times the usual {:.0}s per move.",
+                    event.side, event.elapsed.as_secs_f64(), event.san, SLOW_MOVE_FACTOR, average.as_secs_f64()
+                ));
+            }
+        }
+        let entry = &mut self.totals[side_idx][phase_index(event.phase)];
+        entry.0 += event.elapsed;
+        entry.1 += 1;
+        let (opening_total, opening_moves) = *entry;
+        if event.phase == GamePhase::Opening
+            && !self.opening_warned[side_idx]
+            && opening_moves as usize >= OPENING_SAMPLE
+            && opening_total / opening_moves > OPENING_AVERAGE_LIMIT
+        {
+            self.opening_warned[side_idx] = true;
+            warnings.push(format!(
+                "{:?}, you're spending too long in the opening: {:.0}s per move so far. Save time for the middlegame.",
+                event.side, (opening_total / opening_moves).as_secs_f64()
+            ));
+        }
+        warnings
+    }
+
+    /// Summarises the average think time of each coached side in each phase.
+    ///
+    /// # Returns
+    /// - `Vec<String>`: One line per coached side, e.g.
+    ///   `White average think time: opening 4.2s (10 moves), middlegame 12.0s (21 moves), endgame -`.
+    pub fn summary(&self) -> Vec<String> {
+        Color::iter()
+            .filter(|side| self.sides.contains(side))
+            .map(|side| {
+                let phases = [GamePhase::Opening, GamePhase::Middlegame, GamePhase::Endgame]
+                    .into_iter()
+                    .map(|phase| match self.totals[usize::from(side)][phase_index(phase)] {
+                        (_, 0) => format!("{} -", phase),
+                        (total, moves) => format!("{} {:.1}s ({} moves)", phase, (total / moves).as_secs_f64(), moves),
+                    })
+                    .collect::<Vec<String>>();
+                format!("{:?} average think time: {}", side, phases.join(", "))
+            })
+            .collect()
+    }
+
+    /// Returns the number of moves and the total time recorded for a side over all phases.
+    fn side_totals(&self, side: Color) -> (u32, Duration) {
+        self.totals[usize::from(side)]
+            .iter()
+            .fold((0, Duration::ZERO), |(moves, time), (total, played)| (moves + played, time + *total))
+    }
+}
+
+impl MoveObserver for TimeCoach {
+    fn on_move(&mut self, event: &MoveEvent) {
+        for warning in self.record(event) {
+            println!("Coach: {}", warning);
+        }
+    }
+
+    fn on_game_end(&mut self, _result: Option<GameResult>) {
+        for line in self.summary() {
+            println!("Coach: {}", line);
+        }
+    }
+}
+
+/// Index of a phase in the per-phase totals.
+fn phase_index(phase: GamePhase) -> usize {
+    match phase {
+        GamePhase::Opening => 0,
+        GamePhase::Middlegame => 1,
+        GamePhase::Endgame => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(side: Color, phase: GamePhase, seconds: u64) -> MoveEvent {
+        MoveEvent { ply: 1, side, san: "e4".to_string(), phase, eval: 0, elapsed: Duration::from_secs(seconds) }
+    }
+
+    #[test]
+    fn test_slow_opening_warns_once() {
+        let mut coach = TimeCoach::new(vec![Color::White]);
+        assert!(coach.record(&event(Color::White, GamePhase::Opening, 30)).is_empty());
+        assert!(coach.record(&event(Color::White, GamePhase::Opening, 30)).is_empty());
+        let warnings = coach.record(&event(Color::White, GamePhase::Opening, 30));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("too long in the opening"));
+        assert!(coach.record(&event(Color::White, GamePhase::Opening, 30)).is_empty());
+    }
+
+    #[test]
+    fn test_single_slow_move_warns() {
+        let mut coach = TimeCoach::new(vec![Color::Black]);
+        for _ in 0..5 {
+            coach.record(&event(Color::Black, GamePhase::Middlegame, 5));
+        }
+        assert_eq!(coach.record(&event(Color::Black, GamePhase::Middlegame, 60)).len(), 1);
+        assert!(coach.record(&event(Color::White, GamePhase::Middlegame, 600)).is_empty());
+    }
+
+    #[test]
+    fn test_summary_averages_per_phase() {
+        let mut coach = TimeCoach::new(vec![Color::White]);
+        coach.record(&event(Color::White, GamePhase::Opening, 2));
+        coach.record(&event(Color::White, GamePhase::Opening, 4));
+        coach.record(&event(Color::White, GamePhase::Middlegame, 10));
+        assert_eq!(
+            coach.summary(),
+            vec!["White average think time: opening 3.0s (2 moves), middlegame 10.0s (1 moves), endgame -"]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use crate::engine::game::{Game, GameResult, Move, MoveError};
+use crate::engine::search;
+use crate::gui::san;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::Square;
+
+/// Something a caller asks a [`GameDriver`] to do, the programmatic counterpart of the
+/// commands typed at the prompt.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DriverAction {
+    /// Move the piece on `from` to `to`; a promoting pawn becomes `promotion`, or a queen.
+    Move { from: Square, to: Square, promotion: Option<Piece> },
+    /// Play a move written in standard algebraic notation, e.g. `Nf3` or `e8=N`.
+    San(String),
+    /// Let the engine search the position to the given depth and play its best move.
+    EngineMove { depth: u8 },
+    /// Offer a draw on behalf of the side to move.
+    OfferDraw,
+    /// Accept the opponent's draw offer.
+    AcceptDraw,
+    /// Decline the opponent's draw offer.
+    DeclineDraw,
+    /// Resign on behalf of the side to move.
+    Resign,
+    /// Take back the last move.
+    Undo,
+    /// Play again the last move taken back.
+    Redo,
+}
+
+/// Something that happened in the game after a [`DriverAction`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum DriverEvent {
+    /// A move was played by `side`, written as `san` in algebraic notation.
+    MoveApplied { side: Color, mv: Move, san: String },
+    /// A move of `side` was refused; the position did not change.
+    IllegalMove { side: Color, error: MoveError },
+    /// `side`, now to move, is in check.
+    Check { side: Color },
+    /// The game ended; every later action is answered with this event again.
+    GameOver(GameResult),
+    /// `side` offered a draw.
+    DrawOffered { side: Color },
+    /// `side` declined the opponent's draw offer.
+    DrawDeclined { side: Color },
+    /// A move was taken back.
+    MoveTakenBack(Move),
+    /// An action other than a move could not be carried out, with the reason.
+    Rejected(String),
+}
+
+/// Runs the rules without any GUI: each [`DriverAction`] is applied to the game at once and
+/// answered with the events it caused, so that a web server or another front end can embed
+/// the engine without handing it the terminal.
+pub struct GameDriver {
+    game: Game,
+}
+
+impl GameDriver {
+    /// Creates a driver that plays on from `game`, e.g. [`Game::new`] or a position read with
+    /// [`Game::from_fen`].
+    pub fn new(game: Game) -> Self {
+        Self { game }
+    }
+
+    /// Returns the game in its current state, to draw the board or read its FEN.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Applies an action to the game.
+    ///
+    /// # Arguments
+    /// - `action`: What the player or the embedding program wants to do.
+    ///
+    /// # Returns
+    /// The events the action caused, in order: e.g. `MoveApplied`, then `Check` and
+    /// `GameOver` for a mating move. Actions that cannot be carried out answer with a single
+    /// `IllegalMove` or `Rejected` event and leave the game untouched.
+    pub fn apply(&mut self, action: DriverAction) -> Vec<DriverEvent> {
+        let side = self.game.turn();
+        if let Some(result) = self.game.game_result() {
+            return vec![DriverEvent::GameOver(result)];
+        }
+        tracing::debug!(?side, ?action, "driver action");
+        match action {
+            DriverAction::Move { from, to, promotion } => self.play(from, to, promotion),
+            DriverAction::San(san) => {
+                let resolved = san::parse(&san)
+                    .ok_or(MoveError::InvalidNotation)
+                    .and_then(|parsed| self.game.resolve_san(&parsed).map(|(from, to)| (from, to, parsed.promotion())));
+                match resolved {
+                    Ok((from, to, promotion)) => self.play(from, to, promotion),
+                    Err(error) => vec![DriverEvent::IllegalMove { side, error }],
+                }
+            }
+            DriverAction::EngineMove { depth } => match search::best_move(&self.game, depth) {
+                Some(mv) => self.play(mv.from, mv.to, mv.promotion),
+                None => vec![DriverEvent::Rejected(format!("{:?} has no legal move.", side))],
+            },
+            DriverAction::OfferDraw => Self::answer(self.game.offer_draw(), DriverEvent::DrawOffered { side }),
+            DriverAction::AcceptDraw => match self.game.accept_draw() {
+                Ok(()) => self.outcome(),
+                Err(err) => vec![DriverEvent::Rejected(err)],
+            },
+            DriverAction::DeclineDraw => Self::answer(self.game.decline_draw(), DriverEvent::DrawDeclined { side }),
+            DriverAction::Resign => {
+                self.game.resign();
+                self.outcome()
+            }
+            DriverAction::Undo => match self.game.last_move() {
+                Some(mv) if self.game.undo() => vec![DriverEvent::MoveTakenBack(mv)],
+                _ => vec![DriverEvent::Rejected("There is no move to take back.".to_string())],
+            },
+            DriverAction::Redo => {
+                let before = self.game.clone();
+                if !self.game.redo() {
+                    return vec![DriverEvent::Rejected("There is no move to redo.".to_string())];
+                }
+                let mv = self.game.last_move().expect("a move was redone");
+                let san = before.move_san(mv);
+                let mut events = vec![DriverEvent::MoveApplied { side, mv, san }];
+                events.extend(self.outcome());
+                events
+            }
+        }
+    }
+
+    /// Plays a move, answering with the move played and whether it checks or ends the game.
+    fn play(&mut self, from: Square, to: Square, promotion: Option<Piece>) -> Vec<DriverEvent> {
+        let side = self.game.turn();
+        // a promoting pawn becomes the chosen piece, a queen if none was chosen
+        let san = self.game.describe_move(from, to)
+            .map(|mv| self.game.move_san(Move { promotion: mv.promotion.and(promotion).or(mv.promotion), ..mv }))
+            .unwrap_or_default();
+        if let Err(error) = self.game.play_with_promotion(from, to, promotion) {
+            return vec![DriverEvent::IllegalMove { side, error }];
+        }
+        let mv = self.game.last_move().expect("a move was played");
+        let mut events = vec![DriverEvent::MoveApplied { side, mv, san }];
+        events.extend(self.outcome());
+        events
+    }
+
+    /// Reports a check of the side to move and the end of the game, if any.
+    fn outcome(&self) -> Vec<DriverEvent> {
+        let mut events = Vec::new();
+        if self.game.is_checked() {
+            events.push(DriverEvent::Check { side: self.game.turn() });
+        }
+        if let Some(result) = self.game.game_result() {
+            events.push(DriverEvent::GameOver(result));
+        }
+        events
+    }
+
+    /// Turns the answer of a draw offer or refusal into its event.
+    fn answer(result: Result<(), String>, event: DriverEvent) -> Vec<DriverEvent> {
+        match result {
+            Ok(()) => vec![event],
+            Err(err) => vec![DriverEvent::Rejected(err)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_fools_mate_reports_check_and_game_over() {
+        let mut driver = GameDriver::new(Game::new());
+        for san in ["f3", "e5", "g4"] {
+            assert!(matches!(driver.apply(DriverAction::San(san.to_string()))[..], [DriverEvent::MoveApplied { .. }]));
+        }
+        let events = driver.apply(DriverAction::San("Qh4".to_string()));
+        let [DriverEvent::MoveApplied { side: Color::Black, san, .. }, DriverEvent::Check { side: Color::White }, DriverEvent::GameOver(GameResult::Checkmate(Color::White))] = &events[..] else {
+            panic!("unexpected events {:?}", events);
+        };
+        assert_eq!(san, "Qh4#");
+        assert_eq!(driver.apply(DriverAction::Resign), vec![DriverEvent::GameOver(GameResult::Checkmate(Color::White))]);
+        let mut promotion = GameDriver::new(Game::from_fen("8/5P1k/8/8/8/8/8/4K3 w - - 0 1").unwrap());
+        let events = promotion.apply(DriverAction::Move { from: square("f7"), to: square("f8"), promotion: Some(Piece::Knight) });
+        assert!(matches!(&events[..], [DriverEvent::MoveApplied { san, .. }, DriverEvent::Check { side: Color::Black }] if san == "f8=N+"), "{:?}", events);
+        // the engine plays the underpromotion it found, not a queen
+        let mut engine = GameDriver::new(Game::from_fen("5bn1/6Pk/7p/8/8/8/8/B1K5 w - - 0 1").unwrap());
+        let events = engine.apply(DriverAction::EngineMove { depth: 2 });
+        assert!(matches!(&events[..], [DriverEvent::MoveApplied { san, .. }, .., DriverEvent::GameOver(GameResult::Checkmate(Color::Black))] if san == "gxf8=N#"), "{:?}", events);
+    }
+
+    #[test]
+    fn test_illegal_moves_and_rejected_actions_leave_the_game_untouched() {
+        let mut driver = GameDriver::new(Game::new());
+        let fen = driver.game().fen();
+        let events = driver.apply(DriverAction::Move { from: square("e2"), to: square("e5"), promotion: None });
+        assert!(matches!(events[..], [DriverEvent::IllegalMove { side: Color::White, error: MoveError::IllegalTarget { .. } }]));
+        assert!(matches!(driver.apply(DriverAction::Undo)[..], [DriverEvent::Rejected(_)]));
+        assert!(matches!(driver.apply(DriverAction::AcceptDraw)[..], [DriverEvent::Rejected(_)]));
+        assert_eq!(driver.game().fen(), fen);
+        assert!(matches!(driver.apply(DriverAction::EngineMove { depth: 1 })[..], [DriverEvent::MoveApplied { side: Color::White, .. }]));
+        assert!(matches!(driver.apply(DriverAction::Undo)[..], [DriverEvent::MoveTakenBack(_)]));
+        assert_eq!(driver.game().fen(), fen);
+    }
+}
@@ -0,0 +1,367 @@
+mod config;
+
+use std::fmt;
+use strum::IntoEnumIterator;
+use crate::bitboard::BitBoard;
+use crate::engine::game::Game;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::{File, Rank, Square};
+
+pub use config::EvalConfig;
+
+/// Default material value of each piece in centipawns, indexed by `usize::from(Piece)`.
+pub const PIECE_VALUES: [i32; 6] = [100, 320, 500, 330, 900, 0];
+
+/// Game phase weight of each piece, indexed by `usize::from(Piece)`.
+/// The start position sums to [`MAX_PHASE`]; bare kings and pawns sum to `0`.
+const PHASE_WEIGHTS: [i32; 6] = [0, 1, 2, 1, 4, 0];
+
+/// Phase value of the start position (full middlegame).
+const MAX_PHASE: i32 = 24;
+
+/// The opening lasts until this move number, unless material comes off the board earlier.
+const OPENING_MOVES: u32 = 10;
+
+/// Below this phase value the opening is over, even early in the game.
+const OPENING_MIN_PHASE: i32 = 20;
+
+/// At or below this phase value the game is in the endgame (e.g. rook and minor piece each).
+const ENDGAME_MAX_PHASE: i32 = 8;
+
+/// Broad stage of the game, used to report statistics per stage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamePhase {
+    Opening,
+    Middlegame,
+    Endgame,
+}
+
+impl fmt::Display for GamePhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GamePhase::Opening => "opening",
+            GamePhase::Middlegame => "middlegame",
+            GamePhase::Endgame => "endgame",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// The default piece-square tables of the `EvalConfig`, written from White's point of view
+// as seen on a diagram: the first row is rank 8 and the last row is rank 1.
+
+const PAWN_TABLE: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     10,  10,  20,  30,  30,  20,  10,  10,
+      5,   5,  10,  25,  25,  10,   5,   5,
+      0,   0,   0,  20,  20,   0,   0,   0,
+      5,  -5, -10,   0,   0, -10,  -5,   5,
+      5,  10,  10, -20, -20,  10,  10,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+const KNIGHT_TABLE: [i32; 64] = [
+    -50, -40, -30, -30, -30, -30, -40, -50,
+    -40, -20,   0,   0,   0,   0, -20, -40,
+    -30,   0,  10,  15,  15,  10,   0, -30,
+    -30,   5,  15,  20,  20,  15,   5, -30,
+    -30,   0,  15,  20,  20,  15,   0, -30,
+    -30,   5,  10,  15,  15,  10,   5, -30,
+    -40, -20,   0,   5,   5,   0, -20, -40,
+    -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+const BISHOP_TABLE: [i32; 64] = [
+    -20, -10, -10, -10, -10, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   5,   5,  10,  10,   5,   5, -10,
+    -10,   0,  10,  10,  10,  10,   0, -10,
+    -10,  10,  10,  10,  10,  10,  10, -10,
+    -10,   5,   0,   0,   0,   0,   5, -10,
+    -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+const ROCK_TABLE: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+      5,  10,  10,  10,  10,  10,  10,   5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+      0,   0,   0,   5,   5,   0,   0,   0,
+];
+
+const QUEEN_TABLE: [i32; 64] = [
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+     -5,   0,   5,   5,   5,   5,   0,  -5,
+      0,   0,   5,   5,   5,   5,   0,  -5,
+    -10,   5,   5,   5,   5,   5,   0, -10,
+    -10,   0,   5,   0,   0,   0,   0, -10,
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+const KING_MIDDLEGAME_TABLE: [i32; 64] = [
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -20, -30, -30, -40, -40, -30, -30, -20,
+    -10, -20, -20, -20, -20, -20, -20, -10,
+     20,  20,   0,   0,   0,   0,  20,  20,
+     20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+const KING_ENDGAME_TABLE: [i32; 64] = [
+    -50, -40, -30, -20, -20, -30, -40, -50,
+    -30, -20, -10,   0,   0, -10, -20, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -30,   0,   0,   0,   0, -30, -30,
+    -50, -30, -30, -30, -30, -30, -30, -50,
+];
+
+/// Evaluates the position statically using material, piece-square tables, mobility, rooks on
+/// open files, the bishop pair, the pawn shield of the king and the pawn structure, weighted by
+/// the [`EvalConfig`] of the game.
+///
+/// The king uses separate middlegame and endgame tables, blended by the amount of
+/// non-pawn material left on the board.
+///
+/// # Arguments
+/// - `game`: The `Game` holding the position to score.
+/// - `side`: The `Color` from whose point of view the score is returned.
+///
+/// # Returns
+/// - The score in centipawns; positive values favour `side`.
+pub fn evaluate(game: &Game, side: Color) -> i32 {
+    evaluate_with(game, game.eval_config(), side)
+}
+
+/// Evaluates the position like [`evaluate`], with other weights than those of the game.
+pub(crate) fn evaluate_with(game: &Game, config: &EvalConfig, side: Color) -> i32 {
+    let phase = phase(game);
+    let score = side_score(game, config, Color::White, phase) - side_score(game, config, Color::Black, phase);
+    match side {
+        Color::White => score,
+        Color::Black => -score,
+    }
+}
+
+/// Classifies the position as opening, middlegame or endgame.
+///
+/// The endgame starts once little non-pawn material is left; before that, the first
+/// [`OPENING_MOVES`] moves count as the opening while nearly all pieces are still on the board.
+///
+/// # Arguments
+/// - `game`: The `Game` holding the position to classify.
+pub fn game_phase(game: &Game) -> GamePhase {
+    let phase = phase(game);
+    if phase <= ENDGAME_MAX_PHASE {
+        GamePhase::Endgame
+    } else if game.fullmove_number() <= OPENING_MOVES && phase >= OPENING_MIN_PHASE {
+        GamePhase::Opening
+    } else {
+        GamePhase::Middlegame
+    }
+}
+
+/// Computes the game phase from the non-pawn material left on the board.
+///
+/// # Returns
+/// - A value between `0` (endgame) and [`MAX_PHASE`] (middlegame).
+fn phase(game: &Game) -> i32 {
+    let phase = Color::iter()
+        .flat_map(|side| Piece::iter().map(move |piece| (side, piece)))
+        .map(|(side, piece)| game.pieces(side, piece).count() as i32 * PHASE_WEIGHTS[usize::from(piece)])
+        .sum::<i32>();
+    phase.min(MAX_PHASE)
+}
+
+/// Sums every term of the evaluation for one side.
+///
+/// # Arguments
+/// - `game`: The `Game` holding the position to score.
+/// - `config`: The weights of the terms.
+/// - `side`: The `Color` of the pieces to sum.
+/// - `phase`: The game phase, as returned by [`phase`].
+fn side_score(game: &Game, config: &EvalConfig, side: Color, phase: i32) -> i32 {
+    let material = Piece::iter()
+        .map(|piece| {
+            game.pieces(side, piece)
+                .into_iter()
+                .map(|square| config.piece_values[usize::from(piece)] + square_bonus(config, piece, side, square, phase))
+                .sum::<i32>()
+        })
+        .sum::<i32>();
+    // the move counts are kept up to date with the movement masks, so reading them is cheap
+    let mobility = Piece::iter()
+        .map(|piece| config.mobility[usize::from(piece)] * game.mobility(side, piece) as i32)
+        .sum::<i32>();
+    material + mobility + piece_activity(game, config, side) + king_shield(game, config, side, phase)
+        + pawn_structure(game, config, side)
+}
+
+/// Scores rooks on open and half-open files and the bishop pair.
+fn piece_activity(game: &Game, config: &EvalConfig, side: Color) -> i32 {
+    let own_pawns = game.pieces(side, Piece::Pawn);
+    let opponent_pawns = game.pieces(side.opposite(), Piece::Pawn);
+    let mut score = 0;
+    for rook in game.pieces(side, Piece::Rock) {
+        let file = BitBoard::from(rook.file());
+        if (own_pawns & file).is_empty() {
+            score += if (opponent_pawns & file).is_empty() { config.rook_open_file } else { config.rook_half_open_file };
+        }
+    }
+    if game.pieces(side, Piece::Bishop).count() >= 2 {
+        score += config.bishop_pair;
+    }
+    score
+}
+
+/// Scores the own pawns on the three squares in front of the king, which matter less as
+/// material comes off the board.
+fn king_shield(game: &Game, config: &EvalConfig, side: Color, phase: i32) -> i32 {
+    let Some(king) = game.king_square(side) else {
+        return 0;
+    };
+    let shield_rank = match (side, usize::from(king.rank())) {
+        (Color::White, rank) if rank < 7 => rank + 1,
+        (Color::Black, rank) if rank > 0 => rank - 1,
+        _ => return 0,
+    };
+    let Ok(shield_rank) = Rank::try_from(shield_rank) else {
+        return 0;
+    };
+    let shield = adjacent_files(king.file()) | BitBoard::from(king.file());
+    let pawns = (game.pieces(side, Piece::Pawn) & shield & BitBoard::from(shield_rank)).count() as i32;
+    config.king_shield * pawns * phase / MAX_PHASE
+}
+
+/// Scores doubled, isolated and passed pawns.
+fn pawn_structure(game: &Game, config: &EvalConfig, side: Color) -> i32 {
+    let pawns = game.pieces(side, Piece::Pawn);
+    let opponents = game.pieces(side.opposite(), Piece::Pawn);
+    let mut score = 0;
+    for file in (0..8).filter_map(|idx| File::try_from(idx).ok()) {
+        let count = (pawns & BitBoard::from(file)).count() as i32;
+        if count > 1 {
+            score -= config.doubled_pawn * (count - 1);
+        }
+        if count > 0 && (pawns & adjacent_files(file)).is_empty() {
+            score -= config.isolated_pawn * count;
+        }
+    }
+    for square in pawns {
+        let blockers = opponents & (adjacent_files(square.file()) | BitBoard::from(square.file()));
+        let rank = usize::from(square.rank());
+        let is_passed = blockers.into_iter().all(|blocker| match side {
+            Color::White => usize::from(blocker.rank()) <= rank,
+            Color::Black => usize::from(blocker.rank()) >= rank,
+        });
+        if is_passed {
+            score += config.passed_pawn;
+        }
+    }
+    score
+}
+
+/// Returns the files left and right of `file`.
+fn adjacent_files(file: File) -> BitBoard {
+    let idx = usize::from(file);
+    [idx.checked_sub(1), Some(idx + 1)].into_iter().flatten()
+        .filter_map(|idx| File::try_from(idx).ok())
+        .fold(BitBoard::empty(), |files, file| files | BitBoard::from(file))
+}
+
+/// Looks up the piece-square bonus of a piece.
+///
+/// # Arguments
+/// - `config`: The weights holding the piece-square tables.
+/// - `piece`: The `Piece` type.
+/// - `side`: The `Color` of the piece; Black squares are mirrored vertically.
+/// - `square`: The `Square` the piece stands on.
+/// - `phase`: The game phase, used to blend the king tables.
+fn square_bonus(config: &EvalConfig, piece: Piece, side: Color, square: Square, phase: i32) -> i32 {
+    let [file, rank] = [usize::from(square.file()), usize::from(square.rank())];
+    let idx = match side {
+        Color::White => (7 - rank) * 8 + file,
+        Color::Black => rank * 8 + file,
+    };
+    let bonus = config.piece_square[usize::from(piece)][idx];
+    match piece {
+        Piece::King => (bonus * phase + config.king_endgame[idx] * (MAX_PHASE - phase)) / MAX_PHASE,
+        _ => bonus,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_start_position_is_balanced() {
+        let game = Game::new();
+        assert_eq!(evaluate(&game, Color::White), 0);
+        assert_eq!(evaluate(&game, Color::Black), 0);
+    }
+
+    #[test]
+    fn test_evaluate_central_pawn_push_favours_mover() {
+        let mut game = Game::new();
+        game.play(Square::new(File::E, Rank::Two), Square::new(File::E, Rank::Four)).unwrap();
+        assert!(evaluate(&game, Color::White) > 0);
+        assert_eq!(evaluate(&game, Color::White), -evaluate(&game, Color::Black));
+    }
+
+    #[test]
+    fn test_game_phase() {
+        assert_eq!(game_phase(&Game::new()), GamePhase::Opening);
+        let middlegame = Game::from_fen("r1bq1rk1/pp3ppp/2n2n2/3p4/3P4/2N2N2/PP3PPP/R1BQ1RK1 w - - 0 14").unwrap();
+        assert_eq!(game_phase(&middlegame), GamePhase::Middlegame);
+        let endgame = Game::from_fen("8/5pk1/8/8/8/8/5PK1/3R4 w - - 0 5").unwrap();
+        assert_eq!(game_phase(&endgame), GamePhase::Endgame);
+    }
+
+    #[test]
+    fn test_pawn_structure() {
+        // White: doubled, isolated c-pawns held back by the d4 pawn; Black: an isolated d-pawn
+        // held back by the c3 pawn; then a lone passed h-pawn
+        let game = Game::from_fen("4k3/8/8/8/3p4/2P5/2P5/4K3 w - - 0 1").unwrap();
+        let config = EvalConfig::default();
+        assert_eq!(pawn_structure(&game, &config, Color::White), -config.doubled_pawn - 2 * config.isolated_pawn);
+        assert_eq!(pawn_structure(&game, &config, Color::Black), -config.isolated_pawn);
+        let game = Game::from_fen("4k3/8/7p/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(pawn_structure(&game, &config, Color::Black), config.passed_pawn - config.isolated_pawn);
+    }
+
+    #[test]
+    fn test_piece_activity() {
+        // White: a rook on the open d-file, one on the half-open e-file and both bishops;
+        // Black: a rook behind its own pawn and a single bishop
+        let game = Game::from_fen("r3kb2/p7/8/8/8/4p3/8/2BRRBK1 w - - 0 1").unwrap();
+        let config = EvalConfig::default();
+        let white = config.rook_open_file + config.rook_half_open_file + config.bishop_pair;
+        assert_eq!(piece_activity(&game, &config, Color::White), white);
+        assert_eq!(piece_activity(&game, &config, Color::Black), 0);
+        assert_eq!(game.mobility(Color::White, Piece::Rock), 7 + 2);
+    }
+
+    #[test]
+    fn test_king_tables_blend_by_phase() {
+        let e1 = Square::new(File::E, Rank::One);
+        let e4 = Square::new(File::E, Rank::Four);
+        let config = EvalConfig::default();
+        assert_eq!(square_bonus(&config, Piece::King, Color::White, e1, MAX_PHASE), 0);
+        assert_eq!(square_bonus(&config, Piece::King, Color::White, e4, MAX_PHASE), -40);
+        assert_eq!(square_bonus(&config, Piece::King, Color::White, e4, 0), 40);
+        assert_eq!(square_bonus(&config, Piece::King, Color::Black, Square::new(File::E, Rank::Five), 0), 40);
+    }
+}
@@ -0,0 +1,262 @@
+use std::fs;
+use serde::Deserialize;
+use super::{
+    BISHOP_TABLE, KING_ENDGAME_TABLE, KING_MIDDLEGAME_TABLE, KNIGHT_TABLE, PAWN_TABLE, PIECE_VALUES, QUEEN_TABLE,
+    ROCK_TABLE,
+};
+
+/// The weights of the evaluation terms, in centipawns. Changing them gives the engine a
+/// different personality without recompiling, e.g. a higher mobility weight for an active
+/// style or a higher king shield weight for a cautious one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EvalConfig {
+    /// Material value of each piece, indexed by `usize::from(Piece)`.
+    pub piece_values: [i32; 6],
+    /// Bonus for each move or capture of a piece, ignoring checks, indexed by `usize::from(Piece)`.
+    /// Knights and bishops get more per move since they have fewer moves than rooks and queens.
+    pub mobility: [i32; 6],
+    /// Bonus for each own pawn on the three squares in front of the king, scaled down as
+    /// material comes off the board.
+    pub king_shield: i32,
+    /// Penalty for each pawn standing on a file behind another pawn of the same side.
+    pub doubled_pawn: i32,
+    /// Penalty for each pawn without own pawns on the adjacent files.
+    pub isolated_pawn: i32,
+    /// Bonus for each pawn without opposing pawns in front of it on its own or adjacent files.
+    pub passed_pawn: i32,
+    /// Bonus for each rook on a file without pawns.
+    pub rook_open_file: i32,
+    /// Bonus for each rook on a file with opposing pawns only.
+    pub rook_half_open_file: i32,
+    /// Bonus for keeping both bishops.
+    pub bishop_pair: i32,
+    /// Bonus of each piece per square, indexed by `usize::from(Piece)` and then by square from
+    /// White's point of view, a8 first and h1 last; Black's squares are mirrored. The king's
+    /// table is the middlegame one.
+    pub piece_square: [[i32; 64]; 6],
+    /// Bonus of the king per square in the endgame, blended with its middlegame table by the
+    /// material left on the board.
+    pub king_endgame: [i32; 64],
+}
+
+impl Default for EvalConfig {
+    fn default() -> Self {
+        Self {
+            piece_values: PIECE_VALUES,
+            mobility: [0, 4, 2, 3, 1, 0],
+            king_shield: 10,
+            doubled_pawn: 15,
+            isolated_pawn: 10,
+            passed_pawn: 20,
+            rook_open_file: 25,
+            rook_half_open_file: 10,
+            bishop_pair: 30,
+            piece_square: [PAWN_TABLE, KNIGHT_TABLE, ROCK_TABLE, BISHOP_TABLE, QUEEN_TABLE, KING_MIDDLEGAME_TABLE],
+            king_endgame: KING_ENDGAME_TABLE,
+        }
+    }
+}
+
+/// The config file layout; every entry is optional and falls back to the default weights.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct EvalFile {
+    king_shield: Option<i32>,
+    doubled_pawn: Option<i32>,
+    isolated_pawn: Option<i32>,
+    passed_pawn: Option<i32>,
+    rook_open_file: Option<i32>,
+    rook_half_open_file: Option<i32>,
+    bishop_pair: Option<i32>,
+    #[serde(default)]
+    material: PieceWeightsFile,
+    #[serde(default)]
+    mobility: PieceWeightsFile,
+    #[serde(default)]
+    piece_square: PieceSquareFile,
+}
+
+/// A weight per piece type; the king has none since its value and moves are not scored.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct PieceWeightsFile {
+    pawn: Option<i32>,
+    knight: Option<i32>,
+    rook: Option<i32>,
+    bishop: Option<i32>,
+    queen: Option<i32>,
+}
+
+impl PieceWeightsFile {
+    /// Returns the entries indexed by `usize::from(Piece)`, the king excluded.
+    fn by_piece(&self) -> [Option<i32>; 5] {
+        [self.pawn, self.knight, self.rook, self.bishop, self.queen]
+    }
+}
+
+/// A piece-square table per piece type, each 64 entries from a8 to h1.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct PieceSquareFile {
+    pawn: Option<Vec<i32>>,
+    knight: Option<Vec<i32>>,
+    rook: Option<Vec<i32>>,
+    bishop: Option<Vec<i32>>,
+    queen: Option<Vec<i32>>,
+    king: Option<Vec<i32>>,
+    king_endgame: Option<Vec<i32>>,
+}
+
+impl PieceSquareFile {
+    /// Returns the tables in the order of [`TABLE_NAMES`].
+    fn tables(self) -> [Option<Vec<i32>>; 7] {
+        [self.pawn, self.knight, self.rook, self.bishop, self.queen, self.king, self.king_endgame]
+    }
+}
+
+impl EvalConfig {
+    /// Loads the weights from a TOML file.
+    ///
+    /// # Arguments
+    /// - `path`: The config file, e.g. `eval/aggressive.toml`.
+    ///
+    /// # Returns
+    /// - `Ok(EvalConfig)`: The default weights with the entries of the file applied.
+    /// - `Err(String)`: If the file cannot be read or parsed, or a piece value is not positive.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|err| format!("Cannot read evaluation config {}: {}", path, err))?;
+        Self::parse(&content).map_err(|err| format!("Invalid evaluation config {}: {}", path, err))
+    }
+
+    /// Parses the weights from the content of a TOML file, e.g.
+    ///
+    /// ```toml
+    /// passed_pawn = 40
+    ///
+    /// [material]
+    /// bishop = 350
+    ///
+    /// [mobility]
+    /// knight = 6
+    ///
+    /// [piece_square]
+    /// knight = [-50, -40, ...] # 64 entries, a8 to h1
+    /// ```
+    ///
+    /// # Returns
+    /// - `Ok(EvalConfig)`: The default weights with the given entries applied.
+    /// - `Err(String)`: If the content is not valid, a piece value is not positive or a
+    ///   piece-square table does not have 64 entries.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let file: EvalFile = toml::from_str(content).map_err(|err| err.message().to_string())?;
+        let mut config = EvalConfig::default();
+        for (piece_idx, value) in file.material.by_piece().into_iter().enumerate() {
+            match value {
+                Some(value) if value <= 0 => return Err(format!("piece values must be positive, got {}.", value)),
+                Some(value) => config.piece_values[piece_idx] = value,
+                None => {}
+            }
+        }
+        for (piece_idx, value) in file.mobility.by_piece().into_iter().enumerate() {
+            if let Some(value) = value {
+                config.mobility[piece_idx] = value;
+            }
+        }
+        for (idx, (name, table)) in TABLE_NAMES.iter().zip(file.piece_square.tables()).enumerate() {
+            let Some(table) = table else {
+                continue;
+            };
+            let table = <[i32; 64]>::try_from(table)
+                .map_err(|table| format!("the {} piece-square table must have 64 entries, got {}.", name, table.len()))?;
+            *config.table_mut(idx) = table;
+        }
+        let weights = [
+            (&mut config.king_shield, file.king_shield),
+            (&mut config.doubled_pawn, file.doubled_pawn),
+            (&mut config.isolated_pawn, file.isolated_pawn),
+            (&mut config.passed_pawn, file.passed_pawn),
+            (&mut config.rook_open_file, file.rook_open_file),
+            (&mut config.rook_half_open_file, file.rook_half_open_file),
+            (&mut config.bishop_pair, file.bishop_pair),
+        ];
+        for (weight, value) in weights {
+            if let Some(value) = value {
+                *weight = value;
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl EvalConfig {
+    /// Writes the weights in the layout read by [`EvalConfig::parse`], every entry included.
+    pub fn to_toml(&self) -> String {
+        let scalars = [
+            ("king_shield", self.king_shield),
+            ("doubled_pawn", self.doubled_pawn),
+            ("isolated_pawn", self.isolated_pawn),
+            ("passed_pawn", self.passed_pawn),
+            ("rook_open_file", self.rook_open_file),
+            ("rook_half_open_file", self.rook_half_open_file),
+            ("bishop_pair", self.bishop_pair),
+        ];
+        let mut toml = scalars.iter().map(|(name, value)| format!("{} = {}\n", name, value)).collect::<String>();
+        for (table, weights) in [("material", &self.piece_values), ("mobility", &self.mobility)] {
+            toml.push_str(&format!("\n[{}]\n", table));
+            for (name, value) in PIECE_NAMES.iter().zip(weights) {
+                toml.push_str(&format!("{} = {}\n", name, value));
+            }
+        }
+        toml.push_str("\n[piece_square]\n");
+        for (name, table) in TABLE_NAMES.iter().zip(self.piece_square.iter().chain([&self.king_endgame])) {
+            let rows = table.chunks(8)
+                .map(|row| row.iter().map(|value| format!("{:>4}", value)).collect::<Vec<_>>().join(","))
+                .collect::<Vec<_>>();
+            toml.push_str(&format!("{} = [\n{},\n]\n", name, rows.join(",\n")));
+        }
+        toml
+    }
+
+    /// Returns a piece-square table by its index in [`TABLE_NAMES`].
+    pub(crate) fn table_mut(&mut self, idx: usize) -> &mut [i32; 64] {
+        match self.piece_square.get_mut(idx) {
+            Some(table) => table,
+            None => &mut self.king_endgame,
+        }
+    }
+}
+
+/// The names of the per-piece entries of the config file, indexed by `usize::from(Piece)`.
+const PIECE_NAMES: [&str; 5] = ["pawn", "knight", "rook", "bishop", "queen"];
+
+/// The names of the piece-square tables in the config file: one per piece type, indexed by
+/// `usize::from(Piece)`, then the king's endgame table.
+const TABLE_NAMES: [&str; 7] = ["pawn", "knight", "rook", "bishop", "queen", "king", "king_endgame"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_overrides_only_given_entries() {
+        let config = EvalConfig::parse("bishop_pair = 50\n\n[material]\nbishop = 350\n\n[mobility]\nrook = 3\n").unwrap();
+        assert_eq!((config.bishop_pair, config.mobility), (50, [0, 4, 3, 3, 1, 0]));
+        assert_eq!(config.piece_values, [100, 320, 500, 350, 900, 0]);
+        assert_eq!(config.passed_pawn, EvalConfig::default().passed_pawn);
+        assert!(EvalConfig::parse("[material]\nqueen = 0\n").is_err());
+        assert!(EvalConfig::parse("aggression = 3\n").is_err());
+        let Err(err) = EvalConfig::parse("[piece_square]\npawn = [0, 5]\n") else {
+            panic!("a table needs 64 entries");
+        };
+        assert_eq!(err, "the pawn piece-square table must have 64 entries, got 2.");
+        assert!(EvalConfig::parse(include_str!("../../../eval/aggressive.toml")).is_ok());
+    }
+
+    #[test]
+    fn test_to_toml_round_trips() {
+        let mut config = EvalConfig { passed_pawn: 42, piece_values: [100, 310, 520, 340, 950, 0], ..EvalConfig::default() };
+        config.piece_square[1][27] = 33;
+        config.king_endgame[0] = -70;
+        assert_eq!(EvalConfig::parse(&config.to_toml()), Ok(config));
+    }
+}
@@ -1,23 +1,194 @@
+mod annotation;
+mod atomic;
+mod castling;
+mod codec;
+#[cfg(debug_assertions)]
+mod consistency;
+mod error;
+mod event_log;
+mod explain;
+mod fen;
+mod history;
+mod incremental;
+mod legal;
+mod moves;
+mod outcome;
+mod patterns;
+mod peer;
+mod perft;
+mod perft_suite;
+mod pins;
+mod plausibility;
+mod ponder;
+mod puzzle;
+#[cfg(test)]
+mod reference;
+mod san;
+mod save;
+mod setup;
+mod snapshot;
+mod tactics;
+mod threat;
+mod timing;
+mod tutorial;
+mod validation;
+mod variant;
+mod zobrist;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use crate::bitboard::BitBoard;
+use crate::crash;
+use crate::engine::{eval, search, tablebase, tt};
+use crate::engine::search::Score;
+use crate::engine::board::Board;
+use crate::engine::clock::{Clock, MoveTiming, TimeControl};
+use crate::engine::eval::{EvalConfig, GamePhase};
+use crate::engine::skill::{SkillLevel, WeakenedEngine};
+use crate::engine::observer::{MoveEvent, MoveObserver};
+use crate::engine::options::EngineOption;
+use crate::gui::action::Action;
 use crate::gui::cmd::CommandPromptGUI;
+use crate::gui::display::{DisplayOptions, PieceVisibility};
+use crate::gui::error::GuiError;
+use crate::gui::eval_bar::{EvalBar, EVAL_BAR_DEPTH};
+use crate::gui::highlight::Highlight;
+use crate::gui::image;
+use crate::gui::input::CommandSender;
+use crate::gui::preferences::Notation;
+use crate::gui::search_info::SearchInfoPrinter;
+use crate::gui::status::{CapturedPieces, GameStatus, StatusLine};
+use crate::gui::theme::Theme;
+use crate::net::peer::PeerConnection;
 use crate::pieces::common::{Color};
 use crate::pieces::Piece;
 use crate::square::{File, Rank, Square};
+use history::MoveRecord;
+use moves::PROMOTION_PIECES;
+use ponder::Ponder;
+use threat::ThreatBoard;
+
+pub use annotation::Nag;
+pub(crate) use annotation::MoveAnnotation;
+pub use castling::CastlingRights;
+pub use error::MoveError;
+pub use event_log::{EventLog, GameEvent};
+pub use explain::IllegalReason;
+pub use moves::{Move, MoveFlag, MoveList, MAX_MOVES};
+pub use perft_suite::{parse_perft_suite, PerftCase, PerftMismatch};
+pub use pins::Direction;
+pub use puzzle::{parse_puzzles, Puzzle, MAX_MATE_MOVES};
+pub use snapshot::GameSnapshot;
+pub use variant::Variant;
 
-#[derive(Debug)]
+pub use codec::{PackedMove, PackedPosition};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum GameResult {
     Checkmate(Color),
     Draw,
+    DrawByAgreement,
+    Resignation(Color),
+    /// The given side ran out of time.
+    Timeout(Color),
+    /// The given side ran out of time, but its opponent has too little material to mate.
+    TimeoutDraw(Color),
+    /// The king of the given side was blown up by an Atomic capture.
+    KingExploded(Color),
+}
+
+impl fmt::Display for GameResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameResult::Checkmate(loser) => write!(f, "Checkmate, {:?} wins", loser.opposite()),
+            GameResult::Draw => write!(f, "Draw"),
+            GameResult::DrawByAgreement => write!(f, "Draw by agreement"),
+            GameResult::Resignation(loser) => write!(f, "{:?} resigns, {:?} wins", loser, loser.opposite()),
+            GameResult::Timeout(loser) => write!(f, "{:?} lost on time, {:?} wins", loser, loser.opposite()),
+            GameResult::TimeoutDraw(loser) => write!(f, "{:?} ran out of time, draw by insufficient material", loser),
+            GameResult::KingExploded(loser) => write!(f, "{:?}'s king exploded, {:?} wins", loser, loser.opposite()),
+        }
+    }
+}
+
+/// Number of plies shown in the move list of the engine match view.
+const MATCH_MOVES_SHOWN: usize = 10;
+
+/// How long a player may take to pick the promotion piece in a timed game before it becomes a queen.
+pub const DEFAULT_PROMOTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Who chooses the moves for one side of the board.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PlayerKind {
+    /// Moves are read from the command prompt.
+    Human,
+    /// Moves are chosen by the search, looking `depth` plies ahead.
+    Engine { depth: u8 },
+    /// Moves are received from the game of a peer over the network.
+    Remote,
 }
 
-pub(crate) struct Game {
+pub struct Game {
     gui: CommandPromptGUI,
     pieces_location: [[BitBoard; 6]; 2],
-    pieces_square: [[Vec<Square>; 6]; 2],
+    /// The squares held by each side, kept in step with the location masks so that move
+    /// generation does not combine six boards on each query.
+    occupancy: [BitBoard; 2],
     pieces_capture_movement: [[BitBoard; 6]; 2],
     pieces_movement: [[BitBoard; 6]; 2],
-    castling_rights: [[bool; 2]; 2],
+    /// The number of moves and captures of each piece type, ignoring checks and counted piece
+    /// by piece, so that squares reached by two pieces count twice. Kept up to date with the masks.
+    pieces_mobility: [[u32; 6]; 2],
+    threats: ThreatBoard,
+    castling_rights: CastlingRights,
+    en_passant: Option<Square>,
+    history: Vec<MoveRecord>,
+    redo_stack: Vec<MoveRecord>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+    players: [PlayerKind; 2],
+    draw_offer: Option<Color>,
+    declared_result: Option<GameResult>,
+    peer: Option<PeerConnection>,
+    observers: Vec<Box<dyn MoveObserver>>,
+    /// The snapshot spectators read, replaced after every move.
+    spectators: Option<Arc<RwLock<GameSnapshot>>>,
+    turn_started: Instant,
+    clock: Option<Clock>,
+    tactic_alerts: bool,
+    eval_config: EvalConfig,
+    search_info: bool,
+    pondering: bool,
+    ponder: Option<Ponder>,
+    event_log: Option<EventLog>,
+    variant: Variant,
+    /// The pieces each player sees on the board, indexed by side.
+    blindfold: [PieceVisibility; 2],
+    /// How long each move of the history took, indexed like it; `None` for untimed moves.
+    move_timings: Vec<Option<MoveTiming>>,
+    /// The comments and annotations of the moves of the history, indexed like it.
+    annotations: Vec<MoveAnnotation>,
+    /// Plays the computer's moves instead of the full-strength search when a skill level is set.
+    weakened: Option<WeakenedEngine>,
+    /// How long a player may take to pick the promotion piece in a timed game.
+    promotion_timeout: Duration,
+    /// Pause before each move of an engine match, so that the game can be followed.
+    move_delay: Duration,
+    /// Centipawns the engine gives up to avoid a draw; negative to seek one.
+    contempt: i32,
+    /// Index bits of the transposition tables of the engine's searches.
+    hash_bits: u32,
+    /// How moves are written in the last move line and the move history.
+    notation: Notation,
+    /// Whether the evaluation bar is drawn beside the board.
+    eval_bar: bool,
+    /// The score of the last position searched for the evaluation bar, with its Zobrist hash,
+    /// so that commands that do not move are not searched again.
+    eval_bar_score: Option<(u64, Score)>,
     turn: Color
 }
 
@@ -31,34 +202,41 @@ impl Game {
     ///
     /// # Returns
     /// - `Ok(Piece::King)`: If the castling move is valid.
-    /// - `Err(String)`: If the castling move is invalid, returns an error message explaining the reason.
-    fn validate_castling(&self, from: Square, to: Square) -> Result<Piece, String> {
+    /// - `Err(MoveError)`: If the castling move is invalid, the reason why.
+    fn validate_castling(&self, from: Square, to: Square) -> Result<Piece, MoveError> {
         let rank = from.rank();
-        let side_idx = usize::from(self.turn);
-        let opponent_side_idx = usize::from(self.turn.opposite());
         let king_side = to.file() == File::G;
         let queen_side = to.file() == File::C;
 
-        if king_side && !self.castling_rights[side_idx][0] {
-            return Err("King-side castling is not allowed.".to_string());
+        if king_side && !self.castling_rights.has(self.turn, true) {
+            return Err(MoveError::CastlingRightLost { king_side: true });
         }
-        if queen_side && !self.castling_rights[side_idx][1] {
-            return Err("Queen-side castling is not allowed.".to_string());
+        if queen_side && !self.castling_rights.has(self.turn, false) {
+            return Err(MoveError::CastlingRightLost { king_side: false });
         }
         let square_to_validate = if king_side{
             BitBoard::new(0x6000000000000060)
         } else {
             BitBoard::new(0xe0000000000000e)
         } & BitBoard::from(rank);
-        let pieces = Self::combine(&self.pieces_location[side_idx]) | Self::combine(&self.pieces_location[opponent_side_idx]);
-        let attacked = Self::combine(&self.pieces_capture_movement[opponent_side_idx]);
+        // the king crosses only the c and d files, b only has to be empty
+        let square_to_cross = if king_side{
+            BitBoard::new(0x6000000000000060)
+        } else {
+            BitBoard::new(0xc0000000000000c)
+        } & BitBoard::from(rank);
+        let pieces = self.occupied();
+        let attacked = self.threats.attacked(self.turn.opposite());
+        if !(attacked & BitBoard::from(from)).is_empty() {
+            return Err(MoveError::CastlingOutOfCheck);
+        }
         let is_castle_blocked = !(pieces & square_to_validate).is_empty();
         if is_castle_blocked{
-            return Err("Castle blocked.".to_string());
+            return Err(MoveError::CastlingBlocked);
         }
-        let is_castle_attacked = !(attacked & square_to_validate).is_empty();
+        let is_castle_attacked = !(attacked & square_to_cross).is_empty();
         if is_castle_attacked{
-            return Err("Castle attacked.".to_string());
+            return Err(MoveError::CastlingThroughCheck);
         }
         Ok(Piece::King)
     }
@@ -71,25 +249,28 @@ impl Game {
     ///
     /// # Returns
     /// - `Ok(Piece)`: If the move is valid, returns the `Piece` being moved.
-    /// - `Err(String)`: If the move is invalid, returns an error message explaining why.
-    fn validate_move(&self, from: Square, to: Square) -> Result<Piece, String>{
-        let [_, bit_to] = [BitBoard::from(from), BitBoard::from(to)];
-        let piece = self.get_piece_by_location(self.turn, from);
-        match piece {
-            None =>  Err(format!("Piece doesn't exist in square {:?}", from)),
-            Some(piece) => {
-                if piece == Piece::King && (to == Square::new(File::G, from.rank()) || to == Square::new(File::C, from.rank())) {
-                    return self.validate_castling(from, to);
-                }
-                let (legal_movement, legal_capture) = self.compute_attack_threat_and_move_to_given(from, piece, self.turn);
-                let is_inside_legal_moves = !((legal_movement | legal_capture) & bit_to).is_empty();
-                if !is_inside_legal_moves{
-                    Err(format!("{:?} in square {:?} is not inside legal moves.", piece, from))
-                } else {
-                    Ok(piece)
-                }
-            }
+    /// - `Err(MoveError)`: If the move is invalid, the reason why.
+    #[tracing::instrument(level = "debug", skip_all, fields(%from, %to, turn = ?self.turn), err(level = "debug", Display))]
+    fn validate_move(&self, from: Square, to: Square) -> Result<Piece, MoveError>{
+        let piece = match self.piece_at(from) {
+            None => return Err(MoveError::NoPieceAtSource(from)),
+            Some((_, side)) if side != self.turn => return Err(MoveError::NotYourPiece(from)),
+            Some((piece, _)) => piece,
+        };
+        let is_castling = self.is_castling_move(from, to, piece);
+        if is_castling {
+            self.validate_castling(from, to)?;
+        }
+        if !self.legal_moves(from).contains(&to) {
+            return Err(if is_castling {
+                MoveError::CastlingThroughCheck
+            } else if self.reaches_ignoring_checks(from, to) {
+                MoveError::LeavesKingInCheck { piece, from, to }
+            } else {
+                MoveError::IllegalTarget { piece, from, to }
+            });
         }
+        Ok(piece)
     }
 
     /// Creates a new instance of the `Game` struct and initializes the game state.
@@ -100,16 +281,48 @@ impl Game {
         let pieces_location = Self::start_position_mask();
         let pieces_capture_movement = [[BitBoard::empty(); 6]; 2];
         let pieces_movement = [[BitBoard::empty(); 6]; 2];
-        let pieces_square = Self::start_position();
         let gui = CommandPromptGUI::new();
-        let castling_rights = [[true; 2]; 2];
+        let castling_rights = CastlingRights::ALL;
         let mut game = Self {
             gui,
             pieces_location,
+            occupancy: [BitBoard::empty(); 2],
             pieces_movement,
             pieces_capture_movement,
-            pieces_square,
+            pieces_mobility: [[0; 6]; 2],
+            threats: ThreatBoard::new(),
             castling_rights,
+            en_passant: None,
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            players: [PlayerKind::Human; 2],
+            draw_offer: None,
+            declared_result: None,
+            peer: None,
+            observers: Vec::new(),
+            spectators: None,
+            turn_started: Instant::now(),
+            clock: None,
+            tactic_alerts: false,
+            eval_config: EvalConfig::default(),
+            search_info: false,
+            pondering: false,
+            ponder: None,
+            event_log: None,
+            variant: Variant::Standard,
+            blindfold: [PieceVisibility::All; 2],
+            move_timings: Vec::new(),
+            annotations: Vec::new(),
+            weakened: None,
+            promotion_timeout: DEFAULT_PROMOTION_TIMEOUT,
+            move_delay: Duration::ZERO,
+            contempt: 0,
+            hash_bits: tt::TABLE_BITS,
+            notation: Notation::San,
+            eval_bar: false,
+            eval_bar_score: None,
             turn: Color::White
         };
         game.compute_attack_threat_and_move();
@@ -118,89 +331,935 @@ impl Game {
 
     /// Starts the main game loop, handling rendering, user input, and game state updates.
     pub fn start(&mut self){
-        let mut board_position = self.get_all_position();
+        let _span = tracing::info_span!("game").entered();
+        let mut highlights = Vec::new();
+        let mut evaluations = Vec::new();
+        let mut last_depth = None;
+        let is_engine_match = self.players.iter().all(|player| matches!(player, PlayerKind::Engine { .. }));
+        self.turn_started = Instant::now();
         loop{
-            self.gui.render(&board_position, self.turn);
+            self.record_crash_context();
+            self.check_flag();
+            self.publish_snapshot();
+            self.gui.run_clock(self.clock.map(|clock| (clock, self.turn, self.turn_started)));
+            let last_move = self.last_move().zip(self.last_move_san()).map(|(mv, san)| (mv.from, mv.to, san));
+            self.gui.show_last_move(last_move);
+            self.gui.show_move_time(self.move_time_summary());
+            self.gui.show_status_line(Some(self.status_line()));
+            self.gui.show_captured(Some(self.captured_pieces()));
+            let eval_bar = self.eval_bar.then(|| EvalBar::new(self.eval_bar_score(), self.turn));
+            self.gui.show_eval_bar(eval_bar);
+            let drawn = if is_engine_match {
+                let last_moves = self.move_list_tail(MATCH_MOVES_SHOWN);
+                self.gui.render_match(&self.get_all_position(), self.turn, &last_moves, &evaluations, last_depth, self.status())
+            } else {
+                self.gui.render(&self.get_all_position(), self.turn, &highlights, self.status(), self.display_options())
+            };
+            if let Err(err) = drawn {
+                tracing::error!(%err, "cannot draw the board");
+                break;
+            }
+            highlights.clear();
+            if self.has_draw_offer() && self.declared_result.is_none() {
+                println!();
+                println!("{:?} offers a draw: type accept or decline, or play a move to decline.", self.turn.opposite());
+            }
             if let Some(result) = self.game_result() {
-                println!("Game result: {:?}", result);
+                if let (GameResult::Checkmate(_), Some(pattern)) = (&result, self.mate_pattern()) {
+                    println!("Mating pattern: {}", pattern);
+                }
+                tracing::info!(%result, plies = self.history.len(), "game over");
                 break;
             }
-            if let Some((from, to)) = self.gui.wait_and_process_event() {
-                match self.validate_move(from, to) {
-                    Err(err) =>  println!("{}", err),
-                    Ok(piece) => {
-                        match self.try_update_state(from, to, piece, self.turn) {
-                            Err(err) => println!("{}", err),
-                            Ok(values) => {
-                                for (_from, _to) in values {
-                                    board_position[usize::from(from)] = None;
-                                    board_position[usize::from(to)] = Some((piece, self.turn));
-                                }
-                                self.turn = self.turn.opposite();
-                            }
+            if self.players[usize::from(self.turn)] == PlayerKind::Remote {
+                println!(" Waiting for the peer's move...");
+                let plies = self.history.len();
+                if let Err(err) = self.receive_peer_move() {
+                    println!("{}", err);
+                    break;
+                }
+                if let Some(mv) = self.last_move().filter(|_| self.history.len() > plies) {
+                    self.log_event(GameEvent::Move { side: self.turn.opposite(), from: mv.from, to: mv.to, promotion: mv.promotion });
+                    self.notify_move_observers();
+                }
+                continue;
+            }
+            if let PlayerKind::Engine { depth } = self.players[usize::from(self.turn)] {
+                if self.has_draw_offer() {
+                    self.answer_draw_offer();
+                    continue;
+                }
+                if is_engine_match {
+                    std::thread::sleep(self.move_delay);
+                }
+                let weakened = self.weakened.take();
+                let searched_depth = weakened.as_ref().map_or(depth, |engine| engine.level().depth());
+                let pv = match weakened {
+                    Some(mut engine) => {
+                        let pv = engine.choose_move(self).into_iter().collect();
+                        self.weakened = Some(engine);
+                        pv
+                    }
+                    None => match self.take_ponder_hit() {
+                        Some(pv) => pv,
+                        None if self.search_info => search::search_with_observer(self, depth, &mut SearchInfoPrinter::new(std::io::stdout())),
+                        None => search::principal_variation(self, depth),
+                    },
+                };
+                match pv.first() {
+                    Some(&mv) => {
+                        tracing::debug!(side = ?self.turn, %mv, depth = searched_depth, "engine move");
+                        println!(" {}", self.move_san(mv));
+                        self.play_local(mv.from, mv.to, mv.promotion).expect("search returned an illegal move");
+                        evaluations.push(eval::evaluate(self, Color::White));
+                        last_depth = Some(searched_depth);
+                        self.start_pondering(pv.get(1).copied(), depth);
+                    }
+                    None => break,
+                }
+                continue;
+            }
+            // waiting for the player ends when the flag falls, and the next round ends the game
+            let deadline = self.clock.as_ref().map(|clock| self.turn_started + clock.flag_time(self.turn));
+            let action = if self.gui.has_cursor() {
+                let moves = self.move_map();
+                self.gui.wait_for_cursor_action(&self.get_all_position(), self.turn, self.status(), &moves, self.display_options(), deadline)
+            } else {
+                self.gui.wait_for_action(deadline)
+            };
+            let action = match action {
+                Ok(None) => continue,
+                Ok(action) => action,
+                // piped commands ran out: nobody is left to play this side
+                Err(GuiError::EndOfInput) if self.peer.is_some() => {
+                    println!("The input was closed, leaving the game.");
+                    break;
+                }
+                Err(GuiError::EndOfInput) => {
+                    println!("The input was closed, {:?} resigns.", self.turn);
+                    self.log_event(GameEvent::Resign { side: self.turn });
+                    self.resign();
+                    continue;
+                }
+                Err(err) => {
+                    tracing::error!(%err, "cannot read the next command");
+                    break;
+                }
+            };
+            tracing::debug!(side = ?self.turn, ?action, "player action");
+            // the position the engine ponders on can no longer be reached
+            if matches!(action, Some(Action::Undo | Action::Redo | Action::Goto(_) | Action::Setup | Action::Load(_) | Action::ReplayLog(_))) {
+                self.stop_pondering();
+            }
+            // what the prompt prints itself; a failed write ends the game like a failed render
+            let mut written = Ok(());
+            match action {
+                Some(Action::Move(from, to)) => {
+                    if let Err(err) = self.play_human(from, to, None) {
+                        self.log_event(GameEvent::Rejected { side: self.turn, from: Some(from), to: Some(to), san: None, reason: err.to_string() });
+                        println!("{}", self.explain_refusal(from, to, err));
+                    }
+                }
+                Some(Action::San(san)) => match self.resolve_san(&san) {
+                    Ok((from, to)) => {
+                        if let Err(err) = self.play_human(from, to, san.promotion()) {
+                            self.log_event(GameEvent::Rejected { side: self.turn, from: Some(from), to: Some(to), san: None, reason: err.to_string() });
+                            println!("{}", self.explain_refusal(from, to, err));
+                        }
+                    }
+                    Err(err) => {
+                        self.log_event(GameEvent::Rejected { side: self.turn, from: None, to: None, san: Some(san.to_string()), reason: err.to_string() });
+                        println!("{}", err);
+                    }
+                },
+                Some(
+                    Action::OfferDraw | Action::AcceptDraw | Action::DeclineDraw | Action::Resign | Action::Undo | Action::Redo
+                    | Action::Setup | Action::Save(_) | Action::Load(_) | Action::ReplayLog(_) | Action::Goto(_)
+                ) if self.peer.is_some() => println!("This command is not available in network games."),
+                Some(Action::OfferDraw) => match self.offer_draw() {
+                    Ok(()) => {
+                        self.log_event(GameEvent::OfferDraw { side: self.turn });
+                        println!("{:?} offers a draw.", self.turn);
+                    }
+                    Err(err) => println!("{}", err),
+                },
+                Some(Action::AcceptDraw) => match self.accept_draw() {
+                    Ok(()) => self.log_event(GameEvent::AcceptDraw { side: self.turn }),
+                    Err(err) => println!("{}", err),
+                },
+                Some(Action::DeclineDraw) => match self.decline_draw() {
+                    Ok(()) => {
+                        self.log_event(GameEvent::DeclineDraw { side: self.turn });
+                        println!("{:?} declines the draw offer.", self.turn);
+                    }
+                    Err(err) => println!("{}", err),
+                },
+                Some(Action::Resign) => {
+                    self.log_event(GameEvent::Resign { side: self.turn });
+                    self.resign();
+                }
+                Some(Action::Flip) => self.gui.flip(self.turn),
+                Some(Action::Blind(pieces)) => {
+                    self.set_blindfold(self.turn, pieces);
+                    match pieces {
+                        PieceVisibility::All => println!("Blindfold off for {:?}.", self.turn),
+                        PieceVisibility::Own => println!("{:?} now only sees their own pieces.", self.turn),
+                        PieceVisibility::Hidden => println!("Blindfold on for {:?}: follow the game from the move list.", self.turn),
+                    }
+                }
+                Some(Action::AutoFlip) => {
+                    let enabled = !self.gui.is_auto_flip();
+                    self.gui.set_auto_flip(enabled);
+                    println!("Auto-flip {}.", if enabled { "on" } else { "off" });
+                }
+                Some(Action::EvalBar) => {
+                    self.eval_bar = !self.eval_bar;
+                    println!("Evaluation bar {}.", if self.eval_bar { "on" } else { "off" });
+                }
+                Some(Action::Ponder) => {
+                    self.set_pondering(!self.pondering);
+                    println!("Pondering {}.", if self.pondering { "on" } else { "off" });
+                }
+                Some(Action::StopSearch) => match self.stop_pondering() {
+                    true => println!("Stopped the engine's search."),
+                    false => println!("The engine is not searching."),
+                },
+                Some(Action::Theme(name)) => match Theme::select(&name) {
+                    Ok(theme) => {
+                        self.set_theme(theme);
+                        println!("Theme set to {}.", name);
+                    }
+                    Err(err) => println!("{}", err),
+                },
+                Some(Action::Setup) => self.edit_position(),
+                Some(Action::Save(path)) => match std::fs::write(&path, self.to_json()) {
+                    Ok(()) => println!("Game saved to {}.", path),
+                    Err(err) => println!("Could not save the game to {}: {}", path, err),
+                },
+                Some(Action::Export(path)) => match std::fs::write(&path, self.to_pgn()) {
+                    Ok(()) => println!("Game exported to {}.", path),
+                    Err(err) => println!("Could not export the game to {}: {}", path, err),
+                },
+                Some(Action::ExportImage(format, path)) => {
+                    let last_move = self.last_move().map(|mv| (mv.from, mv.to));
+                    let written = image::render(&self.get_all_position(), last_move, format)
+                        .and_then(|bytes| std::fs::write(&path, bytes).map_err(|err| err.to_string()));
+                    match written {
+                        Ok(()) => println!("Board drawn to {}.", path),
+                        Err(err) => println!("Could not draw the board to {}: {}", path, err),
+                    }
+                }
+                Some(Action::Load(path)) => {
+                    let loaded = std::fs::read_to_string(&path)
+                        .map_err(|err| format!("Could not read {}: {}", path, err))
+                        .and_then(|json| Game::from_json(&json));
+                    match loaded {
+                        Ok(loaded) => {
+                            self.resume(loaded);
+                            self.log_event(GameEvent::Load { saved_game: self.to_json() });
+                            println!("Game loaded from {}.", path);
+                        }
+                        Err(err) => println!("{}", err),
+                    }
+                }
+                Some(Action::ReplayLog(path)) => {
+                    let replayed = std::fs::read_to_string(&path)
+                        .map_err(|err| format!("Could not read {}: {}", path, err))
+                        .and_then(|text| Game::from_event_log(&text));
+                    match replayed {
+                        Ok((replayed, events)) => {
+                            // the players of this session keep their seats
+                            let players = self.players;
+                            self.resume(replayed);
+                            self.players = players;
+                            self.log_event(GameEvent::Load { saved_game: self.to_json() });
+                            println!("Replayed {} events from {}.", events, path);
                         }
+                        Err(err) => println!("{}", err),
                     }
                 }
+                Some(Action::Pack) => {
+                    let packed = self.pack();
+                    let moves = self.history.iter()
+                        .map(|record| format!("{:04x}", PackedMove::new(record.mv.from, record.mv.to, record.mv.promotion).bits()))
+                        .collect::<Vec<String>>();
+                    println!("Packed position ({} bytes): {}", packed.as_bytes().len(), packed.to_hex());
+                    println!("Packed moves: {}", moves.join(" "));
+                }
+                Some(Action::ReadBoard) => written = self.gui.read_board(&self.get_all_position(), self.turn),
+                Some(Action::ReadSquare(square)) => written = self.gui.read_square(&self.get_all_position(), square),
+                Some(Action::Undo) => {
+                    let plies = self.history.len();
+                    if !self.undo() {
+                        println!("Nothing to undo.");
+                    }
+                    // give the move back to the human rather than letting the engine replay
+                    while matches!(self.players[usize::from(self.turn)], PlayerKind::Engine { .. }) && self.undo() {}
+                    if self.history.len() < plies {
+                        self.log_event(GameEvent::Undo { plies: plies - self.history.len() });
+                    }
+                }
+                Some(Action::Redo) => {
+                    let redone = self.redo();
+                    if redone {
+                        self.log_event(GameEvent::Redo);
+                    } else {
+                        println!("Nothing to redo.");
+                    }
+                }
+                Some(Action::History) => match self.move_list().as_str() {
+                    "" => println!("No moves played yet."),
+                    moves => {
+                        println!("Moves: {}", moves);
+                        for line in self.annotation_lines() {
+                            println!("  {}", line);
+                        }
+                    }
+                },
+                Some(Action::Comment(text)) => match self.comment_last_move(&text) {
+                    Ok(()) => println!("Comment added to the last move."),
+                    Err(err) => println!("{}", err),
+                },
+                Some(Action::Annotate(ply, nag)) => match self.annotate(ply, nag) {
+                    Ok(()) => println!("Half-move {} annotated {}.", ply, nag),
+                    Err(err) => println!("{}", err),
+                },
+                Some(Action::Goto(ply)) => match self.goto(ply) {
+                    Ok(()) => {
+                        self.log_event(GameEvent::Goto { ply });
+                        println!("Now at half-move {}. Play a move to start a new line, or goto another half-move.", ply);
+                    }
+                    Err(err) => println!("{}", err),
+                },
+                Some(Action::ShowMoves(square)) => {
+                    highlights = self.move_highlights(square);
+                    let attackers = [Color::White, Color::Black].map(|side| self.attackers_of(square, side));
+                    written = self.gui.show_square_control(&self.get_all_position(), square, &attackers);
+                }
+                Some(Action::Probe) => match tablebase::prober().probe(self) {
+                    Some(probe) => println!("Tablebase: {}", probe),
+                    None => println!("The position is not in the tablebase."),
+                },
+                Some(Action::Evaluate) => {
+                    println!("Evaluation: {} (from White's perspective)", eval::evaluate(self, Color::White));
+                }
+                Some(Action::ShowOptions) => {
+                    for option in self.options() {
+                        println!("{}", option);
+                    }
+                }
+                Some(Action::SetOption(option)) => {
+                    self.set_option(option);
+                    match option {
+                        EngineOption::Skill(None) => println!("The computer plays at full strength."),
+                        EngineOption::Skill(Some(level)) => {
+                            println!("The computer plays at level {} (depth {}).", level, level.depth());
+                        }
+                        _ => {
+                            // the hash size is rounded down to a power of two of entries
+                            let current = self.options().into_iter().find(|current| current.name() == option.name());
+                            println!("Set {}.", current.unwrap_or(option));
+                        }
+                    }
+                }
+                Some(Action::SolveMate(moves)) => match self.solve_mate(moves) {
+                    Some(line) => println!("Mate in {}: {}", line.len().div_ceil(2), self.line_san(&line)),
+                    None => println!("No forced mate in {} move(s).", moves),
+                },
+                Some(Action::Preview(depth)) => {
+                    self.preview(depth.unwrap_or(search::ANALYSIS_DEPTH));
+                }
+                Some(Action::HashStats(depth)) => {
+                    let stats = search::hash_statistics(self, depth.unwrap_or(search::ANALYSIS_DEPTH));
+                    println!("Probes: {}", stats.probes);
+                    println!("Key hits: {}", stats.hits);
+                    println!(
+                        "Verified collisions: {} ({:.4}% of hits)",
+                        stats.collisions, stats.collision_rate() * 100.0
+                    );
+                }
+                Some(Action::SearchStats(depth)) => {
+                    let depth = depth.unwrap_or(search::ANALYSIS_DEPTH);
+                    let windowed = search::search_statistics(self, depth, true);
+                    let full = search::search_statistics(self, depth, false);
+                    println!(
+                        "With aspiration windows: {} nodes, {} fail-high and {} fail-low re-searches",
+                        windowed.nodes, windowed.fail_highs, windowed.fail_lows
+                    );
+                    println!("Without: {} nodes", full.nodes);
+                    let saved = 100.0 * (1.0 - windowed.nodes as f64 / full.nodes.max(1) as f64);
+                    println!("Saved: {:.1}% of the nodes", saved);
+                }
+                Some(Action::PerftDivide(depth)) => {
+                    let divide = self.perft_divide(depth);
+                    for (mv, nodes) in &divide {
+                        println!("{}: {}", mv, nodes);
+                    }
+                    println!("Nodes searched: {}", divide.iter().map(|(_, nodes)| nodes).sum::<u64>());
+                }
+                Some(Action::PerftByPiece(depth)) => {
+                    let stats = self.clone().perft_stats(depth);
+                    for piece in Piece::iter() {
+                        println!("{:?}: {}", piece, stats.by_piece[usize::from(piece)]);
+                    }
+                    println!("Captures: {}", stats.captures);
+                    println!("En passant: {}", stats.en_passant);
+                    println!("Castles: {}", stats.castles);
+                    println!("Promotions: {}", stats.promotions);
+                    println!("Nodes searched: {}", stats.nodes);
+                }
+                None => {}
+            }
+            if let Err(err) = written {
+                tracing::error!(%err, "cannot write to the terminal");
+                break;
+            }
+        }
+        let result = self.game_result();
+        for observer in self.observers.iter_mut() {
+            observer.on_game_end(result);
+        }
+    }
+
+    /// Steps through the engine's principal variation on a copy of the game, one move per
+    /// confirmation, then returns to the real position, which is never modified.
+    ///
+    /// # Arguments
+    /// - `depth`: The search depth, which is also the maximum length of the previewed line.
+    fn preview(&mut self, depth: u8) {
+        let (score, pv) = search::analyze(self, depth);
+        if pv.is_empty() {
+            println!("No legal moves to preview.");
+            return;
+        }
+        let mut board = self.clone();
+        let line = pv.iter()
+            .map(|mv| {
+                let san = board.move_san(*mv);
+                board.play_legal_promoting(mv.from, mv.to, mv.promotion);
+                san
+            })
+            .collect::<Vec<String>>();
+        println!("Principal variation ({}): {}", score, line.join(" "));
+        self.gui.show_last_move(None);
+        self.gui.show_captured(None);
+        let mut board = self.clone();
+        for (idx, mv) in pv.into_iter().enumerate() {
+            board.play_legal_promoting(mv.from, mv.to, mv.promotion);
+            println!("Preview {}/{}: {}", idx + 1, line.len(), line[idx]);
+            let highlights = [(mv.from, Highlight::Move), (mv.to, Highlight::Move)];
+            let drawn = self.gui.render(&board.get_all_position(), board.turn, &highlights, board.status(), self.display_options());
+            // a closed input or terminal also ends the preview, and the game loop reports it
+            if drawn.is_err() || !matches!(self.gui.wait_for_step(), Ok(true)) {
+                break;
             }
         }
+        println!("Back to the game.");
+    }
+
+    /// Words a refused move for the player: the detailed reason of [`Game::explain_illegal`],
+    /// or the error itself for a refusal it does not cover, such as an invalid promotion piece.
+    fn explain_refusal(&self, from: Square, to: Square, err: MoveError) -> String {
+        self.explain_illegal(from, to).map_or_else(|| err.to_string(), |reason| reason.to_string())
+    }
+
+    /// Lets the engine answer a draw offer: it accepts unless it thinks it stands better.
+    fn answer_draw_offer(&mut self) {
+        if eval::evaluate(self, self.turn) <= 0 {
+            println!("{:?} accepts the draw offer.", self.turn);
+            self.accept_draw().expect("a draw offer is pending");
+        } else {
+            println!("{:?} declines the draw offer.", self.turn);
+            self.decline_draw().expect("a draw offer is pending");
+        }
     }
 
-    /// Attempts to update the game state based on a move, validating that the move does not leave the king in check.
+    /// Lets the human players pick their moves with a cursor moved by the arrow keys instead of
+    /// typing them.
+    pub fn enable_cursor(&mut self) {
+        self.gui.enable_cursor();
+    }
+
+    /// Sets the theme used to draw the board.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.gui.set_theme(theme);
+    }
+
+    /// Sets the notation of the last move line and the move history.
+    pub fn set_notation(&mut self, notation: Notation) {
+        self.notation = notation;
+    }
+
+    /// Sets the command aliases of the command prompt, each one word mapped to the command it
+    /// stands for, e.g. `m` to `move`.
+    pub fn set_command_aliases(&mut self, aliases: BTreeMap<String, String>) {
+        self.gui.set_aliases(aliases);
+    }
+
+    /// Sets who plays the given side.
+    ///
     /// # Arguments
+    /// - `side`: The `Color` to configure.
+    /// - `player`: A human at the prompt or the engine with a search depth.
+    pub fn set_player(&mut self, side: Color, player: PlayerKind) {
+        self.players[usize::from(side)] = player;
+    }
+
+    /// Validates and plays a move for the side to move, then passes the turn to the opponent.
     ///
+    /// # Arguments
     /// - `from`: The `Square` where the piece is currently located.
     /// - `to`: The `Square` where the piece is intended to move.
-    /// - `piece`: The `Piece` being moved (e.g., pawn, knight, rook).
-    /// - `side`: The `Color` of the player making the move (e.g., `Color::White` or `Color::Black`).
+    ///
     /// # Returns
+    /// - `Ok(())`: If the move was legal and has been applied.
+    /// - `Err(MoveError)`: If the move is illegal, the game state is left untouched.
+    pub fn play(&mut self, from: Square, to: Square) -> Result<(), MoveError> {
+        self.play_with_promotion(from, to, None)
+    }
+
+    /// Validates and plays a move like [`Game::play`], promoting a pawn that reaches the last
+    /// rank to the chosen piece.
     ///
-    /// - `Ok(())`: If the state is successfully updated and the move is valid.
-    /// - `Err(String)`: If the move leaves the player's king in check, an error is returned with a descriptive message.
-    fn try_update_state(&mut self, from: Square, to: Square, piece: Piece, side: Color) -> Result<Vec<(Square, Square)>, String> {
-        let opponent_side = side.opposite();
-        let side_idx = usize::from(side);
-        let opponent_side_idx = usize::from(opponent_side);
-        let piece_idx = usize::from(piece);
-        let opponent_location = self.get_piece_by_location(opponent_side, to);
-        let mut movement = vec![(from, to)];
-        let game = self.clone();
-        let is_castling_move = piece == Piece::King && (to == Square::new(File::G, from.rank()) || to == Square::new(File::C, from.rank()));
-        if is_castling_move{
-            let is_king_side = to.file() == File::G;
-            let rook_from = if is_king_side { Square::new(File::H, from.rank()) } else { Square::new(File::A, from.rank()) };
-            let rook_to = if is_king_side { Square::new(File::F, from.rank()) } else { Square::new(File::D, from.rank()) };
-            let set_right_idx = if is_king_side {0} else {1};
-            self.castling_rights[side_idx][set_right_idx] = false;
-            let rook_piece_idx = usize::from(Piece::Rock);
-            self.pieces_location[side_idx][rook_piece_idx] ^= BitBoard::from(rook_from);
-            self.pieces_location[side_idx][rook_piece_idx] |= BitBoard::from(rook_to);
-            self.pieces_square[side_idx][rook_piece_idx].retain(|&x| x != rook_from);
-            self.pieces_square[side_idx][rook_piece_idx].push(rook_to);
-            movement.push((rook_from, rook_to));
-        }
-        // update position mask
-        self.pieces_location[side_idx][piece_idx] ^= BitBoard::from(from);
-        self.pieces_location[side_idx][piece_idx] |= BitBoard::from(to);
-        match opponent_location {
-            None => {}
-            Some(piece) => {
-                let opponent_piece_idx = usize::from(piece);
-                self.pieces_location[opponent_side_idx][opponent_piece_idx] &= !BitBoard::from(to);
-                self.pieces_square[opponent_side_idx][opponent_piece_idx].retain(|&x| x != to);
+    /// # Arguments
+    /// - `from`: The `Square` where the piece is currently located.
+    /// - `to`: The `Square` where the piece is intended to move.
+    /// - `promotion`: The piece a promoting pawn becomes; `None` for a queen. Ignored if the
+    ///   move is not a promotion.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the move was legal and has been applied.
+    /// - `Err(MoveError)`: If the move is illegal or the pawn cannot become that piece; the
+    ///   game state is left untouched.
+    pub fn play_with_promotion(&mut self, from: Square, to: Square, promotion: Option<Piece>) -> Result<(), MoveError> {
+        self.validate_move(from, to)?;
+        if let Some(piece) = promotion.filter(|piece| !PROMOTION_PIECES.contains(piece)) {
+            return Err(MoveError::UnsupportedPromotion(piece));
+        }
+        self.play_legal_promoting(from, to, promotion);
+        Ok(())
+    }
+
+    /// Checks whether a move of the side to move takes a pawn to the last rank, so that the
+    /// player has to pick the piece it becomes.
+    pub(crate) fn is_promotion(&self, from: Square, to: Square) -> bool {
+        self.describe_move(from, to).is_some_and(|mv| mv.promotion.is_some())
+    }
+
+    /// Plays a move chosen at this terminal, by the player or the engine, and sends it to the
+    /// peer in a network game.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the move was legal and has been applied.
+    /// - `Err(MoveError)`: If the move is illegal, the game state is left untouched.
+    fn play_local(&mut self, from: Square, to: Square, promotion: Option<Piece>) -> Result<(), MoveError> {
+        self.play_with_promotion(from, to, promotion)?;
+        self.log_event(GameEvent::Move { side: self.turn.opposite(), from, to, promotion });
+        if let Err(err) = self.send_last_move() {
+            println!("{}", err);
+        }
+        self.notify_move_observers();
+        Ok(())
+    }
+
+    /// Plays a move entered by a human player. With tactic alerts on, a move that allows mate
+    /// in one or loses the queen is only played once the player confirms it. A pawn reaching
+    /// the last rank without a chosen piece asks the player for one; in a timed game it
+    /// becomes a queen if the player takes longer than the promotion timeout to choose.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the move was legal and has been applied, or the player took it back.
+    /// - `Err(MoveError)`: If the move is illegal, the game state is left untouched.
+    fn play_human(&mut self, from: Square, to: Square, mut promotion: Option<Piece>) -> Result<(), MoveError> {
+        if promotion.is_none() && self.is_promotion(from, to) {
+            self.validate_move(from, to)?;
+            let timeout = self.clock.is_some().then_some(self.promotion_timeout);
+            // without an answer the move is not played, as if taken back
+            let Ok(piece) = self.gui.ask_promotion(self.turn, timeout) else {
+                return Ok(());
+            };
+            promotion = Some(piece);
+        }
+        if self.tactic_alerts {
+            self.validate_move(from, to)?;
+            if let Some(alert) = self.tactic_alert(from, to) {
+                println!("{}", alert);
+                if !matches!(self.gui.confirm("Play it anyway?"), Ok(true)) {
+                    return Ok(());
+                }
+            }
+        }
+        self.play_local(from, to, promotion)
+    }
+
+    /// Plays the game with chess clocks: each side has the time budget of `control` and loses
+    /// when its flag falls, unless the opponent cannot checkmate.
+    pub fn set_clock(&mut self, control: TimeControl) {
+        self.clock = Some(Clock::new(control));
+    }
+
+    /// Sets which pieces `side` sees on the board while it is that player's turn to look at
+    /// it, for blindfold training.
+    pub fn set_blindfold(&mut self, side: Color, pieces: PieceVisibility) {
+        self.blindfold[usize::from(side)] = pieces;
+    }
+
+    /// Returns how the board is drawn: for the side to move if it plays at this terminal,
+    /// otherwise for its opponent, who is watching the engine or the peer think.
+    fn display_options(&self) -> DisplayOptions {
+        let viewer = match self.players[usize::from(self.turn)] {
+            PlayerKind::Human => self.turn,
+            _ => self.turn.opposite(),
+        };
+        DisplayOptions { pieces: self.blindfold[usize::from(viewer)], viewer }
+    }
+
+    /// Turns the warnings about moves that allow mate in one or lose the queen on or off.
+    pub fn set_tactic_alerts(&mut self, enabled: bool) {
+        self.tactic_alerts = enabled;
+    }
+
+    /// Sets the weights the engine evaluates positions with, for both sides.
+    pub fn set_eval_config(&mut self, config: EvalConfig) {
+        self.eval_config = config;
+    }
+
+    /// Returns the weights the engine evaluates positions with.
+    pub fn eval_config(&self) -> &EvalConfig {
+        &self.eval_config
+    }
+
+    /// Draws the board from Black's side, rank 1 at the top, or from White's side.
+    pub fn set_flipped(&mut self, flipped: bool) {
+        self.gui.set_flipped(flipped);
+    }
+
+    /// Turns drawing the board from the side to move on or off, for two players sharing the screen.
+    pub fn set_auto_flip(&mut self, enabled: bool) {
+        self.gui.set_auto_flip(enabled);
+    }
+
+    /// Turns printing the engine's progress while it searches, depth by depth, on or off.
+    pub fn set_search_info(&mut self, enabled: bool) {
+        self.search_info = enabled;
+    }
+
+    /// Turns the evaluation bar beside the board on or off. It shows the score of a quick
+    /// search after every move, which takes a moment in busy positions.
+    pub fn set_eval_bar(&mut self, enabled: bool) {
+        self.eval_bar = enabled;
+    }
+
+    /// Weakens the computer to a skill level, or plays at full strength with `None`. At a skill
+    /// level the computer searches at the level's depth rather than its own and does not ponder.
+    pub fn set_skill_level(&mut self, level: Option<SkillLevel>) {
+        self.weakened = level.map(WeakenedEngine::new);
+    }
+
+    /// Returns a handle that feeds commands to the game loop from another thread, e.g. a
+    /// network listener, as if they had been typed at the prompt. They are merged with the
+    /// lines typed at the terminal and read in the order they arrive.
+    pub fn command_sender(&self) -> CommandSender {
+        self.gui.command_sender()
+    }
+
+    /// Sets how long a player may take to pick the piece a pawn promotes to in a timed game;
+    /// after that it becomes a queen. Untimed games wait for the choice.
+    pub fn set_promotion_timeout(&mut self, timeout: Duration) {
+        self.promotion_timeout = timeout;
+    }
+
+    /// Sets the pause before each move when the computer plays both sides, to watch the game
+    /// at a readable pace.
+    pub fn set_move_delay(&mut self, delay: Duration) {
+        self.move_delay = delay;
+    }
+
+    /// Sets how many centipawns the engine thinks a draw, by repetition or by the fifty-move
+    /// rule, is worse than an even position for itself: positive to play on, negative to
+    /// steer for a draw against a stronger opponent.
+    pub fn set_contempt(&mut self, contempt: i32) {
+        self.contempt = contempt;
+    }
+
+    /// Returns the contempt of the engine for draws, in centipawns.
+    pub fn contempt(&self) -> i32 {
+        self.contempt
+    }
+
+    /// Returns the skill level of the computer, `None` at full strength.
+    pub fn skill_level(&self) -> Option<SkillLevel> {
+        self.weakened.as_ref().map(WeakenedEngine::level)
+    }
+
+    /// Changes an engine option and tells the observers of the game about it.
+    ///
+    /// # Arguments
+    /// - `option`: The option with its new value, e.g. from [`EngineOption::parse`].
+    pub fn set_option(&mut self, option: EngineOption) {
+        match option {
+            EngineOption::Hash(kilobytes) => self.hash_bits = tt::bits_for_kilobytes(kilobytes),
+            EngineOption::Skill(level) => self.set_skill_level(level),
+            EngineOption::Contempt(contempt) => self.set_contempt(contempt),
+        }
+        for observer in self.observers.iter_mut() {
+            observer.on_option_change(&option);
+        }
+    }
+
+    /// Returns every engine option at its current value, in the order of
+    /// [`crate::engine::options::OPTION_NAMES`]. The hash size is that of the table actually
+    /// used, which may be smaller than the size asked for.
+    pub fn options(&self) -> [EngineOption; 3] {
+        [
+            EngineOption::Hash(tt::kilobytes(self.hash_bits)),
+            EngineOption::Skill(self.skill_level()),
+            EngineOption::Contempt(self.contempt),
+        ]
+    }
+
+    /// Returns the index bits of the transposition tables of the engine's searches.
+    pub(crate) fn hash_bits(&self) -> u32 {
+        self.hash_bits
+    }
+
+    /// Turns pondering on or off: when on, the engine keeps searching during its opponent's
+    /// turn, assuming the reply it expects.
+    pub fn set_pondering(&mut self, enabled: bool) {
+        self.pondering = enabled;
+        if !enabled {
+            self.stop_pondering();
+        }
+    }
+
+    /// Registers an observer that is told about every move played from now on.
+    ///
+    /// # Arguments
+    /// - `observer`: The observer, e.g. a [`crate::engine::observer::CsvMoveLogger`].
+    pub fn add_observer(&mut self, observer: Box<dyn MoveObserver>) {
+        self.observers.push(observer);
+    }
+
+    /// Reports the last played move to every observer, with the time the mover spent on it,
+    /// charges that time to the mover's clock, then starts timing the next move.
+    fn notify_move_observers(&mut self) {
+        let elapsed = self.turn_started.elapsed();
+        self.turn_started = Instant::now();
+        self.punch_clock(elapsed);
+        self.record_move_timing(elapsed);
+        if self.observers.is_empty() {
+            return;
+        }
+        let Some(record) = self.history.last().copied() else {
+            return;
+        };
+        let mut before = self.clone();
+        before.undo();
+        let event = MoveEvent {
+            ply: self.history_ply(),
+            side: self.turn.opposite(),
+            san: before.move_san(record.mv),
+            phase: before.phase(),
+            eval: eval::evaluate(self, Color::White),
+            elapsed,
+        };
+        for observer in self.observers.iter_mut() {
+            observer.on_move(&event);
+        }
+    }
+
+    /// Number of half-moves played since the start of the game, including moves played before
+    /// the position was loaded.
+    fn history_ply(&self) -> usize {
+        2 * (self.fullmove_number as usize - 1) + usize::from(self.turn)
+    }
+
+    /// Plays a move already known to be legal (e.g. taken from [`Game::all_legal_moves`]),
+    /// then passes the turn to the opponent. Any undone moves waiting to be redone are dropped,
+    /// with their timings and annotations, as is a draw offer made to the mover.
+    ///
+    /// # Arguments
+    /// - `from`: The `Square` where the piece is currently located.
+    /// - `to`: The `Square` where the piece is intended to move.
+    pub(crate) fn play_legal(&mut self, from: Square, to: Square) {
+        self.play_legal_promoting(from, to, None);
+    }
+
+    /// Same as [`Game::play_legal`], promoting a pawn that reaches the last rank to
+    /// `promotion`, or to a queen for `None`.
+    fn play_legal_promoting(&mut self, from: Square, to: Square, promotion: Option<Piece>) {
+        self.redo_stack.clear();
+        self.move_timings.truncate(self.history.len());
+        self.annotations.truncate(self.history.len());
+        // moving instead of answering a draw offer declines it
+        if self.has_draw_offer() {
+            self.draw_offer = None;
+        }
+        self.push_move(from, to, promotion);
+    }
+
+    /// Applies a move, records it in the history and passes the turn to the opponent. Does
+    /// nothing if the side to move has no piece on `from`. A promoting pawn becomes
+    /// `promotion`, or a queen for `None`.
+    fn push_move(&mut self, from: Square, to: Square, promotion: Option<Piece>) {
+        if let Some(mut mv) = self.describe_move(from, to) {
+            if mv.promotion.is_some() {
+                mv.promotion = promotion.or(mv.promotion);
+            }
+            let record = self.make_move(mv);
+            self.history.push(record);
+        }
+    }
+
+    /// Moves a piece and updates every derived part of the state: captured piece (including
+    /// en passant), castling rook, promotion, castling rights, en passant target,
+    /// halfmove clock and the movement/capture masks. The move is not validated and the turn
+    /// is not changed.
+    ///
+    /// # Arguments
+    /// - `mv`: The move, as described by [`Game::describe_move`] for the side to move.
+    ///
+    /// # Returns
+    /// - `MoveRecord`: Everything needed to take the move back.
+    fn apply_move(&mut self, mv: Move) -> MoveRecord {
+        let side = self.turn;
+        let Move { from, to, piece, .. } = mv;
+        let rules = self.variant.rules();
+        let has_side_effects = rules.has_side_effects(&mv);
+        let record = MoveRecord {
+            mv,
+            #[cfg(debug_assertions)]
+            hash: self.zobrist(),
+            board_before: has_side_effects.then_some(self.pieces_location),
+            castling_rights: self.castling_rights,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+        };
+        if mv.flag == MoveFlag::Castle {
+            let (rook_from, rook_to) = Self::castling_rook_squares(to);
+            self.take_piece(side, Piece::Rock, rook_from);
+            self.put_piece(side, Piece::Rock, rook_to);
+        }
+        if let (Some(captured), Some(square)) = (mv.captured, mv.captured_square()) {
+            self.take_piece(side.opposite(), captured, square);
+        }
+        self.take_piece(side, piece, from);
+        self.put_piece(side, mv.promotion.unwrap_or(piece), to);
+        self.en_passant = (mv.flag == MoveFlag::DoublePush)
+            .then(|| Square::try_from((usize::from(from) + usize::from(to)) / 2).ok())
+            .flatten();
+        self.update_castling_rights(from, to, piece);
+        let is_pawn_or_capture = piece == Piece::Pawn || mv.captured.is_some();
+        self.halfmove_clock = if is_pawn_or_capture { 0 } else { self.halfmove_clock + 1 };
+        if has_side_effects {
+            // the side effects can change the masks of every piece type of both sides
+            rules.after_move(self, &mv);
+            self.compute_attack_threat_and_move();
+        } else {
+            self.update_masks_after(&mv, side);
+        }
+        record
+    }
+
+    /// Returns the rook's start and end squares for a castling move landing on `king_to`.
+    pub(crate) fn castling_rook_squares(king_to: Square) -> (Square, Square) {
+        let rank = king_to.rank();
+        if king_to.file() == File::G {
+            (Square::new(File::H, rank), Square::new(File::F, rank))
+        } else {
+            (Square::new(File::A, rank), Square::new(File::D, rank))
+        }
+    }
+
+    /// Adds a piece to the location masks.
+    fn put_piece(&mut self, side: Color, piece: Piece, square: Square) {
+        self.pieces_location[usize::from(side)][usize::from(piece)] |= BitBoard::from(square);
+        self.occupancy[usize::from(side)] |= BitBoard::from(square);
+    }
+
+    /// Removes a piece from the location masks.
+    fn take_piece(&mut self, side: Color, piece: Piece, square: Square) {
+        self.pieces_location[usize::from(side)][usize::from(piece)] &= !BitBoard::from(square);
+        self.occupancy[usize::from(side)] &= !BitBoard::from(square);
+    }
+
+    /// Recombines the occupancy of each side from the location masks, after they were replaced
+    /// as a whole rather than piece by piece.
+    fn refresh_occupancy(&mut self) {
+        self.occupancy = [Color::White, Color::Black].map(|side| Self::combine(&self.pieces_location[usize::from(side)]));
+    }
+
+    /// Returns the squares holding a piece of `side`.
+    pub(crate) fn side_pieces(&self, side: Color) -> BitBoard {
+        self.occupancy[usize::from(side)]
+    }
+
+    /// Returns the squares holding a white piece.
+    pub(crate) fn white_pieces(&self) -> BitBoard {
+        self.side_pieces(Color::White)
+    }
+
+    /// Returns the squares holding a black piece.
+    pub(crate) fn black_pieces(&self) -> BitBoard {
+        self.side_pieces(Color::Black)
+    }
+
+    /// Returns the squares holding any piece.
+    pub(crate) fn occupied(&self) -> BitBoard {
+        self.white_pieces() | self.black_pieces()
+    }
+
+    /// Clears the castling rights lost by a move: any king move, a rook leaving its corner,
+    /// or a piece landing on the opponent's rook corner.
+    fn update_castling_rights(&mut self, from: Square, to: Square, piece: Piece) {
+        if piece == Piece::King {
+            self.castling_rights.revoke_side(self.turn);
+        }
+        for side in Color::iter() {
+            let rank = if side == Color::White { Rank::One } else { Rank::Eight };
+            for (king_side, file) in [(true, File::H), (false, File::A)] {
+                let corner = Square::new(file, rank);
+                if from == corner || to == corner {
+                    self.castling_rights.revoke(side, king_side);
+                }
             }
         }
-        // change square
-        self.pieces_square[side_idx][piece_idx].retain(|&x| x != from);
-        self.pieces_square[side_idx][piece_idx].push(to);
-        // TODO: recheck pawn movement
-        // get new attacks
-        self.compute_attack_threat_and_move();
-        if self.is_checked(){
-            self.set_from(game);
-            return Err(format!("After move king is still on check {:?}", from));
+    }
+
+    /// Checks whether a king move is a castling move (two files sideways from the e-file).
+    fn is_castling_move(&self, from: Square, to: Square, piece: Piece) -> bool {
+        piece == Piece::King
+            && from.file() == File::E
+            && from.rank() == to.rank()
+            && (to.file() == File::G || to.file() == File::C)
+    }
+
+    /// Formats the last moves of the game as a numbered move list (e.g. `12. e2e4 e7e5 13. g1f3`).
+    ///
+    /// # Arguments
+    /// - `plies`: The maximum number of half-moves to include.
+    fn move_list_tail(&self, plies: usize) -> String {
+        let current_ply = self.history_ply();
+        let start = self.history.len().saturating_sub(plies);
+        let mut moves = Vec::new();
+        for (idx, MoveRecord { mv, .. }) in self.history.iter().enumerate().skip(start) {
+            let ply = (current_ply + idx).saturating_sub(self.history.len());
+            let number = ply / 2 + 1;
+            if ply.is_multiple_of(2) {
+                moves.push(format!("{}. {}", number, mv));
+            } else if idx == start {
+                moves.push(format!("{}... {}", number, mv));
+            } else {
+                moves.push(mv.to_string());
+            }
         }
-        // TODO: update castle rights
-        Ok(movement)
+        moves.join(" ")
+    }
+
+    /// Records the current position and move history for the crash reporter.
+    fn record_crash_context(&self) {
+        let history = self.history.iter().map(|record| record.mv.to_string()).collect();
+        crash::record_position(self.fen(), history);
     }
 }
 
@@ -237,60 +1296,6 @@ impl Game{
         start_position
     }
 
-    /// Generates the starting positions of all pieces on the chessboard as a nested array of vectors.
-    ///
-    /// # Returns
-    /// - `[[Vec<Square>; 6]; 2]`
-    ///   - The outer array corresponds to the two sides: White and Black.
-    ///   - The inner array corresponds to the six piece types: Pawn, Knight, Rook, Bishop, Queen, and King.
-    ///   - Each vector contains the `Square` positions for the respective pieces.
-    fn start_position() -> [[Vec<Square>; 6]; 2]{
-        let mut start_position: [[Vec<Square>; 6]; 2] =
-            std::array::from_fn(|_| std::array::from_fn(|_| Vec::new()));
-        for piece in Piece::iter(){
-            for side in Color::iter(){
-                let rank = match (side, piece) {
-                    (Color::White, Piece::Pawn) => Rank::Two,
-                    (Color::Black, Piece::Pawn) => Rank::Seven,
-                    (Color::White, _) => Rank::One,
-                    (Color::Black, _) => Rank::Eight,
-                };
-                start_position[usize::from(side)][usize::from(piece)] = match piece {
-                    Piece::Pawn => vec![
-                        Square::new(File::A, rank),
-                        Square::new(File::B, rank),
-                        Square::new(File::C, rank),
-                        Square::new(File::D, rank),
-                        Square::new(File::E, rank),
-                        Square::new(File::F, rank),
-                        Square::new(File::G, rank),
-                        Square::new(File::H, rank),
-                    ],
-                    Piece::Knight => vec![
-                        Square::new(File::B, rank),
-                        Square::new(File::G, rank),
-                    ],
-                    Piece::Rock => vec![
-                        Square::new(File::A, rank),
-                        Square::new(File::H, rank),
-                    ],
-                    Piece::Bishop => vec![
-                        Square::new(File::C, rank),
-                        Square::new(File::F, rank),
-                    ],
-                    Piece::Queen => vec![
-                        Square::new(File::D, rank),
-                    ],
-                    Piece::King => vec![
-                        Square::new(File::E, rank),
-                    ]
-                };
-            }
-
-        }
-        start_position
-    }
-
     /// Combines multiple `BitBoard` values into a single `BitBoard` by performing a bitwise OR operation.
     ///
     /// # Arguments
@@ -301,7 +1306,6 @@ impl Game{
     /// - A single `BitBoard` representing the combined positions of all pieces in the input array.
     fn combine(board: &[BitBoard; 6]) -> BitBoard{
         (Piece::iter())
-            .into_iter()
             .map(|i| board[usize::from(i)])
             .fold(BitBoard::empty(), |acc, board| {
                 acc | board
@@ -322,10 +1326,9 @@ impl Game{
     fn compute_attack_threat_and_move_to_given(&self, square: Square, piece: Piece, color: Color) -> (BitBoard, BitBoard){
         let side_index = usize::from(color);
         let piece_idx = usize::from(piece);
-        let opponent_index = usize::from(color.opposite());
-        let own_pieces =  &Self::combine(&self.pieces_location[side_index]);
-        let opponent_pieces = &Self::combine(&self.pieces_location[opponent_index]);
-        let piece_bit = &self.pieces_location[side_index][piece_idx] & (&BitBoard::from(square));
+        let own_pieces = &self.occupancy[side_index];
+        let opponent_pieces = &self.occupancy[usize::from(color.opposite())];
+        let piece_bit = self.pieces_location[side_index][piece_idx] & BitBoard::from(square);
         let movement = piece.moves_function()(
             &piece_bit,
             square,
@@ -344,50 +1347,105 @@ impl Game{
     }
 
     /// Computes and updates the attack threats and legal moves for all pieces on the board.
+    /// After a move, [`Game::update_masks_after`] does the same for the pieces it affects.
     fn compute_attack_threat_and_move(&mut self){
-        self.pieces_movement.iter_mut()
-            .for_each(|piece_move| piece_move.iter_mut()
-                .for_each(|board| board.clear()));
-        self.pieces_capture_movement.iter_mut()
-            .for_each(|piece_move| piece_move.iter_mut()
-                .for_each(|board| board.clear()));
+        self.refresh_occupancy();
         for side in Color::iter(){
-            let side_index = usize::from(side);
             for piece in Piece::iter(){
-                let piece_idx = usize::from(piece.clone());
-                for square in &self.pieces_square[side_index][piece_idx]{
-                    let (movement, capture) = self.compute_attack_threat_and_move_to_given(square.clone(), piece, side);
-                    self.pieces_movement[side_index][piece_idx] |= movement;
-                    self.pieces_capture_movement[side_index][piece_idx] |= capture;
-                }
+                self.recompute_masks(side, piece);
             }
         };
+        self.refresh_threats();
+    }
+
+    /// Recomputes the movement and capture masks, and the mobility, of one piece type of one side.
+    fn recompute_masks(&mut self, side: Color, piece: Piece) {
+        let (side_index, piece_idx) = (usize::from(side), usize::from(piece));
+        let (mut movement, mut capture, mut mobility) = (BitBoard::empty(), BitBoard::empty(), 0);
+        let opponents = self.side_pieces(side.opposite());
+        for square in self.pieces_location[side_index][piece_idx]{
+            let (moves, captures) = self.compute_attack_threat_and_move_to_given(square, piece, side);
+            movement |= moves;
+            capture |= captures;
+            mobility += (moves | (captures & opponents)).count();
+        }
+        self.pieces_movement[side_index][piece_idx] = movement;
+        self.pieces_capture_movement[side_index][piece_idx] = capture;
+        self.pieces_mobility[side_index][piece_idx] = mobility;
     }
 
     /// Determines if the current player's king is in check.
-    fn is_checked(&self) -> bool{
-        let attack = Self::combine(&self.pieces_capture_movement[usize::from(self.turn.opposite())]);
-        let king_pos = self.pieces_location[usize::from(self.turn)][usize::from(Piece::King)];
-        !(attack & king_pos).is_empty()
+    pub fn is_checked(&self) -> bool{
+        if !self.variant.rules().can_be_checked(self) {
+            return false;
+        }
+        let attack = self.threats.attacked(self.turn.opposite());
+        self.king_square(self.turn).is_some_and(|king| !(attack & BitBoard::from(king)).is_empty())
+    }
+
+    /// Returns the side to move.
+    pub fn turn(&self) -> Color {
+        self.turn
+    }
+
+    /// Returns the castling rights each side still has.
+    pub fn castling_rights(&self) -> CastlingRights {
+        self.castling_rights
     }
 
-    /// Retrieves the current positions of all pieces on the board as a flat array.
+    /// Checks whether either side may still castle on either wing.
+    pub(crate) fn has_castling_rights(&self) -> bool {
+        !self.castling_rights.is_empty()
+    }
+
+    /// Returns the current move number, starting at `1` and incremented after each Black move.
+    pub fn fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
+
+    /// Returns the plies played since the last capture or pawn move, for the fifty-move rule.
+    pub(crate) fn halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
+    /// Returns the location mask of a single piece type for the given side.
+    ///
+    /// # Arguments
+    /// - `color`: The `Color` of the pieces.
+    /// - `piece`: The `Piece` type to look up.
+    pub(crate) fn pieces(&self, color: Color, piece: Piece) -> BitBoard {
+        self.pieces_location[usize::from(color)][usize::from(piece)]
+    }
+
+    /// Returns the square of the king of `side`, read from its bitboard, so that it follows
+    /// every move, capture, castling, explosion and undo without being tracked apart. `None`
+    /// once the king has exploded in Atomic chess.
+    pub(crate) fn king_square(&self, side: Color) -> Option<Square> {
+        self.pieces(side, Piece::King).into_iter().next()
+    }
+
+    /// Returns the number of moves and captures of the pieces of one type and color, ignoring
+    /// checks and counted piece by piece.
+    pub(crate) fn mobility(&self, color: Color, piece: Piece) -> u32 {
+        self.pieces_mobility[usize::from(color)][usize::from(piece)]
+    }
+
+    /// Retrieves the current positions of all pieces on the board.
     ///
     /// # Returns
-    /// - `[Option<(Piece, Color)>; 64]`
-    ///   - An array where each index corresponds to a square on the chessboard (0 for A1, 63 for H8).
-    ///   - Each element is either `Some((Piece, Color))` if a piece occupies the square, or `None` if the square is empty.
-    fn get_all_position(&self) -> [Option<(Piece, Color)>; 64]{
-        let mut board = [None; 64];
-        for side in Color::iter(){
-            for piece in Piece::iter(){
-                for square in &self.pieces_square[usize::from(side)][usize::from(piece)]{
-                    let idx = usize::from(*square);
-                    board[idx] = Some((piece, side));
-                }
-            }
-        }
-        board
+    /// - `Board`: The content of every square, indexed by `Square` (or from 0 for A1 to 63 for
+    ///   H8), along with the bitboards and occupancy masks of the pieces.
+    pub(crate) fn get_all_position(&self) -> Board {
+        Board::from_bitboards(&self.pieces_location)
+    }
+
+    /// Retrieves the piece located at a specific square, whatever its color.
+    ///
+    /// # Returns
+    /// - `Some((Piece, Color))`: The piece and its owner.
+    /// - `None`: If the square is empty.
+    pub fn piece_at(&self, square: Square) -> Option<(Piece, Color)> {
+        Color::iter().find_map(|side| self.get_piece_by_location(side, square).map(|piece| (piece, side)))
     }
 
     /// Retrieves the piece located at a specific square for a given color.
@@ -399,83 +1457,136 @@ impl Game{
     /// # Returns
     /// - `Some(Piece)`: If a piece of the specified color occupies the given square, returns the piece type (e.g., Pawn, Knight, etc.).
     /// - `None`: If no piece of the specified color is present on the given square.
-    fn get_piece_by_location(&self, color: Color, square: Square) -> Option<Piece> {
+    pub(crate) fn get_piece_by_location(&self, color: Color, square: Square) -> Option<Piece> {
+        let square = BitBoard::from(square);
         Piece::iter()
-            .find(|piece| self.pieces_square[usize::from(color)][usize::from(*piece)].contains(&square))
-    }
-
-    /// Copies the state of another `Game` instance into the current instance.
-    ///
-    /// # Arguments
-    /// - `other`: The `Game` instance from which the state will be copied.
-    fn set_from(&mut self, other: Game){
-        self.pieces_square = other.pieces_square;
-        self.pieces_location = other.pieces_location;
-        self.pieces_movement = other.pieces_movement;
-        self.pieces_capture_movement = other.pieces_capture_movement;
+            .find(|piece| !(self.pieces(color, *piece) & square).is_empty())
     }
 
     /// Determines the current result of the game, if any.
     ///
     /// # Returns
     /// - `Some(GameResult)`:
-    ///   - `GameResult::Draw`: If the current player has no legal moves but the game is not in checkmate.
+    ///   - `GameResult::DrawByAgreement` or `GameResult::Resignation(Color)`: If a player ended the game.
+    ///   - `GameResult::Timeout(Color)` or `GameResult::TimeoutDraw(Color)`: If a flag fell.
+    ///   - `GameResult::Draw`: If the current player has no legal moves but is not in check (stalemate).
     ///   - `GameResult::Checkmate(Color)`: If the current player is in checkmate, returns the color of the player who lost.
+    ///   - `GameResult::KingExploded(Color)`: If an Atomic capture blew up the current player's king.
     /// - `None`: If the game is still ongoing and no result has been determined.
-    fn game_result(&self) -> Option<GameResult> {
-        let side_idx = usize::from(self.turn);
-        let opponent_idx = usize::from(self.turn.opposite());
-        let has_no_moves = !self.has_legal_moves();
-        if has_no_moves{ return Some(GameResult::Draw) }
-        let king_position = self.pieces_location[side_idx][usize::from(Piece::King)];
-        let king_movement = self.pieces_movement[side_idx][usize::from(Piece::King)];
-        let possible_moves = !king_position & !king_movement & Self::combine(&self.pieces_movement[side_idx]) | Self::combine(&self.pieces_capture_movement[side_idx]);
-        let capture_moves = Self::combine(&self.pieces_movement[opponent_idx]) | Self::combine(&self.pieces_capture_movement[opponent_idx]);
-        let orig_attacking = self.get_attacking_pieces();
-        let is_king_has_way_to_escape = !(king_movement & !(capture_moves)).is_empty();
-        let attacking = orig_attacking.iter()
-            .filter(|(_, board)| board == &(board & (&!possible_moves)))
-            .map(|(_, board)| board)
-            .collect::<Vec<&BitBoard>>();
-        let is_not_check_mate = orig_attacking.is_empty() || attacking.is_empty() || is_king_has_way_to_escape;
-        match is_not_check_mate {
-            true => None,
-            false => Some(GameResult::Checkmate(self.turn))
-        }
-    }
-
-    /// Determines if the current player has any legal moves available.
-    fn has_legal_moves(&self) -> bool {
-        let side_idx = usize::from(self.turn);
-        self.pieces_movement[side_idx]
-            .iter()
-            .chain(self.pieces_capture_movement[side_idx].iter())
-            .any(|bitboard| !bitboard.is_empty())
-    }
-
-    /// Identifies the opponent's pieces that are currently attacking the player's king.
+    pub fn game_result(&self) -> Option<GameResult> {
+        if self.declared_result.is_some() {
+            return self.declared_result;
+        }
+        if let Some(result) = self.variant.rules().result(self) {
+            return Some(result);
+        }
+        if !self.all_legal_moves(self.turn).is_empty() {
+            return None;
+        }
+        match self.is_checked() {
+            true => Some(GameResult::Checkmate(self.turn)),
+            false => Some(GameResult::Draw),
+        }
+    }
+
+    /// Describes the position for the board display: whether the side to move is in check,
+    /// or how the game ended.
+    pub(crate) fn status(&self) -> GameStatus {
+        let king = self.king_square(self.turn);
+        match self.game_result() {
+            Some(GameResult::Checkmate(loser)) => GameStatus::Checkmate {
+                king: king.expect("a checkmated side has a king"),
+                winner: loser.opposite(),
+            },
+            Some(GameResult::Draw) => GameStatus::Stalemate,
+            Some(GameResult::DrawByAgreement) => GameStatus::Draw,
+            Some(GameResult::Resignation(loser)) => GameStatus::Resignation(loser),
+            Some(GameResult::Timeout(loser)) => GameStatus::Timeout(loser),
+            Some(GameResult::TimeoutDraw(loser)) => GameStatus::TimeoutDraw(loser),
+            Some(GameResult::KingExploded(loser)) => GameStatus::KingExploded(loser),
+            None => match king {
+                Some(king) if self.is_checked() => GameStatus::Check(king),
+                _ => GameStatus::InProgress,
+            },
+        }
+    }
+
+    /// Returns the number of pieces of one type `side` has on the board.
+    pub fn piece_count(&self, side: Color, piece: Piece) -> u32 {
+        self.pieces(side, piece).count()
+    }
+
+    /// Returns the material of `side` in centipawns, counting the pieces at their usual values
+    /// (pawn 100, knight 320, bishop 330, rook 500, queen 900) and the king as nothing, e.g. `4000` in the start position.
+    pub fn material(&self, side: Color) -> i32 {
+        Piece::iter()
+            .map(|piece| self.piece_count(side, piece) as i32 * eval::PIECE_VALUES[usize::from(piece)])
+            .sum()
+    }
+
+    /// Returns the pieces `side` has taken so far, in the order they were captured. Moves taken
+    /// back with [`Game::undo`] no longer count, and neither do captures made before the
+    /// position was loaded from a FEN.
+    ///
+    /// # Arguments
+    /// - `side`: The capturing side.
     ///
     /// # Returns
-    /// - `Vec<(Piece, BitBoard)>`:
-    ///   - A vector where each element represents an opponent piece that is attacking the king.
-    ///   - Each tuple consists of:
-    ///     - `Piece`: The type of the attacking piece (e.g., Pawn, Knight, Rook).
-    ///     - `BitBoard`: The bitboard representing the attacking piece's position.
-    fn get_attacking_pieces(&self) -> Vec<(Piece, BitBoard)>{
-        let side_idx = usize::from(self.turn);
-        let opponent_side = self.turn.opposite();
-        let opponent_side_idx = usize::from(opponent_side);
-        let king_position = self.pieces_location[side_idx][usize::from(Piece::King)];
-        let mut attacking: Vec<(Piece, BitBoard)> = Vec::new();
-        for piece in Piece::iter(){
-            let piece_idx = usize::from(piece);
-            let attacking_board = (self.pieces_location[opponent_side_idx][piece_idx] | self.pieces_capture_movement[opponent_side_idx][piece_idx]) & king_position;
-            if !attacking_board.is_empty(){
-                attacking.push((piece, attacking_board));
-            }
+    /// - `Vec<Piece>`: The captured pieces of the opponent, e.g. `[Pawn, Knight]`.
+    pub fn captured(&self, side: Color) -> Vec<Piece> {
+        // the last move was played by the opponent of the side to move, and the sides alternate
+        let last_mover = self.turn.opposite();
+        self.history.iter().rev()
+            .enumerate()
+            .filter(|(idx, _)| (idx % 2 == 0) == (side == last_mover))
+            .filter_map(|(_, record)| record.mv.captured)
+            .rev()
+            .collect()
+    }
+
+    /// Returns the stage of the game, judged by the material left and the move number: the
+    /// opening, the middlegame or the endgame.
+    pub fn phase(&self) -> GamePhase {
+        eval::game_phase(self)
+    }
+
+    /// Returns the material balance in pawns from White's point of view, counting the pieces
+    /// at their usual values (a knight or bishop is worth about three pawns), rounded.
+    fn material_balance(&self) -> i32 {
+        ((self.material(Color::White) - self.material(Color::Black)) as f64 / 100.0).round() as i32
+    }
+
+    /// Gathers the pieces each side has taken, shown beside the board.
+    pub(crate) fn captured_pieces(&self) -> CapturedPieces {
+        CapturedPieces { pieces: [self.captured(Color::White), self.captured(Color::Black)], material: self.material_balance() }
+    }
+
+    /// Searches the position for the evaluation bar, unless it was the last one searched.
+    fn eval_bar_score(&mut self) -> Score {
+        let hash = self.zobrist();
+        if let Some((_, score)) = self.eval_bar_score.filter(|(searched, _)| *searched == hash) {
+            return score;
+        }
+        let (score, _) = search::analyze(self, EVAL_BAR_DEPTH);
+        self.eval_bar_score = Some((hash, score));
+        score
+    }
+
+    /// Gathers the facts shown in the status line below the board.
+    pub(crate) fn status_line(&self) -> StatusLine {
+        StatusLine {
+            material: self.material_balance(),
+            legal_moves: self.legal_move_list().len(),
+            check: self.is_checked(),
+            halfmove_clock: self.halfmove_clock,
+            en_passant: self.en_passant,
         }
-        attacking
+    }
+}
 
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -483,11 +1594,44 @@ impl Clone for Game{
     fn clone(&self) -> Self {
         Self{
             turn: self.turn,
-            pieces_square: self.pieces_square.clone(),
-            pieces_movement: self.pieces_movement.clone(),
-            pieces_location: self.pieces_location.clone(),
-            pieces_capture_movement: self.pieces_capture_movement.clone(),
-            castling_rights: self.castling_rights.clone(),
+            pieces_movement: self.pieces_movement,
+            pieces_location: self.pieces_location,
+            occupancy: self.occupancy,
+            pieces_capture_movement: self.pieces_capture_movement,
+            pieces_mobility: self.pieces_mobility,
+            threats: self.threats,
+            castling_rights: self.castling_rights,
+            en_passant: self.en_passant,
+            history: self.history.clone(),
+            redo_stack: self.redo_stack.clone(),
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            players: self.players,
+            draw_offer: self.draw_offer,
+            declared_result: self.declared_result,
+            peer: None,
+            observers: Vec::new(),
+            spectators: None,
+            turn_started: self.turn_started,
+            clock: self.clock,
+            tactic_alerts: self.tactic_alerts,
+            eval_config: self.eval_config,
+            search_info: self.search_info,
+            pondering: self.pondering,
+            ponder: None,
+            event_log: None,
+            variant: self.variant,
+            blindfold: self.blindfold,
+            move_timings: self.move_timings.clone(),
+            annotations: self.annotations.clone(),
+            weakened: self.weakened.clone(),
+            promotion_timeout: self.promotion_timeout,
+            move_delay: self.move_delay,
+            contempt: self.contempt,
+            hash_bits: self.hash_bits,
+            notation: self.notation,
+            eval_bar: self.eval_bar,
+            eval_bar_score: self.eval_bar_score,
             gui: CommandPromptGUI::new()
         }
     }
@@ -497,7 +1641,7 @@ impl Clone for Game{
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::square::{File, Rank, Square};
+    use crate::square::{square, File, Rank, Square};
     use crate::pieces::Piece;
     use crate::pieces::Piece::Pawn;
 
@@ -506,12 +1650,14 @@ mod tests {
         let mut game = Game::new();
 
         // Ensure castling rights are allowed
-        game.castling_rights[usize::from(Color::White)] = [true, true];
+        game.castling_rights = CastlingRights::ALL;
 
         game.pieces_location[usize::from(Color::White)] = [BitBoard::empty(); 6];
 
         // Ensure no squares are under attack
         game.pieces_capture_movement[usize::from(Color::Black)] = [BitBoard::empty(); 6];
+        game.refresh_occupancy();
+        game.refresh_threats();
 
         // Test king_side castling
         let from = Square::new(File::E, Rank::One);
@@ -524,30 +1670,34 @@ mod tests {
     fn test_validate_castling_king_side_piece_between() {
         let mut game = Game::new();
 
-        game.castling_rights[usize::from(Color::White)] = [true, true];
+        game.castling_rights = CastlingRights::ALL;
         game.pieces_location[usize::from(Color::White)] = [BitBoard::empty(); 6];
         game.pieces_location[usize::from(Color::White)][usize::from(Piece::Knight)] |= BitBoard::new(0x42);
         game.pieces_capture_movement[usize::from(Color::Black)] = [BitBoard::empty(); 6];
+        game.refresh_occupancy();
+        game.refresh_threats();
 
         let from = Square::new(File::E, Rank::One);
         let to = Square::new(File::G, Rank::One);
         let result = game.validate_castling(from, to);
-        assert!(!result.is_ok(), "king_side castling shouldn't be allowed.");
+        assert!(result.is_err(), "king_side castling shouldn't be allowed.");
     }
 
     #[test]
     fn test_validate_castling_queen_side_piece_between() {
         let mut game = Game::new();
 
-        game.castling_rights[usize::from(Color::White)] = [true, true];
+        game.castling_rights = CastlingRights::ALL;
         game.pieces_location[usize::from(Color::White)] = [BitBoard::empty(); 6];
         game.pieces_location[usize::from(Color::White)][usize::from(Piece::Knight)] |= BitBoard::new(0x42);
         game.pieces_capture_movement[usize::from(Color::Black)] = [BitBoard::empty(); 6];
+        game.refresh_occupancy();
+        game.refresh_threats();
 
         let from = Square::new(File::E, Rank::One);
         let to = Square::new(File::C, Rank::One);
         let result = game.validate_castling(from, to);
-        assert!(!result.is_ok(), "king_side castling shouldn't be allowed.");
+        assert!(result.is_err(), "king_side castling shouldn't be allowed.");
     }
 
     #[test]
@@ -555,15 +1705,17 @@ mod tests {
         let mut game = Game::new();
 
         game.turn = Color::Black;
-        game.castling_rights[usize::from(Color::Black)] = [true, true];
+        game.castling_rights = CastlingRights::ALL;
         game.pieces_location[usize::from(Color::Black)] = [BitBoard::empty(); 6];
         game.pieces_location[usize::from(Color::Black)][usize::from(Piece::Knight)] |= BitBoard::new(0x4200000000000042);
         game.pieces_capture_movement[usize::from(Color::White)] = [BitBoard::empty(); 6];
+        game.refresh_occupancy();
+        game.refresh_threats();
 
         let from = Square::new(File::E, Rank::Eight);
         let to = Square::new(File::G, Rank::Eight);
         let result = game.validate_castling(from, to);
-        assert!(!result.is_ok(), "king_side castling shouldn't be allowed.");
+        assert!(result.is_err(), "king_side castling shouldn't be allowed.");
     }
 
     #[test]
@@ -571,25 +1723,29 @@ mod tests {
         let mut game = Game::new();
 
         game.turn = Color::Black;
-        game.castling_rights[usize::from(Color::Black)] = [true, true];
+        game.castling_rights = CastlingRights::ALL;
         game.pieces_location[usize::from(Color::Black)] = [BitBoard::empty(); 6];
         game.pieces_capture_movement[usize::from(Color::White)] = [BitBoard::empty(); 6];
         game.pieces_capture_movement[usize::from(Color::White)][usize::from(Pawn)] |= BitBoard::new(0x3000000000000042);
+        game.refresh_occupancy();
+        game.refresh_threats();
 
         let from = Square::new(File::E, Rank::Eight);
         let to = Square::new(File::G, Rank::Eight);
         let result = game.validate_castling(from, to);
-        assert!(!result.is_ok(), "king_side castling shouldn't be allowed.");
+        assert!(result.is_err(), "king_side castling shouldn't be allowed.");
     }
 
     #[test]
     fn test_validate_castling_king_side_attacked_no_effect_black() {
         let mut game = Game::new();
         game.turn = Color::Black;
-        game.castling_rights[usize::from(Color::Black)] = [true, true];
+        game.castling_rights = CastlingRights::ALL;
         game.pieces_location[usize::from(Color::Black)] = [BitBoard::empty(); 6];
         game.pieces_capture_movement[usize::from(Color::White)] = [BitBoard::empty(); 6];
         game.pieces_capture_movement[usize::from(Color::White)][usize::from(Pawn)] |= BitBoard::new(0x700000000000042);
+        game.refresh_occupancy();
+        game.refresh_threats();
 
         let from = Square::new(File::E, Rank::Eight);
         let to = Square::new(File::G, Rank::Eight);
@@ -597,6 +1753,127 @@ mod tests {
         assert!(result.is_ok(), "king_side castling shouldn be allowed.");
     }
 
+    #[test]
+    fn test_move_list_tail_numbers_moves() {
+        let mut game = Game::new();
+        for (from, to) in [("e2", "e4"), ("e7", "e5"), ("g1", "f3")] {
+            let from = square(from);
+            let to = square(to);
+            game.play(from, to).unwrap();
+        }
+        assert_eq!(game.move_list_tail(10), "1. e2e4 e7e5 2. g1f3");
+        assert_eq!(game.move_list_tail(2), "1... e7e5 2. g1f3");
+    }
+
+
 
+    #[test]
+    fn test_status_announces_check_mate_and_stalemate() {
+        let e8 = Square::new(File::E, Rank::Eight);
+        assert_eq!(Game::new().status(), GameStatus::InProgress);
+        let check = Game::from_fen("4k3/8/8/8/8/8/8/4RK2 b - - 0 1").unwrap();
+        assert_eq!(check.status(), GameStatus::Check(e8));
+        let mate = Game::from_fen("4k3/4Q3/4K3/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(mate.status(), GameStatus::Checkmate { king: e8, winner: Color::White });
+        let stalemate = Game::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(stalemate.status(), GameStatus::Stalemate);
+        assert_eq!(stalemate.status().to_string(), "Stalemate");
+    }
+
+    #[test]
+    fn test_status_line_counts_material_and_moves() {
+        let line = Game::new().status_line();
+        assert_eq!((line.material, line.legal_moves, line.check), (0, 20, false));
+        // White is a rook up and gives check; Black's king has four squares
+        let line = Game::from_fen("4k3/8/8/8/8/8/8/4RK2 b - - 7 40").unwrap().status_line();
+        assert_eq!(line, StatusLine { material: 5, legal_moves: 4, check: true, halfmove_clock: 7, en_passant: None });
+    }
+
+    #[test]
+    fn test_captured_pieces_follow_undo() {
+        let mut game = Game::new();
+        for (from, to) in [("e2", "e4"), ("d7", "d5"), ("e4", "d5"), ("d8", "d5"), ("b1", "c3"), ("d5", "a2")] {
+            game.play(square(from), square(to)).unwrap();
+        }
+        assert_eq!(game.captured(Color::White), vec![Piece::Pawn]);
+        assert_eq!(game.captured(Color::Black), vec![Piece::Pawn, Piece::Pawn]);
+        game.undo();
+        assert_eq!(game.captured(Color::Black), vec![Piece::Pawn]);
+        assert_eq!(game.captured_pieces().material, 0);
+        game.redo();
+        assert_eq!(game.captured_pieces(), CapturedPieces { pieces: [vec![Piece::Pawn], vec![Piece::Pawn, Piece::Pawn]], material: -1 });
+    }
+
+    #[test]
+    fn test_set_option_applies_and_notifies() {
+        struct Recorder(std::sync::Arc<std::sync::Mutex<Vec<EngineOption>>>);
+        impl MoveObserver for Recorder {
+            fn on_move(&mut self, _event: &MoveEvent) {}
+            fn on_option_change(&mut self, option: &EngineOption) {
+                self.0.lock().unwrap().push(*option);
+            }
+        }
+        let changes = std::sync::Arc::default();
+        let mut game = Game::new();
+        assert_eq!(game.options(), EngineOption::defaults());
+        game.add_observer(Box::new(Recorder(std::sync::Arc::clone(&changes))));
+        let level = SkillLevel::new(4).unwrap();
+        game.set_option(EngineOption::Skill(Some(level)));
+        game.set_option(EngineOption::Contempt(-25));
+        assert_eq!((game.skill_level(), game.contempt()), (Some(level), -25));
+        assert_eq!(*changes.lock().unwrap(), vec![EngineOption::Skill(Some(level)), EngineOption::Contempt(-25)]);
+        // a size between two tables gets the smaller one
+        let [hash, ..] = EngineOption::defaults();
+        let EngineOption::Hash(kilobytes) = hash else { unreachable!() };
+        game.set_option(EngineOption::Hash(3 * kilobytes));
+        assert_eq!(game.options()[0], EngineOption::Hash(2 * kilobytes));
+    }
+
+    #[test]
+    fn test_king_square_follows_moves_castling_and_undo() {
+        let mut game = Game::from_fen("r3k3/8/8/8/8/8/8/R3K2R w KQq - 0 1").unwrap();
+        game.play(square("e1"), square("g1")).unwrap();
+        assert_eq!(game.king_square(Color::White), Some(square("g1")));
+        game.play(square("e8"), square("c8")).unwrap();
+        assert_eq!(game.king_square(Color::Black), Some(square("c8")));
+        game.undo();
+        game.undo();
+        assert_eq!((game.king_square(Color::White), game.king_square(Color::Black)), (Some(square("e1")), Some(square("e8"))));
+        // the rook on e1 is not the king, even on the king's old square
+        game.play(square("e1"), square("f1")).unwrap();
+        game.play(square("a8"), square("a7")).unwrap();
+        game.play(square("h1"), square("h2")).unwrap();
+        assert_eq!(game.king_square(Color::White), Some(square("f1")));
+        let mut atomic = Game::from_fen("4k3/8/8/8/8/8/3q4/3RK3 w - - 0 1").unwrap();
+        atomic.set_variant(Variant::Atomic);
+        atomic.play(square("d1"), square("d2")).unwrap_err();
+        assert_eq!(atomic.king_square(Color::White), Some(square("e1")));
+    }
+
+    #[test]
+    fn test_material_piece_count_and_phase() {
+        let game = Game::new();
+        assert_eq!((game.material(Color::White), game.piece_count(Color::Black, Piece::Pawn)), (4000, 8));
+        assert_eq!(game.phase(), GamePhase::Opening);
+        let game = Game::from_fen("4k3/pp6/8/8/8/8/8/3RK3 w - - 0 40").unwrap();
+        assert_eq!((game.material(Color::White), game.material(Color::Black)), (500, 200));
+        assert_eq!((game.piece_count(Color::White, Piece::Rock), game.piece_count(Color::White, Piece::Queen)), (1, 0));
+        assert_eq!(game.phase(), GamePhase::Endgame);
+    }
+
+    #[test]
+    fn test_occupancy_follows_captures_and_undo() {
+        let mut game = Game::new();
+        let start = game.occupied();
+        assert_eq!((game.white_pieces(), game.black_pieces()), (BitBoard::new(0xffff), BitBoard::new(0xffff << 48)));
+        for (from, to) in [((File::E, Rank::Two), (File::E, Rank::Four)), ((File::D, Rank::Seven), (File::D, Rank::Five)), ((File::E, Rank::Four), (File::D, Rank::Five))] {
+            game.play(Square::new(from.0, from.1), Square::new(to.0, to.1)).unwrap();
+        }
+        let d5 = BitBoard::from(Square::new(File::D, Rank::Five));
+        assert_eq!((game.white_pieces() & d5, game.black_pieces() & d5), (d5, BitBoard::empty()));
+        assert_eq!(game.occupied().count(), 31);
+        while game.undo() {}
+        assert_eq!(game.occupied(), start);
+    }
 }
 
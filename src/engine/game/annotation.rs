@@ -0,0 +1,175 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::pieces::common::Color;
+use super::Game;
+
+/// An assessment of a move, written after it as a symbol such as `?!` and exported to PGN as
+/// the matching Numeric Annotation Glyph, e.g. `$6`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Nag {
+    /// `!`, a good move.
+    Good,
+    /// `?`, a mistake.
+    Mistake,
+    /// `!!`, a brilliant move.
+    Brilliant,
+    /// `??`, a blunder.
+    Blunder,
+    /// `!?`, an interesting move.
+    Interesting,
+    /// `?!`, a dubious move.
+    Dubious,
+}
+
+impl Nag {
+    const ALL: [Nag; 6] = [Nag::Good, Nag::Mistake, Nag::Brilliant, Nag::Blunder, Nag::Interesting, Nag::Dubious];
+
+    /// Returns the number of the glyph in PGN, from `1` for `!` to `6` for `?!`.
+    pub fn code(self) -> u8 {
+        self as u8 + 1
+    }
+
+    /// Returns the symbol written after the move, e.g. `?!`.
+    pub fn symbol(self) -> &'static str {
+        ["!", "?", "!!", "??", "!?", "?!"][self as usize]
+    }
+}
+
+impl FromStr for Nag {
+    type Err = String;
+
+    /// Reads a symbol such as `??`, or a glyph number such as `$4`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Nag::ALL.into_iter()
+            .find(|nag| nag.symbol() == s || s.strip_prefix('$') == Some(&nag.code().to_string()))
+            .ok_or_else(|| format!("Unknown annotation {}; use !, ?, !!, ??, !? or ?!.", s))
+    }
+}
+
+impl fmt::Display for Nag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// What the players attached to a move of the history.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MoveAnnotation {
+    pub(crate) nag: Option<Nag>,
+    pub(crate) comment: Option<String>,
+}
+
+impl Game {
+    /// Attaches a comment to the last move played, replacing any comment it had. It is
+    /// exported to PGN in braces after the move.
+    ///
+    /// # Arguments
+    /// - `text`: The comment, e.g. `That was a blunder`.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the comment was attached.
+    /// - `Err(String)`: If no move has been played, or the comment is empty or holds a `}`,
+    ///   which would end it early in PGN.
+    pub fn comment_last_move(&mut self, text: &str) -> Result<(), String> {
+        let text = text.trim();
+        if text.is_empty() || text.contains('}') {
+            return Err("A comment cannot be empty or contain '}'.".to_string());
+        }
+        let ply = self.history.len();
+        self.annotation_mut(ply)?.comment = Some(text.to_string());
+        Ok(())
+    }
+
+    /// Annotates a move of the history with a symbol such as `?!`, replacing any it had.
+    ///
+    /// # Arguments
+    /// - `ply`: The number of the half-move, starting at `1` for the first move played, as
+    ///   with `goto`.
+    /// - `nag`: The annotation.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the move was annotated.
+    /// - `Err(String)`: If that half-move has not been played.
+    pub fn annotate(&mut self, ply: usize, nag: Nag) -> Result<(), String> {
+        self.annotation_mut(ply)?.nag = Some(nag);
+        Ok(())
+    }
+
+    /// Returns the annotation and the comment of a half-move of the history, numbered as in
+    /// [`Game::annotate`].
+    pub fn annotation(&self, ply: usize) -> (Option<Nag>, Option<&str>) {
+        match ply.checked_sub(1).filter(|idx| *idx < self.history.len()).and_then(|idx| self.annotations.get(idx)) {
+            Some(annotation) => (annotation.nag, annotation.comment.as_deref()),
+            None => (None, None),
+        }
+    }
+
+    /// Returns the annotated moves one per line, e.g. `2. Nf3?! That was a blunder`, or an
+    /// empty list if no move of the history is annotated.
+    pub(crate) fn annotation_lines(&self) -> Vec<String> {
+        let mut replay = self.clone();
+        while replay.undo() {}
+        let mut lines = Vec::new();
+        for ply in 1..=self.history.len() {
+            let number = match replay.turn {
+                Color::White => format!("{}.", replay.fullmove_number),
+                Color::Black => format!("{}...", replay.fullmove_number),
+            };
+            let mv = self.history[ply - 1].mv;
+            let san = replay.move_san(mv);
+            replay.redo();
+            let (nag, comment) = self.annotation(ply);
+            if nag.is_none() && comment.is_none() {
+                continue;
+            }
+            let nag = nag.map_or("", Nag::symbol);
+            lines.push(format!("{} {}{} {}", number, san, nag, comment.unwrap_or_default()).trim_end().to_string());
+        }
+        lines
+    }
+
+    /// Returns the annotation of a half-move, numbered from `1`, for editing.
+    fn annotation_mut(&mut self, ply: usize) -> Result<&mut MoveAnnotation, String> {
+        if ply == 0 || ply > self.history.len() {
+            return Err(match self.history.len() {
+                0 => "No move has been played yet.".to_string(),
+                played => format!("There is no half-move {}; {} have been played.", ply, played),
+            });
+        }
+        if self.annotations.len() < ply {
+            self.annotations.resize(ply, MoveAnnotation::default());
+        }
+        Ok(&mut self.annotations[ply - 1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotations_are_exported_and_survive_undo() {
+        let mut game = Game::new();
+        for san in ["e4", "e5", "Qh5"] {
+            game.play_san(san).unwrap();
+        }
+        game.comment_last_move("Too early for the queen").unwrap();
+        game.annotate(3, "?!".parse().unwrap()).unwrap();
+        game.annotate(1, "$1".parse().unwrap()).unwrap();
+        assert!(game.annotate(4, Nag::Blunder).unwrap_err().starts_with("There is no half-move 4"));
+        assert!(game.comment_last_move("a } b").is_err());
+        assert_eq!(game.annotation_lines(), ["1. e4!", "2. Qh5?! Too early for the queen"]);
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("1. e4 $1 e5 2. Qh5 $6 {Too early for the queen} *"), "{}", pgn);
+        game.undo();
+        game.redo();
+        assert_eq!(game.annotation(3), (Some(Nag::Dubious), Some("Too early for the queen")));
+        // a new move replaces the line, with its annotations
+        game.undo();
+        game.play_san("Nf3").unwrap();
+        assert_eq!(game.annotation(3), (None, None));
+        let restored = Game::from_json(&game.to_json()).unwrap();
+        assert_eq!(restored.annotation(1), (Some(Nag::Good), None));
+    }
+}
@@ -0,0 +1,171 @@
+use strum::IntoEnumIterator;
+use crate::bitboard::BitBoard;
+use crate::pieces::attacks;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::{File, Rank, Square};
+use super::variant::VariantRules;
+use super::{Game, GameResult, Move};
+
+/// The rules of Atomic chess, those of [`super::Variant::Atomic`].
+pub(crate) struct AtomicRules;
+
+impl VariantRules for AtomicRules {
+    /// Every capture explodes.
+    fn has_side_effects(&self, mv: &Move) -> bool {
+        mv.captured.is_some()
+    }
+
+    fn after_move(&self, game: &mut Game, mv: &Move) {
+        game.explode(mv);
+    }
+
+    fn is_legal(&self, game: &Game, from: Square, to: Square, piece: Piece, side: Color) -> bool {
+        game.is_atomic_legal(from, to, piece, side)
+    }
+
+    /// Touching kings are never in check, as the enemy king could only capture by exploding.
+    fn can_be_checked(&self, game: &Game) -> bool {
+        !game.kings_touch()
+    }
+
+    fn kings_can_be_lost(&self) -> bool {
+        true
+    }
+
+    /// Losing the king to an explosion loses the game.
+    fn result(&self, game: &Game) -> Option<GameResult> {
+        game.has_lost_king().then_some(GameResult::KingExploded(game.turn))
+    }
+}
+
+/// The explosions and the legality checks behind [`AtomicRules`].
+impl Game {
+    /// Returns the squares a capture on `center` clears: the square itself and the eight
+    /// around it. Pawns around the center survive the blast.
+    fn blast(center: Square) -> BitBoard {
+        attacks::king(center) | BitBoard::from(center)
+    }
+
+    /// Removes from the location masks the capturing piece, now on `center`, and every piece
+    /// other than a pawn next to it, of either side.
+    fn explode_on(pieces_location: &mut [[BitBoard; 6]; 2], center: Square) {
+        let blast = Self::blast(center);
+        for side in pieces_location.iter_mut() {
+            for (piece, board) in Piece::iter().zip(side.iter_mut()) {
+                let cleared = if piece == Piece::Pawn { BitBoard::from(center) } else { blast };
+                *board &= !cleared;
+            }
+        }
+    }
+
+    /// Applies the explosion of a capture that has just been played on the board and drops
+    /// the castling rights of kings and rooks that were blown up. The masks are not updated.
+    fn explode(&mut self, mv: &Move) {
+        Self::explode_on(&mut self.pieces_location, mv.to);
+        for side in Color::iter() {
+            let back_rank = if side == Color::White { Rank::One } else { Rank::Eight };
+            let has = |piece: Piece, file: File| !(self.pieces(side, piece) & BitBoard::from(Square::new(file, back_rank))).is_empty();
+            let king_home = has(Piece::King, File::E);
+            let kept = [king_home && has(Piece::Rock, File::H), king_home && has(Piece::Rock, File::A)];
+            for (king_side, kept) in [true, false].into_iter().zip(kept) {
+                if !kept {
+                    self.castling_rights.revoke(side, king_side);
+                }
+            }
+        }
+    }
+
+    /// Checks whether a pseudo-legal move is legal in Atomic chess. Kings never capture. A
+    /// move that blows up the mover's own king is illegal, one that blows up the enemy king is
+    /// always legal, and otherwise the mover's king must not be attacked afterwards, unless it
+    /// touches the enemy king, which could only capture it by exploding itself.
+    fn is_atomic_legal(&self, from: Square, to: Square, piece: Piece, side: Color) -> bool {
+        let enemies = self.side_pieces(side.opposite());
+        let to_bit = BitBoard::from(to);
+        let is_en_passant = piece == Piece::Pawn && Some(to) == self.en_passant;
+        let is_capture = !(enemies & to_bit).is_empty() || is_en_passant;
+        if piece == Piece::King && is_capture {
+            return false;
+        }
+        let mut pieces_location = self.pieces_location;
+        let mut captured = to_bit;
+        if is_en_passant {
+            captured |= BitBoard::from(Square::new(to.file(), from.rank()));
+        }
+        for board in pieces_location[usize::from(side.opposite())].iter_mut() {
+            *board &= !captured;
+        }
+        let piece_board = &mut pieces_location[usize::from(side)][usize::from(piece)];
+        *piece_board = (*piece_board & !BitBoard::from(from)) | to_bit;
+        if is_capture {
+            Self::explode_on(&mut pieces_location, to);
+        }
+        let own_king = pieces_location[usize::from(side)][usize::from(Piece::King)].into_iter().next();
+        let enemy_king = pieces_location[usize::from(side.opposite())][usize::from(Piece::King)];
+        match own_king {
+            None => false,
+            Some(_) if enemy_king.is_empty() => true,
+            Some(king) if !(attacks::king(king) & enemy_king).is_empty() => true,
+            Some(king) => !Self::is_square_attacked(&pieces_location, king, side.opposite()),
+        }
+    }
+
+    /// Checks whether the kings stand next to each other, which in Atomic chess means neither
+    /// is in check.
+    fn kings_touch(&self) -> bool {
+        match (self.king_square(Color::White), self.king_square(Color::Black)) {
+            (Some(white), Some(black)) => !(attacks::king(white) & BitBoard::from(black)).is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Checks whether the side to move has lost its king, which only happens when an Atomic
+    /// capture blew it up.
+    pub(crate) fn has_lost_king(&self) -> bool {
+        self.king_square(self.turn).is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::game::Variant;
+    use crate::square::square;
+
+    fn atomic(fen: &str) -> Game {
+        let mut game = Game::from_fen(fen).unwrap();
+        game.set_variant(Variant::Atomic);
+        game
+    }
+
+    #[test]
+    fn test_capture_explodes_neighbours_but_not_pawns() {
+        let mut game = atomic("r3k3/8/8/3pn3/2P1N3/8/8/4K2R w Kq - 0 1");
+        let before = game.zobrist();
+        game.play(square("c4"), square("d5")).unwrap();
+        for name in ["c4", "d5", "e5", "e4"] {
+            assert_eq!(game.piece_at(square(name)), None, "{} should be empty", name);
+        }
+        assert!(game.has_castling_rights());
+        game.undo();
+        assert_eq!(game.zobrist(), before);
+        assert_eq!(game.piece_at(square("e4")), Some((Piece::Knight, Color::White)));
+    }
+
+    #[test]
+    fn test_king_rules_and_winning_by_explosion() {
+        // the king may not capture, and a capture next to the own king is illegal
+        let game = atomic("4k3/8/8/8/8/8/3qP3/4K3 w - - 0 1");
+        assert!(!game.legal_moves(square("e1")).contains(&square("d2")));
+        let game = atomic("4k3/8/8/8/8/8/3q4/3RK3 w - - 0 1");
+        assert!(!game.legal_moves(square("d1")).contains(&square("d2")));
+        // blowing up the king next to the captured piece ends the game
+        let mut game = atomic("3qk3/8/8/8/8/8/8/3QK3 w - - 0 1");
+        game.play(square("d1"), square("d8")).unwrap();
+        assert_eq!(game.game_result(), Some(GameResult::KingExploded(Color::Black)));
+        // touching kings do not give check
+        let game = atomic("8/8/8/8/8/3k4/3K3r/8 w - - 0 1");
+        assert!(!game.is_checked());
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::pieces::common::Color;
+
+/// The castling moves each side keeps the right to, one bit per side and wing: White
+/// king-side, White queen-side, Black king-side and Black queen-side, from the lowest bit up.
+///
+/// A right is lost for good when the king moves, or when its rook leaves its corner or is
+/// captured there. Saved games keep writing the rights as two pairs of flags, indexed by side,
+/// then king-side (`0`) or queen-side (`1`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "[[bool; 2]; 2]", into = "[[bool; 2]; 2]")]
+pub struct CastlingRights(u8);
+
+impl CastlingRights {
+    /// No side may castle.
+    pub const NONE: Self = Self(0);
+    /// Both sides may castle on both wings, as in the start position.
+    pub const ALL: Self = Self(0b1111);
+
+    /// Returns the bit of one right, whose index is also its position in the FEN field order.
+    fn bit(side: Color, king_side: bool) -> u8 {
+        1 << (usize::from(side) * 2 + usize::from(!king_side))
+    }
+
+    /// Whether `side` may still castle on the king-side (`true`) or the queen-side wing.
+    pub fn has(self, side: Color, king_side: bool) -> bool {
+        self.0 & Self::bit(side, king_side) != 0
+    }
+
+    /// Gives `side` the right to castle on one wing.
+    pub fn grant(&mut self, side: Color, king_side: bool) {
+        self.0 |= Self::bit(side, king_side);
+    }
+
+    /// Takes the right to castle on one wing away from `side`.
+    pub fn revoke(&mut self, side: Color, king_side: bool) {
+        self.0 &= !Self::bit(side, king_side);
+    }
+
+    /// Takes both rights away from `side`, e.g. after a king move.
+    pub fn revoke_side(&mut self, side: Color) {
+        self.revoke(side, true);
+        self.revoke(side, false);
+    }
+
+    /// Whether neither side may castle.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the rights as four bits, in the order described on [`CastlingRights`].
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Creates rights from four bits, in the order described on [`CastlingRights`]; higher
+    /// bits are ignored.
+    pub fn from_bits(bits: u8) -> Self {
+        Self(bits & Self::ALL.0)
+    }
+}
+
+impl From<[[bool; 2]; 2]> for CastlingRights {
+    fn from(rights: [[bool; 2]; 2]) -> Self {
+        let mut castling = Self::NONE;
+        for side in [Color::White, Color::Black] {
+            for (idx, king_side) in [true, false].into_iter().enumerate() {
+                if rights[usize::from(side)][idx] {
+                    castling.grant(side, king_side);
+                }
+            }
+        }
+        castling
+    }
+}
+
+impl From<CastlingRights> for [[bool; 2]; 2] {
+    fn from(rights: CastlingRights) -> Self {
+        [Color::White, Color::Black].map(|side| [rights.has(side, true), rights.has(side, false)])
+    }
+}
+
+impl fmt::Display for CastlingRights {
+    /// Formats the rights as the castling field of a FEN, e.g. `KQkq`, `Kq` or `-`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "-");
+        }
+        for (side, king_side, symbol) in [(Color::White, true, 'K'), (Color::White, false, 'Q'), (Color::Black, true, 'k'), (Color::Black, false, 'q')] {
+            if self.has(side, king_side) {
+                write!(f, "{}", symbol)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CastlingRights {
+    type Err = String;
+
+    /// Reads the castling field of a FEN.
+    ///
+    /// # Returns
+    /// - `Ok(CastlingRights)`: The rights named by the field, none for `-`.
+    /// - `Err(String)`: If a character is not one of `KQkq`.
+    fn from_str(castling: &str) -> Result<Self, Self::Err> {
+        let mut rights = Self::NONE;
+        if castling == "-" {
+            return Ok(rights);
+        }
+        for symbol in castling.chars() {
+            let (side, king_side) = match symbol {
+                'K' => (Color::White, true),
+                'Q' => (Color::White, false),
+                'k' => (Color::Black, true),
+                'q' => (Color::Black, false),
+                other => return Err(format!("Invalid castling right '{}'.", other)),
+            };
+            rights.grant(side, king_side);
+        }
+        Ok(rights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rights_round_trip_through_fen_and_save_files() {
+        let mut rights = "Kq".parse::<CastlingRights>().unwrap();
+        assert!(rights.has(Color::White, true) && !rights.has(Color::White, false) && rights.has(Color::Black, false));
+        assert_eq!(rights.to_string(), "Kq");
+        assert_eq!(serde_json::to_string(&rights).unwrap(), "[[true,false],[false,true]]");
+        assert_eq!(serde_json::from_str::<CastlingRights>("[[true,false],[false,true]]").unwrap(), rights);
+        rights.revoke_side(Color::Black);
+        assert_eq!((rights.bits(), rights.to_string()), (0b0001, "K".to_string()));
+        assert_eq!(CastlingRights::ALL.to_string(), "KQkq");
+        assert!("KX".parse::<CastlingRights>().is_err());
+    }
+}
@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use crate::bitboard::BitBoard;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::{Rank, Square};
+use super::{CastlingRights, Game};
+
+/// Bit of the flags byte set when Black is to move.
+const FLAG_BLACK_TO_MOVE: u8 = 1;
+/// First bit of the four castling rights (`K`, `Q`, `k`, `q`) in the flags byte.
+const FLAG_CASTLING_SHIFT: u8 = 1;
+/// Bit of the flags byte set when an en passant target byte follows.
+const FLAG_EN_PASSANT: u8 = 1 << 5;
+/// Size of the fixed header: occupancy (8), flags (1), halfmove clock (2) and fullmove number (2).
+const HEADER_LEN: usize = 13;
+
+/// A position packed into at most 30 bytes, for sending over the network or storing.
+///
+/// Layout (integers little-endian):
+/// - 8 bytes: occupancy mask, bit `i` set if square `i` (`a1` = 0) holds a piece;
+/// - 1 byte: flags, side to move, castling rights `KQkq` and whether en passant is possible;
+/// - 2 bytes: halfmove clock, 2 bytes: fullmove number;
+/// - 1 byte: en passant target square, only if the flag is set;
+/// - one nibble per occupied square, in square order: `color * 6 + piece`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PackedPosition(Vec<u8>);
+
+/// A move packed into 16 bits: origin in bits 0-5, destination in bits 6-11 and the
+/// promotion piece (`0` for none) in bits 12-14.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PackedMove(u16);
+
+impl PackedPosition {
+    /// Wraps bytes received from a peer; they are only checked when unpacked.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the encoded bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Formats the bytes as lowercase hexadecimal.
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Parses bytes written by [`PackedPosition::to_hex`].
+    ///
+    /// # Returns
+    /// - `Err(String)`: If the text has an odd length or a non-hexadecimal digit.
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+            return Err("Packed position must be an even number of hex digits.".to_string());
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|idx| u8::from_str_radix(&hex[idx..idx + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map(Self::from_bytes)
+            .map_err(|_| format!("Invalid hex digit in packed position '{}'.", hex))
+    }
+}
+
+impl PackedMove {
+    /// Packs a move.
+    ///
+    /// # Arguments
+    /// - `from`: The origin square.
+    /// - `to`: The destination square.
+    /// - `promotion`: The piece a pawn promotes to, if any.
+    pub fn new(from: Square, to: Square, promotion: Option<Piece>) -> Self {
+        let promotion = promotion.map_or(0, |piece| usize::from(piece) as u16);
+        Self(usize::from(from) as u16 | (usize::from(to) as u16) << 6 | promotion << 12)
+    }
+
+    /// Unpacks the move, rejecting values no encoder produces.
+    ///
+    /// Only peers receiving moves need this; the game itself only packs them.
+    ///
+    /// # Returns
+    /// - `Ok((from, to, promotion))`: The decoded move.
+    /// - `Err(String)`: If the spare bit is set or the promotion piece is not a knight,
+    ///   bishop, rook or queen.
+    pub fn unpack(&self) -> Result<(Square, Square, Option<Piece>), String> {
+        if self.0 >> 15 != 0 {
+            return Err(format!("Invalid packed move {:#06x}: spare bit set.", self.0));
+        }
+        let square = |bits: u16| Square::try_from(bits as usize & 0x3f).map_err(|_| "Invalid square.".to_string());
+        let promotion = match (self.0 >> 12) & 0x7 {
+            0 => None,
+            idx => match Piece::iter().nth(idx as usize) {
+                Some(piece @ (Piece::Knight | Piece::Rock | Piece::Bishop | Piece::Queen)) => Some(piece),
+                _ => return Err(format!("Invalid promotion in packed move {:#06x}.", self.0)),
+            },
+        };
+        Ok((square(self.0)?, square(self.0 >> 6)?, promotion))
+    }
+
+    /// Returns the 16-bit encoding.
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+}
+
+impl Game {
+    /// Packs the position with the bit layout described on [`PackedPosition`].
+    pub fn pack(&self) -> PackedPosition {
+        let board = self.get_all_position();
+        let occupancy = u64::from(board.occupied());
+        let mut flags = if self.turn == Color::Black { FLAG_BLACK_TO_MOVE } else { 0 };
+        flags |= self.castling_rights.bits() << FLAG_CASTLING_SHIFT;
+        if self.en_passant.is_some() {
+            flags |= FLAG_EN_PASSANT;
+        }
+        let mut bytes = occupancy.to_le_bytes().to_vec();
+        bytes.push(flags);
+        bytes.extend((self.halfmove_clock.min(u16::MAX as u32) as u16).to_le_bytes());
+        bytes.extend((self.fullmove_number.min(u16::MAX as u32) as u16).to_le_bytes());
+        if let Some(square) = self.en_passant {
+            bytes.push(usize::from(square) as u8);
+        }
+        let nibbles = board.iter()
+            .flatten()
+            .map(|(piece, color)| (usize::from(*color) * 6 + usize::from(*piece)) as u8)
+            .collect::<Vec<u8>>();
+        bytes.extend(nibbles.chunks(2).map(|pair| pair[0] | pair.get(1).map_or(0, |high| high << 4)));
+        PackedPosition(bytes)
+    }
+
+    /// Unpacks a position, validating it like a FEN.
+    ///
+    /// # Returns
+    /// - `Ok(Game)`: The decoded game.
+    /// - `Err(String)`: If the bytes are truncated, have trailing data, contain an unknown piece
+    ///   code or an impossible en passant square, or describe an unplayable position.
+    pub fn unpack(packed: &PackedPosition) -> Result<Self, String> {
+        let bytes = packed.as_bytes();
+        if bytes.len() < HEADER_LEN {
+            return Err(format!("Packed position is too short ({} bytes).", bytes.len()));
+        }
+        let occupancy = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
+        let flags = bytes[8];
+        if flags >> 6 != 0 {
+            return Err(format!("Invalid flags {:#04x} in packed position.", flags));
+        }
+        let halfmove_clock = u16::from_le_bytes([bytes[9], bytes[10]]) as u32;
+        let fullmove_number = u16::from_le_bytes([bytes[11], bytes[12]]) as u32;
+        let mut offset = HEADER_LEN;
+        let en_passant = if flags & FLAG_EN_PASSANT != 0 {
+            let square = bytes.get(offset).and_then(|idx| Square::try_from(*idx as usize).ok())
+                .ok_or("Missing or invalid en passant square in packed position.")?;
+            if square.rank() != Rank::Three && square.rank() != Rank::Six {
+                return Err(format!("En passant square {} must be on rank 3 or 6.", square));
+            }
+            offset += 1;
+            Some(square)
+        } else {
+            None
+        };
+        let count = occupancy.count_ones() as usize;
+        let expected = offset + count.div_ceil(2);
+        if bytes.len() != expected {
+            return Err(format!("Packed position must be {} bytes, found {}.", expected, bytes.len()));
+        }
+        let mut pieces_location = [[BitBoard::empty(); 6]; 2];
+        for (nth, square_idx) in (0..64).filter(|idx| occupancy >> idx & 1 == 1).enumerate() {
+            let code = (bytes[offset + nth / 2] >> (4 * (nth % 2))) & 0xf;
+            if code >= 12 {
+                return Err(format!("Invalid piece code {} in packed position.", code));
+            }
+            let square = Square::try_from(square_idx).map_err(|_| "Invalid square.".to_string())?;
+            pieces_location[code as usize / 6][code as usize % 6] |= BitBoard::from(square);
+        }
+        if count % 2 == 1 && bytes[expected - 1] >> 4 != 0 {
+            return Err("Packed position has a non-zero padding nibble.".to_string());
+        }
+        let turn = if flags & FLAG_BLACK_TO_MOVE != 0 { Color::Black } else { Color::White };
+        let castling_rights = CastlingRights::from_bits(flags >> FLAG_CASTLING_SHIFT);
+        let mut game = Self::from_parts(pieces_location, turn, castling_rights, halfmove_clock, fullmove_number)?;
+        game.en_passant = en_passant;
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_pack_start_position() {
+        let packed = Game::new().pack();
+        assert_eq!(packed.as_bytes().len(), HEADER_LEN + 16);
+        assert_eq!(Game::unpack(&packed).unwrap().fen(), Game::new().fen());
+    }
+
+    #[test]
+    fn test_pack_keeps_en_passant_target() {
+        let mut game = Game::new();
+        game.play(square("e2"), square("e4")).unwrap();
+        let unpacked = Game::unpack(&game.pack()).unwrap();
+        assert_eq!(unpacked.en_passant, game.en_passant);
+        assert_eq!(unpacked.fen(), game.fen());
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let packed = Game::new().pack();
+        assert_eq!(PackedPosition::from_hex(&packed.to_hex()), Ok(packed));
+        assert!(PackedPosition::from_hex("abc").is_err());
+        assert!(PackedPosition::from_hex("zz").is_err());
+    }
+
+    #[test]
+    fn test_packed_move_round_trip() {
+        let from = square("e7");
+        let to = square("e8");
+        let packed = PackedMove::new(from, to, Some(Piece::Knight));
+        assert_eq!(packed.unpack(), Ok((from, to, Some(Piece::Knight))));
+        assert!(PackedMove(0x5000).unpack().is_err(), "a king is not a promotion piece");
+    }
+
+    proptest! {
+        #[test]
+        fn fuzz_unpack_never_panics(bytes in proptest::collection::vec(any::<u8>(), 0..40)) {
+            let _ = Game::unpack(&PackedPosition::from_bytes(bytes));
+        }
+
+        #[test]
+        fn fuzz_unpack_accepts_only_canonical_encodings(bytes in proptest::collection::vec(any::<u8>(), 0..40)) {
+            let packed = PackedPosition::from_bytes(bytes);
+            if let Ok(game) = Game::unpack(&packed) {
+                prop_assert_eq!(game.pack(), packed);
+            }
+        }
+
+        #[test]
+        fn fuzz_unpack_move_never_panics(bits in any::<u16>()) {
+            if let Ok((from, to, promotion)) = PackedMove(bits).unpack() {
+                prop_assert_eq!(PackedMove::new(from, to, promotion).bits(), bits);
+            }
+        }
+
+        #[test]
+        fn pack_round_trips_random_games(choices in proptest::collection::vec(any::<usize>(), 0..40)) {
+            let mut game = Game::new();
+            for choice in choices {
+                let moves = game.all_legal_moves(game.turn);
+                if moves.is_empty() {
+                    break;
+                }
+                let (from, to) = moves[choice % moves.len()];
+                game.play_legal(from, to);
+            }
+            let unpacked = Game::unpack(&game.pack()).unwrap();
+            prop_assert_eq!(unpacked.fen(), game.fen());
+            prop_assert_eq!(unpacked.en_passant, game.en_passant);
+        }
+    }
+}
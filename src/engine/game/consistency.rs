@@ -0,0 +1,78 @@
+use strum::IntoEnumIterator;
+use crate::bitboard::BitBoard;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use super::Game;
+
+impl Game {
+    /// Recomputes every derived part of the state from the location masks and panics if it
+    /// differs from the incrementally maintained one: the movement and capture masks, the
+    /// mobility counts, the threat board, and the absence of two pieces on one square.
+    ///
+    /// Only compiled in debug builds, where it runs after each [`Game::make_move`] and
+    /// [`Game::unmake_move`] to catch incremental update bugs as soon as they happen.
+    ///
+    /// # Arguments
+    /// - `context`: Describes the move just made or taken back, for the panic message.
+    pub(crate) fn assert_consistent(&self, context: &str) {
+        let mut occupied = BitBoard::empty();
+        for side in Color::iter() {
+            for piece in Piece::iter() {
+                let (side_idx, piece_idx) = (usize::from(side), usize::from(piece));
+                let location = self.pieces_location[side_idx][piece_idx];
+                assert!((occupied & location).is_empty(), "{}: two pieces share {} ({:?} {:?})", context, occupied & location, side, piece);
+                occupied |= location;
+                let opponents = Self::combine(&self.pieces_location[usize::from(side.opposite())]);
+                let (movement, capture, mobility) = location.into_iter()
+                    .map(|square| self.compute_attack_threat_and_move_to_given(square, piece, side))
+                    .fold((BitBoard::empty(), BitBoard::empty(), 0), |(all_moves, all_captures, count), (moves, captures)| {
+                        (all_moves | moves, all_captures | captures, count + (moves | (captures & opponents)).count())
+                    });
+                assert_eq!(
+                    self.pieces_movement[side_idx][piece_idx], movement,
+                    "{}: movement mask of {:?} {:?} is stale", context, side, piece
+                );
+                assert_eq!(
+                    self.pieces_capture_movement[side_idx][piece_idx], capture,
+                    "{}: capture mask of {:?} {:?} is stale", context, side, piece
+                );
+                assert_eq!(
+                    self.pieces_mobility[side_idx][piece_idx], mobility,
+                    "{}: mobility of {:?} {:?} is stale", context, side, piece
+                );
+            }
+        }
+        let occupancy = [Color::White, Color::Black].map(|side| Self::combine(&self.pieces_location[usize::from(side)]));
+        assert_eq!(
+            [self.white_pieces(), self.black_pieces()], occupancy,
+            "{}: occupancy is stale, the pieces stand on {} and {}", context, occupancy[0].to_fen_occupancy(), occupancy[1].to_fen_occupancy()
+        );
+        let mut threats = super::ThreatBoard::new();
+        threats.update(&self.pieces_capture_movement);
+        assert!(self.threats == threats, "{}: threat board is stale", context);
+        if let Err(err) = self.validate() {
+            panic!("{}: {}", context, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "movement mask of White Knight is stale")]
+    fn test_stale_movement_mask_is_caught() {
+        let mut game = Game::new();
+        game.pieces_movement[usize::from(Color::White)][usize::from(Piece::Knight)] = BitBoard::empty();
+        game.assert_consistent("test");
+    }
+
+    #[test]
+    fn test_make_and_unmake_keep_the_state_consistent() {
+        let mut game = Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        // every make/unmake checks itself in debug builds
+        assert_eq!(game.perft(2), 2039);
+        game.assert_consistent("after perft");
+    }
+}
@@ -0,0 +1,94 @@
+use std::error::Error;
+use std::fmt;
+use crate::pieces::Piece;
+use crate::square::Square;
+
+/// Why a move was refused. Each variant carries what a message needs, so front ends can word
+/// it their own way and tests can check the exact reason.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MoveError {
+    /// The move was written in notation that could not be parsed.
+    InvalidNotation,
+    /// The source square is empty.
+    NoPieceAtSource(Square),
+    /// The piece on the source square belongs to the side not to move.
+    NotYourPiece(Square),
+    /// The piece cannot reach the target square, even ignoring checks.
+    IllegalTarget { piece: Piece, from: Square, to: Square },
+    /// The piece could reach the target square, but the move would leave its king in check.
+    LeavesKingInCheck { piece: Piece, from: Square, to: Square },
+    /// The king or the rook has already moved, so the right to castle on that side is lost.
+    CastlingRightLost { king_side: bool },
+    /// A piece stands between the king and the rook.
+    CastlingBlocked,
+    /// The king is in check, and may not castle out of it.
+    CastlingOutOfCheck,
+    /// The king would cross or land on an attacked square.
+    CastlingThroughCheck,
+    /// No legal move matches the algebraic notation.
+    NoMatchingMove { piece: Piece, to: Square },
+    /// Several legal moves match the algebraic notation; a file or rank is needed to tell them apart.
+    AmbiguousSan { piece: Piece, to: Square },
+    /// A pawn can only promote to a queen, a rook, a bishop or a knight.
+    UnsupportedPromotion(Piece),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::InvalidNotation => write!(f, "Invalid move notation."),
+            MoveError::NoPieceAtSource(square) => write!(f, "There is no piece on {}.", square),
+            MoveError::NotYourPiece(square) => write!(f, "The piece on {} belongs to your opponent.", square),
+            MoveError::IllegalTarget { piece, from, to } => write!(f, "The {:?} on {} cannot move to {}.", piece, from, to),
+            MoveError::LeavesKingInCheck { piece, from, to } => {
+                write!(f, "Moving the {:?} from {} to {} would leave your king in check.", piece, from, to)
+            }
+            MoveError::CastlingRightLost { king_side: true } => write!(f, "King-side castling is not allowed."),
+            MoveError::CastlingRightLost { king_side: false } => write!(f, "Queen-side castling is not allowed."),
+            MoveError::CastlingBlocked => write!(f, "Castle blocked."),
+            MoveError::CastlingOutOfCheck => write!(f, "You cannot castle out of check."),
+            MoveError::CastlingThroughCheck => write!(f, "Castle attacked."),
+            MoveError::NoMatchingMove { piece, to } => write!(f, "No {:?} can move to {}.", piece, to),
+            MoveError::AmbiguousSan { piece, to } => write!(f, "Ambiguous move, more than one {:?} can move to {}.", piece, to),
+            MoveError::UnsupportedPromotion(piece) => write!(f, "A pawn cannot promote to a {:?}.", piece),
+        }
+    }
+}
+
+impl Error for MoveError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::game::Game;
+    use crate::square::square;
+
+    #[test]
+    fn test_move_errors_name_the_reason() {
+        // the e2 knight is pinned against the king by the e8 rook
+        let mut game = Game::from_fen("4r1k1/8/8/8/8/8/4N3/R3K2R w Q - 0 1").unwrap();
+        assert_eq!(game.play(square("d4"), square("d5")), Err(MoveError::NoPieceAtSource(square("d4"))));
+        assert_eq!(game.play(square("g8"), square("g7")), Err(MoveError::NotYourPiece(square("g8"))));
+        assert_eq!(
+            game.play(square("a1"), square("b2")),
+            Err(MoveError::IllegalTarget { piece: Piece::Rock, from: square("a1"), to: square("b2") })
+        );
+        assert_eq!(
+            game.play(square("e2"), square("c3")),
+            Err(MoveError::LeavesKingInCheck { piece: Piece::Knight, from: square("e2"), to: square("c3") })
+        );
+        assert_eq!(game.play_san("O-O"), Err(MoveError::CastlingRightLost { king_side: true }));
+        assert_eq!(game.play_san("Qd1"), Err(MoveError::NoMatchingMove { piece: Piece::Queen, to: square("d1") }));
+        assert_eq!(game.play_san("e9"), Err(MoveError::InvalidNotation));
+    }
+
+    #[test]
+    fn test_castling_errors() {
+        let mut game = Game::from_fen("r3k2r/8/8/8/8/8/8/R3KB1R w KQkq - 0 1").unwrap();
+        assert_eq!(game.play_san("O-O"), Err(MoveError::CastlingBlocked));
+        let mut game = Game::from_fen("r3k2r/8/8/8/8/8/8/R2rK2R w KQkq - 0 1").unwrap();
+        assert_eq!(game.play_san("O-O"), Err(MoveError::CastlingOutOfCheck));
+        let mut game = Game::from_fen("r3kr2/8/8/8/8/8/8/R3K2R w KQq - 0 1").unwrap();
+        assert_eq!(game.play_san("O-O"), Err(MoveError::CastlingThroughCheck));
+    }
+}
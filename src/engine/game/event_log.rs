@@ -0,0 +1,284 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::pieces::common::Color;
+use crate::gui::san;
+use crate::pieces::Piece;
+use crate::square::Square;
+use super::{Game, Variant};
+
+/// Something that happened in a game, as written to the event log.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event")]
+pub enum GameEvent {
+    /// The log was attached to a game in this position; replaying starts over from here.
+    Start {
+        fen: String,
+        #[serde(default)]
+        variant: Variant,
+    },
+    /// A move played by a player, the engine or the peer.
+    Move {
+        side: Color,
+        from: Square,
+        to: Square,
+        /// The piece a pawn promoted to; missing from older logs, where it was a queen.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        promotion: Option<Piece>,
+    },
+    /// A move entered at this terminal and refused, with the reason given to the player: its
+    /// squares, or the SAN as typed if it named no move of the position.
+    Rejected {
+        side: Color,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from: Option<Square>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        to: Option<Square>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        san: Option<String>,
+        reason: String,
+    },
+    OfferDraw { side: Color },
+    AcceptDraw { side: Color },
+    DeclineDraw { side: Color },
+    Resign { side: Color },
+    /// The flag of `side` fell, ending the game on time.
+    FlagFall { side: Color },
+    /// The given number of half-moves was taken back.
+    Undo { plies: usize },
+    Redo,
+    Goto { ply: usize },
+    /// The board editor finished with this position.
+    Setup { fen: String },
+    /// A saved game was loaded, as written by [`Game::to_json`].
+    Load { saved_game: String },
+}
+
+/// One line of the event log: the event and when it happened.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct LoggedEvent {
+    /// Milliseconds since the Unix epoch.
+    at_ms: u64,
+    #[serde(flatten)]
+    event: GameEvent,
+}
+
+/// Appends every action of a game to a file, one JSON object per line, so a reported incident
+/// can be replayed move for move with [`Game::from_event_log`].
+pub struct EventLog {
+    file: File,
+}
+
+impl EventLog {
+    /// Opens the log file for appending; the events of earlier games in the file are kept.
+    ///
+    /// # Arguments
+    /// - `path`: The file to append to.
+    ///
+    /// # Returns
+    /// - `Ok(EventLog)`: The log.
+    /// - `Err(String)`: If the file cannot be opened.
+    pub fn open(path: &str) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|err| format!("Cannot open event log {}: {}", path, err))?;
+        Ok(Self { file })
+    }
+
+    fn append(&mut self, event: GameEvent) {
+        let at_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_millis() as u64).unwrap_or_default();
+        let line = serde_json::to_string(&LoggedEvent { at_ms, event }).expect("an event always serializes");
+        if let Err(err) = writeln!(self.file, "{}", line) {
+            eprintln!("Cannot write to the event log: {}", err);
+        }
+    }
+}
+
+impl Game {
+    /// Writes every following action of the game to `log`, starting with the current position.
+    pub fn set_event_log(&mut self, log: EventLog) {
+        self.event_log = Some(log);
+        self.log_event(GameEvent::Start { fen: self.fen(), variant: self.variant });
+    }
+
+    /// Appends an event to the event log, if the game has one.
+    pub(crate) fn log_event(&mut self, event: GameEvent) {
+        if let Some(log) = self.event_log.as_mut() {
+            log.append(event);
+        }
+    }
+
+    /// Replays an event log written with [`Game::set_event_log`] from its last `Start` event.
+    /// Every move must be legal again and every rejected move rejected again, so a replay that
+    /// succeeds reproduces the logged game exactly.
+    ///
+    /// # Arguments
+    /// - `text`: The content of the log file.
+    ///
+    /// # Returns
+    /// - `Ok((Game, usize))`: The game after the last event, and the number of events replayed.
+    /// - `Err(String)`: If a line is malformed, the log has no `Start` event, or an event does
+    ///   not replay the way it was logged, with the line of the event.
+    pub fn from_event_log(text: &str) -> Result<(Game, usize), String> {
+        let mut events = Vec::new();
+        for (idx, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let logged = serde_json::from_str::<LoggedEvent>(line)
+                .map_err(|err| format!("Event log line {}: {}", idx + 1, err))?;
+            if matches!(logged.event, GameEvent::Start { .. }) {
+                events.clear();
+            }
+            events.push((idx + 1, logged.event));
+        }
+        let Some((_, GameEvent::Start { .. })) = events.first() else {
+            return Err("The event log has no Start event.".to_string());
+        };
+        let mut game = Game::new();
+        for (line, event) in &events {
+            game.replay_event(event).map_err(|err| format!("Event log line {}: {}", line, err))?;
+        }
+        Ok((game, events.len()))
+    }
+
+    fn replay_event(&mut self, event: &GameEvent) -> Result<(), String> {
+        let expect_side = |game: &Game, side: Color| match game.turn == side {
+            true => Ok(()),
+            false => Err(format!("{:?} acted when logged, but {:?} is to move on replay.", side, game.turn)),
+        };
+        match event {
+            GameEvent::Start { fen, variant } => {
+                *self = Game::from_fen(fen)?;
+                self.variant = *variant;
+            }
+            GameEvent::Setup { fen } => {
+                let variant = self.variant;
+                *self = Game::from_fen(fen)?;
+                self.variant = variant;
+            }
+            GameEvent::Load { saved_game } => *self = Game::from_json(saved_game)?,
+            GameEvent::Move { side, from, to, promotion } => {
+                expect_side(self, *side)?;
+                self.play_with_promotion(*from, *to, *promotion).map_err(|err| format!("{}{} is illegal on replay: {}", from, to, err))?;
+            }
+            GameEvent::Rejected { side, from, to, san, .. } => {
+                expect_side(self, *side)?;
+                let (entered, legal) = match (from, to, san) {
+                    (Some(from), Some(to), _) => (format!("{}{}", from, to), self.clone().play(*from, *to).is_ok()),
+                    (_, _, Some(san)) => (san.clone(), san::parse(san).is_some_and(|parsed| self.resolve_san(&parsed).is_ok())),
+                    _ => return Err("A rejected move names neither its squares nor its SAN.".to_string()),
+                };
+                if legal {
+                    return Err(format!("{} was rejected when logged but is legal on replay.", entered));
+                }
+            }
+            GameEvent::OfferDraw { side } => {
+                expect_side(self, *side)?;
+                self.offer_draw()?;
+            }
+            GameEvent::AcceptDraw { side } => {
+                expect_side(self, *side)?;
+                self.accept_draw()?;
+            }
+            GameEvent::DeclineDraw { side } => {
+                expect_side(self, *side)?;
+                self.decline_draw()?;
+            }
+            GameEvent::Resign { side } => {
+                expect_side(self, *side)?;
+                self.resign();
+            }
+            GameEvent::FlagFall { side } => {
+                if self.declared_result.is_some() {
+                    return Err(format!("The flag of {:?} fell when logged, but the game is already over on replay.", side));
+                }
+                self.flag_fall(*side);
+            }
+            GameEvent::Undo { plies } => {
+                for _ in 0..*plies {
+                    if !self.undo() {
+                        return Err("There is no move left to undo on replay.".to_string());
+                    }
+                }
+            }
+            GameEvent::Redo => {
+                if !self.redo() {
+                    return Err("There is no move to redo on replay.".to_string());
+                }
+            }
+            GameEvent::Goto { ply } => self.goto(*ply)?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use crate::engine::clock::TimeControl;
+    use crate::engine::game::GameResult;
+    use crate::square::square;
+
+    fn line(event: GameEvent) -> String {
+        serde_json::to_string(&LoggedEvent { at_ms: 0, event }).unwrap()
+    }
+
+    #[test]
+    fn test_replay_reproduces_the_logged_game() {
+        let fen = Game::new().fen();
+        let log = [
+            line(GameEvent::Start { fen: "8/8/8/8/8/8/8/K6k w - - 0 1".to_string(), variant: Variant::Atomic }),
+            line(GameEvent::Start { fen, variant: Variant::Standard }),
+            line(GameEvent::Move { side: Color::White, from: square("e2"), to: square("e4"), promotion: None }),
+            line(GameEvent::Rejected { side: Color::Black, from: Some(square("e7")), to: Some(square("e4")), san: None, reason: "blocked".to_string() }),
+            line(GameEvent::Rejected { side: Color::Black, from: None, to: None, san: Some("Nd5".to_string()), reason: "no knight".to_string() }),
+            line(GameEvent::Move { side: Color::Black, from: square("e7"), to: square("e5"), promotion: None }),
+            line(GameEvent::Undo { plies: 1 }),
+            line(GameEvent::Redo),
+            line(GameEvent::Resign { side: Color::White }),
+        ].join("\n");
+        let (game, events) = Game::from_event_log(&log).unwrap();
+        assert_eq!(events, 8);
+        assert_eq!(game.move_list(), "1. e4 e5");
+        assert_eq!(game.game_result(), Some(GameResult::Resignation(Color::White)));
+        assert!(log.lines().nth(2).unwrap().contains(r#""event":"Move","side":"White","from":"e2","to":"e4""#));
+    }
+
+    #[test]
+    fn test_replay_reports_events_that_no_longer_match() {
+        let start = line(GameEvent::Start { fen: Game::new().fen(), variant: Variant::Standard });
+        let now_legal = line(GameEvent::Rejected { side: Color::White, from: Some(square("e2")), to: Some(square("e4")), san: None, reason: String::new() });
+        let Err(err) = Game::from_event_log(&format!("{}\n{}", start, now_legal)) else {
+            panic!("e2e4 is legal");
+        };
+        assert!(err.starts_with("Event log line 2: e2e4 was rejected"), "{}", err);
+        let san_now_legal = line(GameEvent::Rejected { side: Color::White, from: None, to: None, san: Some("Nf3".to_string()), reason: String::new() });
+        let Err(err) = Game::from_event_log(&format!("{}\n{}", start, san_now_legal)) else {
+            panic!("Nf3 is legal");
+        };
+        assert!(err.starts_with("Event log line 2: Nf3 was rejected"), "{}", err);
+        assert!(Game::from_event_log("not json").is_err());
+        assert!(Game::from_event_log(&line(GameEvent::Redo)).is_err());
+    }
+
+    #[test]
+    fn test_replay_ends_a_game_lost_on_time() {
+        let path = std::env::temp_dir().join(format!("chessgame-events-{}.jsonl", std::process::id()));
+        let mut game = Game::new();
+        game.set_clock(TimeControl::parse("1").unwrap());
+        game.set_event_log(EventLog::open(path.to_str().unwrap()).unwrap());
+        game.play_local(square("e2"), square("e4"), None).unwrap();
+        // Black thinks for two minutes on a one-minute clock
+        game.turn_started = Instant::now() - Duration::from_secs(120);
+        game.check_flag();
+        assert_eq!(game.game_result(), Some(GameResult::Timeout(Color::Black)));
+        let log = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(log.lines().last().unwrap().contains(r#""event":"FlagFall","side":"Black""#), "{}", log);
+        let (replayed, events) = Game::from_event_log(&log).unwrap();
+        assert_eq!(events, 3);
+        assert_eq!(replayed.game_result(), Some(GameResult::Timeout(Color::Black)));
+    }
+}
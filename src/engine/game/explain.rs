@@ -0,0 +1,189 @@
+use std::fmt;
+use crate::bitboard::BitBoard;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::Square;
+use super::{Direction, Game, MoveError};
+
+/// Why a move is illegal, in more detail than a [`MoveError`]: which piece is in the way,
+/// which enemy piece pins or checks, so that the player learns the rule that refused it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IllegalReason {
+    /// A rule that the [`MoveError`] names in full, e.g. an empty source square or a lost
+    /// castling right.
+    Rule(MoveError),
+    /// The target holds a piece of the mover's own side.
+    OwnPieceOnTarget { piece: Piece, from: Square, to: Square, blocker: Piece },
+    /// The piece moves along that line, but another piece stands on the way.
+    PathBlocked { piece: Piece, from: Square, to: Square, blocker: (Piece, Square) },
+    /// The piece never moves from `from` to `to`, whatever the position.
+    WrongPattern { piece: Piece, from: Square, to: Square },
+    /// The piece stands between its king and an enemy slider, and may only move along that line.
+    Pinned { piece: Piece, from: Square, king: Square, pinner: (Piece, Square) },
+    /// The king is in check and the move neither captures the checker, blocks it nor moves the king.
+    IgnoresCheck { king: Square, checkers: Vec<(Piece, Square)> },
+    /// The king would step onto a square the opponent attacks.
+    KingToAttackedSquare { to: Square, attackers: Vec<(Piece, Square)> },
+}
+
+impl fmt::Display for IllegalReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |pieces: &[(Piece, Square)]| {
+            pieces.iter().map(|(piece, square)| format!("the {:?} on {}", piece, square)).collect::<Vec<_>>().join(" and ")
+        };
+        match self {
+            IllegalReason::Rule(error) => write!(f, "{}", error),
+            IllegalReason::OwnPieceOnTarget { piece, from, to, blocker } => {
+                write!(f, "The {:?} on {} cannot move to {}: your own {:?} stands there.", piece, from, to, blocker)
+            }
+            IllegalReason::PathBlocked { piece, from, to, blocker: (blocker, square) } => {
+                write!(f, "The {:?} on {} cannot move to {}: the {:?} on {} is in the way.", piece, from, to, blocker, square)
+            }
+            IllegalReason::WrongPattern { piece, from, to } => {
+                write!(f, "The {:?} on {} cannot move to {}: a {:?} does not move that way.", piece, from, to, piece)
+            }
+            IllegalReason::Pinned { piece, from, king, pinner: (pinner, square) } => {
+                write!(f, "The {:?} on {} is pinned against the king on {} by the {:?} on {}.", piece, from, king, pinner, square)
+            }
+            IllegalReason::IgnoresCheck { king, checkers } => {
+                write!(f, "Your king on {} is in check from {}, and the move does not stop it.", king, list(checkers))
+            }
+            IllegalReason::KingToAttackedSquare { to, attackers } => {
+                write!(f, "The king cannot move to {}: it is attacked by {}.", to, list(attackers))
+            }
+        }
+    }
+}
+
+impl Game {
+    /// Explains why the side to move may not play a move.
+    ///
+    /// # Arguments
+    /// - `from`: The `Square` the piece would leave.
+    /// - `to`: The `Square` it would reach.
+    ///
+    /// # Returns
+    /// - `Some(IllegalReason)`: The most specific reason the move is refused.
+    /// - `None`: If the move is legal.
+    pub fn explain_illegal(&self, from: Square, to: Square) -> Option<IllegalReason> {
+        let error = self.validate_move(from, to).err()?;
+        let side = self.turn;
+        let reason = match error {
+            MoveError::IllegalTarget { piece, from, to } => match self.piece_at(to) {
+                Some((blocker, owner)) if owner == side => IllegalReason::OwnPieceOnTarget { piece, from, to, blocker },
+                _ => match self.blocker_on_path(piece, from, to) {
+                    Some(blocker) => IllegalReason::PathBlocked { piece, from, to, blocker },
+                    None => IllegalReason::WrongPattern { piece, from, to },
+                },
+            },
+            MoveError::LeavesKingInCheck { piece: Piece::King, to, .. } => {
+                // the king no longer shields the squares behind it on a checking line
+                let mut pieces_location = self.pieces_location;
+                pieces_location[usize::from(side)][usize::from(Piece::King)] = BitBoard::empty();
+                let attackers = self.pieces_on(Self::attackers(&pieces_location, to, side.opposite()).into_iter());
+                IllegalReason::KingToAttackedSquare { to, attackers }
+            }
+            MoveError::LeavesKingInCheck { piece, from, .. } => match (self.pinner(from), self.king_square(side)) {
+                (Some(pinner), Some(king)) => IllegalReason::Pinned { piece, from, king, pinner },
+                (None, Some(king)) if self.is_checked() => {
+                    IllegalReason::IgnoresCheck { king, checkers: self.pieces_on(self.checkers().into_iter()) }
+                }
+                _ => IllegalReason::Rule(error),
+            },
+            error => IllegalReason::Rule(error),
+        };
+        Some(reason)
+    }
+
+    /// Finds the first piece between `from` and `to` on the line a slider or a pawn push
+    /// would follow, the target included for a pawn, which cannot capture straight ahead.
+    fn blocker_on_path(&self, piece: Piece, from: Square, to: Square) -> Option<(Piece, Square)> {
+        let direction = Direction::ALL.into_iter().find(|direction| direction.reaches(from, to))?;
+        let forward = if self.turn == Color::White { Direction::North } else { Direction::South };
+        let start_rank = usize::from(from.rank()) == if self.turn == Color::White { 1 } else { 6 };
+        let distance = usize::from(from.rank()).abs_diff(usize::from(to.rank()));
+        let follows_line = match piece {
+            Piece::Rock => !direction.is_diagonal(),
+            Piece::Bishop => direction.is_diagonal(),
+            Piece::Queen => true,
+            Piece::Pawn => direction == forward && (distance == 1 || distance == 2 && start_rank),
+            Piece::Knight | Piece::King => false,
+        };
+        if !follows_line {
+            return None;
+        }
+        let mut square = from;
+        while let Some(next) = direction.next(square) {
+            if next == to && piece != Piece::Pawn {
+                return None;
+            }
+            if let Some((blocker, _)) = self.piece_at(next) {
+                return Some((blocker, next));
+            }
+            if next == to {
+                return None;
+            }
+            square = next;
+        }
+        None
+    }
+
+    /// Returns the enemy slider pinning the piece on `square` against its king, if any.
+    fn pinner(&self, square: Square) -> Option<(Piece, Square)> {
+        let (_, direction) = self.pinned_pieces(self.turn).into_iter().find(|(pinned, _)| *pinned == square)?;
+        let mut next = direction.next(square);
+        while let Some(square) = next {
+            if let Some((piece, _)) = self.piece_at(square) {
+                return Some((piece, square));
+            }
+            next = direction.next(square);
+        }
+        None
+    }
+
+    /// Names the piece on each square.
+    fn pieces_on(&self, squares: impl Iterator<Item = Square>) -> Vec<(Piece, Square)> {
+        squares.filter_map(|square| self.piece_at(square).map(|(piece, _)| (piece, square))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_explains_pins_checks_and_blocked_paths() {
+        // the e2 knight is pinned against the king by the e8 rook
+        let game = Game::from_fen("4r1k1/8/8/8/8/8/4N3/R3K2R w Q - 0 1").unwrap();
+        let pinned = game.explain_illegal(square("e2"), square("c3")).unwrap();
+        assert_eq!(pinned.to_string(), "The Knight on e2 is pinned against the king on e1 by the Rock on e8.");
+        let own = game.explain_illegal(square("a1"), square("e1")).unwrap();
+        assert_eq!(own, IllegalReason::OwnPieceOnTarget { piece: Piece::Rock, from: square("a1"), to: square("e1"), blocker: Piece::King });
+        let pattern = game.explain_illegal(square("a1"), square("b2")).unwrap();
+        assert_eq!(pattern, IllegalReason::WrongPattern { piece: Piece::Rock, from: square("a1"), to: square("b2") });
+        let king = game.explain_illegal(square("e1"), square("e2")).unwrap();
+        assert!(matches!(king, IllegalReason::OwnPieceOnTarget { blocker: Piece::Knight, .. }));
+        assert_eq!(game.explain_illegal(square("e1"), square("d1")), None);
+        assert_eq!(game.explain_illegal(square("d4"), square("d5")), Some(IllegalReason::Rule(MoveError::NoPieceAtSource(square("d4")))));
+        let start = Game::new();
+        let blocked = start.explain_illegal(square("f1"), square("c4")).unwrap();
+        assert_eq!(blocked.to_string(), "The Bishop on f1 cannot move to c4: the Pawn on e2 is in the way.");
+    }
+
+    #[test]
+    fn test_explains_checks_and_attacked_squares() {
+        // the queen on h4 checks the king on e1 along the diagonal
+        let game = Game::from_fen("4k3/8/8/8/7q/8/3P4/4K2R w K - 0 1").unwrap();
+        let ignored = game.explain_illegal(square("h1"), square("h2")).unwrap();
+        assert_eq!(ignored.to_string(), "Your king on e1 is in check from the Queen on h4, and the move does not stop it.");
+        let attacked = game.explain_illegal(square("e1"), square("f2")).unwrap();
+        assert_eq!(attacked, IllegalReason::KingToAttackedSquare { to: square("f2"), attackers: vec![(Piece::Queen, square("h4"))] });
+        // stepping back along the checking line is attacked through the king's square
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/r3K3 w - - 0 1").unwrap();
+        let behind = game.explain_illegal(square("e1"), square("f1")).unwrap();
+        assert_eq!(behind.to_string(), "The king cannot move to f1: it is attacked by the Rock on a1.");
+        let pawn = Game::from_fen("4k3/8/8/8/4p3/8/4P3/4K3 w - - 0 1").unwrap();
+        assert!(matches!(pawn.explain_illegal(square("e2"), square("e4")), Some(IllegalReason::PathBlocked { blocker: (Piece::Pawn, _), .. })));
+    }
+}
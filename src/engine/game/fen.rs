@@ -0,0 +1,214 @@
+use crate::bitboard::BitBoard;
+use crate::engine::board::Board;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::{File, Rank, Square};
+use super::{CastlingRights, Game};
+
+impl Game {
+    /// Serializes the current position into Forsyth–Edwards Notation.
+    ///
+    /// # Returns
+    /// - A `String` with the six FEN fields: piece placement, side to move, castling rights,
+    ///   en passant target (always `-`), halfmove clock and fullmove number.
+    pub fn fen(&self) -> String {
+        let board = self.get_all_position();
+        let mut placement = String::new();
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                match board[rank * 8 + file] {
+                    None => empty += 1,
+                    Some((piece, color)) => {
+                        if empty > 0 {
+                            placement.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        placement.push(piece.fen_symbol(color));
+                    }
+                }
+            }
+            if empty > 0 {
+                placement.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                placement.push('/');
+            }
+        }
+        let turn = match self.turn {
+            Color::White => 'w',
+            Color::Black => 'b',
+        };
+        let en_passant = self.en_passant.map_or("-".to_string(), |square| square.to_string());
+        format!("{} {} {} {} {} {}", placement, turn, self.castling_rights, en_passant, self.halfmove_clock, self.fullmove_number)
+    }
+
+    /// Creates a game from a position in Forsyth–Edwards Notation.
+    ///
+    /// The en passant field names the square behind a pawn that just advanced two squares,
+    /// or is `-`. The halfmove clock and fullmove number are optional and default to `0` and `1`.
+    ///
+    /// # Arguments
+    /// - `fen`: The position to load.
+    ///
+    /// # Returns
+    /// - `Ok(Game)`: If the FEN is well-formed and each side has exactly one king.
+    /// - `Err(String)`: A message describing the first problem found.
+    pub fn from_fen(fen: &str) -> Result<Self, String> {
+        let fields = fen.split_whitespace().collect::<Vec<&str>>();
+        if fields.len() < 4 || fields.len() > 6 {
+            return Err(format!("FEN must have 4 to 6 fields, found {}.", fields.len()));
+        }
+        let pieces_location = Self::parse_placement(fields[0])?;
+        let turn = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            other => return Err(format!("Invalid side to move '{}', expected 'w' or 'b'.", other)),
+        };
+        let castling_rights = fields[2].parse::<CastlingRights>()?;
+        let halfmove_clock = match fields.get(4) {
+            Some(value) => value.parse().map_err(|_| format!("Invalid halfmove clock '{}'.", value))?,
+            None => 0,
+        };
+        let fullmove_number = match fields.get(5) {
+            Some(value) => value.parse().map_err(|_| format!("Invalid fullmove number '{}'.", value))?,
+            None => 1,
+        };
+        let mut game = Self::from_parts(pieces_location, turn, castling_rights, halfmove_clock, fullmove_number)?;
+        game.en_passant = game.parse_en_passant(fields[3])?;
+        Ok(game)
+    }
+
+    /// Parses the en passant field of a FEN against the position: the square must be on the
+    /// rank the opponent's pawn skipped, empty, with that pawn just in front of it.
+    ///
+    /// # Returns
+    /// - `Ok(Option<Square>)`: The en passant target, `None` for `-`.
+    /// - `Err(String)`: If the field is not a square or no pawn could just have skipped it.
+    fn parse_en_passant(&self, field: &str) -> Result<Option<Square>, String> {
+        if field == "-" {
+            return Ok(None);
+        }
+        let square = Square::try_from(field.to_string()).map_err(|_| format!("Invalid en passant square '{}'.", field))?;
+        self.check_en_passant(square)?;
+        Ok(Some(square))
+    }
+
+    /// Checks that `square` can be the en passant target: it must be on the rank the
+    /// opponent's pawn skipped, empty, with that pawn just in front of it.
+    pub(super) fn check_en_passant(&self, square: Square) -> Result<(), String> {
+        let (skipped, pawn) = match self.turn {
+            Color::White => (Rank::Six, Rank::Five),
+            Color::Black => (Rank::Three, Rank::Four),
+        };
+        let pawn_square = Square::new(square.file(), pawn);
+        if square.rank() != skipped || self.piece_at(square).is_some() || self.piece_at(pawn_square) != Some((Piece::Pawn, self.turn.opposite())) {
+            return Err(format!("No pawn can be taken en passant on {}.", square));
+        }
+        Ok(())
+    }
+
+    /// Creates a game from the parts of a position, checking that it can be played.
+    ///
+    /// # Arguments
+    /// - `pieces_location`: The location masks indexed by side and piece.
+    /// - `turn`: The side to move.
+    /// - `castling_rights`: The rights indexed by side, then king-side (`0`) or queen-side (`1`).
+    /// - `halfmove_clock`: Plies since the last capture or pawn move.
+    /// - `fullmove_number`: The move number, starting at `1`.
+    ///
+    /// # Returns
+    /// - `Ok(Game)`: If the position keeps the invariants of [`Game::validate`].
+    /// - `Err(String)`: A message describing the first problem found.
+    pub(crate) fn from_parts(
+        pieces_location: [[BitBoard; 6]; 2],
+        turn: Color,
+        castling_rights: CastlingRights,
+        halfmove_clock: u32,
+        fullmove_number: u32,
+    ) -> Result<Self, String> {
+        let mut game = Game::new();
+        game.pieces_location = pieces_location;
+        game.turn = turn;
+        game.castling_rights = castling_rights;
+        game.halfmove_clock = halfmove_clock;
+        game.fullmove_number = fullmove_number.max(1);
+        game.refresh_occupancy();
+        game.validate()?;
+        game.compute_attack_threat_and_move();
+        Ok(game)
+    }
+
+    /// Parses the piece placement field of a FEN.
+    ///
+    /// # Returns
+    /// - `[[BitBoard; 6]; 2]` indexed by side and piece, or an error message.
+    fn parse_placement(placement: &str) -> Result<[[BitBoard; 6]; 2], String> {
+        let rows = placement.split('/').collect::<Vec<&str>>();
+        if rows.len() != 8 {
+            return Err(format!("Piece placement must have 8 ranks, found {}.", rows.len()));
+        }
+        let mut board = Board::empty();
+        for (row_idx, row) in rows.iter().enumerate() {
+            let rank = Rank::try_from(7 - row_idx).map_err(|_| "Invalid rank.".to_string())?;
+            let mut file_idx = 0;
+            for symbol in row.chars() {
+                if let Some(skip) = symbol.to_digit(10) {
+                    file_idx += skip as usize;
+                    continue;
+                }
+                let (piece, side) = Piece::from_fen_symbol(symbol)
+                    .ok_or_else(|| format!("Invalid piece '{}' on rank {}.", symbol, rank))?;
+                let file = File::try_from(file_idx)
+                    .map_err(|_| format!("Rank {} has more than 8 squares.", rank))?;
+                board.set(Square::new(file, rank), Some((piece, side)));
+                file_idx += 1;
+            }
+            if file_idx != 8 {
+                return Err(format!("Rank {} must have 8 squares, found {}.", rank, file_idx));
+            }
+        }
+        Ok(board.into_bitboards())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fen_start_position() {
+        let game = Game::new();
+        assert_eq!(game.fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    }
+
+    #[test]
+    fn test_from_fen_round_trip() {
+        let fen = "r3k2r/ppp2ppp/2n5/3qp3/8/2N2N2/PPP2PPP/R2Q1RK1 b kq - 3 12";
+        let game = Game::from_fen(fen).unwrap();
+        assert_eq!(game.fen(), fen);
+        assert_eq!(game.get_piece_by_location(Color::Black, Square::new(File::D, Rank::Five)), Some(Piece::Queen));
+    }
+
+    #[test]
+    fn test_from_fen_rejects_malformed_input() {
+        assert!(Game::from_fen("8/8/8/8/8/8/8/8 w - -").is_err());
+        assert!(Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq -").is_err());
+        assert!(Game::from_fen("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -").is_err());
+        assert!(Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq -").is_err());
+        assert!(Game::from_fen("rnbqkbnP/pppppppp/8/8/8/8/PPPPPPP1/RNBQKBNR w KQkq -").is_err());
+    }
+
+    #[test]
+    fn test_en_passant_square_round_trips() {
+        let mut game = Game::new();
+        game.play(Square::new(File::E, Rank::Two), Square::new(File::E, Rank::Four)).unwrap();
+        let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+        assert_eq!(game.fen(), fen);
+        let loaded = Game::from_fen(fen).unwrap();
+        assert_eq!((loaded.en_passant, loaded.zobrist()), (game.en_passant, game.zobrist()));
+        // no black pawn stands on d5 to have skipped d6
+        assert!(Game::from_fen("4k3/8/8/4P3/8/8/8/4K3 w - d6 0 1").is_err());
+        assert!(Game::from_fen(&fen.replace("e3", "e4")).is_err());
+    }
+}
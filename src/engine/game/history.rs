@@ -0,0 +1,289 @@
+use crate::bitboard::BitBoard;
+use crate::gui::preferences::Notation;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::Square;
+use super::{CastlingRights, Game, Move, MoveFlag};
+
+/// A played move together with the state it destroyed, so it can be taken back exactly.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct MoveRecord {
+    /// The move that was played.
+    pub mv: Move,
+    /// The castling rights before the move.
+    pub castling_rights: CastlingRights,
+    /// The en passant target before the move.
+    pub en_passant: Option<Square>,
+    /// The halfmove clock before the move.
+    pub halfmove_clock: u32,
+    /// The location masks before a move with side effects of the variant, such as an Atomic
+    /// explosion, which may change any piece of the board.
+    pub board_before: Option<[[BitBoard; 6]; 2]>,
+    /// The Zobrist hash before the move, checked when the move is taken back (debug builds only).
+    #[cfg(debug_assertions)]
+    pub hash: u64,
+}
+
+impl Game {
+    /// Plays a move and passes the turn to the opponent, without touching the history, the redo
+    /// stack or a draw offer. Meant for searches, which take every move back with
+    /// [`Game::unmake_move`] instead of copying the game.
+    ///
+    /// # Arguments
+    /// - `mv`: A legal move of the side to move, as described by [`Game::describe_move`].
+    ///
+    /// # Returns
+    /// - `MoveRecord`: The token to pass to [`Game::unmake_move`].
+    pub(crate) fn make_move(&mut self, mv: Move) -> MoveRecord {
+        let record = self.apply_move(mv);
+        if self.turn == Color::Black {
+            self.fullmove_number += 1;
+        }
+        self.turn = self.turn.opposite();
+        #[cfg(debug_assertions)]
+        self.assert_consistent(&format!("after making {}", mv));
+        record
+    }
+
+    /// Takes back a move played with [`Game::make_move`], restoring the exact previous state.
+    /// Moves must be taken back in the reverse order they were made.
+    ///
+    /// # Arguments
+    /// - `record`: The token returned when the move was made.
+    pub(crate) fn unmake_move(&mut self, record: MoveRecord) {
+        self.turn = self.turn.opposite();
+        if self.turn == Color::Black {
+            self.fullmove_number -= 1;
+        }
+        let side = self.turn;
+        let mv = record.mv;
+        if let Some(pieces_location) = record.board_before {
+            self.pieces_location = pieces_location;
+        } else {
+            self.take_piece(side, mv.promotion.unwrap_or(mv.piece), mv.to);
+            self.put_piece(side, mv.piece, mv.from);
+            if mv.flag == MoveFlag::Castle {
+                let (rook_from, rook_to) = Self::castling_rook_squares(mv.to);
+                self.take_piece(side, Piece::Rock, rook_to);
+                self.put_piece(side, Piece::Rock, rook_from);
+            }
+            if let (Some(captured), Some(square)) = (mv.captured, mv.captured_square()) {
+                self.put_piece(side.opposite(), captured, square);
+            }
+        }
+        self.castling_rights = record.castling_rights;
+        self.en_passant = record.en_passant;
+        self.halfmove_clock = record.halfmove_clock;
+        if record.board_before.is_some() {
+            self.compute_attack_threat_and_move();
+        } else {
+            self.update_masks_after(&mv, side);
+        }
+        #[cfg(debug_assertions)]
+        {
+            self.assert_consistent(&format!("after unmaking {}", mv));
+            assert_eq!(self.zobrist(), record.hash, "after unmaking {}: the position hash differs", mv);
+        }
+    }
+
+    /// Takes back the last move and keeps it so it can be redone. A pending draw offer is withdrawn.
+    ///
+    /// # Returns
+    /// - `true`: If a move was taken back.
+    /// - `false`: If no move has been played.
+    pub fn undo(&mut self) -> bool {
+        let Some(record) = self.history.pop() else {
+            return false;
+        };
+        self.unmake_move(record);
+        self.draw_offer = None;
+        self.redo_stack.push(record);
+        true
+    }
+
+    /// Plays again the last move taken back with [`Game::undo`].
+    ///
+    /// # Returns
+    /// - `true`: If a move was redone.
+    /// - `false`: If there is nothing to redo, e.g. a new move was played after the undo.
+    pub fn redo(&mut self) -> bool {
+        let Some(record) = self.redo_stack.pop() else {
+            return false;
+        };
+        self.push_move(record.mv.from, record.mv.to, record.mv.promotion);
+        true
+    }
+
+    /// Returns the last move played, or `None` at the start of the game or after undoing every move.
+    pub fn last_move(&self) -> Option<Move> {
+        self.history.last().map(|record| record.mv)
+    }
+
+    /// Returns the Zobrist hashes of the positions that the current one may repeat: those since
+    /// the last capture or pawn move, which cannot be reached again, the oldest first and the
+    /// current position last.
+    pub(crate) fn repetition_hashes(&self) -> Vec<u64> {
+        let mut replay = self.clone();
+        let mut hashes = vec![replay.zobrist()];
+        for _ in 0..self.halfmove_clock {
+            if !replay.undo() {
+                break;
+            }
+            hashes.push(replay.zobrist());
+        }
+        hashes.reverse();
+        hashes
+    }
+
+    /// Writes the last move played in algebraic notation with its move number, e.g. `12. Nf3`
+    /// or `12... Nf6`.
+    pub(crate) fn last_move_san(&self) -> Option<String> {
+        let mv = self.last_move()?;
+        let mut before = self.clone();
+        before.undo();
+        let dots = if before.turn == Color::White { "." } else { "..." };
+        Some(format!("{}{} {}", before.fullmove_number, dots, before.notate(mv)))
+    }
+
+    /// Writes a legal move of the side to move in the notation set with [`Game::set_notation`].
+    pub(crate) fn notate(&self, mv: Move) -> String {
+        match self.notation {
+            Notation::San => self.move_san(mv),
+            Notation::Coordinate => mv.to_string(),
+        }
+    }
+
+    /// Writes the moves played so far as numbered pairs in the notation set with
+    /// [`Game::set_notation`], e.g. `1. e4 e5 2. Nf3`. A game started with Black to move opens
+    /// with `1... e5`.
+    pub fn move_list(&self) -> String {
+        let mut replay = self.clone();
+        while replay.undo() {}
+        let mut list = Vec::new();
+        for record in &self.history {
+            let san = replay.notate(record.mv);
+            match replay.turn {
+                Color::White => list.push(format!("{}. {}", replay.fullmove_number, san)),
+                Color::Black if list.is_empty() => list.push(format!("{}... {}", replay.fullmove_number, san)),
+                Color::Black => list.push(san),
+            }
+            replay.redo();
+        }
+        list.join(" ")
+    }
+
+    /// Moves through the game to the position after `ply` half-moves, by taking moves back or
+    /// playing again moves taken back. Playing a new move from there starts a new line and
+    /// drops the moves after it.
+    ///
+    /// # Arguments
+    /// - `ply`: The number of half-moves played in the target position, `0` for the start.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the game is now at that position.
+    /// - `Err(String)`: If the game has fewer half-moves, counting those taken back.
+    pub fn goto(&mut self, ply: usize) -> Result<(), String> {
+        let known = self.history.len() + self.redo_stack.len();
+        if ply > known {
+            return Err(format!("The game only has {} half-moves.", known));
+        }
+        while self.history.len() > ply && self.undo() {}
+        while self.history.len() < ply && self.redo() {}
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    fn play_all(game: &mut Game, moves: &[(&str, &str)]) {
+        for (from, to) in moves {
+            let from = square(from);
+            let to = square(to);
+            game.play(from, to).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_undo_restores_capture_and_castling() {
+        let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 4 10";
+        let mut game = Game::from_fen(fen).unwrap();
+        play_all(&mut game, &[("e1", "g1"), ("a8", "a1")]);
+        assert!(game.undo());
+        assert!(game.undo());
+        assert_eq!(game.fen(), fen);
+        assert!(!game.undo());
+    }
+
+    #[test]
+    fn test_undo_restores_en_passant_and_promotion() {
+        let fen = "4k3/1P1p4/8/4P3/8/8/8/4K3 b - - 0 1";
+        let mut game = Game::from_fen(fen).unwrap();
+        play_all(&mut game, &[("d7", "d5"), ("e5", "d6"), ("e8", "f7"), ("b7", "b8")]);
+        let history = game.history.len();
+        for _ in 0..history {
+            assert!(game.undo());
+        }
+        assert_eq!(game.fen(), fen);
+        assert_eq!(game.piece_at(square("d7")), Some((Piece::Pawn, Color::Black)));
+    }
+
+    #[test]
+    fn test_unmake_move_restores_the_position() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let mut game = Game::from_fen(fen).unwrap();
+        for mv in game.legal_move_list() {
+            let record = game.make_move(mv);
+            assert_eq!(game.turn, Color::Black);
+            game.unmake_move(record);
+            assert_eq!(game.fen(), fen, "after {}", mv);
+            assert_eq!(game.en_passant, None);
+        }
+        assert!(game.history.is_empty());
+    }
+
+    #[test]
+    fn test_redo_replays_undone_moves() {
+        let mut game = Game::new();
+        play_all(&mut game, &[("e2", "e4"), ("e7", "e5")]);
+        let fen = game.fen();
+        game.undo();
+        game.undo();
+        assert!(game.redo());
+        assert!(game.redo());
+        assert!(!game.redo());
+        assert_eq!(game.fen(), fen);
+    }
+
+    #[test]
+    fn test_new_move_clears_redo() {
+        let mut game = Game::new();
+        play_all(&mut game, &[("e2", "e4")]);
+        game.undo();
+        play_all(&mut game, &[("d2", "d4")]);
+        assert!(!game.redo());
+    }
+
+    #[test]
+    fn test_move_list_and_goto() {
+        let mut game = Game::new();
+        play_all(&mut game, &[("e2", "e4"), ("e7", "e5"), ("g1", "f3"), ("b8", "c6")]);
+        assert_eq!(game.move_list(), "1. e4 e5 2. Nf3 Nc6");
+        game.set_notation(Notation::Coordinate);
+        assert_eq!(game.move_list(), "1. e2e4 e7e5 2. g1f3 b8c6");
+        game.set_notation(Notation::San);
+        game.goto(1).unwrap();
+        assert_eq!(game.move_list(), "1. e4");
+        assert!(game.goto(5).is_err());
+        game.goto(4).unwrap();
+        assert_eq!(game.piece_at(square("c6")), Some((Piece::Knight, Color::Black)));
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 7").unwrap();
+        play_all(&mut game, &[("e8", "d7"), ("e2", "e4")]);
+        assert_eq!(game.move_list(), "7... Kd7 8. e4");
+        assert_eq!(game.last_move_san().as_deref(), Some("8. e4"));
+        game.undo();
+        assert_eq!(game.last_move_san().as_deref(), Some("7... Kd7"));
+    }
+}
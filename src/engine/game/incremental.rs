@@ -0,0 +1,92 @@
+use std::sync::OnceLock;
+use strum::IntoEnumIterator;
+use crate::bitboard::BitBoard;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::Square;
+use super::{Game, Move, MoveFlag};
+
+/// For every side, piece and square, the squares whose occupancy can change the movement or
+/// capture mask of that piece standing on that square: its full rays, jumps, pushes and
+/// capture diagonals on an empty board.
+type ReachTable = [[[BitBoard; 64]; 6]; 2];
+
+/// Returns the reach table, built on first use.
+fn reach_table() -> &'static ReachTable {
+    static TABLE: OnceLock<ReachTable> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = [[[BitBoard::empty(); 64]; 6]; 2];
+        for side in Color::iter() {
+            for piece in Piece::iter() {
+                for (idx, reach) in table[usize::from(side)][usize::from(piece)].iter_mut().enumerate() {
+                    let Ok(square) = Square::try_from(idx) else {
+                        continue;
+                    };
+                    let piece_bit = BitBoard::from(square);
+                    let empty = BitBoard::empty();
+                    // every other square holds an opponent, so captures reach as far as moves do
+                    let opponents = !piece_bit;
+                    *reach = piece.moves_function()(&piece_bit, square, &empty, &empty, &side)
+                        | piece.capture_function()(&piece_bit, square, &empty, &opponents, &side);
+                }
+            }
+        }
+        table
+    })
+}
+
+impl Game {
+    /// Brings the movement and capture masks up to date after `mv` was made or taken back,
+    /// recomputing only the piece types that may have changed: those that moved, were
+    /// captured or promoted, and those reaching a square whose occupancy changed.
+    ///
+    /// # Arguments
+    /// - `mv`: The move just made or taken back.
+    /// - `side`: The side that plays the move.
+    pub(crate) fn update_masks_after(&mut self, mv: &Move, side: Color) {
+        let mut changed = BitBoard::from(mv.from) | BitBoard::from(mv.to);
+        let mut dirty = [[false; 6]; 2];
+        dirty[usize::from(side)][usize::from(mv.piece)] = true;
+        if let Some(promotion) = mv.promotion {
+            dirty[usize::from(side)][usize::from(promotion)] = true;
+        }
+        if let (Some(captured), Some(square)) = (mv.captured, mv.captured_square()) {
+            changed |= BitBoard::from(square);
+            dirty[usize::from(side.opposite())][usize::from(captured)] = true;
+        }
+        if mv.flag == MoveFlag::Castle {
+            let (rook_from, rook_to) = Self::castling_rook_squares(mv.to);
+            changed |= BitBoard::from(rook_from) | BitBoard::from(rook_to);
+            dirty[usize::from(side)][usize::from(Piece::Rock)] = true;
+        }
+        let reach = reach_table();
+        for side in Color::iter() {
+            let side_idx = usize::from(side);
+            for piece in Piece::iter() {
+                let piece_idx = usize::from(piece);
+                let location = self.pieces_location[side_idx][piece_idx];
+                let reaches_change = || location.into_iter()
+                    .any(|square| !(reach[side_idx][piece_idx][usize::from(square)] & changed).is_empty());
+                if dirty[side_idx][piece_idx] || reaches_change() {
+                    self.recompute_masks(side, piece);
+                }
+            }
+        }
+        self.refresh_threats();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_reach_covers_rays_pushes_and_diagonals() {
+        let reach = reach_table();
+        let white_pawn = reach[usize::from(Color::White)][usize::from(Piece::Pawn)][usize::from(square("e2"))];
+        assert_eq!(white_pawn.into_iter().collect::<Vec<Square>>(), vec![square("d3"), square("e3"), square("f3"), square("e4")]);
+        assert_eq!(reach[usize::from(Color::Black)][usize::from(Piece::Rock)][usize::from(square("a1"))].count(), 14);
+        assert_eq!(reach[usize::from(Color::White)][usize::from(Piece::Knight)][usize::from(square("h8"))].count(), 2);
+    }
+}
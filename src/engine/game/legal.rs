@@ -0,0 +1,351 @@
+use strum::IntoEnumIterator;
+use crate::bitboard::BitBoard;
+use crate::pieces::attacks;
+use crate::pieces::common::{Color, PossibleMoves};
+use crate::pieces::pawn::Pawn;
+use crate::pieces::Piece;
+use crate::square::{File, Rank, Square};
+use super::Game;
+
+impl Game {
+    /// Lists the legal destination squares of the piece standing on `square`.
+    ///
+    /// Unlike the movement masks, the result excludes every move that would leave the mover's
+    /// king attacked (pinned pieces, moves ignoring a check) and castling out of, through or
+    /// into check.
+    ///
+    /// # Arguments
+    /// - `square`: The `Square` of the piece, of either color.
+    ///
+    /// # Returns
+    /// - `Vec<Square>`: The legal targets; empty if the square is empty or the piece cannot move.
+    pub fn legal_moves(&self, square: Square) -> Vec<Square> {
+        self.legal_targets(square).into_iter().collect()
+    }
+
+    /// Same as [`Game::legal_moves`], as a mask of the targets, so that move generation does
+    /// not allocate.
+    pub(crate) fn legal_targets(&self, square: Square) -> BitBoard {
+        let Some((piece, side)) = self.piece_at(square) else {
+            return BitBoard::empty();
+        };
+        let candidates = self.candidate_targets(square, piece, side);
+        self.variant.rules().legal_targets(self, square, piece, side, candidates)
+    }
+
+    /// Filters the pseudo-legal targets of a piece down to the moves that keep its king safe
+    /// by the standard rules. Out of a single check only the captures of the checker and the
+    /// blocks remain, and a pinned piece stays on its pin line.
+    pub(super) fn king_safe_targets(&self, square: Square, piece: Piece, side: Color, candidates: BitBoard) -> BitBoard {
+        let in_check = !(self.threats.attacked(side.opposite()) & self.pieces(side, Piece::King)).is_empty();
+        let king = self.king_square(side);
+        let checkers = if in_check { self.checker_mask(side) } else { BitBoard::empty() };
+        // in a double check no block or capture stops both checkers: only the king moves
+        if checkers.count() > 1 && piece != Piece::King {
+            return BitBoard::empty();
+        }
+        // out of a single check, a piece captures the checker or steps between it and the king
+        let evasions = king.zip(checkers.into_iter().next())
+            .map(|(king, checker)| checkers | attacks::ray_between(king, checker));
+        let pin_line = king
+            .filter(|_| self.pinned_pieces(side).iter().any(|(pinned, _)| *pinned == square))
+            .map(|king| attacks::line_through(king, square));
+        candidates.into_iter()
+            .filter(|to| self.is_legal(square, *to, piece, side, evasions, pin_line))
+            .fold(BitBoard::empty(), |legal, to| legal | BitBoard::from(to))
+    }
+
+    /// Lists every legal move of the given side, as if it were that side's turn.
+    ///
+    /// # Arguments
+    /// - `side`: The `Color` whose moves are generated.
+    ///
+    /// # Returns
+    /// - `Vec<(Square, Square)>`: Each legal move as a `(from, to)` pair.
+    pub fn all_legal_moves(&self, side: Color) -> Vec<(Square, Square)> {
+        Piece::iter()
+            .flat_map(|piece| self.pieces(side, piece).into_iter())
+            .flat_map(|from| self.legal_targets(from).into_iter().map(move |to| (from, to)))
+            .collect()
+    }
+
+    /// Lists every legal capture of the given side, en passant included, using only the
+    /// capture masks of the pieces.
+    ///
+    /// # Arguments
+    /// - `side`: The `Color` whose captures are generated.
+    ///
+    /// # Returns
+    /// - `Vec<(Square, Square)>`: Each legal capture as a `(from, to)` pair.
+    pub(crate) fn all_legal_captures(&self, side: Color) -> Vec<(Square, Square)> {
+        let en_passant = self.en_passant.map_or(BitBoard::empty(), BitBoard::from);
+        // some capture masks (the king's) also cover empty squares
+        let targets = self.side_pieces(side.opposite()) | en_passant;
+        Piece::iter()
+            .flat_map(|piece| self.pieces(side, piece).into_iter().map(move |from| (piece, from)))
+            .flat_map(|(piece, from)| {
+                let (_, mut capture) = self.compute_attack_threat_and_move_to_given(from, piece, side);
+                if piece == Piece::Pawn {
+                    let pawn = BitBoard::from(from);
+                    capture |= Pawn::get_capture(&pawn, from, &BitBoard::empty(), &en_passant, &side);
+                }
+                (capture & targets).into_iter()
+                    .filter(move |to| self.variant.rules().is_legal(self, from, *to, piece, side))
+                    .map(move |to| (from, to))
+            })
+            .collect()
+    }
+
+    /// Checks whether the piece on `from` could move to `to` if checks were ignored.
+    pub(crate) fn reaches_ignoring_checks(&self, from: Square, to: Square) -> bool {
+        self.piece_at(from)
+            .is_some_and(|(piece, side)| !(self.candidate_targets(from, piece, side) & BitBoard::from(to)).is_empty())
+    }
+
+    /// Lists the pseudo-legal targets of a piece: its movement and capture masks, the en passant
+    /// target for a pawn next to it, plus the castling squares for a king on its start square
+    /// while the matching right is kept.
+    fn candidate_targets(&self, from: Square, piece: Piece, side: Color) -> BitBoard {
+        let (movement, capture) = self.compute_attack_threat_and_move_to_given(from, piece, side);
+        let mut targets = movement | capture;
+        if let (Piece::Pawn, Some(en_passant)) = (piece, self.en_passant) {
+            let pawn = BitBoard::from(from);
+            targets |= Pawn::get_capture(&pawn, from, &BitBoard::empty(), &BitBoard::from(en_passant), &side);
+        }
+        let back_rank = if side == Color::White { Rank::One } else { Rank::Eight };
+        if piece == Piece::King && from == Square::new(File::E, back_rank) {
+            for (king_side, file) in [(true, File::G), (false, File::C)] {
+                if self.castling_rights.has(side, king_side) {
+                    targets |= BitBoard::from(Square::new(file, back_rank));
+                }
+            }
+        }
+        targets
+    }
+
+    /// Checks whether a pseudo-legal move keeps the mover's king safe.
+    ///
+    /// A piece other than the king only endangers its king by leaving a pin line or by not
+    /// answering a check, so the move is only simulated for king moves and en passant captures,
+    /// which also remove a pawn from the board.
+    ///
+    /// # Arguments
+    /// - `evasions`: In a single check, the checker and the squares between it and the king.
+    /// - `pin_line`: The line through the king and the mover, if the mover is pinned.
+    fn is_legal(&self, from: Square, to: Square, piece: Piece, side: Color, evasions: Option<BitBoard>, pin_line: Option<BitBoard>) -> bool {
+        if self.is_castling_move(from, to, piece) {
+            return self.is_castling_legal(from, to, side);
+        }
+        let is_en_passant = piece == Piece::Pawn && Some(to) == self.en_passant;
+        if piece == Piece::King || is_en_passant {
+            return self.is_king_safe_after(from, to, piece, side);
+        }
+        let to_bit = BitBoard::from(to);
+        [evasions, pin_line].into_iter().flatten().all(|allowed| !(allowed & to_bit).is_empty())
+    }
+
+    /// Checks every castling condition: the right is kept, the rook is in its corner, the
+    /// squares between king and rook are empty, and the king is not in check, does not cross
+    /// an attacked square and does not land on one.
+    pub(super) fn is_castling_legal(&self, from: Square, to: Square, side: Color) -> bool {
+        let is_king_side = to.file() == File::G;
+        if !self.castling_rights.has(side, is_king_side) {
+            return false;
+        }
+        let (rook, crossed) = Self::castling_rook_squares(to);
+        if (self.pieces(side, Piece::Rock) & BitBoard::from(rook)).is_empty() {
+            return false;
+        }
+        if !attacks::blockers_between(from, rook, self.occupied()).is_empty() {
+            return false;
+        }
+        !Self::is_square_attacked(&self.pieces_location, from, side.opposite())
+            && self.is_king_safe_after(from, crossed, Piece::King, side)
+            && self.is_king_safe_after(from, to, Piece::King, side)
+    }
+
+    /// Plays a move on a copy of the location masks and checks that the mover's king is not attacked.
+    ///
+    /// An en passant capture also removes the captured pawn, which may uncover an attack along
+    /// the rank both pawns stood on.
+    pub(super) fn is_king_safe_after(&self, from: Square, to: Square, piece: Piece, side: Color) -> bool {
+        let mut pieces_location = self.pieces_location;
+        let [from_bit, to_bit] = [BitBoard::from(from), BitBoard::from(to)];
+        let mut captured = to_bit;
+        if piece == Piece::Pawn && Some(to) == self.en_passant {
+            captured |= BitBoard::from(Square::new(to.file(), from.rank()));
+        }
+        for board in pieces_location[usize::from(side.opposite())].iter_mut() {
+            *board &= !captured;
+        }
+        let piece_board = &mut pieces_location[usize::from(side)][usize::from(piece)];
+        *piece_board = (*piece_board & !from_bit) | to_bit;
+        pieces_location[usize::from(side)][usize::from(Piece::King)]
+            .into_iter()
+            .all(|king| !Self::is_square_attacked(&pieces_location, king, side.opposite()))
+    }
+
+    /// Checks whether any piece of `attacker` attacks `square`, by looking outwards from the
+    /// square with the attack tables: a knight a knight's jump away, a rook or queen at the
+    /// end of a line, and so on. This costs the same whatever the number of attackers.
+    pub(crate) fn is_square_attacked(pieces_location: &[[BitBoard; 6]; 2], square: Square, attacker: Color) -> bool {
+        !Self::attackers(pieces_location, square, attacker).is_empty()
+    }
+
+    /// Returns the squares of the pieces of `attacker` that attack `square`, whoever stands on
+    /// it, looking outwards from the square like [`Game::is_square_attacked`]. Pins are ignored.
+    pub(crate) fn attackers(pieces_location: &[[BitBoard; 6]; 2], square: Square, attacker: Color) -> BitBoard {
+        let enemy = &pieces_location[usize::from(attacker)];
+        let enemy_piece = |piece: Piece| enemy[usize::from(piece)];
+        let occupied = Self::combine(&pieces_location[0]) | Self::combine(&pieces_location[1]);
+        let straight = enemy_piece(Piece::Rock) | enemy_piece(Piece::Queen);
+        let diagonal = enemy_piece(Piece::Bishop) | enemy_piece(Piece::Queen);
+        // a pawn attacks the square if a pawn of the other color there would attack the pawn
+        (attacks::rook(square, occupied) & straight)
+            | (attacks::bishop(square, occupied) & diagonal)
+            | (attacks::knight(square) & enemy_piece(Piece::Knight))
+            | (attacks::king(square) & enemy_piece(Piece::King))
+            | (attacks::pawn(square, attacker.opposite()) & enemy_piece(Piece::Pawn))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::game::MoveError;
+    use crate::square::square;
+
+    #[test]
+    fn test_start_position_has_twenty_moves() {
+        let game = Game::new();
+        assert_eq!(game.all_legal_moves(Color::White).len(), 20);
+        assert_eq!(game.all_legal_moves(Color::Black).len(), 20);
+    }
+
+    #[test]
+    fn test_pinned_piece_cannot_leave_the_pin_line() {
+        let game = Game::from_fen("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+        assert!(game.legal_moves(square("e2")).is_empty());
+    }
+
+    #[test]
+    fn test_check_must_be_answered() {
+        let game = Game::from_fen("4k3/8/8/8/8/8/3PP3/r3K2R w K - 0 1").unwrap();
+        let mut moves = game.all_legal_moves(Color::White);
+        moves.sort_by_key(|(from, to)| (usize::from(*from), usize::from(*to)));
+        assert_eq!(moves, vec![(square("e1"), square("f2"))]);
+    }
+
+    #[test]
+    fn test_castling_through_pawn_attack_is_illegal() {
+        let game = Game::from_fen("4k3/8/8/8/8/8/6p1/4K2R w K - 0 1").unwrap();
+        assert!(!game.legal_moves(square("e1")).contains(&square("g1")));
+    }
+
+    #[test]
+    fn test_castling_out_of_check_is_illegal() {
+        let game = Game::from_fen("4r1k1/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+        let moves = game.legal_moves(square("e1"));
+        assert!(!moves.contains(&square("g1")));
+        assert!(!moves.contains(&square("c1")));
+    }
+
+    #[test]
+    fn test_castling_through_or_into_check_is_illegal() {
+        // the knight on e3 covers f1, the bishop on b6 covers g1 and the rook on d8 covers d1
+        for (fen, target) in [
+            ("4k3/8/8/8/8/4n3/8/4K2R w K - 0 1", "g1"),
+            ("4k3/8/1b6/8/8/8/8/4K2R w K - 0 1", "g1"),
+            ("3rk3/8/8/8/8/8/8/R3K3 w Q - 0 1", "c1"),
+        ] {
+            let mut game = Game::from_fen(fen).unwrap();
+            assert!(!game.legal_moves(square("e1")).contains(&square(target)), "{}", fen);
+            assert_eq!(game.play(square("e1"), square(target)).err(), Some(MoveError::CastlingThroughCheck), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn test_queen_side_castling_allows_attacked_b_file() {
+        let game = Game::from_fen("1r2k3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+        assert!(game.legal_moves(square("e1")).contains(&square("c1")));
+    }
+
+    #[test]
+    fn test_castling_right_is_lost_after_rook_moves() {
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        game.play(square("h1"), square("h2")).unwrap();
+        game.play(square("e8"), square("d8")).unwrap();
+        game.play(square("h2"), square("h1")).unwrap();
+        game.play(square("d8"), square("e8")).unwrap();
+        assert!(!game.legal_moves(square("e1")).contains(&square("g1")));
+    }
+
+    #[test]
+    fn test_all_legal_captures_skips_quiet_and_pinned_moves() {
+        let mut game = Game::from_fen("4r1k1/8/3p4/8/4N3/8/8/4K3 w - - 0 1").unwrap();
+        assert!(game.all_legal_captures(Color::White).is_empty());
+        game = Game::from_fen("6k1/8/8/3p1p2/4N3/8/8/4K3 w - - 0 1").unwrap();
+        assert!(game.all_legal_captures(Color::White).is_empty());
+        game = Game::from_fen("6k1/8/3p4/8/4N3/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(game.all_legal_captures(Color::White), vec![(square("e4"), square("d6"))]);
+    }
+
+    #[test]
+    fn test_en_passant_capture_removes_the_pawn() {
+        let mut game = Game::from_fen("4k3/3p4/8/4P3/8/8/8/4K3 b - - 0 1").unwrap();
+        game.play(square("d7"), square("d5")).unwrap();
+        game.play(square("e5"), square("d6")).unwrap();
+        assert_eq!(game.piece_at(square("d5")), None);
+        assert_eq!(game.piece_at(square("d6")), Some((Piece::Pawn, Color::White)));
+    }
+
+    #[test]
+    fn test_en_passant_is_only_available_right_after_the_double_step() {
+        let mut game = Game::from_fen("4k3/3p4/8/4P3/8/8/8/4K3 b - - 0 1").unwrap();
+        game.play(square("d7"), square("d5")).unwrap();
+        game.play(square("e1"), square("e2")).unwrap();
+        game.play(square("e8"), square("e7")).unwrap();
+        assert!(!game.legal_moves(square("e5")).contains(&square("d6")));
+    }
+
+    #[test]
+    fn test_en_passant_exposing_the_king_is_illegal() {
+        let mut game = Game::from_fen("8/8/8/8/k2p3R/8/4P3/4K3 w - - 0 1").unwrap();
+        game.play(square("e2"), square("e4")).unwrap();
+        assert!(!game.legal_moves(square("d4")).contains(&square("e3")));
+    }
+
+    #[test]
+    fn test_pawn_promotes_to_queen() {
+        let mut game = Game::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        game.play(square("a7"), square("a8")).unwrap();
+        assert_eq!(game.piece_at(square("a8")), Some((Piece::Queen, Color::White)));
+        assert!(game.pieces(Color::White, Piece::Pawn).is_empty());
+    }
+
+    #[test]
+    fn test_stalemate_is_a_draw() {
+        let game = Game::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert!(matches!(game.game_result(), Some(crate::engine::game::GameResult::Draw)));
+    }
+
+    #[test]
+    fn test_back_rank_mate_is_checkmate() {
+        let mut game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        game.play(square("a1"), square("a8")).unwrap();
+        assert!(matches!(game.game_result(), Some(crate::engine::game::GameResult::Checkmate(Color::Black))));
+    }
+
+    #[test]
+    fn test_discovered_double_check_allows_only_king_moves() {
+        // the knight checks from f6 and uncovers the rook on the e-file
+        let mut game = Game::from_fen("3qk3/8/8/8/4N3/8/8/K3R3 w - - 0 1").unwrap();
+        assert!(game.checkers().is_empty());
+        game.play(square("e4"), square("f6")).unwrap();
+        assert_eq!(game.checkers(), [square("e1"), square("f6")]);
+        // the queen could take the knight or block the rook, but not both
+        assert!(game.legal_moves(square("d8")).is_empty());
+        let targets = game.legal_move_list().iter().map(|mv| (mv.piece, mv.to)).collect::<Vec<_>>();
+        assert_eq!(targets, [(Piece::King, square("f7")), (Piece::King, square("f8"))]);
+    }
+}
@@ -0,0 +1,380 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::{File, Rank, Square};
+use crate::gui::highlight::Highlight;
+use super::Game;
+
+/// What is special about a move, beyond moving one piece.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoveFlag {
+    /// A plain move or capture.
+    Normal,
+    /// A pawn advancing two squares from its start rank.
+    DoublePush,
+    /// A pawn capturing a pawn that just passed it, which is removed from beside the target.
+    EnPassant,
+    /// The king moving two squares, together with the rook.
+    Castle,
+}
+
+/// A move with everything needed to apply, describe or take it back, so the board does not
+/// have to be consulted again.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Move {
+    /// The square the piece leaves.
+    pub from: Square,
+    /// The square the piece lands on.
+    pub to: Square,
+    /// The piece that moves, before any promotion.
+    pub piece: Piece,
+    /// The captured piece, if any.
+    pub captured: Option<Piece>,
+    /// The piece a pawn is promoted to.
+    pub promotion: Option<Piece>,
+    /// How the move differs from a plain move.
+    pub flag: MoveFlag,
+}
+
+impl Move {
+    /// Returns the square of the captured piece: the target square, or the square beside it
+    /// for an en passant capture.
+    pub fn captured_square(&self) -> Option<Square> {
+        self.captured?;
+        match self.flag {
+            MoveFlag::EnPassant => Some(Square::new(self.to.file(), self.from.rank())),
+            _ => Some(self.to),
+        }
+    }
+
+    /// Returns how the target square of the move is highlighted on the board. A capturing
+    /// promotion is shown as a promotion.
+    pub(crate) fn highlight(&self) -> Highlight {
+        if self.promotion.is_some() {
+            Highlight::Promotion
+        } else if self.flag == MoveFlag::Castle {
+            Highlight::Castle
+        } else if self.captured.is_some() {
+            Highlight::Capture
+        } else {
+            Highlight::Move
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    /// Formats the move in coordinate notation, e.g. `e2e4` or `a7a8q`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.from, self.to)?;
+        if let Some(promotion) = self.promotion {
+            write!(f, "{}", promotion.fen_symbol(Color::Black))?;
+        }
+        Ok(())
+    }
+}
+
+/// The pieces a pawn may promote to, the queen first so that it is searched first.
+pub(crate) const PROMOTION_PIECES: [Piece; 4] = [Piece::Queen, Piece::Rock, Piece::Bishop, Piece::Knight];
+
+/// The most moves a [`MoveList`] holds. No legal position has more than 218 moves.
+pub const MAX_MOVES: usize = 256;
+
+/// A list of moves stored inline, on the stack, so that generating the moves of a position
+/// in the search or in perft does not allocate. It derefs to a slice of moves for sorting,
+/// searching and indexing, and converts into a `Vec` where one is needed.
+#[derive(Clone, Copy)]
+pub struct MoveList {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        let filler = Move {
+            from: Square::new(File::A, Rank::One),
+            to: Square::new(File::A, Rank::One),
+            piece: Piece::Pawn,
+            captured: None,
+            promotion: None,
+            flag: MoveFlag::Normal,
+        };
+        Self { moves: [filler; MAX_MOVES], len: 0 }
+    }
+
+    /// Appends a move.
+    ///
+    /// # Panics
+    /// - If the list already holds [`MAX_MOVES`] moves.
+    pub fn push(&mut self, mv: Move) {
+        assert!(self.len < MAX_MOVES, "a move list holds at most {} moves", MAX_MOVES);
+        self.moves[self.len] = mv;
+        self.len += 1;
+    }
+
+    /// Returns the number of moves in the list.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the list holds no move.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the moves, in the order they were pushed.
+    pub fn iter(&self) -> std::slice::Iter<'_, Move> {
+        self.moves[..self.len].iter()
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+}
+
+impl DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut [Move] {
+        &mut self.moves[..self.len]
+    }
+}
+
+impl fmt::Debug for MoveList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for MoveList {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl FromIterator<Move> for MoveList {
+    fn from_iter<I: IntoIterator<Item = Move>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for mv in iter {
+            list.push(mv);
+        }
+        list
+    }
+}
+
+impl IntoIterator for MoveList {
+    type Item = Move;
+    type IntoIter = std::iter::Take<std::array::IntoIter<Move, MAX_MOVES>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.moves.into_iter().take(self.len)
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl From<MoveList> for Vec<Move> {
+    fn from(list: MoveList) -> Self {
+        list.to_vec()
+    }
+}
+
+impl Game {
+    /// Describes a move of the side to move from the current position. Pawns reaching the last
+    /// rank promote to a queen. The move is not validated.
+    ///
+    /// # Arguments
+    /// - `from`: The `Square` of the piece to move.
+    /// - `to`: The `Square` it moves to.
+    ///
+    /// # Returns
+    /// - `Some(Move)`: The move with its piece, capture, promotion and flag filled in.
+    /// - `None`: If the side to move has no piece on `from`.
+    pub(crate) fn describe_move(&self, from: Square, to: Square) -> Option<Move> {
+        self.describe_move_by(self.turn, from, to)
+    }
+
+    /// Describes a move of the given side, like [`Game::describe_move`] does for the side to
+    /// move.
+    fn describe_move_by(&self, side: Color, from: Square, to: Square) -> Option<Move> {
+        let piece = self.get_piece_by_location(side, from)?;
+        let is_pawn = piece == Piece::Pawn;
+        let mut captured = self.get_piece_by_location(side.opposite(), to);
+        let flag = if is_pawn && captured.is_none() && Some(to) == self.en_passant {
+            captured = Some(Piece::Pawn);
+            MoveFlag::EnPassant
+        } else if is_pawn && usize::from(from).abs_diff(usize::from(to)) == 16 {
+            MoveFlag::DoublePush
+        } else if self.is_castling_move(from, to, piece) {
+            MoveFlag::Castle
+        } else {
+            MoveFlag::Normal
+        };
+        let is_promotion = is_pawn && (to.rank() == Rank::One || to.rank() == Rank::Eight);
+        Some(Move { from, to, piece, captured, promotion: is_promotion.then_some(Piece::Queen), flag })
+    }
+
+    /// Lists every legal move of the side to move with its full description, without
+    /// allocating. A promotion, quiet or capturing, is listed once per promotion piece.
+    ///
+    /// # Returns
+    /// - `MoveList`: The moves, in the order of [`Game::all_legal_moves`].
+    pub fn legal_move_list(&self) -> MoveList {
+        let mut moves = MoveList::new();
+        for piece in Piece::iter() {
+            for from in self.pieces(self.turn, piece) {
+                for to in self.legal_targets(from) {
+                    match self.describe_move(from, to) {
+                        Some(mv) if mv.promotion.is_some() => {
+                            for promotion in PROMOTION_PIECES {
+                                moves.push(Move { promotion: Some(promotion), ..mv });
+                            }
+                        }
+                        Some(mv) => moves.push(mv),
+                        None => {}
+                    }
+                }
+            }
+        }
+        tracing::trace!(turn = ?self.turn, moves = moves.len(), "generated legal moves");
+        moves
+    }
+
+    /// Finds the legal move of the side to move that leads to the position of `after`, e.g. for
+    /// a GUI that sends positions instead of moves.
+    ///
+    /// # Arguments
+    /// - `after`: A game in the position after the move, e.g. loaded with [`Game::from_fen`];
+    ///   only the pieces are compared.
+    ///
+    /// # Returns
+    /// - `Some(Move)`: The move, castling, en passant and promotions included.
+    /// - `None`: If no single legal move leads there. Atomic captures are not recognised, as
+    ///   their explosion removes more than the captured piece.
+    pub fn infer_move(&self, after: &Game) -> Option<Move> {
+        let mv = self.get_all_position().diff(&after.get_all_position())?;
+        self.legal_move_list().contains(&mv).then_some(mv)
+    }
+
+    /// Lists the legal targets of every piece of the side to move, for picking a move with
+    /// the cursor.
+    pub(crate) fn move_map(&self) -> Vec<(Square, Vec<(Square, Highlight)>)> {
+        Piece::iter()
+            .flat_map(|piece| self.pieces(self.turn, piece).into_iter())
+            .map(|from| (from, self.move_highlights(from)))
+            .collect()
+    }
+
+    /// Lists the legal targets of the piece standing on `square`, of either color, each with
+    /// the highlight of its kind of move.
+    ///
+    /// # Returns
+    /// - `Vec<(Square, Highlight)>`: The targets; empty if the square is empty.
+    pub(crate) fn move_highlights(&self, square: Square) -> Vec<(Square, Highlight)> {
+        let Some((_, side)) = self.piece_at(square) else {
+            return Vec::new();
+        };
+        self.legal_moves(square)
+            .into_iter()
+            .filter_map(|to| self.describe_move_by(side, square, to))
+            .map(|mv| (mv.to, mv.highlight()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_describe_move_flags() {
+        let game = Game::from_fen("r3k3/1P6/8/3pP3/8/8/4P3/4K2R w K d6 0 1").unwrap();
+        let describe = |from: &str, to: &str| game.describe_move(square(from), square(to)).unwrap();
+        assert_eq!(describe("e2", "e4").flag, MoveFlag::DoublePush);
+        assert_eq!(describe("e1", "g1").flag, MoveFlag::Castle);
+        let en_passant = describe("e5", "d6");
+        assert_eq!((en_passant.flag, en_passant.captured), (MoveFlag::EnPassant, Some(Piece::Pawn)));
+        assert_eq!(en_passant.captured_square(), Some(square("d5")));
+        let promotion = describe("b7", "a8");
+        assert_eq!((promotion.captured, promotion.promotion), (Some(Piece::Rock), Some(Piece::Queen)));
+        assert_eq!(promotion.to_string(), "b7a8q");
+        assert_eq!(game.describe_move(square("a8"), square("a7")), None);
+    }
+
+    #[test]
+    fn test_infer_move_checks_legality() {
+        let game = Game::from_fen("4k3/8/8/8/8/8/4P3/R3K3 w Q - 0 1").unwrap();
+        let castled = Game::from_fen("4k3/8/8/8/8/8/4P3/2KR4 b - - 1 1").unwrap();
+        assert_eq!(game.infer_move(&castled).map(|mv| (mv.to, mv.flag)), Some((square("c1"), MoveFlag::Castle)));
+        // a rook cannot jump over the king
+        let jumped = Game::from_fen("4k3/8/8/8/8/8/4P3/4K2R b - - 1 1").unwrap();
+        assert_eq!(game.infer_move(&jumped), None);
+        assert_eq!(game.infer_move(&game), None);
+    }
+
+    #[test]
+    fn test_move_highlights_tell_move_kinds_apart() {
+        let game = Game::from_fen("r3k3/1P6/8/3pP3/8/8/4P3/4K2R w K - 0 1").unwrap();
+        let highlight = |from: &str, to: &str| game.move_highlights(square(from))
+            .into_iter()
+            .find(|(target, _)| *target == square(to))
+            .map(|(_, highlight)| highlight);
+        assert_eq!(highlight("e1", "g1"), Some(Highlight::Castle));
+        assert_eq!(highlight("e1", "f1"), Some(Highlight::Move));
+        assert_eq!(highlight("b7", "b8"), Some(Highlight::Promotion));
+        assert_eq!(highlight("b7", "a8"), Some(Highlight::Promotion));
+        assert_eq!(highlight("a8", "b8"), Some(Highlight::Move));
+        assert_eq!(highlight("a8", "a1"), Some(Highlight::Move));
+        let game = Game::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(game.move_highlights(square("e4")), vec![(square("d5"), Highlight::Capture), (square("e5"), Highlight::Move)]);
+    }
+
+    #[test]
+    fn test_legal_move_list_matches_legal_moves() {
+        let game = Game::new();
+        let moves = game.legal_move_list();
+        assert_eq!(moves.len(), 20);
+        assert_eq!(moves.iter().filter(|mv| mv.flag == MoveFlag::DoublePush).count(), 8);
+        let pairs = moves.iter().map(|mv| (mv.from, mv.to)).collect::<Vec<_>>();
+        assert_eq!(pairs, game.all_legal_moves(Color::White));
+        // a quiet promotion and a capturing one, each to all four pieces
+        let game = Game::from_fen("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let promotions = game.legal_move_list().iter().filter_map(|mv| mv.promotion.map(|piece| (mv.to, piece))).collect::<Vec<_>>();
+        assert_eq!(promotions.len(), 8);
+        assert!(promotions.contains(&(square("a8"), Piece::Knight)) && promotions.contains(&(square("b8"), Piece::Rock)));
+    }
+
+    #[test]
+    fn test_move_list_pushes_sorts_and_converts() {
+        let game = Game::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        let mut moves = MoveList::new();
+        assert!(moves.is_empty());
+        moves.push(game.describe_move(square("e4"), square("e5")).unwrap());
+        moves.push(game.describe_move(square("e4"), square("d5")).unwrap());
+        moves.sort_by_key(|mv| mv.captured.is_none());
+        assert_eq!(moves[0].to, square("d5"));
+        let copy = moves.iter().copied().collect::<MoveList>();
+        assert_eq!(copy, moves);
+        assert_eq!(Vec::from(moves).len(), 2);
+        assert_eq!(moves.into_iter().map(|mv| mv.to).collect::<Vec<_>>(), [square("d5"), square("e5")]);
+    }
+}
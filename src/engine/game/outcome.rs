@@ -0,0 +1,170 @@
+use std::time::Duration;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use super::{Game, GameEvent, GameResult};
+
+impl Game {
+    /// Offers a draw on behalf of the side to move. The offer stands until the opponent
+    /// accepts or declines it, or plays a move.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the offer was made.
+    /// - `Err(String)`: If the side to move already has an offer pending.
+    pub(crate) fn offer_draw(&mut self) -> Result<(), String> {
+        if self.draw_offer == Some(self.turn) {
+            return Err(format!("{:?} has already offered a draw.", self.turn));
+        }
+        self.draw_offer = Some(self.turn);
+        Ok(())
+    }
+
+    /// Accepts the opponent's draw offer, ending the game.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If there was an offer to accept.
+    /// - `Err(String)`: If the opponent has not offered a draw.
+    pub(crate) fn accept_draw(&mut self) -> Result<(), String> {
+        if !self.has_draw_offer() {
+            return Err("There is no draw offer to accept.".to_string());
+        }
+        self.draw_offer = None;
+        self.declared_result = Some(GameResult::DrawByAgreement);
+        Ok(())
+    }
+
+    /// Declines the opponent's draw offer; the game goes on.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If there was an offer to decline.
+    /// - `Err(String)`: If the opponent has not offered a draw.
+    pub(crate) fn decline_draw(&mut self) -> Result<(), String> {
+        if !self.has_draw_offer() {
+            return Err("There is no draw offer to decline.".to_string());
+        }
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// Resigns on behalf of the side to move, ending the game.
+    pub(crate) fn resign(&mut self) {
+        self.draw_offer = None;
+        self.declared_result = Some(GameResult::Resignation(self.turn));
+    }
+
+    /// Checks whether the opponent of the side to move has a draw offer pending.
+    pub(crate) fn has_draw_offer(&self) -> bool {
+        self.draw_offer == Some(self.turn.opposite())
+    }
+
+    /// Charges the last move to the clock of the side that played it; if its flag fell, the
+    /// game ends on time.
+    ///
+    /// # Arguments
+    /// - `elapsed`: The time spent on the move.
+    pub(crate) fn punch_clock(&mut self, elapsed: Duration) {
+        let mover = self.turn.opposite();
+        let Some(clock) = self.clock.as_mut() else {
+            return;
+        };
+        if !clock.punch(mover, elapsed) && self.declared_result.is_none() {
+            self.flag_fall(mover);
+        }
+    }
+
+    /// Ends the game on time if the side to move has used up its clock while thinking.
+    pub(crate) fn check_flag(&mut self) {
+        let Some(clock) = &self.clock else {
+            return;
+        };
+        if self.declared_result.is_none() && clock.remaining(self.turn, self.turn_started.elapsed()).is_zero() {
+            self.flag_fall(self.turn);
+        }
+    }
+
+    /// Ends the game when the flag of `loser` falls: the opponent wins, unless it has too
+    /// little material left to ever checkmate, in which case the game is drawn.
+    pub(crate) fn flag_fall(&mut self, loser: Color) {
+        self.log_event(GameEvent::FlagFall { side: loser });
+        self.draw_offer = None;
+        self.declared_result = Some(match self.has_mating_material(loser.opposite()) {
+            true => GameResult::Timeout(loser),
+            false => GameResult::TimeoutDraw(loser),
+        });
+    }
+
+    /// Checks whether `side` has enough material to checkmate: anything more than a lone king
+    /// or a king with a single bishop or knight.
+    pub(crate) fn has_mating_material(&self, side: Color) -> bool {
+        let count = |piece: Piece| self.pieces(side, piece).count();
+        let heavy = count(Piece::Pawn) + count(Piece::Rock) + count(Piece::Queen);
+        heavy > 0 || count(Piece::Bishop) + count(Piece::Knight) > 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::clock::TimeControl;
+    use crate::square::square;
+
+    fn play(game: &mut Game, from: &str, to: &str) {
+        let from = square(from);
+        let to = square(to);
+        game.play(from, to).unwrap();
+    }
+
+    #[test]
+    fn test_accepted_draw_ends_the_game() {
+        let mut game = Game::new();
+        game.offer_draw().unwrap();
+        assert!(game.accept_draw().is_err(), "the offering side cannot accept its own offer");
+        play(&mut game, "e2", "e4");
+        assert!(game.has_draw_offer());
+        game.accept_draw().unwrap();
+        assert_eq!(game.game_result(), Some(GameResult::DrawByAgreement));
+    }
+
+    #[test]
+    fn test_draw_offer_lapses_when_the_opponent_moves() {
+        let mut game = Game::new();
+        play(&mut game, "e2", "e4");
+        game.offer_draw().unwrap();
+        play(&mut game, "e7", "e5");
+        play(&mut game, "g1", "f3");
+        assert!(!game.has_draw_offer());
+        assert!(game.decline_draw().is_err());
+    }
+
+    #[test]
+    fn test_declined_draw_continues_the_game() {
+        let mut game = Game::new();
+        game.offer_draw().unwrap();
+        play(&mut game, "e2", "e4");
+        game.decline_draw().unwrap();
+        assert!(!game.has_draw_offer());
+        assert_eq!(game.game_result(), None);
+    }
+
+    #[test]
+    fn test_resignation_ends_the_game() {
+        let mut game = Game::new();
+        game.resign();
+        assert_eq!(game.game_result(), Some(GameResult::Resignation(Color::White)));
+        assert_eq!(GameResult::Resignation(Color::White).to_string(), "White resigns, Black wins");
+    }
+
+    #[test]
+    fn test_flag_fall_loses_unless_the_opponent_cannot_mate() {
+        // White just moved, too slowly, and Black has only its king left
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1").unwrap();
+        game.set_clock(TimeControl::parse("1").unwrap());
+        game.punch_clock(Duration::from_secs(61));
+        assert_eq!(game.game_result(), Some(GameResult::TimeoutDraw(Color::White)));
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+        game.set_clock(TimeControl::parse("1").unwrap());
+        game.punch_clock(Duration::from_secs(61));
+        assert_eq!(game.game_result(), Some(GameResult::Timeout(Color::Black)));
+        assert!(!Game::from_fen("4k3/8/8/8/8/8/8/1NB1K3 w - - 0 1").unwrap().has_mating_material(Color::Black));
+        assert!(Game::from_fen("4k3/8/8/8/8/8/8/1NB1K3 w - - 0 1").unwrap().has_mating_material(Color::White));
+    }
+}
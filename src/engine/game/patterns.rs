@@ -0,0 +1,159 @@
+use std::fmt;
+use strum::IntoEnumIterator;
+use crate::bitboard::BitBoard;
+use crate::pieces::common::{Color, PossibleMoves};
+use crate::pieces::king::King;
+use crate::pieces::Piece;
+use crate::square::{File, Rank, Square};
+use super::Game;
+
+/// Well-known checkmate patterns recognised at the end of a game.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum MatePattern {
+    /// A rook or queen mates along the king's first rank, the flight squares in front
+    /// of the king being blocked by its own pieces.
+    BackRank,
+    /// A knight mates a king whose neighbouring squares are all occupied by its own pieces.
+    Smothered,
+    /// A rook or queen mates along the edge file while a knight covers the flight squares.
+    Anastasia,
+    /// A rook or queen mates along an edge while a second one covers the adjacent line.
+    Ladder,
+}
+
+impl fmt::Display for MatePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MatePattern::BackRank => "back-rank mate",
+            MatePattern::Smothered => "smothered mate",
+            MatePattern::Anastasia => "Anastasia's mate",
+            MatePattern::Ladder => "ladder mate",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Game {
+    /// Classifies the mating pattern of the position, assuming the side to move is checkmated.
+    ///
+    /// The classification only looks at the geometry of the attack maps: which pieces give
+    /// check, and which of the king's neighbouring squares are blocked or covered.
+    ///
+    /// # Returns
+    /// - `Some(MatePattern)`: If the final position matches a known pattern.
+    /// - `None`: If there is no single checker or the pattern is not recognised.
+    pub(crate) fn mate_pattern(&self) -> Option<MatePattern> {
+        let side = self.turn;
+        let attacker = side.opposite();
+        let king_square = self.king_square(side)?;
+        let king = BitBoard::from(king_square);
+        let checkers = self.checking_pieces(attacker, king);
+        let [(checker, checker_square)] = checkers.as_slice() else {
+            return None;
+        };
+        let own_pieces = self.side_pieces(side);
+        let neighbours = King::get_moves(&king, king_square, &BitBoard::empty(), &BitBoard::empty(), &side);
+        let free_neighbours = neighbours & !own_pieces;
+        let is_heavy = matches!(checker, Piece::Rock | Piece::Queen);
+        let back_rank = if side == Color::White { Rank::One } else { Rank::Eight };
+        let edge_file = [File::A, File::H].into_iter().find(|file| *file == king_square.file());
+        let edge_rank = [Rank::One, Rank::Eight].into_iter().find(|rank| *rank == king_square.rank());
+
+        if *checker == Piece::Knight && free_neighbours.is_empty() {
+            return Some(MatePattern::Smothered);
+        }
+        if !is_heavy {
+            return None;
+        }
+        if let Some(file) = edge_file {
+            let knight_cover = self.pieces_capture_movement[usize::from(attacker)][usize::from(Piece::Knight)];
+            if checker_square.file() == file && !(free_neighbours & knight_cover).is_empty() {
+                return Some(MatePattern::Anastasia);
+            }
+        }
+        let on_edge_line = |line: BitBoard| !(line & BitBoard::from(*checker_square)).is_empty();
+        if king_square.rank() == back_rank && on_edge_line(BitBoard::from(back_rank)) {
+            let in_front = neighbours & !BitBoard::from(back_rank);
+            if (in_front & !own_pieces).is_empty() {
+                return Some(MatePattern::BackRank);
+            }
+        }
+        let edges = edge_rank.map(BitBoard::from).into_iter().chain(edge_file.map(BitBoard::from));
+        for edge in edges {
+            if !on_edge_line(edge) {
+                continue;
+            }
+            let inner = free_neighbours & !edge;
+            let cover = self.heavy_piece_cover(attacker, *checker_square);
+            if !inner.is_empty() && (inner & !cover).is_empty() {
+                return Some(MatePattern::Ladder);
+            }
+        }
+        None
+    }
+
+    /// Lists the pieces of `attacker` whose capture mask hits the king.
+    ///
+    /// # Returns
+    /// - `Vec<(Piece, Square)>`: The type and location of each checking piece.
+    fn checking_pieces(&self, attacker: Color, king: BitBoard) -> Vec<(Piece, Square)> {
+        Piece::iter()
+            .flat_map(|piece| self.pieces(attacker, piece).into_iter().map(move |square| (piece, square)))
+            .filter(|(piece, square)| {
+                let (_, capture) = self.compute_attack_threat_and_move_to_given(*square, *piece, attacker);
+                !(capture & king).is_empty()
+            })
+            .collect()
+    }
+
+    /// Combines the capture masks of every rook and queen of `attacker` except the one on `excluded`.
+    fn heavy_piece_cover(&self, attacker: Color, excluded: Square) -> BitBoard {
+        [Piece::Rock, Piece::Queen].into_iter()
+            .flat_map(|piece| self.pieces(attacker, piece).into_iter().map(move |square| (piece, square)))
+            .filter(|(_, square)| *square != excluded)
+            .fold(BitBoard::empty(), |cover, (piece, square)| {
+                cover | self.compute_attack_threat_and_move_to_given(square, piece, attacker).1
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smothered_mate() {
+        let game = Game::from_fen("6rk/5Npp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+        assert_eq!(game.mate_pattern(), Some(MatePattern::Smothered));
+    }
+
+    #[test]
+    fn test_back_rank_mate() {
+        let game = Game::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+        assert_eq!(game.mate_pattern(), Some(MatePattern::BackRank));
+    }
+
+    #[test]
+    fn test_anastasia_mate() {
+        let game = Game::from_fen("8/4N1pk/8/7R/8/8/8/6K1 b - - 0 1").unwrap();
+        assert_eq!(game.mate_pattern(), Some(MatePattern::Anastasia));
+    }
+
+    #[test]
+    fn test_ladder_mate() {
+        let game = Game::from_fen("1R4k1/R7/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+        assert_eq!(game.mate_pattern(), Some(MatePattern::Ladder));
+    }
+
+    #[test]
+    fn test_ladder_mate_for_white_king() {
+        let game = Game::from_fen("6k1/8/8/8/8/8/r7/1r4K1 w - - 0 1").unwrap();
+        assert_eq!(game.mate_pattern(), Some(MatePattern::Ladder));
+    }
+
+    #[test]
+    fn test_unrecognised_mate() {
+        let game = Game::from_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(game.mate_pattern(), None);
+    }
+}
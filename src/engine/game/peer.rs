@@ -0,0 +1,152 @@
+use crate::net::peer::{PeerConnection, PeerMessage};
+use super::{Game, PackedMove, PlayerKind};
+
+impl Game {
+    /// Plays against a game on another machine: the peer chooses the moves of `remote_side`,
+    /// and every local move is sent to it.
+    ///
+    /// # Arguments
+    /// - `peer`: The open connection to the other game.
+    /// - `remote_side`: The `Color` played by the peer.
+    pub fn set_peer(&mut self, peer: PeerConnection, remote_side: crate::pieces::common::Color) {
+        self.peer = Some(peer);
+        self.players[usize::from(remote_side)] = PlayerKind::Remote;
+    }
+
+    /// Sends the last played move to the peer together with the hash of the resulting position,
+    /// so the peer can detect a desync right away. Does nothing in a local game.
+    pub(crate) fn send_last_move(&mut self) -> Result<(), String> {
+        let (Some(record), Some(_)) = (self.history.last(), &self.peer) else {
+            return Ok(());
+        };
+        let message = PeerMessage::Move {
+            packed: PackedMove::new(record.mv.from, record.mv.to, record.mv.promotion),
+            hash: self.zobrist(),
+        };
+        self.send_to_peer(&message)
+    }
+
+    /// Waits for the peer's move and plays it. If the move is illegal here or the position
+    /// hashes differently than on the peer, the peer's position is requested and loaded
+    /// from FEN instead.
+    ///
+    /// # Returns
+    /// - `Ok(())`: Once the peer's move has been played or the position resynchronized.
+    /// - `Err(String)`: If the connection fails or the peer sends an unexpected message.
+    pub(crate) fn receive_peer_move(&mut self) -> Result<(), String> {
+        loop {
+            let message = self.peer.as_mut().ok_or("No peer is connected.")?.receive()?;
+            match message {
+                PeerMessage::Move { packed, hash } => match self.apply_peer_move(packed, hash) {
+                    Ok(()) => return Ok(()),
+                    Err(err) => {
+                        println!("{} Resyncing from the peer's position.", err);
+                        self.send_to_peer(&PeerMessage::ResyncRequest)?;
+                    }
+                },
+                PeerMessage::ResyncRequest => self.send_to_peer(&PeerMessage::Resync { fen: self.fen() })?,
+                PeerMessage::Resync { fen } => {
+                    self.load_position(Game::from_fen(&fen)?);
+                    return Ok(());
+                }
+                PeerMessage::Start { .. } => return Err("The peer restarted the game.".to_string()),
+            }
+        }
+    }
+
+    fn send_to_peer(&mut self, message: &PeerMessage) -> Result<(), String> {
+        self.peer.as_mut().ok_or("No peer is connected.")?.send(message)
+    }
+
+    /// Plays a move received from the peer and checks that both games now agree.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the move is legal and the hashes match.
+    /// - `Err(String)`: Describing the illegal move or the hash mismatch.
+    pub(crate) fn apply_peer_move(&mut self, packed: PackedMove, hash: u64) -> Result<(), String> {
+        let (from, to, promotion) = packed.unpack()?;
+        self.play_with_promotion(from, to, promotion).map_err(|err| format!("Desync detected, the peer played an illegal move: {}", err))?;
+        if self.zobrist() != hash {
+            return Err(format!("Desync detected: local hash {:016x}, peer hash {:016x}.", self.zobrist(), hash));
+        }
+        Ok(())
+    }
+
+    /// Replaces the position with another game's, keeping the players, the GUI and the peer.
+    /// The move history is cleared since it no longer leads to the position.
+    pub(crate) fn load_position(&mut self, other: Game) {
+        self.pieces_location = other.pieces_location;
+        self.occupancy = other.occupancy;
+        self.pieces_capture_movement = other.pieces_capture_movement;
+        self.pieces_movement = other.pieces_movement;
+        self.pieces_mobility = other.pieces_mobility;
+        self.threats = other.threats;
+        self.castling_rights = other.castling_rights;
+        self.en_passant = other.en_passant;
+        self.halfmove_clock = other.halfmove_clock;
+        self.fullmove_number = other.fullmove_number;
+        self.turn = other.turn;
+        self.history.clear();
+        self.redo_stack.clear();
+        self.move_timings.clear();
+        self.annotations.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_peer_move_with_matching_hash_is_played() {
+        let mut sender = Game::new();
+        sender.play(square("e2"), square("e4")).unwrap();
+        let mut receiver = Game::new();
+        let packed = PackedMove::new(square("e2"), square("e4"), None);
+        assert!(receiver.apply_peer_move(packed, sender.zobrist()).is_ok());
+        assert_eq!(receiver.fen(), sender.fen());
+    }
+
+    #[test]
+    fn test_peer_move_with_other_hash_is_a_desync() {
+        let mut receiver = Game::new();
+        let packed = PackedMove::new(square("e2"), square("e4"), None);
+        let err = receiver.apply_peer_move(packed, 0).unwrap_err();
+        assert!(err.starts_with("Desync detected"));
+    }
+
+    #[test]
+    fn test_desync_resyncs_from_the_peer_fen() {
+        use std::io::{BufRead, BufReader, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let fen = "4k3/8/8/8/4P3/8/8/4K3 b - - 0 1";
+        let remote = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let packed = PackedMove::new(square("e2"), square("e4"), None);
+            let wrong_hash = PeerMessage::Move { packed, hash: 0 };
+            writeln!(stream, "{}", serde_json::to_string(&wrong_hash).unwrap()).unwrap();
+            let mut request = String::new();
+            BufReader::new(stream.try_clone().unwrap()).read_line(&mut request).unwrap();
+            assert_eq!(serde_json::from_str::<PeerMessage>(&request).unwrap(), PeerMessage::ResyncRequest);
+            let resync = PeerMessage::Resync { fen: fen.to_string() };
+            writeln!(stream, "{}", serde_json::to_string(&resync).unwrap()).unwrap();
+        });
+        let mut game = Game::new();
+        game.set_peer(PeerConnection::connect(&address).unwrap(), crate::pieces::common::Color::White);
+        game.receive_peer_move().unwrap();
+        remote.join().unwrap();
+        assert_eq!(game.fen(), fen);
+    }
+
+    #[test]
+    fn test_load_position_replaces_the_board() {
+        let mut game = Game::new();
+        game.play(square("e2"), square("e4")).unwrap();
+        let fen = "4k3/8/8/8/8/8/8/4K3 b - - 5 40";
+        game.load_position(Game::from_fen(fen).unwrap());
+        assert_eq!(game.fen(), fen);
+        assert!(!game.undo());
+    }
+}
@@ -0,0 +1,172 @@
+use super::{Game, Move, MoveFlag};
+
+/// Breakdown of the moves played on the last ply of a perft run, whose total is the perft count.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct PerftStats {
+    /// The number of leaf nodes, as returned by [`Game::perft`].
+    pub nodes: u64,
+    /// The leaves reached by a move of each piece type, indexed by `usize::from(Piece)`.
+    pub by_piece: [u64; 6],
+    /// The leaves reached by a capture, en passant included.
+    pub captures: u64,
+    /// The leaves reached by an en passant capture.
+    pub en_passant: u64,
+    /// The leaves reached by castling.
+    pub castles: u64,
+    /// The leaves reached by a promotion.
+    pub promotions: u64,
+}
+
+impl Game {
+    /// Counts the leaf nodes of the legal move tree, the standard way to validate move
+    /// generation against published reference numbers. Moves are made and taken back on this
+    /// game, which ends up in the position it started from.
+    ///
+    /// # Arguments
+    /// - `depth`: The number of plies to expand from the current position.
+    ///
+    /// # Returns
+    /// - `u64`: The number of positions reached after exactly `depth` plies.
+    pub fn perft(&mut self, depth: u8) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_move_list();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        moves.into_iter()
+            .map(|mv| {
+                let record = self.make_move(mv);
+                let nodes = self.perft(depth - 1);
+                self.unmake_move(record);
+                nodes
+            })
+            .sum()
+    }
+
+    /// Counts the perft leaves like [`Game::perft`], split by the type of the piece and the
+    /// kind of move played on the last ply, to localize a move generation bug to one piece or
+    /// one special move.
+    ///
+    /// # Arguments
+    /// - `depth`: The number of plies to expand from the current position (at least 1).
+    ///
+    /// # Returns
+    /// - `PerftStats`: The leaf count and its breakdown.
+    pub(crate) fn perft_stats(&mut self, depth: u8) -> PerftStats {
+        let mut stats = PerftStats::default();
+        for mv in self.legal_move_list() {
+            if depth <= 1 {
+                stats.nodes += 1;
+                stats.by_piece[usize::from(mv.piece)] += 1;
+                stats.captures += u64::from(mv.captured.is_some());
+                stats.en_passant += u64::from(mv.flag == MoveFlag::EnPassant);
+                stats.castles += u64::from(mv.flag == MoveFlag::Castle);
+                stats.promotions += u64::from(mv.promotion.is_some());
+                continue;
+            }
+            let record = self.make_move(mv);
+            let child = self.perft_stats(depth - 1);
+            self.unmake_move(record);
+            stats.nodes += child.nodes;
+            for piece in 0..stats.by_piece.len() {
+                stats.by_piece[piece] += child.by_piece[piece];
+            }
+            stats.captures += child.captures;
+            stats.en_passant += child.en_passant;
+            stats.castles += child.castles;
+            stats.promotions += child.promotions;
+        }
+        stats
+    }
+
+    /// Splits the perft count by root move, so a mismatch against a reference engine can be
+    /// followed down to the move that generates it.
+    ///
+    /// # Arguments
+    /// - `depth`: The number of plies to expand from the current position (at least 1).
+    ///
+    /// # Returns
+    /// - `Vec<(Move, u64)>`: Each legal root move with its leaf count at `depth - 1`; each
+    ///   promotion piece is a move of its own.
+    pub fn perft_divide(&self, depth: u8) -> Vec<(Move, u64)> {
+        let mut game = self.clone();
+        game.legal_move_list()
+            .into_iter()
+            .map(|mv| {
+                let record = game.make_move(mv);
+                let nodes = game.perft(depth.max(1) - 1);
+                game.unmake_move(record);
+                (mv, nodes)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    const ENDGAME: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+    const PROMOTIONS: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+
+    #[test]
+    fn test_perft_start_position() {
+        let mut game = Game::new();
+        assert_eq!(game.perft(1), 20);
+        assert_eq!(game.perft(2), 400);
+        assert_eq!(game.perft(3), 8902);
+    }
+
+    #[test]
+    fn test_perft_kiwipete() {
+        let mut game = Game::from_fen(KIWIPETE).unwrap();
+        assert_eq!(game.perft(1), 48);
+        assert_eq!(game.perft(2), 2039);
+    }
+
+    #[test]
+    fn test_perft_endgame_with_en_passant_pins() {
+        let mut game = Game::from_fen(ENDGAME).unwrap();
+        assert_eq!(game.perft(1), 14);
+        assert_eq!(game.perft(2), 191);
+        assert_eq!(game.perft(3), 2812);
+    }
+
+    #[test]
+    fn test_perft_stats_match_reference_counts() {
+        let mut game = Game::from_fen(KIWIPETE).unwrap();
+        let stats = game.perft_stats(2);
+        assert_eq!(stats.nodes, 2039);
+        assert_eq!((stats.captures, stats.en_passant, stats.castles, stats.promotions), (351, 1, 91, 0));
+        assert_eq!(stats.by_piece.iter().sum::<u64>(), stats.nodes);
+        let mut game = Game::from_fen(ENDGAME).unwrap();
+        let stats = game.perft_stats(3);
+        assert_eq!((stats.nodes, stats.captures, stats.en_passant), (2812, 209, 2));
+    }
+
+    #[test]
+    fn test_perft_divide_sums_to_perft() {
+        let game = Game::from_fen(KIWIPETE).unwrap();
+        let divide = game.perft_divide(2);
+        assert_eq!(divide.len(), 48);
+        assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<u64>(), 2039);
+    }
+
+    #[test]
+    fn test_perft_counts_every_underpromotion() {
+        // both sides promote, by advancing and by capturing
+        let mut game = Game::from_fen(PROMOTIONS).unwrap();
+        assert_eq!(game.perft(1), 6);
+        assert_eq!(game.perft(2), 264);
+        assert_eq!(game.perft(3), 9467);
+        let mut game = Game::from_fen("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8").unwrap();
+        let stats = game.perft_stats(1);
+        assert_eq!((stats.nodes, stats.promotions), (44, 4));
+        assert_eq!(game.perft(2), 1486);
+        let divide = game.perft_divide(1);
+        assert!(divide.iter().any(|(mv, _)| mv.to_string() == "d7c8n"));
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+use super::{Game, Move};
+
+/// One line of a perft suite: a position, a depth and the number of leaf nodes a correct move
+/// generator reaches.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PerftCase {
+    /// The line of the suite file, starting at `1`.
+    pub line: usize,
+    pub fen: String,
+    pub depth: u8,
+    pub expected: u64,
+}
+
+/// A perft count that differs from the one the suite expects, with the count of every root
+/// move, to compare against a reference engine and find the move tree that diverges.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PerftMismatch {
+    pub case: PerftCase,
+    /// The number of leaf nodes counted.
+    pub nodes: u64,
+    /// Each root move with its leaf count, in coordinate notation order.
+    pub divide: Vec<(Move, u64)>,
+}
+
+impl fmt::Display for PerftMismatch {
+    /// Writes the mismatch and the divide breakdown, one `e2e4: 8102` line per root move, as
+    /// most engines print it.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let case = &self.case;
+        write!(f, "Perft line {}: {} nodes at depth {} instead of {} for {}", case.line, self.nodes, case.depth, case.expected, case.fen)?;
+        for (mv, nodes) in &self.divide {
+            write!(f, "\n{}: {}", mv, nodes)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PerftMismatch {}
+
+impl PerftCase {
+    /// Counts the leaf nodes of the position at the depth of the case.
+    ///
+    /// # Returns
+    /// - `Ok(u64)`: The count, equal to the expected one.
+    /// - `Err(PerftMismatch)`: If the count differs, with the divide breakdown.
+    pub fn run(&self) -> Result<u64, PerftMismatch> {
+        let game = Game::from_fen(&self.fen).expect("the suite parser checks the FEN");
+        let mut divide = game.perft_divide(self.depth);
+        let nodes = divide.iter().map(|(_, nodes)| nodes).sum();
+        if nodes == self.expected {
+            return Ok(nodes);
+        }
+        divide.sort_by_key(|(mv, _)| mv.to_string());
+        Err(PerftMismatch { case: self.clone(), nodes, divide })
+    }
+}
+
+/// Reads a perft suite: one case per line, the FEN, the depth and the expected number of leaf
+/// nodes separated by `;`, e.g. `8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1; 3; 2812`. Blank
+/// lines and lines starting with `#` are skipped.
+///
+/// # Arguments
+/// - `text`: The content of the file.
+///
+/// # Returns
+/// - `Ok(Vec<PerftCase>)`: The cases, in file order; at least one.
+/// - `Err(String)`: If a line does not have three fields, has an invalid FEN, or a depth or
+///   count that is not a positive number, with the number of the line.
+pub fn parse_perft_suite(text: &str) -> Result<Vec<PerftCase>, String> {
+    let mut cases = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |message: String| format!("Perft line {}: {}", idx + 1, message);
+        let [fen, depth, expected] = line.split(';').map(str::trim).collect::<Vec<_>>()[..] else {
+            return Err(error("expected a FEN, a depth and a node count separated by ';'.".to_string()));
+        };
+        Game::from_fen(fen).map_err(&error)?;
+        let depth = depth.parse().ok().filter(|depth| *depth > 0).ok_or_else(|| error(format!("invalid depth {}.", depth)))?;
+        let expected = expected.parse().ok().filter(|nodes| *nodes > 0).ok_or_else(|| error(format!("invalid node count {}.", expected)))?;
+        cases.push(PerftCase { line: idx + 1, fen: fen.to_string(), depth, expected });
+    }
+    match cases.is_empty() {
+        true => Err("The perft suite has no case.".to_string()),
+        false => Ok(cases),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suite_reports_mismatches_with_a_divide() {
+        let suite = "# start position\nrnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1; 2; 400\n\n\
+            rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1; 1; 21\n";
+        let cases = parse_perft_suite(suite).unwrap();
+        assert_eq!((cases[0].line, cases[0].depth, cases[0].expected), (2, 2, 400));
+        assert_eq!(cases[0].run(), Ok(400));
+        let mismatch = cases[1].run().unwrap_err();
+        assert_eq!((mismatch.nodes, mismatch.divide.len()), (20, 20));
+        let report = mismatch.to_string();
+        assert!(report.starts_with("Perft line 4: 20 nodes at depth 1 instead of 21"), "{}", report);
+        assert!(report.contains("\na2a3: 1\n"), "{}", report);
+        assert!(parse_perft_suite("8/8/8/8/8/8/8/8 w - - 0 1; 1; 1").unwrap_err().starts_with("Perft line 1:"));
+        assert!(parse_perft_suite("# nothing").is_err());
+    }
+}
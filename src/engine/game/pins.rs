@@ -0,0 +1,149 @@
+use crate::bitboard::BitBoard;
+use crate::pieces::attacks;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::{File, Rank, Square};
+use super::Game;
+
+/// One of the eight directions a sliding piece moves in, as seen from White's side.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// Every direction, clockwise from North.
+    pub(crate) const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// Returns the change of file and rank of one step in this direction.
+    fn step(self) -> (isize, isize) {
+        match self {
+            Direction::North => (0, 1),
+            Direction::NorthEast => (1, 1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, -1),
+            Direction::South => (0, -1),
+            Direction::SouthWest => (-1, -1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, 1),
+        }
+    }
+
+    /// Checks whether a piece sliding in this direction is a bishop, or a rook otherwise.
+    pub(crate) fn is_diagonal(self) -> bool {
+        let (file, rank) = self.step();
+        file != 0 && rank != 0
+    }
+
+    /// Returns the square one step away from `square`, or `None` off the board.
+    pub(crate) fn next(self, square: Square) -> Option<Square> {
+        let (file_step, rank_step) = self.step();
+        let file = usize::from(square.file()).checked_add_signed(file_step)?;
+        let rank = usize::from(square.rank()).checked_add_signed(rank_step)?;
+        Some(Square::new(File::try_from(file).ok()?, Rank::try_from(rank).ok()?))
+    }
+
+    /// Checks whether `to` lies on the ray leaving `from` in this direction.
+    pub(crate) fn reaches(self, from: Square, to: Square) -> bool {
+        let mut square = from;
+        while let Some(next) = self.next(square) {
+            if next == to {
+                return true;
+            }
+            square = next;
+        }
+        false
+    }
+}
+
+impl Game {
+    /// Lists the pieces of `side` that are absolutely pinned: moving them off the line between
+    /// their king and an enemy rook, bishop or queen would expose the king.
+    ///
+    /// # Arguments
+    /// - `side`: The side whose pinned pieces are listed.
+    ///
+    /// # Returns
+    /// - `Vec<(Square, Direction)>`: Each pinned piece with the direction from its king towards
+    ///   the pinning piece; a pinned piece may only move along that line.
+    pub fn pinned_pieces(&self, side: Color) -> Vec<(Square, Direction)> {
+        self.king_square(side)
+            .map(|king| self.x_rays(king, side, side.opposite()))
+            .unwrap_or_default()
+    }
+
+    /// Lists the pieces of `side` that give a discovered check when they leave the line between
+    /// one of their own rooks, bishops or queens and the enemy king.
+    ///
+    /// # Arguments
+    /// - `side`: The side that would give the check.
+    ///
+    /// # Returns
+    /// - `Vec<Square>`: The squares of the pieces that unmask the check by moving off the line.
+    pub fn discovered_check_candidates(&self, side: Color) -> Vec<Square> {
+        self.king_square(side.opposite())
+            .map(|king| self.x_rays(king, side, side).into_iter().map(|(square, _)| square).collect())
+            .unwrap_or_default()
+    }
+
+    /// Looks along every line from a king for a piece of `blocker` followed by a rook, bishop or
+    /// queen of `slider` able to slide along that line.
+    ///
+    /// # Returns
+    /// - `Vec<(Square, Direction)>`: Each blocking piece with the direction from the king,
+    ///   clockwise from North.
+    fn x_rays(&self, king: Square, blocker: Color, slider: Color) -> Vec<(Square, Direction)> {
+        let queens = self.pieces(slider, Piece::Queen);
+        // the sliders that would reach the king on an empty board
+        let sliders = (attacks::rook(king, BitBoard::empty()) & (self.pieces(slider, Piece::Rock) | queens))
+            | (attacks::bishop(king, BitBoard::empty()) & (self.pieces(slider, Piece::Bishop) | queens));
+        let occupied = self.occupied();
+        let mut x_rays = sliders.into_iter()
+            .filter_map(|square| {
+                let blockers = attacks::blockers_between(king, square, occupied);
+                let shield = blockers.into_iter().next().filter(|_| blockers.count() == 1)?;
+                let direction = Direction::ALL.into_iter().find(|direction| direction.reaches(king, shield))?;
+                (!(self.side_pieces(blocker) & blockers).is_empty()).then_some((shield, direction))
+            })
+            .collect::<Vec<_>>();
+        x_rays.sort_by_key(|(_, direction)| Direction::ALL.iter().position(|other| other == direction));
+        x_rays
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_pins_need_a_matching_slider() {
+        // the knight is pinned by the rook; the d2 bishop is not, as the c3 pawn also shields the king from the queen
+        let game = Game::from_fen("4r1k1/8/8/8/1q3b2/2P5/3BN3/4K3 w - - 0 1").unwrap();
+        assert_eq!(game.pinned_pieces(Color::White), vec![(square("e2"), Direction::North)]);
+        assert!(game.pinned_pieces(Color::Black).is_empty());
+    }
+
+    #[test]
+    fn test_discovered_check_candidates() {
+        let game = Game::from_fen("4k3/8/8/4N3/8/8/1B6/4R1K1 w - - 0 1").unwrap();
+        assert_eq!(game.discovered_check_candidates(Color::White), vec![square("e5")]);
+        assert!(game.discovered_check_candidates(Color::Black).is_empty());
+    }
+}
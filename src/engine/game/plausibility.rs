@@ -0,0 +1,80 @@
+use strum::IntoEnumIterator;
+use crate::bitboard::BitBoard;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use super::Game;
+
+/// Mask of the light squares (`b1`, `a2`, ...).
+const LIGHT_SQUARES: u64 = 0x55AA55AA55AA55AA;
+
+impl Game {
+    /// Runs the optional "strict plausibility" check on the position.
+    ///
+    /// The check never rejects a position, it only reports material that could not arise in
+    /// a real game: more than 8 pawns, more than 16 pieces, or more promoted pieces (extra
+    /// queens, rooks, knights, or several bishops on the same square color) than missing pawns.
+    /// Useful when composing studies, where such positions may still be intended.
+    ///
+    /// # Returns
+    /// - `Vec<String>`: One human-readable warning per problem; empty if the position is plausible.
+    pub fn plausibility_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for side in Color::iter() {
+            let count = |piece: Piece| self.pieces(side, piece).count() as usize;
+            let pawns = count(Piece::Pawn);
+            let total = Piece::iter().map(count).sum::<usize>();
+            if pawns > 8 {
+                warnings.push(format!("{:?} has {} pawns, at most 8 are possible.", side, pawns));
+            }
+            if total > 16 {
+                warnings.push(format!("{:?} has {} pieces, at most 16 are possible.", side, total));
+            }
+            let bishops = self.pieces(side, Piece::Bishop);
+            let light_bishops = (bishops & BitBoard::new(LIGHT_SQUARES)).count() as usize;
+            let dark_bishops = (bishops & !BitBoard::new(LIGHT_SQUARES)).count() as usize;
+            let promoted = count(Piece::Queen).saturating_sub(1)
+                + count(Piece::Rock).saturating_sub(2)
+                + count(Piece::Knight).saturating_sub(2)
+                + light_bishops.saturating_sub(1)
+                + dark_bishops.saturating_sub(1);
+            if pawns + promoted > 8 {
+                warnings.push(format!(
+                    "{:?} has {} pawns but at least {} promoted pieces ({} light-squared and {} dark-squared bishops).",
+                    side, pawns, promoted, light_bishops, dark_bishops
+                ));
+            }
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_position_is_plausible() {
+        assert!(Game::new().plausibility_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_promotion_with_missing_pawn_is_plausible() {
+        let game = Game::from_fen("4k3/8/8/8/8/8/PPPPPPP1/QQ2K3 w - - 0 1").unwrap();
+        assert!(game.plausibility_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_same_colored_bishops_with_all_pawns_warns() {
+        let game = Game::from_fen("4k3/8/8/8/8/8/PPPPPPPP/2B1KB1B w - - 0 1").unwrap();
+        let warnings = game.plausibility_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("White has 8 pawns but at least 1 promoted pieces"));
+    }
+
+    #[test]
+    fn test_too_many_pawns_warns() {
+        let game = Game::from_fen("4k3/pppppppp/p7/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let warnings = game.plausibility_warnings();
+        assert_eq!(warnings[0], "Black has 9 pawns, at most 8 are possible.");
+    }
+}
@@ -0,0 +1,80 @@
+use crate::engine::search::{self, SearchHandle};
+use super::{Game, Move, PlayerKind};
+
+/// A search of the position the engine expects after the opponent's reply, running while the
+/// opponent thinks.
+pub(crate) struct Ponder {
+    /// The Zobrist hash of the expected position; the search is only used if the game reaches it.
+    hash: u64,
+    handle: SearchHandle,
+}
+
+impl Game {
+    /// Starts pondering after an engine move: searches, on another thread, the position after
+    /// the reply the engine expects, so that its next move is ready sooner if the opponent
+    /// plays it. Does nothing when pondering is off, the opponent is an engine too, or the
+    /// principal variation has no reply.
+    ///
+    /// # Arguments
+    /// - `expected_reply`: The second move of the engine's principal variation.
+    /// - `depth`: The depth the engine searches at.
+    pub(crate) fn start_pondering(&mut self, expected_reply: Option<Move>, depth: u8) {
+        self.stop_pondering();
+        if !self.pondering || matches!(self.players[usize::from(self.turn)], PlayerKind::Engine { .. }) {
+            return;
+        }
+        let Some(mv) = expected_reply else {
+            return;
+        };
+        let mut expected = self.clone();
+        expected.make_move(mv);
+        if expected.game_result().is_some() {
+            return;
+        }
+        self.ponder = Some(Ponder { hash: expected.zobrist(), handle: search::spawn_search(&expected, depth) });
+    }
+
+    /// Stops the ponder search, if any, and waits for its thread to end.
+    ///
+    /// # Returns
+    /// - `true` if a ponder search was running.
+    pub(crate) fn stop_pondering(&mut self) -> bool {
+        // dropping the handle stops the thread and joins it
+        self.ponder.take().is_some()
+    }
+
+    /// Takes the result of the ponder search if the game reached the position it searched
+    /// (a ponder hit), waiting for it to complete; otherwise stops it.
+    ///
+    /// # Returns
+    /// - `Some(pv)`: The principal variation of the current position, on a ponder hit.
+    /// - `None`: If there was no ponder search or the opponent played another move.
+    pub(crate) fn take_ponder_hit(&mut self) -> Option<Vec<Move>> {
+        let ponder = self.ponder.take()?;
+        if ponder.hash != self.zobrist() {
+            return None;
+        }
+        Some(ponder.handle.join()).filter(|pv| !pv.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_ponder_hit_and_miss() {
+        let mut game = Game::new();
+        game.set_pondering(true);
+        game.start_pondering(game.describe_move(square("e2"), square("e4")), 2);
+        assert!(game.ponder.is_some());
+        game.play_san("e4").unwrap();
+        assert_eq!(game.take_ponder_hit(), Some(search::principal_variation(&game, 2)));
+
+        game.start_pondering(game.describe_move(square("e7"), square("e5")), 2);
+        game.play_san("c5").unwrap();
+        assert_eq!(game.take_ponder_hit(), None);
+        assert!(game.ponder.is_none());
+    }
+}
@@ -0,0 +1,295 @@
+use crate::gui::action::Action;
+use crate::gui::display::DisplayOptions;
+use crate::gui::error::GuiError;
+use crate::pieces::Piece;
+use crate::square::Square;
+use super::{Game, Move};
+
+/// Longest mate the `mate` command searches for; the exhaustive search grows too slow beyond it.
+pub const MAX_MATE_MOVES: u8 = 4;
+
+/// Number of wrong moves allowed on one puzzle before the solution is shown.
+const MAX_ATTEMPTS: usize = 3;
+
+/// A position and the moves that solve it, as read from a puzzle file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Puzzle {
+    pub fen: String,
+    /// The solution in algebraic notation: the solver's moves, each followed by the reply.
+    pub solution: Vec<String>,
+}
+
+/// Reads a puzzle file: one puzzle per line, the FEN, a `;` and the solution in algebraic
+/// notation, e.g. `6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1; Ra8#`. Blank lines and lines starting
+/// with `#` are skipped. Move numbers in the solution are allowed and ignored.
+///
+/// # Arguments
+/// - `text`: The content of the file.
+///
+/// # Returns
+/// - `Ok(Vec<Puzzle>)`: The puzzles, in file order; at least one.
+/// - `Err(String)`: If a line has no solution, an invalid FEN or a solution move that cannot be
+///   played, with the number of the line.
+pub fn parse_puzzles(text: &str) -> Result<Vec<Puzzle>, String> {
+    let mut puzzles = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |message: String| format!("Puzzle line {}: {}", idx + 1, message);
+        let Some((fen, solution)) = line.split_once(';') else {
+            return Err(error("expected a FEN, ';' and the solution.".to_string()));
+        };
+        let solution = solution.split_whitespace()
+            .filter(|token| !token.ends_with('.'))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if solution.is_empty() {
+            return Err(error("the solution is empty.".to_string()));
+        }
+        let mut board = Game::from_fen(fen.trim()).map_err(&error)?;
+        for san in &solution {
+            board.play_san(san).map_err(|err| error(format!("{}: {}", san, err)))?;
+        }
+        puzzles.push(Puzzle { fen: fen.trim().to_string(), solution });
+    }
+    match puzzles.is_empty() {
+        true => Err("The puzzle file has no puzzle.".to_string()),
+        false => Ok(puzzles),
+    }
+}
+
+impl Game {
+    /// Searches every line for a checkmate of the side not to move in at most `moves` moves,
+    /// against any defence.
+    ///
+    /// # Arguments
+    /// - `moves`: The most moves of the side to move the mate may take.
+    ///
+    /// # Returns
+    /// - `Some(line)`: The forcing line of the shortest mate, the defence that lasts longest
+    ///   included, ending with the mating move. Promotions keep the piece they promote to.
+    /// - `None`: If no mate is forced within `moves` moves.
+    pub fn solve_mate(&self, moves: u8) -> Option<Vec<Move>> {
+        let mut board = self.clone();
+        (1..=moves).find_map(|moves| board.forced_mate(moves))
+    }
+
+    /// Finds a move of the side to move that mates in at most `moves` moves against every reply.
+    fn forced_mate(&mut self, moves: u8) -> Option<Vec<Move>> {
+        for mv in self.legal_move_list() {
+            let record = self.make_move(mv);
+            let line = match self.is_mated() {
+                true => Some(Vec::new()),
+                false if moves > 1 => self.longest_defence(moves - 1),
+                false => None,
+            };
+            self.unmake_move(record);
+            if let Some(line) = line {
+                return Some(std::iter::once(mv).chain(line).collect());
+            }
+        }
+        None
+    }
+
+    /// Checks that every reply of the side to move runs into a mate in at most `moves` moves,
+    /// and returns the reply that delays it longest with the rest of the line.
+    fn longest_defence(&mut self, moves: u8) -> Option<Vec<Move>> {
+        let replies = self.legal_move_list();
+        if replies.is_empty() {
+            // stalemate
+            return None;
+        }
+        let mut longest: Option<Vec<Move>> = None;
+        for mv in replies {
+            let record = self.make_move(mv);
+            let line = (1..=moves).find_map(|moves| self.forced_mate(moves));
+            self.unmake_move(record);
+            let line = std::iter::once(mv).chain(line?).collect::<Vec<_>>();
+            if longest.as_ref().is_none_or(|longest| line.len() > longest.len()) {
+                longest = Some(line);
+            }
+        }
+        longest
+    }
+
+    /// Whether the side to move is checkmated, or has lost its king in Atomic chess.
+    fn is_mated(&self) -> bool {
+        self.has_lost_king() || (self.is_checked() && self.legal_move_list().is_empty())
+    }
+
+    /// Writes a line of moves from this position in algebraic notation, e.g. `Qh5+ Kd7 Qd5#`.
+    pub(crate) fn line_san(&self, line: &[Move]) -> String {
+        let mut board = self.clone();
+        line.iter()
+            .map(|&mv| {
+                let san = board.move_san(mv);
+                board.play_legal_promoting(mv.from, mv.to, mv.promotion);
+                san
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Quizzes the player on each puzzle in turn. The player plays the solver's moves and the
+    /// replies of the solution are played for them. A wrong move is taken back and may be
+    /// retried; after [`MAX_ATTEMPTS`] wrong moves the solution is shown. A different move that
+    /// mates at once is accepted too. A pawn reaching the last rank without a chosen piece asks
+    /// the player for one, as in a game. This game is never modified.
+    ///
+    /// # Arguments
+    /// - `puzzles`: The puzzles, as read by [`parse_puzzles`].
+    pub fn start_puzzles(&mut self, puzzles: &[Puzzle]) {
+        println!("Find the best move, then keep going until the puzzle is solved.");
+        println!("Type moves as in a game, show <square> to see where a piece can go, or quit to leave.");
+        let mut solved = 0;
+        for (idx, puzzle) in puzzles.iter().enumerate() {
+            let mut board = Game::from_fen(&puzzle.fen).expect("puzzle positions are checked when read");
+            println!();
+            println!("Puzzle {}/{}: {:?} to move.", idx + 1, puzzles.len(), board.turn);
+            let mut ply = 0;
+            let mut attempts = 0;
+            let mut highlights = Vec::new();
+            while ply < puzzle.solution.len() && attempts < MAX_ATTEMPTS {
+                let event = self.gui.render(&board.get_all_position(), board.turn, &highlights, board.status(), DisplayOptions::default())
+                    .map_err(GuiError::from)
+                    .and_then(|()| self.gui.wait_and_process_event());
+                highlights.clear();
+                let attempt = match event {
+                    Ok(Some(Action::Move(from, to))) => Ok((from, to, None)),
+                    Ok(Some(Action::San(san))) => board.resolve_san(&san)
+                        .map(|(from, to)| (from, to, san.promotion()))
+                        .map_err(|err| err.to_string()),
+                    Ok(Some(Action::ShowMoves(square))) => {
+                        highlights = board.move_highlights(square);
+                        continue;
+                    }
+                    Err(err) => {
+                        println!("{} Leaving the puzzles.", err);
+                        return;
+                    }
+                    _ => {
+                        println!("That command is not available in puzzles.");
+                        continue;
+                    }
+                };
+                let attempt = match attempt {
+                    Ok((from, to, None)) if board.is_promotion(from, to) => match self.gui.ask_promotion(board.turn, None) {
+                        Ok(piece) => Ok((from, to, Some(piece))),
+                        Err(err) => {
+                            println!("{} Leaving the puzzles.", err);
+                            return;
+                        }
+                    },
+                    attempt => attempt,
+                };
+                match attempt.and_then(|(from, to, promotion)| board.try_puzzle_move(puzzle, ply, from, to, promotion)) {
+                    Ok(true) => ply = puzzle.solution.len(),
+                    Ok(false) => {
+                        ply += 1;
+                        if let Some(reply) = puzzle.solution.get(ply) {
+                            println!("Reply: {}", reply);
+                            board.play_san(reply).expect("puzzle solutions are checked when read");
+                            ply += 1;
+                        }
+                    }
+                    Err(err) => {
+                        attempts += 1;
+                        println!("{} ({} of {} attempts)", err, attempts, MAX_ATTEMPTS);
+                    }
+                }
+            }
+            if ply >= puzzle.solution.len() {
+                solved += 1;
+                if let Err(err) = self.gui.render(&board.get_all_position(), board.turn, &[], board.status(), DisplayOptions::default()) {
+                    tracing::error!(%err, "cannot draw the board");
+                    return;
+                }
+                println!();
+                println!("Solved!");
+            } else {
+                println!("The solution was: {}", puzzle.solution.join(" "));
+            }
+        }
+        println!();
+        println!("You solved {} of {} puzzles.", solved, puzzles.len());
+    }
+
+    /// Plays a move on a puzzle board and checks it against the solution.
+    ///
+    /// # Arguments
+    /// - `puzzle`: The puzzle being solved.
+    /// - `ply`: The index of the solution move the player has to find.
+    /// - `from`: The `Square` where the piece is currently located.
+    /// - `to`: The `Square` where the piece is intended to move.
+    /// - `promotion`: The piece a pawn reaching the last rank becomes, a queen if `None`.
+    ///
+    /// # Returns
+    /// - `Ok(false)`: If the move is the one of the solution; it stays on the board.
+    /// - `Ok(true)`: If the move mates, solving the puzzle even if the solution goes on
+    ///   differently; it stays on the board.
+    /// - `Err(String)`: If the move is illegal or not the solution, explaining why. The board is
+    ///   left untouched.
+    fn try_puzzle_move(&mut self, puzzle: &Puzzle, ply: usize, from: Square, to: Square, promotion: Option<Piece>) -> Result<bool, String> {
+        let mut expected = self.clone();
+        expected.play_san(&puzzle.solution[ply]).map_err(|err| err.to_string())?;
+        self.play_with_promotion(from, to, promotion).map_err(|err| err.to_string())?;
+        if self.is_mated() {
+            return Ok(true);
+        }
+        if self.fen() != expected.fen() {
+            let played = self.history.last().map(|record| record.mv.to_string()).unwrap_or_default();
+            self.undo();
+            return Err(format!("{} is legal, but it is not the solution.", played));
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_solver_finds_the_forcing_line() {
+        // back rank mate in one
+        let game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let line = game.solve_mate(2).unwrap();
+        assert_eq!(game.line_san(&line), "Ra8#");
+        // two rooks roll the king up the board
+        let game = Game::from_fen("7k/8/8/8/8/8/R7/1R4K1 w - - 0 1").unwrap();
+        assert_eq!(game.solve_mate(1), None);
+        let line = game.solve_mate(2).unwrap();
+        assert_eq!(line.len(), 3);
+        assert!(game.line_san(&line).ends_with('#'), "{}", game.line_san(&line));
+    }
+
+    #[test]
+    fn test_puzzle_file_and_moves_are_checked() {
+        let text = "# back rank\n6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1; 1. Ra8#\n";
+        let puzzles = parse_puzzles(text).unwrap();
+        assert_eq!(puzzles[0].solution, ["Ra8#"]);
+        let Err(err) = parse_puzzles("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1; Rb8#") else {
+            panic!("Rb8 is illegal");
+        };
+        assert!(err.starts_with("Puzzle line 1: Rb8#"), "{}", err);
+        assert_eq!(parse_puzzles(include_str!("../../../puzzles/mates.txt")).map(|puzzles| puzzles.len()), Ok(5));
+        let mut board = Game::from_fen(&puzzles[0].fen).unwrap();
+        assert!(board.try_puzzle_move(&puzzles[0], 0, square("a1"), square("a7"), None).is_err());
+        assert_eq!(board.fen(), puzzles[0].fen);
+        assert_eq!(board.try_puzzle_move(&puzzles[0], 0, square("a1"), square("a8"), None), Ok(true));
+    }
+
+    #[test]
+    fn test_underpromotions_are_played_and_solved() {
+        let puzzles = parse_puzzles("5bn1/6Pk/7p/8/8/8/8/B1K5 w - - 0 1; gxf8=N#").unwrap();
+        let mut board = Game::from_fen(&puzzles[0].fen).unwrap();
+        let queen = board.try_puzzle_move(&puzzles[0], 0, square("g7"), square("f8"), Some(Piece::Queen));
+        assert_eq!(queen, Err("g7f8q is legal, but it is not the solution.".to_string()));
+        assert_eq!(board.try_puzzle_move(&puzzles[0], 0, square("g7"), square("f8"), Some(Piece::Knight)), Ok(true));
+        let game = Game::from_fen(&puzzles[0].fen).unwrap();
+        assert_eq!(game.line_san(&game.solve_mate(1).unwrap()), "gxf8=N#");
+    }
+}
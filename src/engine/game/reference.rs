@@ -0,0 +1,214 @@
+//! A second move generator, written the plain way: the board is an array of 64 squares and
+//! pieces walk it one step at a time. It shares no code with the bitboard generator of
+//! [`Game`], so the property tests below can check one against the other on random positions.
+
+use proptest::prelude::*;
+use strum::IntoEnumIterator;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::Square;
+use super::Game;
+
+/// The piece on each square, indexed from `a1` (0) to `h8` (63).
+type Mailbox = [Option<(Piece, Color)>; 64];
+
+/// A generated move, comparable with the others: the piece, its squares and the promotion.
+type Key = (Piece, usize, usize, Option<Piece>);
+
+const KNIGHT_STEPS: [(i32, i32); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const KING_STEPS: [(i32, i32); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+const ROOK_RAYS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const BISHOP_RAYS: [(i32, i32); 4] = [(1, 1), (-1, 1), (-1, -1), (1, -1)];
+const PROMOTIONS: [Piece; 4] = [Piece::Queen, Piece::Rock, Piece::Bishop, Piece::Knight];
+
+/// Returns the index of the square `(file, rank)` steps away, or `None` off the board.
+fn step(idx: usize, (file, rank): (i32, i32)) -> Option<usize> {
+    let (file, rank) = ((idx % 8) as i32 + file, (idx / 8) as i32 + rank);
+    ((0..8).contains(&file) && (0..8).contains(&rank)).then(|| (rank * 8 + file) as usize)
+}
+
+/// The squares a slider reaches along one ray, up to and including the first piece.
+fn ray(board: &Mailbox, from: usize, direction: (i32, i32)) -> Vec<usize> {
+    let mut squares = Vec::new();
+    let mut idx = from;
+    while let Some(next) = step(idx, direction) {
+        squares.push(next);
+        if board[next].is_some() {
+            break;
+        }
+        idx = next;
+    }
+    squares
+}
+
+/// Whether a piece of `by` attacks the square `idx`.
+fn attacked(board: &Mailbox, idx: usize, by: Color) -> bool {
+    let holds = |target: Option<usize>, pieces: &[Piece]| {
+        target.and_then(|target| board[target]).is_some_and(|(piece, side)| side == by && pieces.contains(&piece))
+    };
+    // a pawn of `by` attacks from one rank behind, seen from its side
+    let pawn_rank = if by == Color::White { -1 } else { 1 };
+    KNIGHT_STEPS.iter().any(|&offset| holds(step(idx, offset), &[Piece::Knight]))
+        || KING_STEPS.iter().any(|&offset| holds(step(idx, offset), &[Piece::King]))
+        || [-1, 1].iter().any(|&file| holds(step(idx, (file, pawn_rank)), &[Piece::Pawn]))
+        || ROOK_RAYS.iter().any(|&direction| holds(ray(board, idx, direction).last().copied(), &[Piece::Rock, Piece::Queen]))
+        || BISHOP_RAYS.iter().any(|&direction| holds(ray(board, idx, direction).last().copied(), &[Piece::Bishop, Piece::Queen]))
+}
+
+/// Lists the legal moves of the side to move of `game`, with the castling rights and the
+/// en passant square read from it but every move found on the array.
+fn reference_moves(game: &Game) -> Vec<Key> {
+    let mut board: Mailbox = [None; 64];
+    for (idx, square) in board.iter_mut().enumerate() {
+        *square = game.piece_at(Square::try_from(idx).unwrap());
+    }
+    let side = game.turn;
+    let en_passant = game.en_passant.map(usize::from);
+    let mut moves = Vec::new();
+    for from in 0..64 {
+        let Some((piece, owner)) = board[from] else { continue };
+        if owner != side {
+            continue;
+        }
+        let lands = |to: usize| board[to].is_none_or(|(_, other)| other != side);
+        let mut targets = Vec::new();
+        match piece {
+            Piece::Knight => targets.extend(KNIGHT_STEPS.iter().filter_map(|&offset| step(from, offset))),
+            Piece::King => {
+                targets.extend(KING_STEPS.iter().filter_map(|&offset| step(from, offset)));
+                targets.extend(castling_targets(&board, side, game.castling_rights.bits(), from));
+            }
+            Piece::Rock => targets.extend(ROOK_RAYS.iter().flat_map(|&direction| ray(&board, from, direction))),
+            Piece::Bishop => targets.extend(BISHOP_RAYS.iter().flat_map(|&direction| ray(&board, from, direction))),
+            Piece::Queen => targets.extend(ROOK_RAYS.iter().chain(&BISHOP_RAYS).flat_map(|&direction| ray(&board, from, direction))),
+            Piece::Pawn => {
+                let forward = if side == Color::White { 1 } else { -1 };
+                let start_rank = if side == Color::White { 1 } else { 6 };
+                if let Some(one) = step(from, (0, forward)).filter(|&one| board[one].is_none()) {
+                    targets.push(one);
+                    if from / 8 == start_rank {
+                        targets.extend(step(one, (0, forward)).filter(|&two| board[two].is_none()));
+                    }
+                }
+                targets.extend(
+                    [-1, 1].iter()
+                        .filter_map(|&file| step(from, (file, forward)))
+                        .filter(|&to| board[to].is_some_and(|(_, other)| other != side) || Some(to) == en_passant),
+                );
+            }
+        }
+        for to in targets.into_iter().filter(|&to| lands(to)) {
+            let promotes = piece == Piece::Pawn && (to / 8 == 0 || to / 8 == 7);
+            if !promotes {
+                moves.push((piece, from, to, None));
+            } else {
+                moves.extend(PROMOTIONS.iter().map(|&promotion| (piece, from, to, Some(promotion))));
+            }
+        }
+    }
+    moves.retain(|&mv| keeps_king_safe(&board, side, mv, en_passant));
+    sorted(moves)
+}
+
+/// Sorts moves by piece, squares and promotion, so that two lists of the same moves are equal.
+fn sorted(mut moves: Vec<Key>) -> Vec<Key> {
+    moves.sort_by_key(|&(piece, from, to, promotion)| (usize::from(piece), from, to, promotion.map(usize::from)));
+    moves
+}
+
+/// The squares the king on `from` can castle to: the rights must remain, the squares between
+/// king and rook must be empty, and the king may not be in check, pass through it or land in it.
+fn castling_targets(board: &Mailbox, side: Color, rights: u8, from: usize) -> Vec<usize> {
+    let home = if side == Color::White { 0 } else { 56 };
+    if from != home + 4 || attacked(board, from, side.opposite()) {
+        return Vec::new();
+    }
+    // the rights in FEN order: white king side, white queen side, black king side, black queen side
+    let (king_side, queen_side) = if side == Color::White { (rights & 1, rights >> 1 & 1) } else { (rights >> 2 & 1, rights >> 3 & 1) };
+    let mut targets = Vec::new();
+    for (right, rook, between, path) in [(king_side, 7, 5..7, [5, 6]), (queen_side, 0, 1..4, [3, 2])] {
+        let rook_home = board[home + rook] == Some((Piece::Rock, side));
+        let empty = between.clone().all(|file| board[home + file].is_none());
+        let safe = path.iter().all(|&file| !attacked(board, home + file, side.opposite()));
+        if right == 1 && rook_home && empty && safe {
+            targets.push(home + path[1]);
+        }
+    }
+    targets
+}
+
+/// Plays the move on a copy of the array and checks that it does not leave the own king
+/// attacked.
+fn keeps_king_safe(board: &Mailbox, side: Color, (piece, from, to, promotion): Key, en_passant: Option<usize>) -> bool {
+    let mut after = *board;
+    after[from] = None;
+    after[to] = Some((promotion.unwrap_or(piece), side));
+    if piece == Piece::Pawn && Some(to) == en_passant && board[to].is_none() {
+        // the captured pawn stands beside the mover, on the target's file
+        after[from / 8 * 8 + to % 8] = None;
+    }
+    if piece == Piece::King && from.abs_diff(to) == 2 {
+        let (rook_from, rook_to) = if to > from { (from + 3, from + 1) } else { (from - 4, from - 1) };
+        after[rook_to] = after[rook_from].take();
+    }
+    let Some(king) = (0..64).find(|&idx| after[idx] == Some((Piece::King, side))) else {
+        return false;
+    };
+    !attacked(&after, king, side.opposite())
+}
+
+/// Lists the legal moves of the side to move with the bitboard generator, in the same form.
+fn bitboard_moves(game: &Game) -> Vec<Key> {
+    sorted(game.legal_move_list()
+        .iter()
+        .map(|mv| (mv.piece, usize::from(mv.from), usize::from(mv.to), mv.promotion))
+        .collect())
+}
+
+/// Plays random legal moves from `game`, each picked by the next choice, and checks at every
+/// position that both generators agree on the moves of each piece type.
+fn check_playout(mut game: Game, choices: Vec<usize>) -> Result<(), TestCaseError> {
+    for choice in choices {
+        let (reference, bitboard) = (reference_moves(&game), bitboard_moves(&game));
+        for piece in Piece::iter() {
+            let of = |moves: &[Key]| moves.iter().filter(|mv| mv.0 == piece).copied().collect::<Vec<_>>();
+            prop_assert_eq!(of(&bitboard), of(&reference), "{:?} moves differ in {}", piece, game.fen());
+        }
+        let Some(&(_, from, to, promotion)) = bitboard.get(choice % bitboard.len().max(1)) else {
+            break;
+        };
+        let (from, to) = (Square::try_from(from).unwrap(), Square::try_from(to).unwrap());
+        game.play_with_promotion(from, to, promotion).unwrap();
+    }
+    Ok(())
+}
+
+#[test]
+fn test_generators_agree_on_special_moves() {
+    // castling both ways, through and out of check, en passant and promotions with capture
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/8/8/8/8/8/8/R3K1qR w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "4k3/8/8/2pP4/8/8/8/4K3 w - c6 0 2",
+        "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+    ] {
+        let game = Game::from_fen(fen).unwrap();
+        assert_eq!(bitboard_moves(&game), reference_moves(&game), "{}", fen);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn generators_agree_on_random_games(choices in proptest::collection::vec(any::<usize>(), 0..80)) {
+        check_playout(Game::new(), choices)?;
+    }
+
+    #[test]
+    fn generators_agree_on_random_middlegames(choices in proptest::collection::vec(any::<usize>(), 0..60)) {
+        let game = Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        check_playout(game, choices)?;
+    }
+}
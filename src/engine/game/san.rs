@@ -0,0 +1,176 @@
+use crate::gui::san::{self, SanMove};
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::{File, Rank, Square};
+use super::{Game, Move, MoveError, MoveFlag};
+use super::moves::PROMOTION_PIECES;
+
+impl Game {
+    /// Finds the legal move of the side to move described by an algebraic notation move.
+    ///
+    /// # Arguments
+    /// - `san`: The parsed move, with the disambiguation hints the player wrote.
+    ///
+    /// # Returns
+    /// - `Ok((from, to))`: The only legal move matching the description.
+    /// - `Err(MoveError)`: If no legal move matches, or several do and the move is ambiguous.
+    pub(crate) fn resolve_san(&self, san: &SanMove) -> Result<(Square, Square), MoveError> {
+        let (piece, from_file, from_rank, to, promotion) = match *san {
+            SanMove::Castle { king_side } => {
+                let rank = if self.turn == Color::White { Rank::One } else { Rank::Eight };
+                let from = Square::new(File::E, rank);
+                let to = Square::new(if king_side { File::G } else { File::C }, rank);
+                // a king off its start square has moved and lost both rights
+                return match self.piece_at(from) {
+                    Some((Piece::King, side)) if side == self.turn => self.validate_move(from, to).map(|_| (from, to)),
+                    _ => Err(MoveError::CastlingRightLost { king_side }),
+                };
+            }
+            SanMove::Normal { piece, from_file, from_rank, to, promotion } => (piece, from_file, from_rank, to, promotion),
+        };
+        if let Some(promotion) = promotion.filter(|promotion| !PROMOTION_PIECES.contains(promotion)) {
+            return Err(MoveError::UnsupportedPromotion(promotion));
+        }
+        let candidates = self.all_legal_moves(self.turn)
+            .into_iter()
+            .filter(|(from, target)| {
+                *target == to
+                    && self.get_piece_by_location(self.turn, *from) == Some(piece)
+                    && from_file.is_none_or(|file| from.file() == file)
+                    && from_rank.is_none_or(|rank| from.rank() == rank)
+            })
+            .collect::<Vec<(Square, Square)>>();
+        match candidates.as_slice() {
+            [single] => Ok(*single),
+            [] => Err(MoveError::NoMatchingMove { piece, to }),
+            _ => Err(MoveError::AmbiguousSan { piece, to }),
+        }
+    }
+
+    /// Plays a move of the side to move written in standard algebraic notation, e.g. `Nf3`,
+    /// `exd5+` or `O-O`.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the move was legal and has been applied.
+    /// - `Err(MoveError)`: If the notation is malformed, matches no legal move or is ambiguous;
+    ///   the game state is left untouched.
+    pub fn play_san(&mut self, san: &str) -> Result<(), MoveError> {
+        let parsed = san::parse(san).ok_or(MoveError::InvalidNotation)?;
+        let (from, to) = self.resolve_san(&parsed)?;
+        self.play_with_promotion(from, to, parsed.promotion())
+    }
+
+    /// Writes a legal move of the side to move in standard algebraic notation, with the
+    /// minimal disambiguation and a `+` or `#` suffix for check and mate.
+    ///
+    /// # Arguments
+    /// - `from`: The `Square` of the moving piece.
+    /// - `to`: The destination `Square`.
+    ///
+    /// # Returns
+    /// - A `String` such as `Nbd2`, `exd5`, `O-O` or `e8=Q#`.
+    pub fn san(&self, from: Square, to: Square) -> String {
+        match self.describe_move(from, to) {
+            Some(mv) => self.move_san(mv),
+            None => format!("{}{}", from, to),
+        }
+    }
+
+    /// Writes a legal move of the side to move in standard algebraic notation, like
+    /// [`Game::san`], promoting to the piece the move names.
+    pub(crate) fn move_san(&self, mv: Move) -> String {
+        let (from, to, piece) = (mv.from, mv.to, mv.piece);
+        let mut san = String::new();
+        if mv.flag == MoveFlag::Castle {
+            san.push_str(if to.file() == File::G { "O-O" } else { "O-O-O" });
+        } else {
+            let is_capture = mv.captured.is_some();
+            if piece == Piece::Pawn {
+                if is_capture {
+                    san.push_str(&from.file().to_string());
+                }
+            } else {
+                san.push(piece.fen_symbol(Color::White));
+                san.push_str(&self.disambiguation(from, to, piece));
+            }
+            if is_capture {
+                san.push('x');
+            }
+            san.push_str(&to.to_string());
+            if let Some(promotion) = mv.promotion {
+                san.push('=');
+                san.push(promotion.fen_symbol(Color::White));
+            }
+        }
+        let mut child = self.clone();
+        child.make_move(mv);
+        if child.is_checked() {
+            san.push(if child.all_legal_moves(child.turn).is_empty() { '#' } else { '+' });
+        }
+        san
+    }
+
+    /// Returns the file, rank or square needed to tell the move apart from the same move by
+    /// another piece of the same type.
+    fn disambiguation(&self, from: Square, to: Square, piece: Piece) -> String {
+        let rivals = self.all_legal_moves(self.turn)
+            .into_iter()
+            .filter(|(other, target)| {
+                *target == to && *other != from && self.get_piece_by_location(self.turn, *other) == Some(piece)
+            })
+            .map(|(other, _)| other)
+            .collect::<Vec<Square>>();
+        if rivals.is_empty() {
+            String::new()
+        } else if rivals.iter().all(|other| other.file() != from.file()) {
+            from.file().to_string()
+        } else if rivals.iter().all(|other| other.rank() != from.rank()) {
+            from.rank().to_string()
+        } else {
+            from.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::san;
+    use crate::square::square;
+
+    #[test]
+    fn test_resolve_san_with_disambiguation() {
+        let game = Game::from_fen("4k3/8/8/8/8/8/4K3/R6R w - - 0 1").unwrap();
+        let ambiguous = san::parse("Rd1").unwrap();
+        assert_eq!(game.resolve_san(&ambiguous), Err(MoveError::AmbiguousSan { piece: Piece::Rock, to: square("d1") }));
+        let by_file = san::parse("Rad1").unwrap();
+        assert_eq!(game.resolve_san(&by_file), Ok((square("a1"), square("d1"))));
+    }
+
+    #[test]
+    fn test_resolve_san_pawn_capture_and_castling() {
+        let game = Game::from_fen("4k3/8/8/3p4/4P3/8/8/4K2R w K - 0 1").unwrap();
+        assert_eq!(game.resolve_san(&san::parse("exd5").unwrap()), Ok((square("e4"), square("d5"))));
+        assert_eq!(game.resolve_san(&san::parse("O-O").unwrap()), Ok((square("e1"), square("g1"))));
+        assert!(game.resolve_san(&san::parse("O-O-O").unwrap()).is_err());
+        assert_eq!(game.san(square("e1"), square("g1")), "O-O");
+    }
+
+    #[test]
+    fn test_san_output() {
+        let game = Game::from_fen("6k1/5ppp/8/8/8/8/1P2K3/R6R w - - 0 1").unwrap();
+        assert_eq!(game.san(square("a1"), square("a8")), "Ra8#");
+        assert_eq!(game.san(square("h1"), square("d1")), "Rhd1");
+        assert_eq!(game.san(square("b2"), square("b4")), "b4");
+        let promotion = Game::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(promotion.san(square("a7"), square("a8")), "a8=Q+");
+    }
+
+    #[test]
+    fn test_play_san_underpromotion() {
+        let mut game = Game::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(game.clone().play_with_promotion(square("a7"), square("a8"), Some(Piece::King)), Err(MoveError::UnsupportedPromotion(Piece::King)));
+        assert_eq!(game.play_san("a8=N"), Ok(()));
+        assert_eq!(game.piece_at(square("a8")), Some((Piece::Knight, Color::White)));
+    }
+}
@@ -0,0 +1,210 @@
+use std::time::Instant;
+use serde::{Deserialize, Serialize};
+use crate::bitboard::BitBoard;
+use crate::engine::clock::Clock;
+use crate::engine::skill::SkillLevel;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::Square;
+use super::{CastlingRights, Game, GameResult, Move, MoveAnnotation, PlayerKind, Variant};
+
+/// A position as written to a save file, piece by piece rather than as masks.
+#[derive(Serialize, Deserialize)]
+struct SavedPosition {
+    pieces: Vec<(Piece, Color, Square)>,
+    turn: Color,
+    castling_rights: CastlingRights,
+    en_passant: Option<Square>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+}
+
+/// A game as written to a save file: the position the moves start from, the moves played and
+/// the moves taken back, so that undo and redo keep working once the game is loaded.
+#[derive(Serialize, Deserialize)]
+struct SavedGame {
+    start: SavedPosition,
+    moves: Vec<Move>,
+    /// The moves taken back, the next one to redo first.
+    undone: Vec<Move>,
+    players: [PlayerKind; 2],
+    draw_offer: Option<Color>,
+    declared_result: Option<GameResult>,
+    clock: Option<Clock>,
+    tactic_alerts: bool,
+    /// Missing in files saved before variants existed, which are standard games.
+    #[serde(default)]
+    variant: Variant,
+    /// The computer's skill level, missing at full strength.
+    #[serde(default)]
+    skill_level: Option<SkillLevel>,
+    /// The comments and annotations of the moves, then of the moves taken back; missing in
+    /// files saved before moves could be annotated.
+    #[serde(default)]
+    annotations: Vec<MoveAnnotation>,
+}
+
+impl SavedPosition {
+    fn from_game(game: &Game) -> Self {
+        let pieces = game.get_all_position().iter().enumerate()
+            .filter_map(|(idx, content)| {
+                let (piece, side) = (*content)?;
+                Some((piece, side, Square::try_from(idx).ok()?))
+            })
+            .collect();
+        Self {
+            pieces,
+            turn: game.turn,
+            castling_rights: game.castling_rights,
+            en_passant: game.en_passant,
+            halfmove_clock: game.halfmove_clock,
+            fullmove_number: game.fullmove_number,
+        }
+    }
+
+    fn to_game(&self) -> Result<Game, String> {
+        let mut pieces_location = [[BitBoard::empty(); 6]; 2];
+        for &(piece, side, square) in &self.pieces {
+            let location = &mut pieces_location[usize::from(side)][usize::from(piece)];
+            *location |= BitBoard::from(square);
+        }
+        let mut game = Game::from_parts(pieces_location, self.turn, self.castling_rights, self.halfmove_clock, self.fullmove_number)?;
+        if let Some(square) = self.en_passant {
+            game.check_en_passant(square)?;
+        }
+        game.en_passant = self.en_passant;
+        Ok(game)
+    }
+}
+
+impl Game {
+    /// Serializes the whole game to JSON: the position the game started from, the moves played
+    /// and taken back, the players, the clocks and any pending draw offer or declared result.
+    ///
+    /// # Returns
+    /// - A `String` that [`Game::from_json`] reads back.
+    pub fn to_json(&self) -> String {
+        let mut start = self.clone();
+        while start.undo() {}
+        let saved = SavedGame {
+            start: SavedPosition::from_game(&start),
+            moves: self.history.iter().map(|record| record.mv).collect(),
+            undone: self.redo_stack.iter().rev().map(|record| record.mv).collect(),
+            players: self.players,
+            draw_offer: self.draw_offer,
+            declared_result: self.declared_result,
+            clock: self.clock,
+            tactic_alerts: self.tactic_alerts,
+            variant: self.variant,
+            skill_level: self.skill_level(),
+            annotations: self.annotations.clone(),
+        };
+        serde_json::to_string_pretty(&saved).expect("a game always serializes")
+    }
+
+    /// Reads back a game saved with [`Game::to_json`], replaying its moves from the start
+    /// position so that the history, undo and redo work as before.
+    ///
+    /// # Arguments
+    /// - `json`: The saved game.
+    ///
+    /// # Returns
+    /// - `Ok(Game)`: The restored game, with its turn timer started now.
+    /// - `Err(String)`: If the JSON is malformed, the start position is invalid or a saved move
+    ///   is not legal where it is played.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let saved = serde_json::from_str::<SavedGame>(json).map_err(|err| format!("Invalid saved game: {}", err))?;
+        if saved.players.contains(&PlayerKind::Remote) {
+            return Err("Network games cannot be resumed from a saved game.".to_string());
+        }
+        let mut game = saved.start.to_game()?;
+        game.variant = saved.variant;
+        for mv in saved.moves.iter().chain(&saved.undone) {
+            if !game.legal_move_list().contains(mv) {
+                return Err(format!("Saved move {} is not legal in the position it is played from.", mv));
+            }
+            game.push_move(mv.from, mv.to, mv.promotion);
+        }
+        game.annotations = saved.annotations;
+        for _ in &saved.undone {
+            game.undo();
+        }
+        game.players = saved.players;
+        game.draw_offer = saved.draw_offer;
+        game.declared_result = saved.declared_result;
+        game.clock = saved.clock;
+        game.tactic_alerts = saved.tactic_alerts;
+        game.set_skill_level(saved.skill_level);
+        Ok(game)
+    }
+
+    /// Replaces this game with a loaded one, keeping the GUI and the move observers.
+    pub(crate) fn resume(&mut self, loaded: Game) {
+        let history = loaded.history.clone();
+        let redo_stack = loaded.redo_stack.clone();
+        let annotations = loaded.annotations.clone();
+        self.players = loaded.players;
+        self.draw_offer = loaded.draw_offer;
+        self.declared_result = loaded.declared_result;
+        self.clock = loaded.clock;
+        self.tactic_alerts = loaded.tactic_alerts;
+        self.variant = loaded.variant;
+        self.weakened = loaded.weakened.clone();
+        self.load_position(loaded);
+        self.history = history;
+        self.redo_stack = redo_stack;
+        self.annotations = annotations;
+        self.turn_started = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_saved_game_round_trips_with_history() {
+        let mut game = Game::from_fen("4k3/1P6/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+        for (from, to) in [("e1", "g1"), ("e8", "d7"), ("b7", "b8"), ("d7", "c6")] {
+            game.play(square(from), square(to)).unwrap();
+        }
+        game.undo();
+        game.offer_draw().unwrap();
+        let mut loaded = Game::from_json(&game.to_json()).unwrap();
+        assert_eq!(loaded.fen(), game.fen());
+        assert_eq!(loaded.draw_offer, Some(Color::Black));
+        assert!(loaded.redo());
+        assert_eq!(loaded.piece_at(square("c6")), Some((Piece::King, Color::Black)));
+        for _ in 0..4 {
+            assert!(loaded.undo());
+        }
+        assert_eq!(loaded.fen(), "4k3/1P6/8/8/8/8/8/R3K2R w KQ - 0 1");
+    }
+
+    #[test]
+    fn test_illegal_saved_move_is_rejected() {
+        let mut game = Game::new();
+        game.play(square("e2"), square("e4")).unwrap();
+        let json = game.to_json().replace("\"e4\"", "\"e5\"");
+        assert!(Game::from_json(&json).is_err_and(|err| err.contains("not legal")));
+        assert!(Game::from_json("{}").is_err());
+    }
+
+    #[test]
+    fn test_impossible_en_passant_target_is_rejected() {
+        let json = Game::new().to_json();
+        for target in ["d5", "a1"] {
+            let edited = json.replacen("\"en_passant\": null", &format!("\"en_passant\": \"{}\"", target), 1);
+            assert_ne!(edited, json);
+            let Err(err) = Game::from_json(&edited) else {
+                panic!("{} cannot be an en passant target", target);
+            };
+            assert_eq!(err, format!("No pawn can be taken en passant on {}.", target));
+        }
+        // a real target is kept
+        let mut start = Game::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").unwrap();
+        start.play(square("e8"), square("d8")).unwrap();
+        assert!(Game::from_json(&start.to_json()).is_ok());
+    }
+}
@@ -0,0 +1,111 @@
+use strum::IntoEnumIterator;
+use crate::gui::display::DisplayOptions;
+use crate::gui::error::GuiError;
+use crate::gui::setup::SetupCommand;
+use crate::gui::status::GameStatus;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::{File, Rank, Square};
+use super::{CastlingRights, Game, GameEvent};
+
+impl Game {
+    /// Runs the board editor: the player places and removes pieces on a copy of the current
+    /// position, then `done` replaces the game with the new position once it passes the same
+    /// checks as a FEN (one king per side, no pawn on a back rank). `cancel` keeps the game.
+    pub(crate) fn edit_position(&mut self) {
+        println!("Board editor: put <piece><square> (uppercase is White), remove <square>, clear, turn <white|black>, done, cancel");
+        let mut board = self.clone();
+        self.gui.show_last_move(None);
+        self.gui.show_captured(None);
+        loop {
+            let command = self.gui.render(&board.get_all_position(), board.turn, &[], GameStatus::InProgress, DisplayOptions::default())
+                .map_err(GuiError::from)
+                .and_then(|()| self.gui.wait_for_setup_command());
+            match command {
+                Ok(SetupCommand::Put(piece, side, square)) => {
+                    board.clear_square(square);
+                    board.put_piece(side, piece, square);
+                }
+                Ok(SetupCommand::Remove(square)) => board.clear_square(square),
+                Ok(SetupCommand::Clear) => {
+                    for idx in 0..64 {
+                        if let Ok(square) = Square::try_from(idx) {
+                            board.clear_square(square);
+                        }
+                    }
+                }
+                Ok(SetupCommand::Turn(side)) => board.turn = side,
+                // a closed input cancels, and the game loop reports it
+                Ok(SetupCommand::Cancel) | Err(_) => {
+                    println!("Setup cancelled.");
+                    return;
+                }
+                Ok(SetupCommand::Done) => match board.finish_setup() {
+                    Ok(position) => {
+                        self.load_position(position);
+                        self.draw_offer = None;
+                        self.declared_result = None;
+                        self.log_event(GameEvent::Setup { fen: self.fen() });
+                        println!("Position set up: {}", self.fen());
+                        return;
+                    }
+                    Err(err) => println!("{}", err),
+                },
+            }
+        }
+    }
+
+    /// Removes the piece standing on `square`, if any.
+    fn clear_square(&mut self, square: Square) {
+        if let Some((piece, side)) = self.piece_at(square) {
+            self.take_piece(side, piece, square);
+        }
+    }
+
+    /// Checks an edited position and turns it into a fresh game. Castling is allowed wherever
+    /// the king and the rook stand on their original squares.
+    ///
+    /// # Returns
+    /// - `Ok(Game)`: If each side has exactly one king and no pawn stands on a back rank.
+    /// - `Err(String)`: A message describing the first problem found.
+    fn finish_setup(&self) -> Result<Game, String> {
+        let mut castling_rights = CastlingRights::NONE;
+        for side in Color::iter() {
+            let rank = if side == Color::White { Rank::One } else { Rank::Eight };
+            let has = |piece: Piece, file: File| self.piece_at(Square::new(file, rank)) == Some((piece, side));
+            if has(Piece::King, File::E) {
+                for (king_side, file) in [(true, File::H), (false, File::A)] {
+                    if has(Piece::Rock, file) {
+                        castling_rights.grant(side, king_side);
+                    }
+                }
+            }
+        }
+        Self::from_parts(self.pieces_location, self.turn, castling_rights, 0, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_finish_setup_validates_kings_and_pawns() {
+        let mut board = Game::new();
+        for idx in 0..64 {
+            board.clear_square(Square::try_from(idx).unwrap());
+        }
+        board.put_piece(Color::White, Piece::King, square("e1"));
+        assert_eq!(board.finish_setup().err(), Some("Black must have exactly one king.".to_string()));
+        board.put_piece(Color::Black, Piece::King, square("g8"));
+        board.put_piece(Color::White, Piece::Pawn, square("c8"));
+        assert!(board.finish_setup().is_err());
+        board.clear_square(square("c8"));
+        board.put_piece(Color::White, Piece::Rock, square("h1"));
+        board.put_piece(Color::Black, Piece::Rock, square("a8"));
+        let game = board.finish_setup().unwrap();
+        // the black king is not on e8, so Black cannot castle
+        assert_eq!(game.fen(), "r5k1/8/8/8/8/8/8/4K2R w K - 0 1");
+    }
+}
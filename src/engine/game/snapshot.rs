@@ -0,0 +1,97 @@
+use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use crate::pieces::common::Color;
+use super::Game;
+
+/// The state of a game in progress as spectators see it. The game loop publishes one after
+/// every move to the snapshot shared with [`Game::publish_to`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GameSnapshot {
+    /// Counts the publications, so that a viewer can tell whether anything changed.
+    pub version: u64,
+    pub fen: String,
+    /// The ranks from 8 down to 1, one FEN letter per square and `.` for an empty one.
+    pub board: Vec<String>,
+    pub turn: Color,
+    /// The moves played, e.g. `1. e4 e5 2. Nf3`.
+    pub moves: String,
+    /// The last move with its number, e.g. `2. Nf3`.
+    pub last_move: Option<String>,
+    /// Both players' remaining time, in games with clocks.
+    pub clock: Option<String>,
+    /// The result, once the game is decided.
+    pub result: Option<String>,
+}
+
+impl Default for GameSnapshot {
+    /// The snapshot of a game not started yet: the standard starting position.
+    fn default() -> Self {
+        Game::new().snapshot()
+    }
+}
+
+impl Game {
+    /// Returns the state of the game as spectators see it.
+    pub fn snapshot(&self) -> GameSnapshot {
+        let fen = self.fen();
+        let board = fen.split(' ').next().unwrap_or_default().split('/')
+            .map(|rank| rank.chars().map(|symbol| match symbol.to_digit(10) {
+                Some(empty) => ".".repeat(empty as usize),
+                None => symbol.to_string(),
+            }).collect())
+            .collect();
+        GameSnapshot {
+            version: 0,
+            board,
+            fen,
+            turn: self.turn,
+            moves: self.move_list(),
+            last_move: self.last_move_san(),
+            clock: self.clock.map(|clock| clock.display(self.turn, self.turn_started.elapsed())),
+            result: self.game_result().map(|result| result.to_string()),
+        }
+    }
+
+    /// Publishes the state of the game to `shared` after every move from now on, e.g. for a
+    /// [`crate::SpectatorServer`] to serve.
+    ///
+    /// # Arguments
+    /// - `shared`: The snapshot read by the spectators; it is updated at once.
+    pub fn publish_to(&mut self, shared: Arc<RwLock<GameSnapshot>>) {
+        self.spectators = Some(shared);
+        self.publish_snapshot();
+    }
+
+    /// Replaces the published snapshot with the current state, if the game is watched.
+    pub(crate) fn publish_snapshot(&self) {
+        let Some(shared) = &self.spectators else {
+            return;
+        };
+        let mut snapshot = self.snapshot();
+        // a spectator thread that panicked does not stop the game
+        let mut published = shared.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        snapshot.version = published.version + 1;
+        *published = snapshot;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_published_snapshot_follows_the_moves() {
+        let shared = Arc::new(RwLock::new(GameSnapshot::default()));
+        let mut game = Game::new();
+        game.publish_to(Arc::clone(&shared));
+        assert_eq!(shared.read().unwrap().version, 1);
+        game.play_san("e4").unwrap();
+        game.publish_snapshot();
+        let snapshot = shared.read().unwrap().clone();
+        assert_eq!(snapshot.version, 2);
+        assert_eq!((snapshot.turn, snapshot.moves.as_str(), snapshot.last_move.as_deref()), (Color::Black, "1. e4", Some("1. e4")));
+        assert_eq!(snapshot.board[4], "....P...");
+        assert_eq!(snapshot.board[7], "RNBQKBNR");
+        assert_eq!(snapshot.result, None);
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt;
+use crate::pieces::Piece;
+use crate::square::Square;
+use super::Game;
+
+/// A blunder spotted by looking one reply ahead, shown before the move is played.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum TacticAlert {
+    /// The opponent can checkmate at once with the given move.
+    AllowsMate(String),
+    /// The opponent can win the queen with the given capture.
+    LosesQueen(String),
+}
+
+impl fmt::Display for TacticAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TacticAlert::AllowsMate(reply) => write!(f, "Careful: this move allows mate in one with {}.", reply),
+            TacticAlert::LosesQueen(reply) => write!(f, "Careful: this move loses your queen to {}.", reply),
+        }
+    }
+}
+
+impl Game {
+    /// Looks at every reply to a move of the side to move and reports the worst blunder: a
+    /// reply that checkmates, or else one that wins the queen, i.e. a capture of the queen by
+    /// a lesser piece, or by the opposing queen when it cannot be taken back.
+    ///
+    /// # Arguments
+    /// - `from`: The `Square` where the piece is currently located.
+    /// - `to`: The `Square` where the piece is intended to move; the move must be legal.
+    ///
+    /// # Returns
+    /// - `Some(TacticAlert)`: If the move allows mate in one or loses the queen.
+    /// - `None`: If neither reply exists.
+    pub(crate) fn tactic_alert(&self, from: Square, to: Square) -> Option<TacticAlert> {
+        let mut board = self.clone();
+        board.play_legal(from, to);
+        let mut queen_loss = None;
+        for reply in board.legal_move_list() {
+            let san = board.move_san(reply);
+            let record = board.make_move(reply);
+            let answers = board.legal_move_list();
+            let is_mate = answers.is_empty() && board.is_checked();
+            let wins_queen = reply.captured == Some(Piece::Queen)
+                && (reply.piece != Piece::Queen || answers.iter().all(|answer| answer.to != reply.to));
+            board.unmake_move(record);
+            if is_mate {
+                return Some(TacticAlert::AllowsMate(san));
+            }
+            if wins_queen && queen_loss.is_none() {
+                queen_loss = Some(TacticAlert::LosesQueen(san));
+            }
+        }
+        queen_loss
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_alert_on_allowing_mate_in_one() {
+        let mut game = Game::new();
+        game.play(square("f2"), square("f3")).unwrap();
+        game.play(square("e7"), square("e5")).unwrap();
+        assert_eq!(game.tactic_alert(square("g2"), square("g4")), Some(TacticAlert::AllowsMate("Qh4#".to_string())));
+        assert_eq!(game.tactic_alert(square("e2"), square("e4")), None);
+    }
+
+    #[test]
+    fn test_alert_on_hanging_the_queen() {
+        let game = Game::from_fen("4k3/8/8/2p5/8/8/8/3QK3 w - - 0 1").unwrap();
+        assert_eq!(game.tactic_alert(square("d1"), square("d4")), Some(TacticAlert::LosesQueen("cxd4".to_string())));
+        assert_eq!(game.tactic_alert(square("d1"), square("d3")), None);
+        // a queen trade is not a loss when the queen can be taken back
+        let game = Game::from_fen("3qk3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+        assert_eq!(game.tactic_alert(square("d1"), square("d2")), None);
+        assert_eq!(game.tactic_alert(square("d1"), square("d5")), Some(TacticAlert::LosesQueen("Qxd5".to_string())));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::bitboard::BitBoard;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::Square;
+use super::Game;
+
+/// The squares attacked by each side, refreshed from the capture masks after every move so
+/// that check and castling tests look at a single mask instead of combining six.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct ThreatBoard {
+    attacked: [BitBoard; 2],
+}
+
+impl ThreatBoard {
+    /// Creates a threat board where no square is attacked.
+    pub(crate) fn new() -> Self {
+        Self { attacked: [BitBoard::empty(); 2] }
+    }
+
+    /// Recomputes the attacked squares of both sides.
+    ///
+    /// # Arguments
+    /// - `capture_masks`: The capture masks of every piece, indexed by side and piece.
+    pub(crate) fn update(&mut self, capture_masks: &[[BitBoard; 6]; 2]) {
+        for (attacked, masks) in self.attacked.iter_mut().zip(capture_masks) {
+            *attacked = Game::combine(masks);
+        }
+    }
+
+    /// Returns every square attacked by `side`.
+    pub(crate) fn attacked(&self, side: Color) -> BitBoard {
+        self.attacked[usize::from(side)]
+    }
+}
+
+impl Game {
+    /// Lists the squares attacked by a side, whether or not they hold a piece, e.g. to show
+    /// where the opponent's king may not go.
+    ///
+    /// # Arguments
+    /// - `side`: The attacking side.
+    ///
+    /// # Returns
+    /// - `Vec<Square>`: The attacked squares, ordered from `a1` to `h8`.
+    pub fn attacked_squares(&self, side: Color) -> Vec<Square> {
+        self.threats.attacked(side).into_iter().collect()
+    }
+
+    /// Lists the pieces of a side that attack a square, or defend it if one of its own pieces
+    /// stands there. Unlike [`Game::attacked_squares`], a square held by the side counts.
+    ///
+    /// # Arguments
+    /// - `square`: The square to look at.
+    /// - `side`: The side whose pieces are listed.
+    ///
+    /// # Returns
+    /// - `Vec<(Piece, Square)>`: Each attacking piece and where it stands, ordered from `a1` to `h8`.
+    pub fn attackers_of(&self, square: Square, side: Color) -> Vec<(Piece, Square)> {
+        Self::attackers(&self.pieces_location, square, side)
+            .into_iter()
+            .filter_map(|from| self.piece_at(from).map(|(piece, _)| (piece, from)))
+            .collect()
+    }
+
+    /// Lists the squares of the pieces giving check to the side to move: none, one, or two
+    /// in a double check, where only a king move gets out of check.
+    ///
+    /// # Returns
+    /// - `Vec<Square>`: The squares of the checking pieces, ordered from `a1` to `h8`.
+    pub fn checkers(&self) -> Vec<Square> {
+        match self.is_checked() {
+            true => self.checker_mask(self.turn).into_iter().collect(),
+            false => Vec::new(),
+        }
+    }
+
+    /// Returns the squares of the enemy pieces attacking the king of `side`.
+    pub(crate) fn checker_mask(&self, side: Color) -> BitBoard {
+        self.king_square(side)
+            .map_or(BitBoard::empty(), |king| Self::attackers(&self.pieces_location, king, side.opposite()))
+    }
+
+    /// Recomputes the threat board from the capture masks of the pieces.
+    pub(crate) fn refresh_threats(&mut self) {
+        self.threats.update(&self.pieces_capture_movement);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_attacked_squares_follow_the_moves() {
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        let rook_and_king = game.attacked_squares(Color::White);
+        assert_eq!(rook_and_king.len(), 14);
+        // squares held by the side's own pieces are not attacked
+        assert!(rook_and_king.contains(&square("a8")) && !rook_and_king.contains(&square("e1")));
+        game.play(square("a1"), square("a7")).unwrap();
+        let attacked = game.attacked_squares(Color::White);
+        assert!(attacked.contains(&square("h7")) && attacked.contains(&square("e2")) && !attacked.contains(&square("b1")));
+        game.undo();
+        assert_eq!(game.attacked_squares(Color::White), rook_and_king);
+    }
+
+    #[test]
+    fn test_attackers_and_defenders_of_a_square() {
+        // the e5 pawn is attacked by the rook and the knight, defended by the pawn and the queen
+        let game = Game::from_fen("4k3/4q3/3p4/4p3/8/5N2/8/4RK2 w - - 0 1").unwrap();
+        assert_eq!(game.attackers_of(square("e5"), Color::White), [(Piece::Rock, square("e1")), (Piece::Knight, square("f3"))]);
+        assert_eq!(game.attackers_of(square("e5"), Color::Black), [(Piece::Pawn, square("d6")), (Piece::Queen, square("e7"))]);
+        assert!(game.attackers_of(square("e1"), Color::White).contains(&(Piece::King, square("f1"))));
+    }
+}
@@ -0,0 +1,164 @@
+use std::time::Duration;
+use crate::engine::clock::{MoveTiming, PgnTime};
+use crate::engine::pgn;
+use crate::pieces::common::Color;
+use super::{Game, GameResult, PlayerKind};
+
+impl Game {
+    /// Remembers how long the move just played took, next to it in the history. The time left
+    /// on the mover's clock is kept too in a timed game, so it must be called after the clock
+    /// was punched.
+    pub(crate) fn record_move_timing(&mut self, elapsed: Duration) {
+        let Some(ply) = self.history.len().checked_sub(1) else {
+            return;
+        };
+        let side = self.turn.opposite();
+        // moves played without a timer, e.g. by the tutorial, have no timing
+        self.move_timings.resize(ply, None);
+        let clock = self.clock.map(|clock| clock.remaining(side, Duration::ZERO));
+        self.move_timings.push(Some(MoveTiming { side, elapsed, clock }));
+    }
+
+    /// Returns the timing of each move of the history, `None` for the moves that were not timed.
+    fn timings(&self) -> impl Iterator<Item = Option<MoveTiming>> + '_ {
+        (0..self.history.len()).map(|ply| self.move_timings.get(ply).copied().flatten())
+    }
+
+    /// Returns the time a side has spent thinking over the moves of the history.
+    pub fn time_used(&self, side: Color) -> Duration {
+        self.timings().flatten().filter(|timing| timing.side == side).map(|timing| timing.elapsed).sum()
+    }
+
+    /// Returns the line shown under the board after a timed move, e.g.
+    /// `Move time: 3.2s | Time used: White 0:00:04 | Black 0:00:07`, or `None` before the first one.
+    pub(crate) fn move_time_summary(&self) -> Option<String> {
+        let last = self.timings().last().flatten()?;
+        Some(format!(
+            "Move time: {:.1}s | Time used: White {} | Black {}",
+            last.elapsed.as_secs_f64(),
+            PgnTime(self.time_used(Color::White)),
+            PgnTime(self.time_used(Color::Black)),
+        ))
+    }
+
+    /// Writes the game in PGN, with the seven standard tags, the starting position if it is not
+    /// the standard one, the annotations and comments of the moves, and after each timed move a
+    /// `[%clk]` comment with the time left on the mover's clock, or an `[%emt]` comment with the
+    /// time the move took if the game is not played with a clock. A checkmate of a known
+    /// pattern is named in a comment before the result, e.g. `{back-rank mate} 1-0`.
+    pub fn to_pgn(&self) -> String {
+        let player = |player: PlayerKind| match player {
+            PlayerKind::Human => "Human".to_string(),
+            PlayerKind::Engine { depth } => format!("Engine (depth {})", depth),
+            PlayerKind::Remote => "Peer".to_string(),
+        };
+        let result = match self.game_result() {
+            Some(GameResult::Checkmate(loser) | GameResult::Resignation(loser) | GameResult::Timeout(loser) | GameResult::KingExploded(loser)) => {
+                if loser == Color::White { "0-1" } else { "1-0" }
+            }
+            Some(GameResult::Draw | GameResult::DrawByAgreement | GameResult::TimeoutDraw(_)) => "1/2-1/2",
+            None => "*",
+        };
+        let mut replay = self.clone();
+        while replay.undo() {}
+        let mut tags = vec![
+            ("Event", "Casual game".to_string()),
+            ("Site", "?".to_string()),
+            ("Date", "????.??.??".to_string()),
+            ("Round", "-".to_string()),
+            ("White", player(self.players[usize::from(Color::White)])),
+            ("Black", player(self.players[usize::from(Color::Black)])),
+            ("Result", result.to_string()),
+        ];
+        if replay.fen() != Game::new().fen() {
+            tags.push(("SetUp", "1".to_string()));
+            tags.push(("FEN", replay.fen()));
+        }
+        if self.variant != Default::default() {
+            tags.push(("Variant", self.variant.to_string()));
+        }
+        let mut tokens = Vec::new();
+        for (ply, (record, timing)) in self.history.iter().zip(self.timings()).enumerate() {
+            let san = replay.move_san(record.mv);
+            match replay.turn {
+                Color::White => tokens.push(format!("{}. {}", replay.fullmove_number, san)),
+                Color::Black if tokens.is_empty() => tokens.push(format!("{}... {}", replay.fullmove_number, san)),
+                Color::Black => tokens.push(san),
+            }
+            let (nag, comment) = self.annotation(ply + 1);
+            if let Some(nag) = nag {
+                tokens.push(format!("${}", nag.code()));
+            }
+            if let Some(comment) = comment {
+                tokens.push(format!("{{{}}}", comment));
+            }
+            match timing {
+                Some(MoveTiming { clock: Some(clock), .. }) => tokens.push(format!("{{[%clk {}]}}", PgnTime(clock))),
+                Some(MoveTiming { elapsed, .. }) => tokens.push(format!("{{[%emt {}]}}", PgnTime(elapsed))),
+                None => {}
+            }
+            replay.redo();
+        }
+        if let (Some(GameResult::Checkmate(_)), Some(pattern)) = (self.game_result(), self.mate_pattern()) {
+            tokens.push(format!("{{{}}}", pattern));
+        }
+        tokens.push(result.to_string());
+        pgn::write_pgn(&tags, tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::clock::{Clock, TimeControl};
+    use crate::square::square;
+
+    #[test]
+    fn test_timings_are_summed_and_exported_as_clock_comments() {
+        let mut game = Game::new();
+        game.clock = Some(Clock::new(TimeControl::parse("5").unwrap()));
+        for (from, to, seconds) in [("e2", "e4", 3), ("e7", "e5", 10), ("g1", "f3", 2)] {
+            game.play(square(from), square(to)).unwrap();
+            game.punch_clock(Duration::from_secs(seconds));
+            game.record_move_timing(Duration::from_secs(seconds));
+        }
+        assert_eq!(game.time_used(Color::White), Duration::from_secs(5));
+        assert_eq!(game.move_time_summary().unwrap(), "Move time: 2.0s | Time used: White 0:00:05 | Black 0:00:10");
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("1. e4 {[%clk 0:04:57]} e5 {[%clk 0:04:50]} 2. Nf3 {[%clk 0:04:55]} *"), "{}", pgn);
+        let parsed = pgn::parse_pgn(&pgn).unwrap();
+        assert_eq!(parsed[0].moves, ["e4", "e5", "Nf3"]);
+    }
+
+    #[test]
+    fn test_untimed_moves_get_no_comment_and_taken_back_moves_no_time() {
+        let mut game = Game::new();
+        game.play(square("e2"), square("e4")).unwrap();
+        game.record_move_timing(Duration::from_millis(1500));
+        game.play(square("e7"), square("e5")).unwrap();
+        game.undo();
+        game.undo();
+        assert_eq!(game.time_used(Color::White), Duration::ZERO);
+        game.play(square("d2"), square("d4")).unwrap();
+        game.play(square("d7"), square("d5")).unwrap();
+        game.record_move_timing(Duration::from_secs(61));
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("1. d4 d5 {[%emt 0:01:01]} *"), "{}", pgn);
+    }
+
+    #[test]
+    fn test_pgn_keeps_underpromotions() {
+        let mut game = Game::from_fen("8/5P1k/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        game.play_san("f8=N+").unwrap();
+        assert!(game.to_pgn().contains("1. f8=N+ *"), "{}", game.to_pgn());
+    }
+
+    #[test]
+    fn test_checkmate_pattern_is_commented_before_the_result() {
+        let mut game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        game.play_san("Ra8#").unwrap();
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("1. Ra8# {back-rank mate} 1-0"), "{}", pgn);
+        assert_eq!(pgn::parse_pgn(&pgn).unwrap()[0].moves, ["Ra8#"]);
+    }
+}
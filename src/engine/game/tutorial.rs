@@ -0,0 +1,199 @@
+use crate::gui::display::DisplayOptions;
+use crate::gui::error::GuiError;
+use crate::gui::action::Action;
+use crate::square::Square;
+use super::Game;
+
+/// One step of the tutorial: a small position and the move the player has to find in it.
+struct Lesson {
+    title: &'static str,
+    /// How the piece of the lesson moves, shown before the task and again after a wrong move.
+    rule: &'static str,
+    fen: &'static str,
+    task: &'static str,
+    from: &'static str,
+    to: &'static str,
+}
+
+/// The lessons of the tutorial, in the order they are taught.
+const LESSONS: [Lesson; 9] = [
+    Lesson {
+        title: "The rook",
+        rule: "A rook moves any number of squares along its rank or its file, but cannot jump over pieces.",
+        fen: "7k/8/8/8/8/8/8/R3K3 w - - 0 1",
+        task: "Move the rook from a1 all the way up to a8.",
+        from: "a1",
+        to: "a8",
+    },
+    Lesson {
+        title: "The bishop",
+        rule: "A bishop moves any number of squares diagonally, so it always stays on squares of one color.",
+        fen: "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        task: "Move the bishop from c1 to h6.",
+        from: "c1",
+        to: "h6",
+    },
+    Lesson {
+        title: "The queen",
+        rule: "A queen moves like a rook and a bishop together: along ranks, files and diagonals.",
+        fen: "4k3/8/8/8/8/8/8/3QK3 w - - 0 1",
+        task: "Move the queen diagonally from d1 to a4.",
+        from: "d1",
+        to: "a4",
+    },
+    Lesson {
+        title: "The knight",
+        rule: "A knight jumps in an L shape: two squares in one direction and one to the side. It is the only piece that jumps over others.",
+        fen: "4k3/8/8/8/8/8/3PP3/1N2K3 w - - 0 1",
+        task: "Jump with the knight from b1 to c3.",
+        from: "b1",
+        to: "c3",
+    },
+    Lesson {
+        title: "The pawn",
+        rule: "A pawn moves one square forward, or two squares from its starting rank, if the squares are empty.",
+        fen: "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+        task: "Push the pawn two squares, from e2 to e4.",
+        from: "e2",
+        to: "e4",
+    },
+    Lesson {
+        title: "Pawn captures",
+        rule: "A pawn captures one square diagonally forward; it cannot capture straight ahead.",
+        fen: "4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1",
+        task: "Capture the black pawn on d5 with the pawn on e4.",
+        from: "e4",
+        to: "d5",
+    },
+    Lesson {
+        title: "Castling",
+        rule: "The king moves one square in any direction. Once per game it may castle: it moves two squares towards a rook that has not moved, and the rook jumps over it. The squares in between must be empty and the king may not pass through check.",
+        fen: "4k3/8/8/8/8/8/8/4K2R w K - 0 1",
+        task: "Castle king-side by moving the king from e1 to g1.",
+        from: "e1",
+        to: "g1",
+    },
+    Lesson {
+        title: "Promotion",
+        rule: "A pawn that reaches the last rank is promoted, here to a queen.",
+        fen: "4k3/P7/8/8/8/8/8/4K3 w - - 0 1",
+        task: "Promote the pawn by moving it from a7 to a8.",
+        from: "a7",
+        to: "a8",
+    },
+    Lesson {
+        title: "Checkmate",
+        rule: "A king that is attacked and cannot escape is checkmated, which wins the game.",
+        fen: "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1",
+        task: "The black king is boxed in by its own pawns. Checkmate it with the rook.",
+        from: "a1",
+        to: "a8",
+    },
+];
+
+impl Game {
+    /// Walks a new player through the lessons of the tutorial, one at a time. Each lesson sets
+    /// up its position on a separate board and waits until the player plays the requested move,
+    /// explaining why any other move was refused. This game is never modified.
+    pub fn start_tutorial(&mut self) {
+        println!("Welcome to the tutorial. Type moves as in a game (e.g. Nf3 or move g1 f3),");
+        println!("show <square> to see where a piece can go, or quit to leave.");
+        for (idx, lesson) in LESSONS.iter().enumerate() {
+            let mut board = Game::from_fen(lesson.fen).expect("lesson positions are valid");
+            println!();
+            println!("Lesson {}/{}: {}", idx + 1, LESSONS.len(), lesson.title);
+            println!("{}", lesson.rule);
+            let mut highlights = Vec::new();
+            loop {
+                println!("{}", lesson.task);
+                let event = self.gui.render(&board.get_all_position(), board.turn, &highlights, board.status(), DisplayOptions::default())
+                    .map_err(GuiError::from)
+                    .and_then(|()| self.gui.wait_and_process_event());
+                highlights.clear();
+                let attempt = match event {
+                    Ok(Some(Action::Move(from, to))) => Ok((from, to)),
+                    Ok(Some(Action::San(san))) => board.resolve_san(&san).map_err(|err| err.to_string()),
+                    Ok(Some(Action::ShowMoves(square))) => {
+                        highlights = board.move_highlights(square);
+                        continue;
+                    }
+                    Err(err) => {
+                        println!("{} Leaving the tutorial.", err);
+                        return;
+                    }
+                    _ => {
+                        println!("That command is not part of the tutorial.");
+                        continue;
+                    }
+                };
+                match attempt.and_then(|(from, to)| board.try_lesson_move(lesson, from, to)) {
+                    Ok(()) => break,
+                    Err(err) => {
+                        println!("{}", err);
+                        println!("Remember: {}", lesson.rule);
+                    }
+                }
+            }
+            if let Err(err) = self.gui.render(&board.get_all_position(), board.turn, &[], board.status(), DisplayOptions::default()) {
+                tracing::error!(%err, "cannot draw the board");
+                return;
+            }
+            println!();
+            println!("Well done!");
+        }
+        println!();
+        println!("You have finished the tutorial. Run the game without --tutorial to play a full game.");
+    }
+
+    /// Plays a move on a lesson board and checks that it is the move the lesson asks for.
+    ///
+    /// # Arguments
+    /// - `lesson`: The lesson being played.
+    /// - `from`: The `Square` where the piece is currently located.
+    /// - `to`: The `Square` where the piece is intended to move.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the move was the requested one; it stays on the board.
+    /// - `Err(String)`: If the move is illegal or is not the requested one, explaining why. The
+    ///   board is left untouched.
+    fn try_lesson_move(&mut self, lesson: &Lesson, from: Square, to: Square) -> Result<(), String> {
+        self.play(from, to).map_err(|err| err.to_string())?;
+        if from.to_string() != lesson.from || to.to_string() != lesson.to {
+            self.undo();
+            return Err(format!(
+                "{}{} is a legal move, but this lesson asks for {}{}.",
+                from, to, lesson.from, lesson.to
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_every_lesson_move_is_legal() {
+        for lesson in &LESSONS {
+            let mut board = Game::from_fen(lesson.fen).unwrap();
+            assert_eq!(board.try_lesson_move(lesson, square(lesson.from), square(lesson.to)), Ok(()), "{}", lesson.title);
+        }
+        let mut board = Game::from_fen(LESSONS[8].fen).unwrap();
+        board.try_lesson_move(&LESSONS[8], square("a1"), square("a8")).unwrap();
+        assert!(board.game_result().is_some());
+    }
+
+    #[test]
+    fn test_wrong_lesson_moves_are_explained_and_taken_back() {
+        let lesson = &LESSONS[0];
+        let mut board = Game::from_fen(lesson.fen).unwrap();
+        let fen = board.fen();
+        let err = board.try_lesson_move(lesson, square("a1"), square("a5")).unwrap_err();
+        assert!(err.contains("legal move"), "{}", err);
+        assert_eq!(board.fen(), fen);
+        assert!(board.try_lesson_move(lesson, square("a1"), square("b2")).is_err());
+        assert_eq!(board.fen(), fen);
+    }
+}
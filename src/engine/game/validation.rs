@@ -0,0 +1,92 @@
+use strum::IntoEnumIterator;
+use crate::bitboard::BitBoard;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::{File, Rank, Square};
+use super::Game;
+
+impl Game {
+    /// Checks the invariants every playable position keeps: no two pieces on one square, the
+    /// occupancy of each side matching its pieces, exactly one king per side (none after an
+    /// Atomic explosion), no pawn on a back rank, castling rights only with the king and rook
+    /// on their original squares, and an en passant target only behind a pawn that just
+    /// moved two squares.
+    ///
+    /// Debug builds run it after every move made or taken back.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the position keeps every invariant.
+    /// - `Err(String)`: A message describing the first one broken.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut occupied = BitBoard::empty();
+        for side in Color::iter() {
+            for piece in Piece::iter() {
+                let location = self.pieces(side, piece);
+                if !(occupied & location).is_empty() {
+                    return Err(format!("Two pieces stand on {}.", occupied & location));
+                }
+                occupied |= location;
+            }
+            if self.side_pieces(side) != Self::combine(&self.pieces_location[usize::from(side)]) {
+                return Err(format!("The occupancy of {:?} does not match its pieces.", side));
+            }
+        }
+        for side in Color::iter() {
+            let kings = self.pieces(side, Piece::King).count();
+            let lost = kings == 0 && self.variant.rules().kings_can_be_lost();
+            if kings != 1 && !lost {
+                return Err(format!("{:?} must have exactly one king.", side));
+            }
+        }
+        let back_ranks = BitBoard::from(Rank::One) | BitBoard::from(Rank::Eight);
+        let pawns = self.pieces(Color::White, Piece::Pawn) | self.pieces(Color::Black, Piece::Pawn);
+        if !(pawns & back_ranks).is_empty() {
+            return Err("Pawns cannot stand on the first or last rank.".to_string());
+        }
+        for side in Color::iter() {
+            let home = if side == Color::White { Rank::One } else { Rank::Eight };
+            for (king_side, rook_file) in [(true, File::H), (false, File::A)] {
+                let in_place = self.piece_at(Square::new(File::E, home)) == Some((Piece::King, side))
+                    && self.piece_at(Square::new(rook_file, home)) == Some((Piece::Rock, side));
+                if self.castling_rights.has(side, king_side) && !in_place {
+                    let wing = if king_side { "king" } else { "queen" };
+                    return Err(format!("{:?} cannot castle {}-side without its king and rook on their original squares.", side, wing));
+                }
+            }
+        }
+        if let Some(square) = self.en_passant {
+            self.check_en_passant(square)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_played_positions_are_valid() {
+        let mut game = Game::new();
+        assert_eq!(game.validate(), Ok(()));
+        for choice in [3, 17, 5, 9, 0, 21, 12, 7] {
+            let moves = game.all_legal_moves(game.turn);
+            let (from, to) = moves[choice % moves.len()];
+            game.play_legal(from, to);
+            assert_eq!(game.validate(), Ok(()), "after {}{}", from, to);
+        }
+    }
+
+    #[test]
+    fn test_broken_invariants_are_reported() {
+        let mut game = Game::new();
+        game.castling_rights.grant(Color::White, true);
+        game.pieces_location[usize::from(Color::White)][usize::from(Piece::Rock)] = BitBoard::from(Square::new(File::A, Rank::One));
+        game.refresh_occupancy();
+        assert_eq!(game.validate(), Err("White cannot castle king-side without its king and rook on their original squares.".to_string()));
+        let mut game = Game::new();
+        game.pieces_location[usize::from(Color::Black)][usize::from(Piece::Knight)] |= BitBoard::from(Square::new(File::E, Rank::Two));
+        assert_eq!(game.validate(), Err("Two pieces stand on {e2}.".to_string()));
+        assert!(Game::from_fen("4k3/8/8/8/8/8/8/4K2R w Q - 0 1").is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::bitboard::BitBoard;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::Square;
+use super::atomic::AtomicRules;
+use super::{Game, GameResult, Move};
+
+/// The rules a game is played by. Standard chess is the default; a variant changes how moves
+/// are applied and which of them are legal through its [`VariantRules`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Variant {
+    #[default]
+    Standard,
+    /// Every capture explodes: the capturing piece and every piece other than a pawn next to
+    /// the capture square leave the board. Kings cannot capture, and exploding the enemy king
+    /// wins the game.
+    Atomic,
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Variant::Standard => write!(f, "Standard"),
+            Variant::Atomic => write!(f, "Atomic"),
+        }
+    }
+}
+
+impl Variant {
+    /// Returns the rules of the variant, which the game consults wherever they differ from
+    /// one variant to another.
+    pub(crate) fn rules(self) -> &'static dyn VariantRules {
+        match self {
+            Variant::Standard => &StandardRules,
+            Variant::Atomic => &AtomicRules,
+        }
+    }
+}
+
+/// The hooks through which a variant changes how moves are applied, which of them are legal
+/// and how the game ends. The defaults are the standard rules.
+pub(crate) trait VariantRules: Sync {
+    /// Whether a move changes the board beyond moving, capturing and castling, in which case
+    /// the board is saved before it so that it can be taken back, and
+    /// [`VariantRules::after_move`] is called once it is played.
+    fn has_side_effects(&self, _mv: &Move) -> bool {
+        false
+    }
+
+    /// Applies the side effects of a move that has just been played on the board. The masks
+    /// are computed again afterwards.
+    fn after_move(&self, _game: &mut Game, _mv: &Move) {}
+
+    /// Checks whether a pseudo-legal move other than castling is legal.
+    fn is_legal(&self, game: &Game, from: Square, to: Square, piece: Piece, side: Color) -> bool {
+        game.is_king_safe_after(from, to, piece, side)
+    }
+
+    /// Filters the pseudo-legal targets of a piece down to its legal moves.
+    ///
+    /// # Arguments
+    /// - `game`: The game, whose position the piece stands in.
+    /// - `from`: The `Square` of the piece.
+    /// - `piece`: The `Piece` type.
+    /// - `side`: The `Color` of the piece.
+    /// - `candidates`: The pseudo-legal targets, castling squares included.
+    fn legal_targets(&self, game: &Game, from: Square, piece: Piece, side: Color, candidates: BitBoard) -> BitBoard {
+        candidates.into_iter()
+            .filter(|to| match game.is_castling_move(from, *to, piece) {
+                true => game.is_castling_legal(from, *to, side),
+                false => self.is_legal(game, from, *to, piece, side),
+            })
+            .fold(BitBoard::empty(), |legal, to| legal | BitBoard::from(to))
+    }
+
+    /// Whether the king of the side to move can be in check in the current position.
+    fn can_be_checked(&self, _game: &Game) -> bool {
+        true
+    }
+
+    /// Whether a side may have no king left, as after it was captured.
+    fn kings_can_be_lost(&self) -> bool {
+        false
+    }
+
+    /// Returns the result of a game the variant ends by its own rules, before checkmate and
+    /// stalemate are looked for.
+    fn result(&self, _game: &Game) -> Option<GameResult> {
+        None
+    }
+}
+
+/// The rules of standard chess.
+struct StandardRules;
+
+impl VariantRules for StandardRules {
+    /// Only simulates the moves that may expose the king, using the pins and checkers of the
+    /// position for the others.
+    fn legal_targets(&self, game: &Game, from: Square, piece: Piece, side: Color, candidates: BitBoard) -> BitBoard {
+        game.king_safe_targets(from, piece, side, candidates)
+    }
+}
+
+impl Game {
+    /// Plays the game by the rules of `variant`. Meant to be called before the first move;
+    /// the position is kept as it is.
+    pub fn set_variant(&mut self, variant: Variant) {
+        self.variant = variant;
+    }
+
+    /// Returns the rules the game is played by.
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_rules_follow_the_variant() {
+        let game = Game::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        let capture = game.describe_move(square("e4"), square("d5")).unwrap();
+        let push = game.describe_move(square("e4"), square("e5")).unwrap();
+        assert!(!Variant::Standard.rules().has_side_effects(&capture));
+        assert!(Variant::Atomic.rules().has_side_effects(&capture));
+        assert!(!Variant::Atomic.rules().has_side_effects(&push));
+        // a rook checks the king beside the enemy king only in standard chess
+        let game = Game::from_fen("8/8/8/8/8/3k4/3K3r/8 w - - 0 1").unwrap();
+        assert!(Variant::Standard.rules().can_be_checked(&game));
+        assert!(!Variant::Atomic.rules().can_be_checked(&game));
+        assert_eq!(Variant::Standard.rules().result(&game), None);
+    }
+}
@@ -0,0 +1,126 @@
+use strum::IntoEnumIterator;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use super::Game;
+
+/// Index of the side-to-move key, after the 2 * 6 * 64 piece-square keys.
+const SIDE_KEY: usize = 768;
+/// Index of the first of the four castling keys (`K`, `Q`, `k`, `q`).
+const CASTLING_KEYS: usize = 769;
+/// Index of the first of the eight en passant file keys.
+const EN_PASSANT_KEYS: usize = 773;
+/// Number of random keys.
+const KEY_COUNT: usize = 781;
+
+/// Random keys, generated at compile time with a fixed seed so every build (and every
+/// network peer) hashes positions the same way.
+static KEYS: [u64; KEY_COUNT] = generate_keys(0x9E37_79B9_7F4A_7C15);
+
+/// A second, independent set of keys, used to check that two positions with the same
+/// Zobrist hash really are the same position.
+static VERIFICATION_KEYS: [u64; KEY_COUNT] = generate_keys(0xD1B5_4A32_D192_ED03);
+
+/// Fills the key table with the splitmix64 generator.
+const fn generate_keys(seed: u64) -> [u64; KEY_COUNT] {
+    let mut keys = [0; KEY_COUNT];
+    let mut state = seed;
+    let mut idx = 0;
+    while idx < KEY_COUNT {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        keys[idx] = z ^ (z >> 31);
+        idx += 1;
+    }
+    keys
+}
+
+impl Game {
+    /// Computes the Zobrist hash of the position: pieces, side to move, castling rights and
+    /// en passant file. Equal positions reached by different move orders hash the same.
+    ///
+    /// # Returns
+    /// - `u64`: The hash of the current position.
+    pub(crate) fn zobrist(&self) -> u64 {
+        self.hash_with(&KEYS)
+    }
+
+    /// Computes a second hash of the position with independent keys. Two positions with equal
+    /// [`Game::zobrist`] hashes but different verification hashes are a hash collision.
+    pub(crate) fn verification_hash(&self) -> u64 {
+        self.hash_with(&VERIFICATION_KEYS)
+    }
+
+    /// Hashes the position with the given key table.
+    fn hash_with(&self, keys: &[u64; KEY_COUNT]) -> u64 {
+        let mut hash = 0;
+        for side in Color::iter() {
+            for piece in Piece::iter() {
+                let base = (usize::from(side) * 6 + usize::from(piece)) * 64;
+                for square in self.pieces(side, piece) {
+                    hash ^= keys[base + usize::from(square)];
+                }
+            }
+        }
+        if self.turn == Color::Black {
+            hash ^= keys[SIDE_KEY];
+        }
+        for idx in 0..4 {
+            if self.castling_rights.bits() >> idx & 1 == 1 {
+                hash ^= keys[CASTLING_KEYS + idx];
+            }
+        }
+        if let Some(square) = self.en_passant {
+            hash ^= keys[EN_PASSANT_KEYS + usize::from(square.file())];
+        }
+        hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    fn play_all(game: &mut Game, moves: &[(&str, &str)]) {
+        for (from, to) in moves {
+            let from = square(from);
+            let to = square(to);
+            game.play(from, to).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_transpositions_hash_the_same() {
+        let mut first = Game::new();
+        play_all(&mut first, &[("g1", "f3"), ("g8", "f6"), ("b1", "c3"), ("b8", "c6")]);
+        let mut second = Game::new();
+        play_all(&mut second, &[("b1", "c3"), ("b8", "c6"), ("g1", "f3"), ("g8", "f6")]);
+        assert_eq!(first.zobrist(), second.zobrist());
+        assert_ne!(first.zobrist(), Game::new().zobrist());
+    }
+
+    #[test]
+    fn test_side_castling_and_en_passant_change_the_hash() {
+        let white = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+        let black = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 b Q - 0 1").unwrap();
+        let no_rights = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert_ne!(white.zobrist(), black.zobrist());
+        assert_ne!(white.zobrist(), no_rights.zobrist());
+        let mut double_step = Game::new();
+        play_all(&mut double_step, &[("e2", "e4")]);
+        let mut no_en_passant = double_step.clone();
+        no_en_passant.en_passant = None;
+        assert_ne!(double_step.zobrist(), no_en_passant.zobrist());
+    }
+
+    #[test]
+    fn test_undo_restores_the_hash() {
+        let mut game = Game::new();
+        let hash = game.zobrist();
+        play_all(&mut game, &[("e2", "e4")]);
+        game.undo();
+        assert_eq!(game.zobrist(), hash);
+    }
+}
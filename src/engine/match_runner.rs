@@ -0,0 +1,245 @@
+use std::fmt;
+use crate::engine::eval::EvalConfig;
+use crate::engine::game::{Game, GameResult};
+use crate::engine::{pgn, search};
+use crate::engine::tablebase::{self, Wdl};
+use crate::pieces::common::Color;
+
+/// Openings the games start from, in algebraic notation, so that two deterministic engines do
+/// not play the same game over and over. Each opening is played twice, once with each engine
+/// as White.
+const OPENINGS: [&str; 8] = ["", "e4 e5", "d4 d5", "e4 c5", "d4 Nf6", "c4 e5", "e4 e6", "e4 c6"];
+
+/// Number of plies after which an undecided game is scored as a draw, when not set otherwise.
+pub const DEFAULT_MAX_PLIES: usize = 200;
+
+/// One of the engines playing a match.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EngineConfig {
+    /// The name shown in the results and in the PGN headers.
+    pub name: String,
+    /// The search depth in plies.
+    pub depth: u8,
+    /// The weights the engine evaluates positions with.
+    pub eval: EvalConfig,
+}
+
+impl EngineConfig {
+    /// Creates an engine named after its search depth, e.g. `depth 3`, with the default weights.
+    pub fn with_depth(depth: u8) -> Self {
+        Self { name: format!("depth {}", depth), depth, eval: EvalConfig::default() }
+    }
+}
+
+/// How a match game ended.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MatchOutcome {
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+impl MatchOutcome {
+    /// Returns the result as written in PGN: `1-0`, `0-1` or `1/2-1/2`.
+    pub fn pgn(self) -> &'static str {
+        match self {
+            MatchOutcome::WhiteWins => "1-0",
+            MatchOutcome::BlackWins => "0-1",
+            MatchOutcome::Draw => "1/2-1/2",
+        }
+    }
+}
+
+/// A finished game of a match.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchGame {
+    /// Number of the game in the match, starting at `1`.
+    pub round: usize,
+    pub white: String,
+    pub black: String,
+    /// Every move of the game, opening included, in algebraic notation.
+    pub moves: Vec<String>,
+    pub outcome: MatchOutcome,
+    /// Why the game ended, e.g. `Checkmate, White wins` or `Draw, move limit`.
+    pub termination: String,
+}
+
+impl MatchGame {
+    /// Writes the game in PGN, with the seven standard tags and a termination comment.
+    pub fn pgn(&self) -> String {
+        let tags = [
+            ("Event", "Engine match".to_string()),
+            ("Site", "?".to_string()),
+            ("Date", "????.??.??".to_string()),
+            ("Round", self.round.to_string()),
+            ("White", self.white.clone()),
+            ("Black", self.black.clone()),
+            ("Result", self.outcome.pgn().to_string()),
+        ];
+        let tokens = self.moves.iter().enumerate()
+            .map(|(ply, san)| match ply % 2 {
+                0 => format!("{}. {}", ply / 2 + 1, san),
+                _ => san.clone(),
+            })
+            .chain([format!("{{{}}}", self.termination), self.outcome.pgn().to_string()]);
+        pgn::write_pgn(&tags, tokens)
+    }
+}
+
+/// Wins, draws and losses of the first engine of a match.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MatchStats {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl MatchStats {
+    /// Returns the points scored per game, between `0.0` and `1.0`; `0.5` before any game.
+    pub fn score(&self) -> f64 {
+        let games = self.wins + self.draws + self.losses;
+        if games == 0 {
+            return 0.5;
+        }
+        (f64::from(self.wins) + f64::from(self.draws) / 2.0) / f64::from(games)
+    }
+
+    /// Estimates how much stronger the first engine is, in Elo points, from its score.
+    ///
+    /// # Returns
+    /// - `Some(f64)`: The difference; positive if the first engine scored more.
+    /// - `None`: If one engine won every game, which puts no bound on the difference.
+    pub fn elo_difference(&self) -> Option<f64> {
+        let score = self.score();
+        (score > 0.0 && score < 1.0).then(|| 400.0 * (score / (1.0 - score)).log10())
+    }
+}
+
+impl fmt::Display for MatchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "+{} ={} -{} ({:.1}%", self.wins, self.draws, self.losses, self.score() * 100.0)?;
+        match self.elo_difference() {
+            Some(elo) => write!(f, ", Elo {:+.0})", elo),
+            None => write!(f, ")"),
+        }
+    }
+}
+
+/// Plays a series of games between two engines, alternating colors, to check whether one is
+/// stronger than the other.
+pub struct MatchRunner {
+    engines: [EngineConfig; 2],
+    games: usize,
+    max_plies: usize,
+}
+
+impl MatchRunner {
+    /// Creates a match of `games` games; the first engine is White in the odd games.
+    pub fn new(first: EngineConfig, second: EngineConfig, games: usize) -> Self {
+        Self { engines: [first, second], games, max_plies: DEFAULT_MAX_PLIES }
+    }
+
+    /// Sets the number of plies after which an undecided game is scored as a draw.
+    pub fn set_max_plies(&mut self, max_plies: usize) {
+        self.max_plies = max_plies;
+    }
+
+    /// Plays the match.
+    ///
+    /// # Arguments
+    /// - `on_game`: Called after every game, e.g. to print the result or write the PGN.
+    ///
+    /// # Returns
+    /// - `MatchStats`: The wins, draws and losses of the first engine.
+    pub fn run(&self, mut on_game: impl FnMut(&MatchGame, &MatchStats)) -> MatchStats {
+        let mut stats = MatchStats::default();
+        for round in 1..=self.games {
+            let first_is_white = round % 2 == 1;
+            let [white, black] = if first_is_white {
+                [&self.engines[0], &self.engines[1]]
+            } else {
+                [&self.engines[1], &self.engines[0]]
+            };
+            let opening = OPENINGS[(round - 1) / 2 % OPENINGS.len()];
+            let game = self.play(round, white, black, opening);
+            let first_won = match game.outcome {
+                MatchOutcome::Draw => None,
+                outcome => Some((outcome == MatchOutcome::WhiteWins) == first_is_white),
+            };
+            match first_won {
+                Some(true) => stats.wins += 1,
+                Some(false) => stats.losses += 1,
+                None => stats.draws += 1,
+            }
+            on_game(&game, &stats);
+        }
+        stats
+    }
+
+    /// Plays one game from the given opening, until it is decided, the tablebase knows it is
+    /// drawn, or the move limit is reached.
+    fn play(&self, round: usize, white: &EngineConfig, black: &EngineConfig, opening: &str) -> MatchGame {
+        let mut game = Game::new();
+        let mut moves = Vec::new();
+        for san in opening.split_whitespace() {
+            game.play_san(san).expect("the match openings are legal");
+            moves.push(san.to_string());
+        }
+        let (outcome, termination) = loop {
+            if let Some(result) = game.game_result() {
+                let outcome = match result {
+                    GameResult::Checkmate(Color::Black) | GameResult::Resignation(Color::Black) | GameResult::Timeout(Color::Black)
+                    | GameResult::KingExploded(Color::Black) => {
+                        MatchOutcome::WhiteWins
+                    }
+                    GameResult::Checkmate(Color::White) | GameResult::Resignation(Color::White) | GameResult::Timeout(Color::White)
+                    | GameResult::KingExploded(Color::White) => {
+                        MatchOutcome::BlackWins
+                    }
+                    GameResult::Draw | GameResult::DrawByAgreement | GameResult::TimeoutDraw(_) => MatchOutcome::Draw,
+                };
+                break (outcome, result.to_string());
+            }
+            if tablebase::prober().probe(&game).is_some_and(|probe| probe.wdl == Wdl::Draw) {
+                break (MatchOutcome::Draw, "Draw, tablebase".to_string());
+            }
+            if moves.len() >= self.max_plies {
+                break (MatchOutcome::Draw, "Draw, move limit".to_string());
+            }
+            let engine = if game.turn() == Color::White { white } else { black };
+            game.set_eval_config(engine.eval);
+            let mv = search::best_move(&game, engine.depth).expect("an undecided game has a legal move");
+            moves.push(game.move_san(mv));
+            game.play_with_promotion(mv.from, mv.to, mv.promotion).expect("search returned an illegal move");
+        };
+        MatchGame { round, white: white.name.clone(), black: black.name.clone(), moves, outcome, termination }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_score_and_elo() {
+        let stats = MatchStats { wins: 3, draws: 2, losses: 1 };
+        assert!((stats.score() - 4.0 / 6.0).abs() < 1e-9);
+        assert_eq!(stats.elo_difference().map(f64::round), Some(120.0));
+        assert_eq!(stats.to_string(), "+3 =2 -1 (66.7%, Elo +120)");
+        assert_eq!(MatchStats { wins: 2, draws: 0, losses: 0 }.elo_difference(), None);
+    }
+
+    #[test]
+    fn test_match_alternates_colors_and_writes_pgn() {
+        let mut runner = MatchRunner::new(EngineConfig::with_depth(1), EngineConfig::with_depth(2), 2);
+        runner.set_max_plies(12);
+        let mut games = Vec::new();
+        let stats = runner.run(|game, _| games.push(game.clone()));
+        assert_eq!(stats.wins + stats.draws + stats.losses, 2);
+        assert_eq!((games[0].white.as_str(), games[1].white.as_str()), ("depth 1", "depth 2"));
+        let pgn = games[0].pgn();
+        assert!(pgn.starts_with("[Event \"Engine match\"]\n"), "{}", pgn);
+        assert!(pgn.contains("[Round \"1\"]") && pgn.contains("\n\n1. "), "{}", pgn);
+        assert!(pgn.trim_end().ends_with(games[0].outcome.pgn()), "{}", pgn);
+    }
+}
@@ -1 +1,17 @@
-pub(crate) mod game;
\ No newline at end of file
+pub(crate) mod board;
+pub(crate) mod clock;
+pub(crate) mod coach;
+pub(crate) mod driver;
+pub(crate) mod eval;
+pub(crate) mod game;
+pub(crate) mod match_runner;
+pub(crate) mod observer;
+pub(crate) mod options;
+pub(crate) mod pgn;
+pub(crate) mod rating;
+pub(crate) mod script;
+pub(crate) mod search;
+pub(crate) mod skill;
+pub(crate) mod tablebase;
+pub(crate) mod tt;
+pub(crate) mod tuner;
\ No newline at end of file
@@ -0,0 +1,103 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::time::Duration;
+use crate::engine::eval::GamePhase;
+use crate::engine::game::GameResult;
+use crate::engine::options::EngineOption;
+use crate::pieces::common::Color;
+
+/// A move that has just been played, as reported to the observers of a game.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveEvent {
+    /// Number of the half-move in the game, starting at `1` for White's first move.
+    pub ply: usize,
+    /// The side that played the move.
+    pub side: Color,
+    /// The move in standard algebraic notation.
+    pub san: String,
+    /// The phase of the game the move was played in.
+    pub phase: GamePhase,
+    /// The evaluation after the move, in centipawns from White's perspective.
+    pub eval: i32,
+    /// The time the side spent on the move.
+    pub elapsed: Duration,
+}
+
+/// Receives every move played in a game, e.g. to log or broadcast it, and the changes of its
+/// engine options.
+pub trait MoveObserver: Send {
+    fn on_move(&mut self, event: &MoveEvent);
+
+    /// Called after an engine option was changed with [`crate::Game::set_option`]; does
+    /// nothing by default.
+    fn on_option_change(&mut self, _option: &EngineOption) {}
+
+    /// Called once the game loop ends, with the result if the game was decided; does nothing
+    /// by default.
+    fn on_game_end(&mut self, _result: Option<GameResult>) {}
+}
+
+/// Appends one CSV row per move (`ply,side,san,eval,time_s`), so games can be analysed
+/// in a spreadsheet.
+pub struct CsvMoveLogger {
+    file: File,
+}
+
+impl CsvMoveLogger {
+    /// Opens the CSV file for appending, writing the header if the file is new or empty.
+    ///
+    /// # Arguments
+    /// - `path`: The file to append to.
+    ///
+    /// # Returns
+    /// - `Ok(CsvMoveLogger)`: The logger.
+    /// - `Err(String)`: If the file cannot be opened.
+    pub fn open(path: &str) -> Result<Self, String> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|err| format!("Cannot open move log {}: {}", path, err))?;
+        let is_empty = file.metadata().map(|metadata| metadata.len() == 0).unwrap_or(true);
+        if is_empty {
+            writeln!(file, "ply,side,san,eval,time_s").map_err(|err| err.to_string())?;
+        }
+        Ok(Self { file })
+    }
+}
+
+impl MoveObserver for CsvMoveLogger {
+    fn on_move(&mut self, event: &MoveEvent) {
+        let row = format!(
+            "{},{:?},{},{},{:.3}",
+            event.ply, event.side, event.san, event.eval, event.elapsed.as_secs_f64()
+        );
+        if let Err(err) = writeln!(self.file, "{}", row) {
+            eprintln!("Cannot write to the move log: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_logger_appends_rows_after_a_single_header() {
+        let path = std::env::temp_dir().join(format!("chessgame-moves-{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let event = MoveEvent {
+            ply: 1,
+            side: Color::White,
+            san: "e4".to_string(),
+            phase: GamePhase::Opening,
+            eval: 35,
+            elapsed: Duration::from_millis(1500),
+        };
+        CsvMoveLogger::open(path).unwrap().on_move(&event);
+        CsvMoveLogger::open(path).unwrap().on_move(&MoveEvent { ply: 2, side: Color::Black, ..event });
+        let content = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(content, "ply,side,san,eval,time_s\n1,White,e4,35,1.500\n2,Black,e4,35,1.500\n");
+    }
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+use crate::engine::skill::SkillLevel;
+use crate::engine::tt;
+
+/// The names of the engine options, in the order `set` lists them.
+pub const OPTION_NAMES: [&str; 3] = ["hash", "skill", "contempt"];
+
+/// The largest transposition table, in kilobytes (1 GB).
+const MAX_HASH_KB: usize = 1 << 20;
+
+/// The largest contempt either way, in centipawns: more than a queen would make the engine
+/// sacrifice material to avoid or reach a draw.
+const MAX_CONTEMPT: i32 = 1000;
+
+/// An engine setting with its value. The in-game `set` command and the command-line flags
+/// both read options through [`EngineOption::parse`], so that they accept the same values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EngineOption {
+    /// The size of the transposition table in kilobytes. The table holds the largest power of
+    /// two of entries that fits.
+    Hash(usize),
+    /// How well the computer plays, `None` at full strength.
+    Skill(Option<SkillLevel>),
+    /// How many centipawns a draw is worse than an even position for the engine, see
+    /// [`crate::Game::set_contempt`].
+    Contempt(i32),
+}
+
+impl EngineOption {
+    /// Reads the value of an option, as typed after `set <name>`.
+    ///
+    /// # Arguments
+    /// - `name`: One of [`OPTION_NAMES`], in any case.
+    /// - `value`: The value: kilobytes for `hash`, a level from 1 to 10 or `off` for `skill`,
+    ///   centipawns for `contempt`.
+    ///
+    /// # Returns
+    /// - `Ok(EngineOption)`: The option with its value.
+    /// - `Err(String)`: If the option is unknown or the value is out of range.
+    pub fn parse(name: &str, value: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "hash" => match value.parse() {
+                Ok(kilobytes) if (1..=MAX_HASH_KB).contains(&kilobytes) => Ok(EngineOption::Hash(kilobytes)),
+                _ => Err(format!("The hash size must be between 1 and {} kilobytes.", MAX_HASH_KB)),
+            },
+            "skill" if value.eq_ignore_ascii_case("off") => Ok(EngineOption::Skill(None)),
+            "skill" => {
+                let level = value.parse().map_err(|_| format!("Invalid skill level, {}", value))?;
+                Ok(EngineOption::Skill(Some(SkillLevel::new(level)?)))
+            }
+            "contempt" => match value.parse() {
+                Ok(contempt) if (-MAX_CONTEMPT..=MAX_CONTEMPT).contains(&contempt) => Ok(EngineOption::Contempt(contempt)),
+                _ => Err(format!("The contempt must be between -{} and {} centipawns.", MAX_CONTEMPT, MAX_CONTEMPT)),
+            },
+            _ => Err(format!("Unknown option {}; the options are {}.", name, OPTION_NAMES.join(", "))),
+        }
+    }
+
+    /// Returns the name the option is set with, e.g. `hash`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EngineOption::Hash(_) => OPTION_NAMES[0],
+            EngineOption::Skill(_) => OPTION_NAMES[1],
+            EngineOption::Contempt(_) => OPTION_NAMES[2],
+        }
+    }
+
+    /// Returns every option at the value a new game starts with.
+    pub fn defaults() -> [EngineOption; 3] {
+        [EngineOption::Hash(tt::kilobytes(tt::TABLE_BITS)), EngineOption::Skill(None), EngineOption::Contempt(0)]
+    }
+}
+
+impl fmt::Display for EngineOption {
+    /// Formats the option as the `set` command takes it, e.g. `hash 384` or `skill off`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineOption::Hash(kilobytes) => write!(f, "hash {}", kilobytes),
+            EngineOption::Skill(Some(level)) => write!(f, "skill {}", level),
+            EngineOption::Skill(None) => write!(f, "skill off"),
+            EngineOption::Contempt(contempt) => write!(f, "contempt {}", contempt),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_validates_and_round_trips() {
+        for option in EngineOption::defaults() {
+            let text = option.to_string();
+            let (name, value) = text.split_once(' ').unwrap();
+            assert_eq!(EngineOption::parse(name, value), Ok(option));
+        }
+        assert_eq!(EngineOption::parse("Skill", "3"), Ok(EngineOption::Skill(SkillLevel::new(3).ok())));
+        assert!(EngineOption::parse("skill", "11").is_err());
+        assert!(EngineOption::parse("hash", "0").is_err());
+        assert!(EngineOption::parse("contempt", "-2000").is_err());
+        assert!(EngineOption::parse("threads", "2").unwrap_err().starts_with("Unknown option threads"));
+    }
+}
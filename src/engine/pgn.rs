@@ -0,0 +1,256 @@
+use std::fmt;
+use crate::engine::game::{Game, MoveError};
+use crate::pieces::common::Color;
+
+/// Why a PGN file could not be read or replayed, with the line it happened on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PgnError {
+    /// The line of the file, starting at `1`.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PGN line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+/// One game read from a PGN file: its tag pairs and the moves of its main line.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PgnGame {
+    /// The tag pairs in file order, e.g. `("White", "Kasparov")`.
+    pub tags: Vec<(String, String)>,
+    /// The moves of the main line in algebraic notation, without move numbers, comments,
+    /// annotation glyphs or variations.
+    pub moves: Vec<String>,
+    /// The game termination marker: `1-0`, `0-1`, `1/2-1/2` or `*`.
+    pub result: String,
+    /// The line each move was read from, for error messages.
+    move_lines: Vec<usize>,
+}
+
+impl PgnGame {
+    /// Returns the value of a tag, e.g. `tag("Event")`.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Plays the moves of the game from its start position: the `FEN` tag if there is one,
+    /// the standard position otherwise.
+    ///
+    /// # Returns
+    /// - `Ok(Game)`: The game after its last move, with every move in its history.
+    /// - `Err(PgnError)`: If the `FEN` tag is invalid or a move is illegal, with the move.
+    pub fn replay(&self) -> Result<Game, PgnError> {
+        let mut game = match self.tag("FEN") {
+            Some(fen) => Game::from_fen(fen).map_err(|err| PgnError { line: 1, message: format!("Invalid FEN tag: {}", err) })?,
+            None => Game::new(),
+        };
+        for (san, line) in self.moves.iter().zip(&self.move_lines) {
+            let number = game.fullmove_number();
+            let dots = if game.turn() == Color::White { "." } else { "..." };
+            game.play_san(san).map_err(|err: MoveError| PgnError {
+                line: *line,
+                message: format!("{}{} {}: {}", number, dots, san, err),
+            })?;
+        }
+        Ok(game)
+    }
+}
+
+/// Reads every game of a PGN file. Comments (`{...}` and `;` to the end of the line), numeric
+/// annotation glyphs (`$1`), move suffixes (`!?`) and variations in parentheses are skipped.
+///
+/// # Arguments
+/// - `text`: The content of the file.
+///
+/// # Returns
+/// - `Ok(Vec<PgnGame>)`: The games, in file order; at least one.
+/// - `Err(PgnError)`: If a tag pair is malformed, a comment or variation is not closed, a
+///   token is not a move, or the file has no game.
+pub fn parse_pgn(text: &str) -> Result<Vec<PgnGame>, PgnError> {
+    let mut games = Vec::new();
+    let mut game = PgnGame::default();
+    let mut comment_start = None;
+    let mut variation_depth = 0;
+    let mut variation_start = 0;
+    for (idx, raw_line) in text.lines().enumerate() {
+        let line = idx + 1;
+        let mut rest = raw_line.trim();
+        if comment_start.is_none() && variation_depth == 0 && rest.starts_with('[') {
+            if !game.moves.is_empty() {
+                return Err(PgnError { line, message: "Tag pair after the moves; is a result marker missing?".to_string() });
+            }
+            game.tags.push(parse_tag(rest).ok_or_else(|| PgnError { line, message: format!("Malformed tag pair: {}", rest) })?);
+            continue;
+        }
+        while !rest.is_empty() {
+            if comment_start.is_some() {
+                match rest.find('}') {
+                    Some(end) => {
+                        comment_start = None;
+                        rest = &rest[end + 1..];
+                    }
+                    None => break,
+                }
+                continue;
+            }
+            rest = rest.trim_start();
+            let Some(symbol) = rest.chars().next() else {
+                break;
+            };
+            match symbol {
+                '{' => {
+                    comment_start = Some(line);
+                    rest = &rest[1..];
+                    continue;
+                }
+                ';' => break,
+                '(' => {
+                    if variation_depth == 0 {
+                        variation_start = line;
+                    }
+                    variation_depth += 1;
+                    rest = &rest[1..];
+                    continue;
+                }
+                ')' => {
+                    if variation_depth == 0 {
+                        return Err(PgnError { line, message: "Unmatched ')'.".to_string() });
+                    }
+                    variation_depth -= 1;
+                    rest = &rest[1..];
+                    continue;
+                }
+                _ => {}
+            }
+            let end = rest.find(|symbol: char| symbol.is_whitespace() || "{;()".contains(symbol)).unwrap_or(rest.len());
+            let token = &rest[..end];
+            rest = &rest[end..];
+            if variation_depth > 0 {
+                continue;
+            }
+            match token {
+                "1-0" | "0-1" | "1/2-1/2" | "*" => {
+                    game.result = token.to_string();
+                    games.push(std::mem::take(&mut game));
+                }
+                _ if token.starts_with('$') => {}
+                _ => {
+                    // move numbers may be glued to the move, e.g. `12.Nf3` or `12...Nf6`
+                    let san = token.trim_start_matches(|symbol: char| symbol.is_ascii_digit()).trim_start_matches('.');
+                    if san.is_empty() {
+                        continue;
+                    }
+                    if crate::gui::san::parse(san).is_none() {
+                        return Err(PgnError { line, message: format!("'{}' is not a move.", token) });
+                    }
+                    game.moves.push(san.trim_end_matches(['!', '?']).to_string());
+                    game.move_lines.push(line);
+                }
+            }
+        }
+    }
+    if let Some(line) = comment_start {
+        return Err(PgnError { line, message: "Comment is never closed.".to_string() });
+    }
+    if variation_depth > 0 {
+        return Err(PgnError { line: variation_start, message: "Variation is never closed.".to_string() });
+    }
+    // a last game without a result marker is still read
+    if !game.moves.is_empty() || !game.tags.is_empty() {
+        game.result = "*".to_string();
+        games.push(game);
+    }
+    if games.is_empty() {
+        return Err(PgnError { line: 1, message: "The file has no game.".to_string() });
+    }
+    Ok(games)
+}
+
+/// Writes a game in PGN: the tag pairs, a blank line, then the movetext tokens (move numbers
+/// and moves, comments, the result) on lines kept under 80 characters.
+///
+/// # Arguments
+/// - `tags`: The tag pairs, in the order they are written.
+/// - `tokens`: The movetext, one token per item; a token is never split across lines.
+///
+/// # Returns
+/// - The PGN text of the game, ending with a blank line.
+pub(crate) fn write_pgn(tags: &[(&str, String)], tokens: impl IntoIterator<Item = String>) -> String {
+    let mut pgn = tags.iter()
+        .map(|(tag, value)| format!("[{} \"{}\"]\n", tag, value.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<String>();
+    pgn.push('\n');
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + token.len() >= 80 {
+            pgn.push_str(&line);
+            pgn.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    pgn.push_str(&line);
+    pgn.push_str("\n\n");
+    pgn
+}
+
+/// Parses a tag pair such as `[White "Kasparov, Garry"]`, where `\"` and `\\` are escapes.
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?.trim();
+    let (name, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(symbol) = chars.next() {
+        unescaped.push(if symbol == '\\' { chars.next()? } else { symbol });
+    }
+    Some((name.to_string(), unescaped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPERA_GAME: &str = r#"[Event "Paris"]
+[White "Morphy, Paul"]
+[Black "Duke Karl / Count Isouard"]
+[Result "1-0"]
+
+1. e4 e5 2. Nf3 d6 3. d4 Bg4 {This is a weak move already.} 4. dxe5 Bxf3
+5. Qxf3 dxe5 6. Bc4 Nf6 7. Qb3 $2 Qe7 8. Nc3 c6 9. Bg5 b5 10. Nxb5! cxb5
+11. Bxb5+ Nbd7 12. O-O-O Rd8 13. Rxd7 Rxd7 14. Rd1 Qe6 (14... Nxd7 15. Qxb5) 15. Bxd7+ Nxd7
+16. Qb8+ Nxb8 17. Rd8# ; mate
+1-0
+"#;
+
+    #[test]
+    fn test_parse_and_replay_the_opera_game() {
+        let games = parse_pgn(OPERA_GAME).unwrap();
+        assert_eq!(games.len(), 1);
+        let game = &games[0];
+        assert_eq!(game.tag("White"), Some("Morphy, Paul"));
+        assert_eq!((game.moves.len(), game.moves[18].as_str(), game.result.as_str()), (33, "Nxb5", "1-0"));
+        let replayed = game.replay().unwrap();
+        assert_eq!(replayed.move_list().split_whitespace().last(), Some("Rd8#"));
+    }
+
+    #[test]
+    fn test_errors_name_the_line() {
+        let illegal = "[Event \"?\"]\n\n1. e4 e5\n2. Ke3 *\n";
+        let Err(err) = parse_pgn(illegal).unwrap().remove(0).replay() else {
+            panic!("Ke3 is not legal");
+        };
+        assert_eq!(err.line, 4);
+        assert!(err.message.starts_with("2. Ke3:"), "{}", err);
+        assert_eq!(parse_pgn("1. e4 {unclosed\n").unwrap_err().line, 1);
+        assert_eq!(parse_pgn("1. e4 e5\n2. Zz9 *").unwrap_err().line, 2);
+    }
+}
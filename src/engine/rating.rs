@@ -0,0 +1,160 @@
+use std::fs;
+use serde::{Deserialize, Serialize};
+use crate::engine::game::GameResult;
+use crate::engine::observer::{MoveEvent, MoveObserver};
+use crate::pieces::common::Color;
+
+/// Rating of a player without any rated game yet.
+const INITIAL_RATING: f64 = 1200.0;
+
+/// Largest rating change a single game can cause.
+const K_FACTOR: f64 = 32.0;
+
+/// Nominal rating of the engine searching one ply deep.
+const ENGINE_BASE_RATING: f64 = 600.0;
+
+/// Rating the engine gains with every extra ply of search depth.
+const ENGINE_RATING_PER_PLY: f64 = 200.0;
+
+/// The human player's rating and record against the engine, as saved in the stats file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PlayerStats {
+    rating: f64,
+    wins: u32,
+    draws: u32,
+    losses: u32,
+}
+
+impl Default for PlayerStats {
+    fn default() -> Self {
+        Self { rating: INITIAL_RATING, wins: 0, draws: 0, losses: 0 }
+    }
+}
+
+impl PlayerStats {
+    /// Updates the rating and the record with the result of one game, Elo style.
+    ///
+    /// # Arguments
+    /// - `score`: `1.0` for a win, `0.5` for a draw and `0.0` for a loss.
+    /// - `opponent`: The rating of the opponent.
+    ///
+    /// # Returns
+    /// - `f64`: The rating change.
+    pub(crate) fn record(&mut self, score: f64, opponent: f64) -> f64 {
+        let expected = 1.0 / (1.0 + 10f64.powf((opponent - self.rating) / 400.0));
+        let change = K_FACTOR * (score - expected);
+        self.rating += change;
+        match score {
+            score if score > 0.5 => self.wins += 1,
+            score if score < 0.5 => self.losses += 1,
+            _ => self.draws += 1,
+        }
+        change
+    }
+}
+
+/// Returns the nominal rating of the engine at the given search depth.
+pub(crate) fn engine_rating(depth: u8) -> f64 {
+    ENGINE_BASE_RATING + ENGINE_RATING_PER_PLY * f64::from(depth)
+}
+
+/// Rates the human player of a game against the engine: when the game ends, the result
+/// updates the player's rating in the stats file and the record so far is printed.
+pub struct RatingTracker {
+    path: String,
+    human: Color,
+    engine_depth: u8,
+    stats: PlayerStats,
+}
+
+impl RatingTracker {
+    /// Loads the stats file, or starts from the initial rating if it does not exist yet.
+    ///
+    /// # Arguments
+    /// - `path`: The stats file, in TOML.
+    /// - `human`: The side played by the human.
+    /// - `engine_depth`: The search depth of the engine, which sets its rating.
+    ///
+    /// # Returns
+    /// - `Ok(RatingTracker)`: The tracker.
+    /// - `Err(String)`: If the file exists but cannot be read or parsed.
+    pub fn open(path: &str, human: Color, engine_depth: u8) -> Result<Self, String> {
+        let stats = match fs::read_to_string(path) {
+            Ok(content) => toml::from_str(&content).map_err(|err| format!("Invalid stats file {}: {}", path, err))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => PlayerStats::default(),
+            Err(err) => return Err(format!("Cannot read stats file {}: {}", path, err)),
+        };
+        Ok(Self { path: path.to_string(), human, engine_depth, stats })
+    }
+
+    /// Returns the score of the human in a finished game.
+    fn score(&self, result: GameResult) -> f64 {
+        match result {
+            GameResult::Checkmate(loser) | GameResult::Resignation(loser) | GameResult::Timeout(loser)
+            | GameResult::KingExploded(loser) if loser == self.human => 0.0,
+            GameResult::Checkmate(_) | GameResult::Resignation(_) | GameResult::Timeout(_) | GameResult::KingExploded(_) => 1.0,
+            GameResult::Draw | GameResult::DrawByAgreement | GameResult::TimeoutDraw(_) => 0.5,
+        }
+    }
+
+    /// Writes the stats file.
+    fn save(&self) -> Result<(), String> {
+        let content = toml::to_string(&self.stats).map_err(|err| err.to_string())?;
+        fs::write(&self.path, content).map_err(|err| format!("Cannot write stats file {}: {}", self.path, err))
+    }
+}
+
+impl MoveObserver for RatingTracker {
+    fn on_move(&mut self, _event: &MoveEvent) {}
+
+    fn on_game_end(&mut self, result: Option<GameResult>) {
+        let Some(result) = result else {
+            return;
+        };
+        let before = self.stats.rating;
+        let change = self.stats.record(self.score(result), engine_rating(self.engine_depth));
+        println!(
+            "Rating: {:.0} -> {:.0} ({:+.0}) against the depth {} engine rated {:.0}",
+            before, self.stats.rating, change, self.engine_depth, engine_rating(self.engine_depth)
+        );
+        println!("Record: {} wins, {} draws, {} losses", self.stats.wins, self.stats.draws, self.stats.losses);
+        if let Err(err) = self.save() {
+            println!("{}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rating_moves_by_the_surprise_of_the_result() {
+        let mut stats = PlayerStats::default();
+        assert_eq!(stats.record(1.0, INITIAL_RATING), K_FACTOR / 2.0);
+        assert_eq!(stats.rating, INITIAL_RATING + 16.0);
+        let mut stats = PlayerStats::default();
+        let upset = stats.record(1.0, INITIAL_RATING + 400.0);
+        let expected = PlayerStats::default().record(1.0, INITIAL_RATING - 400.0);
+        assert!(upset > 29.0 && expected < 3.0, "{} {}", upset, expected);
+        stats.record(0.5, INITIAL_RATING);
+        stats.record(0.0, INITIAL_RATING);
+        assert_eq!((stats.wins, stats.draws, stats.losses), (1, 1, 1));
+    }
+
+    #[test]
+    fn test_tracker_persists_stats_between_games() {
+        let path = std::env::temp_dir().join(format!("chessgame-stats-{}.toml", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut tracker = RatingTracker::open(path, Color::White, 3).unwrap();
+        tracker.on_game_end(Some(GameResult::Checkmate(Color::Black)));
+        tracker.on_game_end(None);
+        let mut tracker = RatingTracker::open(path, Color::Black, 3).unwrap();
+        tracker.on_game_end(Some(GameResult::Resignation(Color::Black)));
+        let stats = RatingTracker::open(path, Color::White, 3).unwrap().stats;
+        std::fs::remove_file(path).unwrap();
+        assert_eq!((stats.wins, stats.draws, stats.losses), (1, 0, 1));
+        // the loss costs a little more than the win earned, as the player was rated higher by then
+        assert!(stats.rating < INITIAL_RATING && stats.rating > INITIAL_RATING - 1.0, "{}", stats.rating);
+    }
+}
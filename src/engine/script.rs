@@ -0,0 +1,129 @@
+use std::fmt;
+use crate::engine::driver::{DriverAction, DriverEvent, GameDriver};
+use crate::square::Square;
+
+/// Why a script stopped, with the line it happened on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptError {
+    /// The line of the script, starting at `1`.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Script line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// Plays a script on a game, without a terminal. Each line holds either one command, `move
+/// <from> <to>`, `engine <depth>`, `draw`, `accept`, `decline`, `resign`, `undo` or `redo`, or
+/// moves in algebraic or coordinate notation separated by spaces, e.g. `1. e4 e5 2. g1f3`.
+/// Move numbers are skipped, as are empty lines and lines starting with `#`.
+///
+/// # Arguments
+/// - `driver`: The game to play the script on; it keeps the moves played before an error.
+/// - `script`: The content of the script.
+///
+/// # Returns
+/// - `Ok(())`: If every command was carried out.
+/// - `Err(ScriptError)`: At the first command that is unknown or refused, such as an illegal
+///   move, or a move after the end of the game.
+pub fn run_script(driver: &mut GameDriver, script: &str) -> Result<(), ScriptError> {
+    for (idx, text) in script.lines().enumerate() {
+        let line = idx + 1;
+        let error = |message: String| ScriptError { line, message };
+        for (action, word) in parse_line(text).map_err(error)? {
+            if let Some(result) = driver.game().game_result() {
+                return Err(error(format!("{} comes after the end of the game ({}).", word, result)));
+            }
+            for event in driver.apply(action) {
+                match event {
+                    DriverEvent::IllegalMove { error: err, .. } => return Err(error(format!("Illegal move {}: {}", word, err))),
+                    DriverEvent::Rejected(reason) => return Err(error(format!("Cannot {}: {}", word, reason))),
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads the actions of one script line, each with the word that names it in error messages.
+fn parse_line(text: &str) -> Result<Vec<(DriverAction, String)>, String> {
+    let words = text.split_whitespace().collect::<Vec<_>>();
+    let Some(first) = words.first() else {
+        return Ok(Vec::new());
+    };
+    let command = |action: DriverAction| Ok(vec![(action, first.to_lowercase())]);
+    match (first.to_lowercase().as_str(), &words[1..]) {
+        (command, _) if command.starts_with('#') => Ok(Vec::new()),
+        ("move", [from, to]) => {
+            let from = from.parse::<Square>().map_err(|err| format!("Invalid square, {}.", err))?;
+            let to = to.parse::<Square>().map_err(|err| format!("Invalid square, {}.", err))?;
+            Ok(vec![(DriverAction::Move { from, to, promotion: None }, format!("{}{}", from, to))])
+        }
+        ("engine", [depth]) => match depth.parse() {
+            Ok(depth) if depth > 0 => command(DriverAction::EngineMove { depth }),
+            _ => Err(format!("Invalid engine depth, {}", depth)),
+        },
+        ("draw", []) => command(DriverAction::OfferDraw),
+        ("accept", []) => command(DriverAction::AcceptDraw),
+        ("decline", []) => command(DriverAction::DeclineDraw),
+        ("resign", []) => command(DriverAction::Resign),
+        ("undo", []) => command(DriverAction::Undo),
+        ("redo", []) => command(DriverAction::Redo),
+        ("move" | "engine" | "draw" | "accept" | "decline" | "resign" | "undo" | "redo", _) => {
+            Err(format!("Wrong arguments for {}: {}", first, text.trim()))
+        }
+        _ => Ok(words.iter()
+            // move numbers, e.g. `12.` or `12...`
+            .filter(|word| !word.trim_end_matches('.').chars().all(|symbol| symbol.is_ascii_digit()))
+            .map(|word| (DriverAction::San(coordinate_promotion(word)), word.to_string()))
+            .collect()),
+    }
+}
+
+/// Writes the promotion piece of a coordinate move in uppercase, as algebraic notation does,
+/// e.g. `e7e8q` becomes `e7e8Q`; other words are kept.
+fn coordinate_promotion(word: &str) -> String {
+    let symbols = word.chars().collect::<Vec<_>>();
+    match symbols[..] {
+        [file, rank, _, _, piece] if file.is_ascii_lowercase() && rank.is_ascii_digit() && "qrbn".contains(piece) => {
+            format!("{}{}", &word[..4], piece.to_ascii_uppercase())
+        }
+        _ => word.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::game::{Game, GameResult};
+    use crate::pieces::common::Color;
+
+    #[test]
+    fn test_script_plays_moves_and_commands() {
+        let mut driver = GameDriver::new(Game::new());
+        run_script(&mut driver, "# fool's mate\n1. f3 e5\n\nmove g2 g4\nundo\ng2g4 Qh4#\n").unwrap();
+        assert_eq!(driver.game().game_result(), Some(GameResult::Checkmate(Color::White)));
+        assert_eq!(driver.game().move_list(), "1. f3 e5 2. g4 Qh4#");
+        let mut driver = GameDriver::new(Game::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap());
+        run_script(&mut driver, "b7b8n").unwrap();
+        assert_eq!(driver.game().move_list(), "1. b8=N");
+    }
+
+    #[test]
+    fn test_script_errors_name_the_line() {
+        let mut driver = GameDriver::new(Game::new());
+        let err = run_script(&mut driver, "e4 e5\nNf3 Ke7 Ke3\n").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(err.message.starts_with("Illegal move Ke3"), "{}", err);
+        // the moves before the error stay played
+        assert_eq!(driver.game().move_list(), "1. e4 e5 2. Nf3 Ke7");
+        assert_eq!(run_script(&mut driver, "engine x").unwrap_err().message, "Invalid engine depth, x");
+        assert!(run_script(&mut driver, "resign\ne4").unwrap_err().message.contains("after the end of the game"));
+    }
+}
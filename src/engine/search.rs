@@ -0,0 +1,827 @@
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use crate::engine::eval;
+use crate::engine::tablebase::{self, TablebaseProber, Wdl};
+use crate::engine::game::{Game, Move, MoveList};
+use crate::engine::tt::{HashStats, TranspositionTable};
+
+/// Score of a checkmate at the root; mates found deeper score slightly less so the
+/// shortest mate is preferred.
+pub const MATE_SCORE: i32 = 100_000;
+
+/// Scores at least this far from zero are mates: [`MATE_SCORE`] less the plies to the mate,
+/// which never get near this many.
+const MATE_BOUND: i32 = MATE_SCORE - 1_000;
+
+/// Score of a won tablebase position at the root, for tablebases that do not count the plies
+/// to mate: wins found deeper score slightly less, and all of them stay below the mates.
+const TABLEBASE_WIN: i32 = MATE_BOUND - 1;
+
+/// Search depth used for analysis commands when none is given.
+pub const ANALYSIS_DEPTH: u8 = 3;
+
+/// The window that holds every score, used by the first iteration and after the aspiration
+/// window was missed too often.
+const FULL_WINDOW: (i32, i32) = (-MATE_SCORE - 1, MATE_SCORE + 1);
+
+/// Half-width in centipawns of the first aspiration window around the previous iteration's score.
+const ASPIRATION_WINDOW: i32 = 50;
+
+/// Factor the aspiration window grows by on the side that failed, before the re-search.
+const ASPIRATION_GROWTH: i32 = 4;
+
+/// Number of nodes between two calls of [`SearchObserver::on_node_count`].
+pub const NODE_REPORT_INTERVAL: u64 = 4096;
+
+/// The score of a position from the side to move's point of view: an evaluation in
+/// centipawns, or a forced mate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Score {
+    /// The evaluation in centipawns; positive values favour the side to move.
+    Centipawns(i32),
+    /// A forced mate in this many moves: positive if the side to move mates, negative if it
+    /// is mated. `Mate(0)` means the side to move is already mated.
+    Mate(i32),
+}
+
+impl Score {
+    /// Turns a score of the search, where mates are [`MATE_SCORE`] less the plies to the mate,
+    /// into a `Score`.
+    pub(crate) fn from_raw(raw: i32) -> Self {
+        if raw >= MATE_BOUND {
+            Score::Mate((MATE_SCORE - raw + 1) / 2)
+        } else if raw <= -MATE_BOUND {
+            Score::Mate(-(MATE_SCORE + raw) / 2)
+        } else {
+            Score::Centipawns(raw)
+        }
+    }
+
+    /// Turns the score back into the search's scale, where it can be compared and added to.
+    pub(crate) fn raw(self) -> i32 {
+        match self {
+            Score::Centipawns(centipawns) => centipawns,
+            Score::Mate(moves) if moves > 0 => MATE_SCORE - (2 * moves - 1),
+            Score::Mate(moves) => -MATE_SCORE - 2 * moves,
+        }
+    }
+
+    /// Whether the score is a forced mate for either side.
+    pub fn is_mate(self) -> bool {
+        matches!(self, Score::Mate(_))
+    }
+}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Mating is better than any evaluation, and mating sooner is better still.
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.raw().cmp(&other.raw())
+    }
+}
+
+/// Writes mates as `#3` or `#-2` and evaluations in pawns, e.g. `+0.35`.
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Score::Centipawns(centipawns) => format!("{:+.2}", *centipawns as f64 / 100.0),
+            Score::Mate(moves) => format!("#{}", moves),
+        };
+        f.pad(&text)
+    }
+}
+
+/// Receives the progress of a running search, e.g. to print it or to check it in tests, so that
+/// the search itself never prints. Every method does nothing by default.
+pub trait SearchObserver {
+    /// Called when an iteration of the search is complete.
+    ///
+    /// # Arguments
+    /// - `depth`: The depth of the iteration.
+    /// - `score`: Its score, from the side to move's point of view.
+    /// - `pv`: Its principal variation, the best move first.
+    fn on_depth_complete(&mut self, _depth: u8, _score: Score, _pv: &[Move]) {}
+
+    /// Called when a root move scores better than the moves searched before it in the
+    /// current iteration.
+    ///
+    /// # Arguments
+    /// - `depth`: The depth of the iteration.
+    /// - `best`: The move, with the piece a pawn promotes to.
+    /// - `score`: Its score, a lower bound if it exceeded the search window.
+    fn on_new_best_move(&mut self, _depth: u8, _best: Move, _score: Score) {}
+
+    /// Called every [`NODE_REPORT_INTERVAL`] nodes with the number of nodes visited so far.
+    fn on_node_count(&mut self, _nodes: u64) {}
+}
+
+/// Counters gathered during a search.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SearchStats {
+    /// Positions visited, quiescence positions included.
+    pub nodes: u64,
+    /// Iterations searched again because the score was above the aspiration window.
+    pub fail_highs: u32,
+    /// Iterations searched again because the score was below the aspiration window.
+    pub fail_lows: u32,
+}
+
+/// Searches the position with a fixed-depth alpha-beta negamax and returns the best move.
+///
+/// # Arguments
+/// - `game`: The position to search; it is not modified.
+/// - `depth`: The number of plies to look ahead (at least 1).
+///
+/// # Returns
+/// - `Some(Move)`: The best move found for the side to move, with the piece a pawn promotes to.
+/// - `None`: If the side to move has no legal moves.
+pub fn best_move(game: &Game, depth: u8) -> Option<Move> {
+    principal_variation(game, depth).first().copied()
+}
+
+/// Searches the position like [`best_move`] and returns the whole line the engine expects:
+/// its best move, the best reply, and so on.
+///
+/// # Arguments
+/// - `game`: The position to search; it is not modified.
+/// - `depth`: The number of plies to look ahead (at least 1).
+///
+/// # Returns
+/// - `Vec<Move>`: The principal variation, at most `depth` moves long; empty if the side to
+///   move has no legal moves.
+pub fn principal_variation(game: &Game, depth: u8) -> Vec<Move> {
+    analyze(game, depth).1
+}
+
+/// Searches the position like [`principal_variation`] and returns the score of the line too.
+///
+/// # Arguments
+/// - `game`: The position to search; it is not modified.
+/// - `depth`: The number of plies to look ahead (at least 1).
+///
+/// # Returns
+/// - `(Score, Vec<Move>)`: The score from the side to move's point of view, e.g.
+///   `Score::Mate(2)` for a forced mate in two, and the principal variation.
+pub fn analyze(game: &Game, depth: u8) -> (Score, Vec<Move>) {
+    let (score, pv) = search(game, depth, &mut TranspositionTable::new(game.hash_bits(), false));
+    (Score::from_raw(score), pv)
+}
+
+/// Runs the same search as [`principal_variation`] with a transposition table in verification
+/// mode, to check how often the Zobrist keys it stores collide.
+///
+/// # Arguments
+/// - `game`: The position to search; it is not modified.
+/// - `depth`: The number of plies to look ahead (at least 1).
+///
+/// # Returns
+/// - `HashStats`: The probes, key hits and verified collisions of the search.
+pub fn hash_statistics(game: &Game, depth: u8) -> HashStats {
+    let mut table = TranspositionTable::new(game.hash_bits(), true);
+    search(game, depth, &mut table);
+    table.stats()
+}
+
+/// Searches the position like [`principal_variation`], reporting the progress to `observer`
+/// as the search goes.
+///
+/// # Arguments
+/// - `game`: The position to search; it is not modified.
+/// - `depth`: The number of plies to look ahead (at least 1).
+/// - `observer`: Receives each completed iteration, new best root move and node count.
+///
+/// # Returns
+/// - `Vec<Move>`: The principal variation; empty if the side to move has no legal moves.
+pub fn search_with_observer(game: &Game, depth: u8, observer: &mut dyn SearchObserver) -> Vec<Move> {
+    let mut table = TranspositionTable::new(game.hash_bits(), false);
+    Searcher { observer: Some(observer), ..Searcher::new(&mut table, game) }.iterate(game, depth).1
+}
+
+/// A search running on a thread of its own, e.g. to ponder during the opponent's turn.
+/// Dropping the handle stops the search and waits for the thread to end.
+pub struct SearchHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Vec<Move>>>,
+}
+
+impl SearchHandle {
+    /// Asks the search to stop; it ends with the line of its last complete iteration.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the search has ended, by reaching its depth or by being stopped.
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(|thread| thread.is_finished())
+    }
+
+    /// Waits for the search to end.
+    ///
+    /// # Returns
+    /// - `Vec<Move>`: The principal variation, as [`principal_variation`] returns it.
+    pub fn join(mut self) -> Vec<Move> {
+        self.thread.take().map(|thread| thread.join().expect("the search thread panicked")).unwrap_or_default()
+    }
+}
+
+impl Drop for SearchHandle {
+    fn drop(&mut self) {
+        self.stop();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Starts searching the position like [`principal_variation`] on another thread.
+///
+/// # Arguments
+/// - `game`: The position to search; it is copied, so the game can go on meanwhile.
+/// - `depth`: The number of plies to look ahead (at least 1).
+///
+/// # Returns
+/// - `SearchHandle`: Stops the search, or waits for its principal variation.
+pub fn spawn_search(game: &Game, depth: u8) -> SearchHandle {
+    let stop = Arc::new(AtomicBool::new(false));
+    let game = game.clone();
+    let thread_stop = Arc::clone(&stop);
+    let thread = thread::spawn(move || {
+        let mut table = TranspositionTable::new(game.hash_bits(), false);
+        let stop = Some(thread_stop.as_ref());
+        Searcher { stop, ..Searcher::new(&mut table, &game) }.iterate(&game, depth).1
+    });
+    SearchHandle { stop, thread: Some(thread) }
+}
+
+/// Runs the same search as [`principal_variation`], with or without aspiration windows, and
+/// counts the nodes it visits, so that the two can be compared.
+///
+/// # Arguments
+/// - `game`: The position to search; it is not modified.
+/// - `depth`: The number of plies to look ahead (at least 1).
+/// - `aspiration`: Whether iterations after the first search a window around the previous score.
+///
+/// # Returns
+/// - `SearchStats`: The nodes visited and the re-searches after the window was missed.
+pub fn search_statistics(game: &Game, depth: u8, aspiration: bool) -> SearchStats {
+    let mut table = TranspositionTable::new(game.hash_bits(), false);
+    let mut searcher = Searcher { aspiration, ..Searcher::new(&mut table, game) };
+    searcher.iterate(game, depth);
+    searcher.stats
+}
+
+/// Scores every legal move of the position, each with a full window so that the scores of the
+/// moves that are not the best are exact too, e.g. to play a weaker move on purpose.
+///
+/// # Arguments
+/// - `game`: The position to search; it is not modified.
+/// - `depth`: The number of plies to look ahead (at least 1), the move itself included.
+///
+/// # Returns
+/// - `Vec<(Move, Score)>`: Each move and its score from the side to move's point of view, best
+///   first; empty if the side to move has no legal moves.
+pub(crate) fn root_scores(game: &Game, depth: u8) -> Vec<(Move, Score)> {
+    let mut table = TranspositionTable::new(game.hash_bits(), false);
+    let mut searcher = Searcher { aspiration: false, ..Searcher::new(&mut table, game) };
+    let mut game = game.clone();
+    let mut scores = ordered(game.legal_move_list()).into_iter()
+        .map(|mv| {
+            let record = game.make_move(mv);
+            let score = -searcher.negamax(&mut game, depth.max(1) - 1, 1, FULL_WINDOW.0, FULL_WINDOW.1, &mut Vec::new());
+            game.unmake_move(record);
+            (mv, Score::from_raw(score))
+        })
+        .collect::<Vec<_>>();
+    scores.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    scores
+}
+
+/// Searches the root position and returns its score and principal variation.
+fn search(game: &Game, depth: u8, table: &mut TranspositionTable) -> (i32, Vec<Move>) {
+    Searcher::new(table, game).iterate(game, depth)
+}
+
+/// The state shared by every node of one search.
+struct Searcher<'a> {
+    /// Best moves of positions already searched, tried first; updated as the search goes.
+    table: &'a mut TranspositionTable,
+    stats: SearchStats,
+    /// Whether iterations after the first search a window around the previous score.
+    aspiration: bool,
+    observer: Option<&'a mut dyn SearchObserver>,
+    /// Set from another thread to end the search early.
+    stop: Option<&'a AtomicBool>,
+    /// Hashes of the positions from the last capture or pawn move of the game up to the parent
+    /// of the node being searched, to find repetitions.
+    path: Vec<u64>,
+    /// Centipawns a draw is worth less than an even position to the side to move at the root.
+    contempt: i32,
+    /// The tablebase scoring the positions with few pieces.
+    prober: &'a dyn TablebaseProber,
+}
+
+impl<'a> Searcher<'a> {
+    /// Creates a searcher for `game` with aspiration windows, no observer and no way to stop
+    /// it early. Positions played in the game before count as repetitions.
+    fn new(table: &'a mut TranspositionTable, game: &Game) -> Self {
+        Self {
+            table,
+            stats: SearchStats::default(),
+            aspiration: true,
+            observer: None,
+            stop: None,
+            path: game.repetition_hashes(),
+            contempt: game.contempt(),
+            prober: tablebase::prober(),
+        }
+    }
+
+    /// Counts a visited node, telling the observer every [`NODE_REPORT_INTERVAL`] nodes.
+    fn count_node(&mut self) {
+        self.stats.nodes += 1;
+        if self.stats.nodes.is_multiple_of(NODE_REPORT_INTERVAL) {
+            if let Some(observer) = self.observer.as_mut() {
+                observer.on_node_count(self.stats.nodes);
+            }
+        }
+    }
+
+    /// Scores a draw `ply` plies from the root from the side to move's point of view: the
+    /// contempt counts against the root side and for its opponent.
+    fn draw_score(&self, ply: i32) -> i32 {
+        if ply % 2 == 0 { -self.contempt } else { self.contempt }
+    }
+
+    /// Whether the position is drawn by the fifty-move rule, unless the side to move is mated,
+    /// or repeats a position on the path with the same side to move. A single repetition is
+    /// enough: whatever could be gained from it, could be gained the first time.
+    fn is_draw(&self, game: &Game, hash: u64) -> bool {
+        let clock = game.halfmove_clock() as usize;
+        if clock >= 100 {
+            return !game.is_checked() || !game.legal_move_list().is_empty();
+        }
+        // the positions with the same side to move, two plies back, four, and so on
+        self.path.iter().rev().take(clock).skip(1).step_by(2).any(|&seen| seen == hash)
+    }
+
+    /// Whether the search was asked to stop.
+    fn stopped(&self) -> bool {
+        self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed))
+    }
+
+    /// Searches depth 1, then 2, up to `depth`. Each iteration fills the transposition table
+    /// with best moves that order the next one, and, with aspiration windows, its score
+    /// narrows the window of the next one. A score outside the window is not exact, so the
+    /// iteration is searched again with the window widened on the side that failed.
+    ///
+    /// When the search is stopped, the unfinished iteration is thrown away and the line of the
+    /// last complete one is returned with its score, or the unfinished line if not even depth 1
+    /// completed.
+    fn iterate(&mut self, game: &Game, depth: u8) -> (i32, Vec<Move>) {
+        let _span = tracing::debug_span!("search", depth, aspiration = self.aspiration).entered();
+        let mut game = game.clone();
+        let mut pv = Vec::new();
+        let mut score = 0;
+        for iteration in 1..=depth.max(1) {
+            let _span = tracing::debug_span!("iteration", depth = iteration).entered();
+            let mut width = ASPIRATION_WINDOW;
+            let (mut alpha, mut beta) = if self.aspiration && iteration > 1 {
+                (score - width, score + width)
+            } else {
+                (FULL_WINDOW.0, FULL_WINDOW.1)
+            };
+            loop {
+                let (iteration_score, line) = self.root(&mut game, iteration, alpha, beta, pv.first().copied());
+                if self.stopped() {
+                    tracing::debug!(nodes = self.stats.nodes, "search stopped");
+                    return if pv.is_empty() { (iteration_score, line) } else { (score, pv) };
+                }
+                if iteration_score <= alpha && alpha > FULL_WINDOW.0 {
+                    tracing::trace!(alpha, score = iteration_score, "failed low");
+                    self.stats.fail_lows += 1;
+                    width *= ASPIRATION_GROWTH;
+                    alpha = (score - width).max(FULL_WINDOW.0);
+                } else if iteration_score >= beta && beta < FULL_WINDOW.1 {
+                    tracing::trace!(beta, score = iteration_score, "failed high");
+                    self.stats.fail_highs += 1;
+                    width *= ASPIRATION_GROWTH;
+                    beta = (score + width).min(FULL_WINDOW.1);
+                } else {
+                    (score, pv) = (iteration_score, line);
+                    tracing::debug!(score, nodes = self.stats.nodes, best = ?pv.first(), "iteration complete");
+                    if let Some(observer) = self.observer.as_mut() {
+                        observer.on_depth_complete(iteration, Score::from_raw(score), &pv);
+                    }
+                    break;
+                }
+            }
+        }
+        (score, pv)
+    }
+
+    /// Searches the moves of the root position within a window, the best move of the previous
+    /// iteration first.
+    ///
+    /// # Returns
+    /// - The score, at most `alpha` if every move failed low and at least `beta` if one
+    ///   failed high, and the principal variation.
+    fn root(
+        &mut self,
+        game: &mut Game,
+        depth: u8,
+        mut alpha: i32,
+        beta: i32,
+        previous_best: Option<Move>,
+    ) -> (i32, Vec<Move>) {
+        let mut moves = ordered(game.legal_move_list());
+        if let Some(idx) = moves.iter().position(|mv| Some(*mv) == previous_best) {
+            moves[..=idx].rotate_right(1);
+        }
+        let mut pv = Vec::new();
+        for mv in moves {
+            let record = game.make_move(mv);
+            let mut child_pv = Vec::new();
+            let mut score = -self.negamax(game, depth - 1, 1, -beta, -alpha, &mut child_pv);
+            // the distance of the tablebase is read here only, where it picks the move to play
+            if let Some(tablebase_score) = self.tablebase_score(game, 1, true) {
+                score = -tablebase_score;
+            }
+            game.unmake_move(record);
+            if score > alpha {
+                if let Some(observer) = self.observer.as_mut() {
+                    observer.on_new_best_move(depth, mv, Score::from_raw(score.min(beta)));
+                }
+            }
+            if score >= beta {
+                return (beta, std::iter::once(mv).chain(child_pv).collect());
+            }
+            // keep the first move when every move fails low, so there is always a line
+            if pv.is_empty() || score > alpha {
+                alpha = alpha.max(score);
+                pv = std::iter::once(mv).chain(child_pv).collect();
+            }
+        }
+        (alpha, pv)
+    }
+
+    /// Scores the position from the side to move's point of view.
+    ///
+    /// Moves are made and taken back on `game`, which is left in the position it started from.
+    /// Leaves found in the endgame tablebase get its score instead of a quiescence search.
+    /// Repetitions and positions drawn by the fifty-move rule score as draws. The window is
+    /// narrowed to the mates still possible this far from the root, so that lines longer than
+    /// a mate already found are cut off.
+    ///
+    /// # Arguments
+    /// - `game`: The position to score.
+    /// - `depth`: Remaining plies to search.
+    /// - `ply`: Distance from the root, used to prefer shorter mates.
+    /// - `alpha`: Lower bound of the search window.
+    /// - `beta`: Upper bound of the search window.
+    /// - `pv`: Receives the best line found from this position, if any move raised `alpha`.
+    fn negamax(
+        &mut self,
+        game: &mut Game,
+        depth: u8,
+        ply: i32,
+        alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Move>,
+    ) -> i32 {
+        // no line from here mates sooner than a mate on the next ply, or is mated later than now
+        let (alpha, beta) = (alpha.max(-MATE_SCORE + ply), beta.min(MATE_SCORE - ply - 1));
+        if alpha >= beta {
+            return alpha;
+        }
+        if game.has_lost_king() {
+            return -MATE_SCORE + ply;
+        }
+        let hash = game.zobrist();
+        if self.is_draw(game, hash) {
+            return self.draw_score(ply);
+        }
+        if depth == 0 {
+            return match self.tablebase_score(game, ply, false) {
+                Some(score) => score,
+                None => self.quiescence(game, alpha, beta),
+            };
+        }
+        self.count_node();
+        if self.stopped() {
+            return 0;
+        }
+        self.path.push(hash);
+        let score = self.search_moves(game, depth, ply, alpha, beta, pv);
+        self.path.pop();
+        score
+    }
+
+    /// Searches the moves of a node of [`Searcher::negamax`], with its hash on the path.
+    fn search_moves(
+        &mut self,
+        game: &mut Game,
+        depth: u8,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Move>,
+    ) -> i32 {
+        let mut moves = ordered(game.legal_move_list());
+        if moves.is_empty() {
+            return if game.is_checked() { -MATE_SCORE + ply } else { self.draw_score(ply) };
+        }
+        if let Some(best) = self.table.probe(game) {
+            if let Some(idx) = moves.iter().position(|mv| *mv == best) {
+                moves[..=idx].rotate_right(1);
+            }
+        }
+        for mv in moves {
+            let record = game.make_move(mv);
+            let mut child_pv = Vec::new();
+            let score = -self.negamax(game, depth - 1, ply + 1, -beta, -alpha, &mut child_pv);
+            game.unmake_move(record);
+            if score >= beta {
+                self.table.store(game, mv);
+                return beta;
+            }
+            if score > alpha {
+                alpha = score;
+                pv.clear();
+                pv.push(mv);
+                pv.extend(child_pv);
+            }
+        }
+        if let Some(best) = pv.first() {
+            self.table.store(game, *best);
+        }
+        alpha
+    }
+
+    /// Scores a position found in the tablebase from the side to move's point of view.
+    ///
+    /// Wins of a tablebase counting to mate score as mates. Other tablebases count to the
+    /// next capture or pawn move, so their wins score below every mate and above every
+    /// evaluation, faster ones higher.
+    ///
+    /// # Arguments
+    /// - `game`: The position to look up.
+    /// - `ply`: Distance from the root, so that nearer wins score higher.
+    /// - `with_distance`: Whether to read the distance to add to `ply` too, rather than the
+    ///   outcome only.
+    ///
+    /// # Returns
+    /// - `Some(i32)`: The score, if the position is in the tablebase.
+    /// - `None`: If it is not.
+    fn tablebase_score(&self, game: &Game, ply: i32, with_distance: bool) -> Option<i32> {
+        let counts_to_mate = self.prober.counts_to_mate();
+        let (wdl, distance) = match with_distance || counts_to_mate {
+            true => self.prober.probe(game).map(|probe| (probe.wdl, ply + probe.dtz as i32))?,
+            false => (self.prober.probe_wdl(game)?, ply),
+        };
+        let win = if counts_to_mate { MATE_SCORE } else { TABLEBASE_WIN };
+        Some(match wdl {
+            Wdl::Win => win - distance,
+            Wdl::Loss => -win + distance,
+            Wdl::Draw => 0,
+        })
+    }
+
+    /// Scores a leaf of the main search by playing out captures until the position is quiet,
+    /// so the engine does not stop counting material in the middle of an exchange.
+    ///
+    /// The side to move may always "stand pat" on the static evaluation instead of capturing.
+    ///
+    /// # Arguments
+    /// - `game`: The position to score; left unchanged once the function returns.
+    /// - `alpha`: Lower bound of the search window.
+    /// - `beta`: Upper bound of the search window.
+    fn quiescence(&mut self, game: &mut Game, mut alpha: i32, beta: i32) -> i32 {
+        self.count_node();
+        if game.has_lost_king() {
+            return -MATE_SCORE;
+        }
+        let stand_pat = eval::evaluate(game, game.turn());
+        if stand_pat >= beta {
+            return beta;
+        }
+        alpha = alpha.max(stand_pat);
+        let captures = game.all_legal_captures(game.turn())
+            .into_iter()
+            .filter_map(|(from, to)| game.describe_move(from, to))
+            .collect();
+        for mv in ordered(captures) {
+            let record = game.make_move(mv);
+            let score = -self.quiescence(game, -beta, -alpha);
+            game.unmake_move(record);
+            if score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+}
+
+/// Orders moves for the search: captures first, by the value of the captured piece, so that
+/// alpha-beta cuts off earlier.
+fn ordered(mut moves: MoveList) -> MoveList {
+    moves.sort_by_key(|mv| -mv.captured.map_or(0, |piece| eval::PIECE_VALUES[usize::from(piece)]));
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::game::GameResult;
+    use crate::engine::tablebase::TablebaseProbe;
+    use crate::pieces::common::Color;
+
+    /// Returns the move in coordinate notation, e.g. `d2d5` or `g7f8n`.
+    fn coordinates(mv: Option<Move>) -> Option<String> {
+        mv.map(|mv| mv.to_string())
+    }
+
+    #[test]
+    fn test_best_move_takes_hanging_queen() {
+        let game = Game::from_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+        assert_eq!(coordinates(best_move(&game, 2)).as_deref(), Some("d2d5"));
+    }
+
+    #[test]
+    fn test_best_move_finds_back_rank_mate() {
+        let game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        assert_eq!(coordinates(best_move(&game, 2)).as_deref(), Some("a1a8"));
+    }
+
+    #[test]
+    fn test_best_move_avoids_defended_pawn_beyond_the_horizon() {
+        let game = Game::from_fen("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1").unwrap();
+        assert_ne!(coordinates(best_move(&game, 1)).as_deref(), Some("d1d5"));
+    }
+
+    #[test]
+    fn test_aspiration_windows_find_the_same_move() {
+        let game = Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        let windowed = search_statistics(&game, 3, true);
+        let full = search_statistics(&game, 3, false);
+        assert!(windowed.nodes > 0 && full.nodes > 0);
+        assert_eq!((full.fail_highs, full.fail_lows), (0, 0));
+        let mut table = TranspositionTable::new(game.hash_bits(), false);
+        let (_, without) = Searcher { aspiration: false, ..Searcher::new(&mut table, &game) }.iterate(&game, 3);
+        assert_eq!(best_move(&game, 3), without.first().copied());
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        depths: Vec<(u8, Score)>,
+        best_moves: usize,
+        node_reports: Vec<u64>,
+    }
+
+    impl SearchObserver for Recorder {
+        fn on_depth_complete(&mut self, depth: u8, score: Score, pv: &[Move]) {
+            assert!(!pv.is_empty());
+            self.depths.push((depth, score));
+        }
+
+        fn on_new_best_move(&mut self, _depth: u8, _best: Move, _score: Score) {
+            self.best_moves += 1;
+        }
+
+        fn on_node_count(&mut self, nodes: u64) {
+            self.node_reports.push(nodes);
+        }
+    }
+
+    #[test]
+    fn test_observer_sees_every_depth() {
+        let game = Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        let mut recorder = Recorder::default();
+        let pv = search_with_observer(&game, 3, &mut recorder);
+        assert_eq!(recorder.depths.iter().map(|(depth, _)| *depth).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert!(recorder.best_moves >= 3);
+        assert_eq!(recorder.node_reports.first(), Some(&NODE_REPORT_INTERVAL));
+        assert_eq!(pv.first().copied(), best_move(&game, 3));
+    }
+
+    #[test]
+    fn test_spawned_search_can_be_stopped() {
+        let game = Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        assert_eq!(spawn_search(&game, 2).join(), principal_variation(&game, 2));
+        let handle = spawn_search(&game, 30);
+        handle.stop();
+        let pv = handle.join();
+        assert!(game.legal_move_list().iter().any(|mv| Some(mv) == pv.first()));
+    }
+
+    #[test]
+    fn test_principal_variation_continues_after_best_move() {
+        let game = Game::from_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+        let pv = principal_variation(&game, 3);
+        assert_eq!(pv.len(), 3);
+        assert_eq!(pv[0].to_string(), "d2d5");
+        let mut preview = game.clone();
+        for mv in pv {
+            preview.play_with_promotion(mv.from, mv.to, mv.promotion).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_repetitions_score_the_contempt() {
+        let mut game = Game::new();
+        for san in ["Nf3", "Nf6", "Ng1"] {
+            game.play_san(san).unwrap();
+        }
+        game.set_contempt(50);
+        // Black takes its knight back to the starting position, a draw it does not want
+        let repetition = |game: &Game| root_scores(game, 3).into_iter().find(|(mv, _)| mv.to_string() == "f6g8").unwrap().1;
+        assert_eq!(repetition(&game), Score::Centipawns(-50));
+        game.set_contempt(0);
+        assert_eq!(repetition(&game), Score::Centipawns(0));
+    }
+
+    #[test]
+    fn test_fifty_move_rule_draws_during_the_search() {
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 99 80").unwrap();
+        assert!(root_scores(&game, 2).iter().all(|&(_, score)| score == Score::Centipawns(0)));
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 80").unwrap();
+        assert!(root_scores(&game, 2)[0].1 > Score::Centipawns(500));
+    }
+
+    #[test]
+    fn test_mates_are_scored_in_moves() {
+        let game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let (score, pv) = analyze(&game, 3);
+        assert_eq!((score, pv.len()), (Score::Mate(1), 1));
+        assert_eq!(score.to_string(), "#1");
+        // Kb8 is forced, then Rh8 mates
+        let mated = Game::from_fen("k7/8/1K6/8/8/8/8/7R b - - 0 1").unwrap();
+        assert_eq!(analyze(&mated, 3).0, Score::Mate(-1));
+        for score in [Score::Mate(3), Score::Mate(-2), Score::Mate(0), Score::Centipawns(-35)] {
+            assert_eq!(Score::from_raw(score.raw()), score);
+        }
+        assert!(Score::Mate(1) > Score::Mate(2) && Score::Mate(2) > Score::Centipawns(900));
+        assert!(Score::Centipawns(-900) > Score::Mate(-3) && Score::Mate(-3) > Score::Mate(-1));
+        assert_eq!(Score::Centipawns(35).to_string(), "+0.35");
+    }
+
+    #[test]
+    fn test_tablebase_guides_the_shortest_mate() {
+        let game = Game::from_fen("8/8/8/8/8/2k5/8/1K1R4 w - - 0 1").unwrap();
+        let before = tablebase::prober().probe(&game).unwrap();
+        let mv = best_move(&game, 1).unwrap();
+        let mut board = game.clone();
+        board.play(mv.from, mv.to).unwrap();
+        let after = tablebase::prober().probe(&board).unwrap();
+        assert_eq!((before.wdl, after.wdl, after.dtz), (Wdl::Win, Wdl::Loss, before.dtz - 1));
+    }
+
+    /// A tablebase, like the Syzygy ones, that counts to the next capture or pawn move and
+    /// knows every position as a win for White.
+    struct WhiteWins;
+
+    impl TablebaseProber for WhiteWins {
+        fn max_pieces(&self) -> usize {
+            32
+        }
+
+        fn probe(&self, game: &Game) -> Option<TablebaseProbe> {
+            let wdl = if game.turn() == Color::White { Wdl::Win } else { Wdl::Loss };
+            Some(TablebaseProbe { wdl, dtz: 13 })
+        }
+    }
+
+    #[test]
+    fn test_tablebase_wins_are_mates_only_when_counted_to_mate() {
+        let game = Game::from_fen("8/8/8/4k3/8/8/4PK2/7R w - - 0 1").unwrap();
+        let mut table = TranspositionTable::new(game.hash_bits(), false);
+        let (raw, pv) = Searcher { prober: &WhiteWins, ..Searcher::new(&mut table, &game) }.iterate(&game, 3);
+        assert_eq!(Score::from_raw(raw), Score::Centipawns(TABLEBASE_WIN - 1 - 13));
+        // the search still plays out the line the score comes from
+        assert_eq!(pv.len(), 3);
+        // the built-in tables count to mate, so their wins are mates
+        let rook = Game::from_fen("8/8/8/8/8/2k5/8/1K1R4 w - - 0 1").unwrap();
+        assert!(analyze(&rook, 1).0.is_mate());
+    }
+
+    #[test]
+    fn test_search_plays_the_underpromotion_that_mates() {
+        // only the knight promotion mates; the queen wins a bishop but gives no check
+        let game = Game::from_fen("5bn1/6Pk/7p/8/8/8/8/B1K5 w - - 0 1").unwrap();
+        for depth in 2..=3 {
+            assert_eq!(coordinates(best_move(&game, depth)).as_deref(), Some("g7f8n"), "depth {}", depth);
+        }
+        let (score, pv) = analyze(&game, 2);
+        assert_eq!(score, Score::Mate(1));
+        let mut board = game.clone();
+        board.play_with_promotion(pv[0].from, pv[0].to, pv[0].promotion).unwrap();
+        assert_eq!(board.game_result(), Some(GameResult::Checkmate(Color::Black)));
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::engine::game::{Game, Move};
+use crate::engine::search;
+
+/// The strongest skill level, which plays the best move it finds.
+pub const MAX_LEVEL: u8 = 10;
+
+/// Centipawns of noise added to the score of each move, per level below [`MAX_LEVEL`].
+const NOISE_PER_LEVEL: i32 = 20;
+
+/// Chance in percent of playing the second or third best move, per level below [`MAX_LEVEL`].
+const MISTAKE_PER_LEVEL: u64 = 5;
+
+/// How well the computer plays, from `1` for a beginner to [`MAX_LEVEL`]. Lower levels search
+/// less deep, blur their scores with random noise and now and then play the second or third
+/// best move, so that they can be beaten.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillLevel(u8);
+
+impl SkillLevel {
+    /// Creates a skill level.
+    ///
+    /// # Arguments
+    /// - `level`: The level, from `1` to [`MAX_LEVEL`].
+    ///
+    /// # Returns
+    /// - `Ok(SkillLevel)`: The level.
+    /// - `Err(String)`: If the level is out of range.
+    pub fn new(level: u8) -> Result<Self, String> {
+        match level {
+            1..=MAX_LEVEL => Ok(Self(level)),
+            _ => Err(format!("The skill level must be between 1 and {}.", MAX_LEVEL)),
+        }
+    }
+
+    /// Returns the search depth of the level: `1` at levels 1 and 2, up to `4` at levels 9 and 10.
+    pub fn depth(self) -> u8 {
+        1 + self.0 / 3
+    }
+
+    /// The most noise, in centipawns either way, added to the score of a move.
+    fn noise(self) -> i32 {
+        NOISE_PER_LEVEL * i32::from(MAX_LEVEL - self.0)
+    }
+
+    /// The chance in percent of not playing the best move.
+    fn mistake_chance(self) -> u64 {
+        MISTAKE_PER_LEVEL * u64::from(MAX_LEVEL - self.0)
+    }
+}
+
+impl fmt::Display for SkillLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A computer player weakened to a skill level, with the random numbers it picks its moves with.
+#[derive(Clone, Debug)]
+pub(crate) struct WeakenedEngine {
+    level: SkillLevel,
+    /// State of the splitmix64 generator.
+    state: u64,
+}
+
+impl WeakenedEngine {
+    /// Creates an engine playing at `level`, seeded with the current time so that two games do
+    /// not go the same way.
+    pub(crate) fn new(level: SkillLevel) -> Self {
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_nanos() as u64).unwrap_or_default();
+        Self::with_seed(level, seed)
+    }
+
+    fn with_seed(level: SkillLevel, seed: u64) -> Self {
+        Self { level, state: seed }
+    }
+
+    pub(crate) fn level(&self) -> SkillLevel {
+        self.level
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Chooses the move to play: every move is scored at the level's depth, the scores are
+    /// blurred by noise, and with the level's mistake chance the second or third best move of
+    /// the blurred ranking is played instead of the best.
+    ///
+    /// # Arguments
+    /// - `game`: The position to move in; it is not modified.
+    ///
+    /// # Returns
+    /// - `Some(Move)`: The move chosen, with the piece a pawn promotes to.
+    /// - `None`: If the side to move has no legal moves.
+    pub(crate) fn choose_move(&mut self, game: &Game) -> Option<Move> {
+        let depth = self.level.depth();
+        if self.level.noise() == 0 && self.level.mistake_chance() == 0 {
+            return search::best_move(game, depth);
+        }
+        let noise = self.level.noise();
+        let mut moves = search::root_scores(game, depth).into_iter()
+            .map(|(mv, score)| (mv, score.raw() + (self.next_random() % (2 * noise as u64 + 1)) as i32 - noise))
+            .collect::<Vec<_>>();
+        moves.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        let rank = match self.next_random() % 100 < self.level.mistake_chance() {
+            true => 1 + (self.next_random() % 2) as usize,
+            false => 0,
+        };
+        moves.get(rank).or(moves.last()).map(|&(mv, _)| mv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_are_validated_and_deepen() {
+        assert!(SkillLevel::new(0).is_err());
+        assert!(SkillLevel::new(MAX_LEVEL + 1).is_err());
+        let depths = [1, 10].map(|level| SkillLevel::new(level).unwrap().depth());
+        assert_eq!(depths, [1, 4]);
+    }
+
+    #[test]
+    fn test_beginner_misses_moves_the_top_level_finds() {
+        // the black queen hangs to the rook
+        let game = Game::from_fen("k2q4/8/8/8/8/8/8/K2R4 w - - 0 1").unwrap();
+        let capture = Some("d1d8".to_string());
+        let mut best = WeakenedEngine::with_seed(SkillLevel::new(MAX_LEVEL).unwrap(), 1);
+        assert_eq!(best.choose_move(&game).map(|mv| mv.to_string()), capture);
+        let mut beginner = WeakenedEngine::with_seed(SkillLevel::new(1).unwrap(), 1);
+        let captures = (0..50).filter(|_| beginner.choose_move(&game).map(|mv| mv.to_string()) == capture).count();
+        assert!((1..50).contains(&captures), "the beginner took the queen {} times out of 50", captures);
+    }
+}
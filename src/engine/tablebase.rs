@@ -0,0 +1,386 @@
+#[cfg(feature = "syzygy")]
+mod syzygy;
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::OnceLock;
+use strum::IntoEnumIterator;
+use crate::engine::game::{Game, Variant};
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+
+#[cfg(feature = "syzygy")]
+pub use syzygy::SyzygyTablebase;
+
+/// Outcome of a tablebase position with perfect play, for the side to move.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Wdl {
+    Loss,
+    Draw,
+    Win,
+}
+
+/// What a tablebase knows about a position.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TablebaseProbe {
+    /// The outcome for the side to move.
+    pub wdl: Wdl,
+    /// Distance to zeroing: the number of plies to the next capture, pawn move or mate with
+    /// perfect play; `0` for drawn positions.
+    pub dtz: u32,
+}
+
+impl fmt::Display for TablebaseProbe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.wdl {
+            Wdl::Win => write!(f, "win for the side to move, DTZ {}", self.dtz),
+            Wdl::Loss => write!(f, "loss for the side to move, DTZ {}", self.dtz),
+            Wdl::Draw => write!(f, "draw"),
+        }
+    }
+}
+
+/// A source of exact results for positions with few pieces, consulted by the search and by
+/// the `probe` command.
+pub trait TablebaseProber: Sync {
+    /// Largest number of pieces, kings included, in the positions the prober may know.
+    fn max_pieces(&self) -> usize;
+
+    /// Looks the position up.
+    ///
+    /// # Returns
+    /// - `Some(TablebaseProbe)`: The exact result of the position.
+    /// - `None`: If the position is not covered, e.g. it has too many pieces or castling rights.
+    fn probe(&self, game: &Game) -> Option<TablebaseProbe>;
+
+    /// Looks up only the outcome of the position, which the search does at its leaves, where
+    /// the distance is not needed and may cost more to read.
+    ///
+    /// # Returns
+    /// - `Some(Wdl)`: The outcome for the side to move.
+    /// - `None`: If the position is not covered.
+    fn probe_wdl(&self, game: &Game) -> Option<Wdl> {
+        self.probe(game).map(|probe| probe.wdl)
+    }
+
+    /// Whether the distances of the probes count the plies to mate rather than to the next
+    /// capture or pawn move. Only then does the search report the wins it finds as mates.
+    fn counts_to_mate(&self) -> bool {
+        false
+    }
+}
+
+/// Number of positions of a table: side to move, strong king, weak king, extra piece.
+const TABLE_SIZE: usize = 2 * 64 * 64 * 64;
+
+/// Plies to mate of a position that is not won, or not legal.
+const UNRESOLVED: u8 = u8::MAX;
+
+/// Moves left to the weak side in a position where it can take the extra piece.
+const ESCAPES: u8 = u8::MAX;
+
+/// Exact results of the endgames with at most three pieces and no pawn, generated in memory by
+/// retrograde analysis the first time they are needed: a lone king, or a king and a knight
+/// or bishop, cannot mate, and king and queen or king and rook against king mates by force.
+pub struct BuiltinTablebase {
+    queen: OnceLock<MateTable>,
+    rook: OnceLock<MateTable>,
+}
+
+impl BuiltinTablebase {
+    /// Creates the tablebase; no table is generated yet.
+    pub const fn new() -> Self {
+        Self { queen: OnceLock::new(), rook: OnceLock::new() }
+    }
+}
+
+impl Default for BuiltinTablebase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TablebaseProber for BuiltinTablebase {
+    fn max_pieces(&self) -> usize {
+        3
+    }
+
+    /// The strong side wins without capturing, so the distance to zeroing is the distance to
+    /// mate.
+    fn counts_to_mate(&self) -> bool {
+        true
+    }
+
+    fn probe(&self, game: &Game) -> Option<TablebaseProbe> {
+        // the table is built with the standard rules
+        if game.variant() != Variant::Standard {
+            return None;
+        }
+        let mut extra = None;
+        let mut count = 0;
+        for side in Color::iter() {
+            for piece in Piece::iter() {
+                let pieces = game.pieces(side, piece).count() as usize;
+                count += pieces;
+                if count > self.max_pieces() {
+                    return None;
+                }
+                if piece != Piece::King && pieces > 0 {
+                    extra = Some((piece, side));
+                }
+            }
+        }
+        if game.has_castling_rights() {
+            return None;
+        }
+        let draw = TablebaseProbe { wdl: Wdl::Draw, dtz: 0 };
+        let (piece, strong) = match extra {
+            None | Some((Piece::Knight | Piece::Bishop, _)) => return Some(draw),
+            Some((Piece::Pawn, _)) => return None,
+            Some(extra) => extra,
+        };
+        let table = match piece {
+            Piece::Queen => self.queen.get_or_init(|| MateTable::generate(Piece::Queen)),
+            _ => self.rook.get_or_init(|| MateTable::generate(Piece::Rock)),
+        };
+        // the tables are built with White as the strong side; mirror the board otherwise
+        let flip = if strong == Color::White { 0 } else { 56 };
+        let square = |side: Color, piece: Piece| game.pieces(side, piece).into_iter().next().map(|square| usize::from(square) ^ flip);
+        let idx = index(
+            game.turn() == strong,
+            square(strong, Piece::King)?,
+            square(strong.opposite(), Piece::King)?,
+            square(strong, piece)?,
+        );
+        Some(match (table.plies[idx], game.turn() == strong) {
+            (UNRESOLVED, _) => draw,
+            (plies, true) => TablebaseProbe { wdl: Wdl::Win, dtz: u32::from(plies) },
+            (plies, false) => TablebaseProbe { wdl: Wdl::Loss, dtz: u32::from(plies) },
+        })
+    }
+}
+
+/// The tablebase set with [`set_prober`], used instead of the built-in one.
+static INSTALLED: OnceLock<Box<dyn TablebaseProber + Send>> = OnceLock::new();
+
+/// Returns the tablebase used by the search and the `probe` command: the one set with
+/// [`set_prober`], or else the [`BuiltinTablebase`].
+pub fn prober() -> &'static dyn TablebaseProber {
+    static BUILTIN: BuiltinTablebase = BuiltinTablebase::new();
+    match INSTALLED.get() {
+        Some(installed) => installed.as_ref(),
+        None => &BUILTIN,
+    }
+}
+
+/// Replaces the built-in tablebase for the rest of the program, e.g. with a
+/// `SyzygyTablebase`.
+///
+/// # Returns
+/// - `Ok(())`: If the tablebase is used from now on.
+/// - `Err(String)`: If a tablebase was already set.
+pub fn set_prober(prober: impl TablebaseProber + Send + 'static) -> Result<(), String> {
+    INSTALLED.set(Box::new(prober)).map_err(|_| "A tablebase is already set.".to_string())
+}
+
+/// Plies to mate of every position of king and queen, or king and rook, against king.
+struct MateTable {
+    plies: Vec<u8>,
+}
+
+impl MateTable {
+    /// Runs the retrograde analysis: starting from the mates, walks back to the positions
+    /// leading to them. A position of the strong side is won as soon as one move reaches a
+    /// lost position; one of the weak side is lost once all its moves reach won positions.
+    fn generate(piece: Piece) -> Self {
+        let mut plies = vec![UNRESOLVED; TABLE_SIZE];
+        let mut moves_left = vec![0u8; TABLE_SIZE];
+        let mut queue = VecDeque::new();
+        for (strong_king, weak_king, extra) in placements() {
+            let idx = index(false, strong_king, weak_king, extra);
+            let (count, escapes) = weak_moves(piece, strong_king, weak_king, extra);
+            moves_left[idx] = if escapes { ESCAPES } else { count };
+            let in_check = attacks(piece, extra, bit(strong_king)) & bit(weak_king) != 0;
+            if count == 0 && !escapes && in_check {
+                plies[idx] = 0;
+                queue.push_back(idx);
+            }
+        }
+        while let Some(idx) = queue.pop_front() {
+            let (strong_to_move, strong_king, weak_king, extra) = unindex(idx);
+            let next = plies[idx] + 1;
+            if strong_to_move {
+                // the weak king stepped here from a neighbouring square
+                for from in king_steps(weak_king) {
+                    if from == extra || from == strong_king || adjacent(from, strong_king) {
+                        continue;
+                    }
+                    let before = index(false, strong_king, from, extra);
+                    if plies[before] != UNRESOLVED || moves_left[before] == ESCAPES {
+                        continue;
+                    }
+                    moves_left[before] -= 1;
+                    if moves_left[before] == 0 {
+                        plies[before] = next;
+                        queue.push_back(before);
+                    }
+                }
+                continue;
+            }
+            // the strong king or the extra piece moved last, without capturing
+            let king_moves = king_steps(strong_king)
+                .filter(|from| *from != weak_king && *from != extra && !adjacent(*from, weak_king))
+                .map(|from| (from, extra));
+            let piece_moves = squares(attacks(piece, extra, bit(strong_king) | bit(weak_king)) & !bit(strong_king) & !bit(weak_king))
+                .map(|from| (strong_king, from));
+            for (strong_king, extra) in king_moves.chain(piece_moves).collect::<Vec<_>>() {
+                if attacks(piece, extra, bit(strong_king)) & bit(weak_king) != 0 {
+                    continue;
+                }
+                let before = index(true, strong_king, weak_king, extra);
+                if plies[before] == UNRESOLVED {
+                    plies[before] = next;
+                    queue.push_back(before);
+                }
+            }
+        }
+        Self { plies }
+    }
+}
+
+/// Returns the table index of a position.
+fn index(strong_to_move: bool, strong_king: usize, weak_king: usize, extra: usize) -> usize {
+    (usize::from(strong_to_move) << 18) | (strong_king << 12) | (weak_king << 6) | extra
+}
+
+/// Splits a table index into side to move, strong king, weak king and extra piece.
+fn unindex(idx: usize) -> (bool, usize, usize, usize) {
+    (idx >> 18 == 1, (idx >> 12) & 63, (idx >> 6) & 63, idx & 63)
+}
+
+/// Lists the placements of the three pieces where they stand on different squares and the
+/// kings do not touch.
+fn placements() -> impl Iterator<Item = (usize, usize, usize)> {
+    (0..64).flat_map(|strong_king| (0..64).flat_map(move |weak_king| (0..64).map(move |extra| (strong_king, weak_king, extra))))
+        .filter(|(strong_king, weak_king, extra)| {
+            strong_king != weak_king && extra != strong_king && extra != weak_king && !adjacent(*strong_king, *weak_king)
+        })
+}
+
+/// Counts the moves of the lone king to squares the strong side does not attack.
+///
+/// # Returns
+/// - `(u8, bool)`: The number of moves that keep the material, and whether the king can take
+///   the undefended extra piece instead.
+fn weak_moves(piece: Piece, strong_king: usize, weak_king: usize, extra: usize) -> (u8, bool) {
+    let mut count = 0;
+    let mut escapes = false;
+    let attacked = attacks(piece, extra, bit(strong_king));
+    for to in king_steps(weak_king) {
+        if to == strong_king || adjacent(to, strong_king) {
+            continue;
+        }
+        if to == extra {
+            escapes = true;
+        } else if attacked & bit(to) == 0 {
+            count += 1;
+        }
+    }
+    (count, escapes)
+}
+
+/// The steps of a king, the four of a rook first.
+const KING_DIRECTIONS: [(isize, isize); 8] = [(0, 1), (1, 0), (0, -1), (-1, 0), (1, 1), (1, -1), (-1, -1), (-1, 1)];
+
+fn bit(square: usize) -> u64 {
+    1 << square
+}
+
+fn adjacent(a: usize, b: usize) -> bool {
+    (a % 8).abs_diff(b % 8) <= 1 && (a / 8).abs_diff(b / 8) <= 1
+}
+
+/// Lists the squares one king step away from `square`.
+fn king_steps(square: usize) -> impl Iterator<Item = usize> {
+    squares((0..8).fold(0, |mask, direction| mask | ray(square, direction, !0)))
+}
+
+/// Lists the squares of the set bits of a mask.
+fn squares(mut mask: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        let square = mask.trailing_zeros() as usize;
+        mask &= mask.checked_sub(1)?;
+        Some(square)
+    })
+}
+
+/// Returns the squares a queen or rook on `from` attacks, each ray stopping at the first
+/// occupied square, which is included.
+fn attacks(piece: Piece, from: usize, occupied: u64) -> u64 {
+    let directions = if piece == Piece::Queen { 0..8 } else { 0..4 };
+    directions.fold(0, |mask, direction| mask | ray(from, direction, occupied))
+}
+
+/// Returns the squares from `from` in one of [`KING_DIRECTIONS`] up to the first occupied
+/// square, included.
+fn ray(from: usize, direction: usize, occupied: u64) -> u64 {
+    let rays = empty_board_rays();
+    let full = rays[from][direction];
+    let blockers = full & occupied;
+    if blockers == 0 {
+        return full;
+    }
+    let (file_step, rank_step) = KING_DIRECTIONS[direction];
+    let blocker = if rank_step * 8 + file_step > 0 { blockers.trailing_zeros() } else { 63 - blockers.leading_zeros() };
+    full & !rays[blocker as usize][direction]
+}
+
+/// Returns the rays of every square in every direction on an empty board.
+fn empty_board_rays() -> &'static [[u64; 8]; 64] {
+    static RAYS: OnceLock<[[u64; 8]; 64]> = OnceLock::new();
+    RAYS.get_or_init(|| {
+        std::array::from_fn(|from| std::array::from_fn(|direction| {
+            let (file_step, rank_step) = KING_DIRECTIONS[direction];
+            let (mut file, mut rank) = ((from % 8) as isize, (from / 8) as isize);
+            let mut mask = 0;
+            loop {
+                file += file_step;
+                rank += rank_step;
+                if !(0..8).contains(&file) || !(0..8).contains(&rank) {
+                    return mask;
+                }
+                mask |= bit((rank * 8 + file) as usize);
+            }
+        }))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(fen: &str) -> Option<TablebaseProbe> {
+        prober().probe(&Game::from_fen(fen).unwrap())
+    }
+
+    #[test]
+    fn test_probe_queen_and_rook_mates() {
+        assert_eq!(probe("7k/8/6K1/8/8/8/8/1Q6 w - - 0 1"), Some(TablebaseProbe { wdl: Wdl::Win, dtz: 1 }));
+        assert_eq!(probe("Q6k/8/6K1/8/8/8/8/8 b - - 0 1"), Some(TablebaseProbe { wdl: Wdl::Loss, dtz: 0 }));
+        // Black's rook mates in 12 moves from here
+        let rook = prober().probe(&Game::from_fen("8/8/3k4/8/8/4K3/8/7r w - - 0 1").unwrap()).unwrap();
+        assert_eq!(rook.wdl, Wdl::Loss);
+        assert_eq!(rook.dtz, 24);
+        // the lone king takes the undefended rook
+        assert_eq!(probe("8/8/8/8/8/2K5/7k/6R1 b - - 0 1"), Some(TablebaseProbe { wdl: Wdl::Draw, dtz: 0 }));
+    }
+
+    #[test]
+    fn test_probe_draws_and_uncovered_positions() {
+        assert_eq!(probe("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1").map(|probe| probe.wdl), Some(Wdl::Draw));
+        assert_eq!(probe("7k/8/5KQ1/8/8/8/8/8 b - - 0 1").map(|probe| probe.wdl), Some(Wdl::Draw));
+        assert_eq!(probe("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"), None);
+        assert_eq!(probe("4k3/8/8/8/8/8/8/4K2R w K - 0 1"), None);
+        assert_eq!(probe("4k3/8/8/8/8/8/8/R3K2R w - - 0 1"), None);
+    }
+}
@@ -0,0 +1,107 @@
+use shakmaty::fen::Fen;
+use shakmaty::{CastlingMode, Chess, Position};
+use shakmaty_syzygy::{AmbiguousWdl, Tablebase, Wdl as SyzygyWdl};
+use crate::engine::game::{Game, Variant};
+use super::{TablebaseProbe, TablebaseProber, Wdl};
+
+/// Syzygy tablebase files read from a directory, e.g. the 3-4-5 piece tables. The files are
+/// opened the first time a position of their material is probed.
+///
+/// Wins and losses that the fifty-move rule turns into draws are reported as draws, since the
+/// game applies the rule. The DTZ of some tables is rounded, so it may be one ply off.
+pub struct SyzygyTablebase {
+    tables: Tablebase<Chess>,
+}
+
+impl SyzygyTablebase {
+    /// Finds the tables in a directory.
+    ///
+    /// # Arguments
+    /// - `directory`: The directory holding the `.rtbw` and `.rtbz` files, e.g. `syzygy/3-4-5`.
+    ///
+    /// # Returns
+    /// - `Ok(SyzygyTablebase)`: The tables found.
+    /// - `Err(String)`: If the directory cannot be read or holds no table.
+    pub fn open(directory: &str) -> Result<Self, String> {
+        let mut tables = Tablebase::new();
+        let found = tables.add_directory(directory)
+            .map_err(|err| format!("Cannot read the tablebase directory {}: {}", directory, err))?;
+        if found == 0 {
+            return Err(format!("No Syzygy table (.rtbw or .rtbz file) in {}.", directory));
+        }
+        Ok(Self { tables })
+    }
+
+    /// Returns the position of the game as the tables read it, if it may be in them.
+    fn covered(&self, game: &Game) -> Option<Chess> {
+        // the tables are built with the standard rules
+        if game.variant() != Variant::Standard {
+            return None;
+        }
+        let position = position(game)?;
+        (position.board().occupied().count() <= self.max_pieces()).then_some(position)
+    }
+}
+
+impl TablebaseProber for SyzygyTablebase {
+    fn max_pieces(&self) -> usize {
+        self.tables.max_pieces()
+    }
+
+    fn probe(&self, game: &Game) -> Option<TablebaseProbe> {
+        let position = self.covered(game)?;
+        let wdl = match self.tables.probe_wdl(&position).ok()? {
+            AmbiguousWdl::Win | AmbiguousWdl::MaybeWin => Wdl::Win,
+            AmbiguousWdl::Loss | AmbiguousWdl::MaybeLoss => Wdl::Loss,
+            AmbiguousWdl::CursedWin | AmbiguousWdl::Draw | AmbiguousWdl::BlessedLoss => Wdl::Draw,
+        };
+        let dtz = match wdl {
+            Wdl::Draw => 0,
+            _ => self.tables.probe_dtz(&position).ok()?.ignore_rounding().0.unsigned_abs(),
+        };
+        Some(TablebaseProbe { wdl, dtz })
+    }
+
+    /// Reads the WDL tables only, as if the last move had been a capture or pawn move: the
+    /// fifty-move clock is only taken into account by [`TablebaseProber::probe`].
+    fn probe_wdl(&self, game: &Game) -> Option<Wdl> {
+        let position = self.covered(game)?;
+        Some(match self.tables.probe_wdl_after_zeroing(&position).ok()? {
+            SyzygyWdl::Win => Wdl::Win,
+            SyzygyWdl::Loss => Wdl::Loss,
+            SyzygyWdl::CursedWin | SyzygyWdl::Draw | SyzygyWdl::BlessedLoss => Wdl::Draw,
+        })
+    }
+}
+
+/// Returns the position of the game as the tables read it; `None` if it is not legal for them.
+fn position(game: &Game) -> Option<Chess> {
+    let fen = game.fen().parse::<Fen>().ok()?;
+    fen.into_position(CastlingMode::Standard).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_needs_a_directory_with_tables() {
+        let directory = std::env::temp_dir().join(format!("chess_game_syzygy_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let Err(err) = SyzygyTablebase::open(directory.to_str().unwrap()) else {
+            panic!("an empty directory has no tables");
+        };
+        assert!(err.starts_with("No Syzygy table"), "{}", err);
+        assert!(SyzygyTablebase::open("/nonexistent/syzygy").is_err());
+        std::fs::remove_dir(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_positions_are_read_with_their_castling_rights() {
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 3 20").unwrap();
+        let position = position(&game).unwrap();
+        assert_eq!(position.board().occupied().count(), 4);
+        assert!(position.castles().has(shakmaty::Color::White, shakmaty::CastlingSide::KingSide));
+        assert_eq!(position.halfmoves(), 3);
+    }
+}
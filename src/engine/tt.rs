@@ -0,0 +1,153 @@
+use crate::engine::game::{Game, Move};
+
+/// Number of index bits of the table used by the engine (16384 entries).
+pub const TABLE_BITS: u32 = 14;
+
+/// Returns the memory taken by a table with `2^bits` slots, in kilobytes.
+pub(crate) fn kilobytes(bits: u32) -> usize {
+    (std::mem::size_of::<Option<Entry>>() << bits) / 1024
+}
+
+/// Returns the number of index bits of the largest table that fits in `kilobytes`.
+pub(crate) fn bits_for_kilobytes(kilobytes: usize) -> u32 {
+    (kilobytes * 1024 / std::mem::size_of::<Option<Entry>>()).max(1).ilog2()
+}
+
+/// Counters gathered while probing a [`TranspositionTable`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HashStats {
+    /// Number of lookups.
+    pub probes: u64,
+    /// Lookups whose stored key matched the position's key.
+    pub hits: u64,
+    /// Hits whose position turned out to be different, only counted in verification mode.
+    pub collisions: u64,
+}
+
+impl HashStats {
+    /// Returns the share of hits that were collisions, `0.0` when there were no hits.
+    pub fn collision_rate(&self) -> f64 {
+        if self.hits == 0 {
+            0.0
+        } else {
+            self.collisions as f64 / self.hits as f64
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    /// The upper half of the Zobrist hash; the lower bits select the slot.
+    key: u32,
+    /// The verification hash of the position, only filled in verification mode.
+    verification: u64,
+    /// The best move found from the position.
+    best: Move,
+}
+
+/// Remembers the best move found for positions already searched, so the search can try it
+/// first when the position comes up again, through a transposition or a deeper iteration.
+///
+/// Entries are addressed by the Zobrist hash and only keep part of it, like most engines do.
+/// In verification mode every entry also stores an independent second hash, so that a key
+/// match on a different position is detected and counted as a collision.
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+    verify: bool,
+    stats: HashStats,
+}
+
+impl TranspositionTable {
+    /// Creates an empty table with `2^bits` slots.
+    ///
+    /// # Arguments
+    /// - `bits`: The number of index bits (at most 32).
+    /// - `verify`: Whether to check every hit against a second hash and count collisions.
+    pub fn new(bits: u32, verify: bool) -> Self {
+        Self { entries: vec![None; 1 << bits], verify, stats: HashStats::default() }
+    }
+
+    /// Looks up the best move stored for the position.
+    ///
+    /// # Returns
+    /// - `Some(Move)`: The stored move, if the key matched (and, in verification mode,
+    ///   the position is really the same).
+    /// - `None`: If the position is unknown or was replaced.
+    pub fn probe(&mut self, game: &Game) -> Option<Move> {
+        let hash = game.zobrist();
+        self.stats.probes += 1;
+        let entry = self.entries[self.slot(hash)].filter(|entry| entry.key == Self::key(hash))?;
+        self.stats.hits += 1;
+        if self.verify && entry.verification != game.verification_hash() {
+            self.stats.collisions += 1;
+            return None;
+        }
+        Some(entry.best)
+    }
+
+    /// Stores the best move found for the position, replacing whatever used the slot.
+    pub fn store(&mut self, game: &Game, best: Move) {
+        let hash = game.zobrist();
+        let verification = if self.verify { game.verification_hash() } else { 0 };
+        let slot = self.slot(hash);
+        self.entries[slot] = Some(Entry { key: Self::key(hash), verification, best });
+    }
+
+    /// Returns the counters gathered since the table was created.
+    pub fn stats(&self) -> HashStats {
+        self.stats
+    }
+
+    fn slot(&self, hash: u64) -> usize {
+        (hash as usize) & (self.entries.len() - 1)
+    }
+
+    fn key(hash: u64) -> u32 {
+        (hash >> 32) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::{File, Rank, Square};
+
+    #[test]
+    fn test_table_size_in_kilobytes() {
+        assert_eq!(bits_for_kilobytes(kilobytes(TABLE_BITS)), TABLE_BITS);
+        assert_eq!(bits_for_kilobytes(2 * kilobytes(TABLE_BITS) - 1), TABLE_BITS);
+        // the largest table that fits, never a larger one
+        let bits = bits_for_kilobytes(1000);
+        assert!(kilobytes(bits) <= 1000 && kilobytes(bits + 1) > 1000);
+    }
+
+    #[test]
+    fn test_probe_returns_the_stored_move() {
+        let mut table = TranspositionTable::new(8, true);
+        let game = Game::new();
+        let best = game.describe_move(Square::new(File::E, Rank::Two), Square::new(File::E, Rank::Four)).unwrap();
+        assert_eq!(table.probe(&game), None);
+        table.store(&game, best);
+        assert_eq!(table.probe(&game), Some(best));
+        assert_eq!(table.stats(), HashStats { probes: 2, hits: 1, collisions: 0 });
+    }
+
+    #[test]
+    fn test_verification_detects_a_collision() {
+        let mut table = TranspositionTable::new(8, true);
+        let game = Game::new();
+        let other = Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let best = game.describe_move(Square::new(File::E, Rank::Two), Square::new(File::E, Rank::Four)).unwrap();
+        table.store(&other, best);
+        // make the other position's entry look like it belongs to the start position
+        let hash = game.zobrist();
+        let other_slot = table.slot(other.zobrist());
+        let mut entry = table.entries[other_slot].take().unwrap();
+        entry.key = TranspositionTable::key(hash);
+        let slot = table.slot(hash);
+        table.entries[slot] = Some(entry);
+        assert_eq!(table.probe(&game), None);
+        assert_eq!(table.stats().collisions, 1);
+        assert_eq!(table.stats().collision_rate(), 1.0);
+    }
+}
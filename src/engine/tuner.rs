@@ -0,0 +1,280 @@
+use crate::engine::eval::{self, EvalConfig};
+use crate::engine::game::Game;
+use crate::engine::match_runner::{MatchGame, MatchOutcome};
+use crate::pieces::common::Color;
+
+/// Plies at the start of a self-play game whose positions are left out of the data set, as
+/// they come from the fixed match openings rather than the engines.
+const SKIPPED_OPENING_PLIES: usize = 8;
+
+/// Steps the weights are moved by, coarse first, in centipawns.
+const STEPS: [i32; 3] = [16, 4, 1];
+
+/// Range searched for the scaling constant of the win probability.
+const SCALING_RANGE: (f64, f64) = (0.05, 3.0);
+
+/// A position with the result of the game it was played in.
+pub struct TuningPosition {
+    game: Game,
+    /// The points White scored: `1.0` for a win, `0.5` for a draw, `0.0` for a loss.
+    result: f64,
+}
+
+impl TuningPosition {
+    /// Creates a position from its FEN and the points White scored in the game.
+    ///
+    /// # Returns
+    /// - `Ok(TuningPosition)`: The position.
+    /// - `Err(String)`: If the FEN is invalid or the result is not `0`, `0.5` or `1`.
+    pub fn new(fen: &str, result: f64) -> Result<Self, String> {
+        if ![0.0, 0.5, 1.0].contains(&result) {
+            return Err(format!("Invalid result {}, expected 0, 0.5 or 1.", result));
+        }
+        Ok(Self { game: Game::from_fen(fen)?, result })
+    }
+}
+
+/// Reads a data set of positions, one per line: a FEN followed by the result of the game,
+/// written `1-0`, `0-1` or `1/2-1/2`, or `[1.0]`, `[0.5]` or `[0.0]`. The EPD form
+/// `<fen> c9 "1-0";` is read too. Empty lines and lines starting with `#` are skipped.
+///
+/// # Returns
+/// - `Ok(Vec<TuningPosition>)`: The positions, in file order.
+/// - `Err(String)`: The first invalid line and why.
+pub fn parse_tuning_positions(text: &str) -> Result<Vec<TuningPosition>, String> {
+    text.lines()
+        .enumerate()
+        .map(|(idx, line)| (idx, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(idx, line)| parse_line(line).map_err(|err| format!("Line {}: {}", idx + 1, err)))
+        .collect()
+}
+
+fn parse_line(line: &str) -> Result<TuningPosition, String> {
+    let mut fields = line.trim_end_matches(';').split_whitespace().collect::<Vec<_>>();
+    let result = match fields.pop().map(|result| result.trim_matches('"')) {
+        Some("1-0" | "[1.0]" | "[1]") => 1.0,
+        Some("1/2-1/2" | "[0.5]") => 0.5,
+        Some("0-1" | "[0.0]" | "[0]") => 0.0,
+        _ => return Err("expected a FEN followed by a result such as 1-0 or [0.5].".to_string()),
+    };
+    if fields.last() == Some(&"c9") {
+        fields.pop();
+    }
+    TuningPosition::new(&fields.join(" "), result)
+}
+
+/// Turns a self-play game into tuning positions: every position after the opening, labelled
+/// with the result of the game. Positions in check are left out, since their static
+/// evaluation ignores the threat.
+pub fn self_play_positions(game: &MatchGame) -> Vec<TuningPosition> {
+    let result = match game.outcome {
+        MatchOutcome::WhiteWins => 1.0,
+        MatchOutcome::Draw => 0.5,
+        MatchOutcome::BlackWins => 0.0,
+    };
+    let mut replay = Game::new();
+    let mut positions = Vec::new();
+    for (ply, san) in game.moves.iter().enumerate() {
+        if replay.play_san(san).is_err() {
+            break;
+        }
+        if ply + 1 >= SKIPPED_OPENING_PLIES && !replay.is_checked() {
+            positions.push(TuningPosition { game: replay.clone(), result });
+        }
+    }
+    positions
+}
+
+/// Tunes the evaluation weights by Texel's method: the static evaluation of each position is
+/// turned into White's expected score, `1 / (1 + 10^(-k * eval / 400))`, and each weight is
+/// nudged up or down as long as that lowers the mean squared error against the results.
+///
+/// The positions should be quiet, since captures in progress are not searched. The pawn
+/// value anchors the scale and is not tuned; every other weight is, the entries of the
+/// piece-square tables included.
+pub struct Tuner {
+    positions: Vec<TuningPosition>,
+    /// Scales the evaluation before it becomes a win probability, fitted to the data set.
+    scaling: f64,
+    /// Most passes over every weight for each step size.
+    max_passes: usize,
+}
+
+impl Tuner {
+    /// Creates a tuner over the given positions; the scaling constant is fitted by
+    /// [`Tuner::tune`].
+    pub fn new(positions: Vec<TuningPosition>) -> Self {
+        Self { positions, scaling: 1.0, max_passes: 50 }
+    }
+
+    /// Sets the most passes over every weight for each step size, to bound the time taken.
+    pub fn set_max_passes(&mut self, max_passes: usize) {
+        self.max_passes = max_passes;
+    }
+
+    /// Returns the mean squared error between the results and the scores the weights
+    /// predict; `0.0` without positions.
+    pub fn error(&self, config: &EvalConfig) -> f64 {
+        if self.positions.is_empty() {
+            return 0.0;
+        }
+        let total = self.positions.iter()
+            .map(|position| {
+                let eval = f64::from(eval::evaluate_with(&position.game, config, Color::White));
+                let expected = 1.0 / (1.0 + 10f64.powf(-self.scaling * eval / 400.0));
+                (position.result - expected).powi(2)
+            })
+            .sum::<f64>();
+        total / self.positions.len() as f64
+    }
+
+    /// Fits the scaling constant that gives `config` the lowest error, by golden-section
+    /// search.
+    fn fit_scaling(&mut self, config: &EvalConfig) {
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        let (mut low, mut high) = SCALING_RANGE;
+        for _ in 0..40 {
+            let left = high - ratio * (high - low);
+            let right = low + ratio * (high - low);
+            self.scaling = left;
+            let left_error = self.error(config);
+            self.scaling = right;
+            if left_error < self.error(config) {
+                high = right;
+            } else {
+                low = left;
+            }
+        }
+        self.scaling = (low + high) / 2.0;
+    }
+
+    /// Tunes the weights, starting from `config`.
+    ///
+    /// # Arguments
+    /// - `config`: The weights to start from.
+    /// - `on_pass`: Called after every pass over the weights with the step size and the
+    ///   error reached, e.g. to report progress.
+    ///
+    /// # Returns
+    /// The tuned weights; the weights not tuned keep their value from `config`.
+    pub fn tune(&mut self, config: EvalConfig, mut on_pass: impl FnMut(i32, f64)) -> EvalConfig {
+        self.fit_scaling(&config);
+        let mut best = config;
+        let mut best_error = self.error(&best);
+        for step in STEPS {
+            for _ in 0..self.max_passes {
+                let mut improved = false;
+                for idx in 0..TUNED_WEIGHTS {
+                    for delta in [step, -step] {
+                        let mut candidate = best;
+                        let weight = weight_mut(&mut candidate, idx);
+                        *weight += delta;
+                        // a piece must stay worth something
+                        if idx < TUNED_PIECE_VALUES && *weight <= 0 {
+                            continue;
+                        }
+                        let error = self.error(&candidate);
+                        if error < best_error {
+                            (best, best_error, improved) = (candidate, error, true);
+                            break;
+                        }
+                    }
+                }
+                on_pass(step, best_error);
+                if !improved {
+                    break;
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Number of piece values tuned: knight, rook, bishop and queen.
+const TUNED_PIECE_VALUES: usize = 4;
+
+/// Number of single weights tuned: the piece values, the mobility of the same pieces and the
+/// seven positional terms.
+const TUNED_TERMS: usize = TUNED_PIECE_VALUES * 2 + 7;
+
+/// Number of weights tuned: the single weights, then every entry of the six piece-square
+/// tables and the king's endgame table.
+const TUNED_WEIGHTS: usize = TUNED_TERMS + 7 * 64;
+
+/// Returns one of the tuned weights by index, the piece values first and the piece-square
+/// tables last.
+fn weight_mut(config: &mut EvalConfig, idx: usize) -> &mut i32 {
+    match idx {
+        0..4 => &mut config.piece_values[idx + 1],
+        4..8 => &mut config.mobility[idx - 3],
+        8 => &mut config.king_shield,
+        9 => &mut config.doubled_pawn,
+        10 => &mut config.isolated_pawn,
+        11 => &mut config.passed_pawn,
+        12 => &mut config.rook_open_file,
+        13 => &mut config.rook_half_open_file,
+        14 => &mut config.bishop_pair,
+        _ => {
+            let entry = idx - TUNED_TERMS;
+            &mut config.table_mut(entry / 64)[entry % 64]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pieces::Piece;
+
+    #[test]
+    fn test_parse_tuning_positions() {
+        let text = "# white is a knight up\n\
+            4k3/8/8/8/8/8/8/3NK3 w - - 0 1 1-0\n\
+            4k3/8/8/8/8/8/8/4K3 b - - c9 \"1/2-1/2\";\n\
+            4k3/8/8/8/8/8/8/3nK3 w - - 0 1 [0.0]\n";
+        let positions = parse_tuning_positions(text).unwrap();
+        assert_eq!(positions.iter().map(|position| position.result).collect::<Vec<_>>(), vec![1.0, 0.5, 0.0]);
+        assert_eq!(positions[1].game.turn(), Color::Black);
+        let Err(err) = parse_tuning_positions("4k3/8/8/8/8/8/8/4K3 w - - 0 1 win") else {
+            panic!("a result is required");
+        };
+        assert!(err.starts_with("Line 1:"), "{}", err);
+    }
+
+    #[test]
+    fn test_tuning_lowers_the_error() {
+        // a lone extra knight wins here far less often than its 320 centipawns predict
+        let text = "4k3/8/8/8/8/8/8/3NK3 w - - 0 1 1/2-1/2\n\
+            3nk3/8/8/8/8/8/8/4K3 w - - 0 1 1/2-1/2\n\
+            4k3/8/8/8/8/8/8/3QK3 w - - 0 1 1-0\n\
+            3qk3/8/8/8/8/8/8/4K3 w - - 0 1 0-1\n";
+        let mut tuner = Tuner::new(parse_tuning_positions(text).unwrap());
+        tuner.set_max_passes(5);
+        let before = EvalConfig::default();
+        tuner.fit_scaling(&before);
+        let before_error = tuner.error(&before);
+        let tuned = tuner.tune(before, |_, _| {});
+        assert!(tuner.error(&tuned) < before_error);
+        assert!(tuned.piece_values[1] < before.piece_values[1]);
+        assert_eq!(tuned.piece_values[0], 100);
+    }
+
+    #[test]
+    fn test_tuning_moves_the_piece_square_tables() {
+        // a knight in the corner only draws while one in the centre wins
+        let text = "4k3/8/8/8/8/8/8/N3K3 w - - 0 1 1/2-1/2\n\
+            n3k3/8/8/8/8/8/8/4K3 w - - 0 1 1/2-1/2\n\
+            4k3/8/8/8/3N4/8/8/4K3 w - - 0 1 1-0\n\
+            4k3/8/8/3n4/8/8/8/4K3 w - - 0 1 0-1\n";
+        let mut tuner = Tuner::new(parse_tuning_positions(text).unwrap());
+        tuner.set_max_passes(5);
+        let before = EvalConfig::default();
+        let tuned = tuner.tune(before, |_, _| {});
+        let knight = usize::from(Piece::Knight);
+        // a1 and d4 from White's point of view
+        let (corner, centre) = (7 * 8, 4 * 8 + 3);
+        assert!(tuned.piece_square[knight][corner] < before.piece_square[knight][corner]);
+        assert!(tuned.piece_square[knight][centre] > before.piece_square[knight][centre]);
+    }
+}
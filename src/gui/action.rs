@@ -0,0 +1,91 @@
+use crate::engine::game::Nag;
+use crate::engine::options::EngineOption;
+use crate::gui::display::PieceVisibility;
+use crate::gui::image::ImageFormat;
+use crate::gui::san::SanMove;
+use crate::square::Square;
+
+/// A command issued by the player through the GUI.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Action {
+    /// Move the piece from the first square to the second one.
+    Move(Square, Square),
+    /// Play a move written in standard algebraic notation (e.g. `Nf3`).
+    San(SanMove),
+    /// Highlight the legal moves of the piece on the square.
+    ShowMoves(Square),
+    /// Offer a draw to the opponent.
+    OfferDraw,
+    /// Accept the opponent's draw offer.
+    AcceptDraw,
+    /// Decline the opponent's draw offer.
+    DeclineDraw,
+    /// Resign the game.
+    Resign,
+    /// Take back the last move.
+    Undo,
+    /// Play again the last move taken back.
+    Redo,
+    /// Print the moves played so far as numbered SAN pairs, with the annotated moves.
+    History,
+    /// Attach the comment to the last move played.
+    Comment(String),
+    /// Annotate the half-move with the given number, starting at `1`.
+    Annotate(usize, Nag),
+    /// Go to the position after the given number of half-moves.
+    Goto(usize),
+    /// Print the bit-packed encoding of the position and of the moves played.
+    Pack,
+    /// Read the whole position aloud, rank by rank.
+    ReadBoard,
+    /// Read the content of a single square.
+    ReadSquare(Square),
+    /// Print the static evaluation of the current position.
+    Evaluate,
+    /// Turn the board around.
+    Flip,
+    /// Turn drawing the board from the side to move on or off.
+    AutoFlip,
+    /// Show the player who typed it all pieces, only their own, or none.
+    Blind(PieceVisibility),
+    /// Print the perft node count of every legal move at the given depth.
+    PerftDivide(u8),
+    /// Print the perft node count at the given depth split by piece type and move kind.
+    PerftByPiece(u8),
+    /// Search the position with a verifying transposition table and print its hash collision
+    /// statistics, optionally to the given depth.
+    HashStats(Option<u8>),
+    /// Search the position with and without aspiration windows and print the node counts,
+    /// optionally to the given depth.
+    SearchStats(Option<u8>),
+    /// Open the board editor to set up a position to play from.
+    Setup,
+    /// Look the position up in the endgame tablebase.
+    Probe,
+    /// Turn pondering on or off.
+    Ponder,
+    /// Turn the evaluation bar beside the board on or off.
+    EvalBar,
+    /// Stop the engine's search running in the background.
+    StopSearch,
+    /// Step through the engine's principal variation, optionally searched to the given depth.
+    Preview(Option<u8>),
+    /// Search for a forced mate in at most the given number of moves and print its line.
+    SolveMate(u8),
+    /// Change an engine option, e.g. the skill level with `level` or any option with `set`.
+    SetOption(EngineOption),
+    /// List the engine options and their values.
+    ShowOptions,
+    /// Save the game as JSON to the given file.
+    Save(String),
+    /// Write the game as PGN, with the time of each move, to the given file.
+    Export(String),
+    /// Draw the board as an image in the given format to the given file.
+    ExportImage(ImageFormat, String),
+    /// Resume the game saved in the given file.
+    Load(String),
+    /// Replay the event log in the given file and continue from the position it ends in.
+    ReplayLog(String),
+    /// Draw the board with a built-in theme (`unicode`, `ascii`) or the given theme file.
+    Theme(String),
+}
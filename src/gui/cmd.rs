@@ -1,61 +1,420 @@
-use std::io::{Write, self};
-use crossterm::style::{self, style, StyledContent, Stylize};
+use std::collections::BTreeMap;
+use std::io::{IsTerminal, Write, self};
+use std::time::{Duration, Instant};
+use crossterm::event::{self, Event, KeyEventKind};
+use crossterm::style::{style, StyledContent, Stylize};
+use crossterm::terminal::{self, Clear, ClearType};
 use regex::Regex;
+use crate::crash;
+use crate::engine::board::Board;
+use crate::engine::clock::Clock;
+use crate::engine::eval;
+use crate::engine::options::EngineOption;
+use crate::gui::action::Action;
+use crate::gui::error::GuiError;
+use crate::gui::eval_bar::EvalBar;
+use crate::gui::display::{DisplayOptions, PieceVisibility};
+use crate::gui::cursor::{self, Cursor, CursorKey, MoveMap};
+use crate::gui::highlight::Highlight;
+use crate::gui::image::ImageFormat;
+use crate::gui::input::{CommandSender, InputQueue};
+use crate::gui::line_editor::{LineEdit, LineEditor};
+use crate::gui::preferences;
+use crate::gui::promotion::{self, PromotionSelector};
+use crate::gui::san;
+use crate::gui::setup::{self, SetupCommand};
+use crate::gui::sparkline::sparkline;
+use crate::gui::speech;
+use crate::gui::status::{CapturedPieces, GameStatus, StatusLine};
+use crate::gui::theme::Theme;
 use crate::pieces::common::Color;
 use crate::pieces::Piece;
-use crate::square::{Square};
+use crate::square::{Rank, Square, SquareParseError};
 
 pub struct CommandPromptGUI{
     writer: io::Stdout,
-    reader: io::Stdin,
+    /// The lines typed at the terminal and the commands sent from other threads.
+    input: InputQueue,
+    /// One-word commands typed in place of longer ones, e.g. `m` for `move`.
+    aliases: BTreeMap<String, String>,
+    theme: Theme,
+    /// The cursor used to pick moves with the arrow keys; `None` in line-command mode.
+    cursor: Option<Cursor>,
+    /// The clocks of a timed game, with the side whose clock runs and when its turn started,
+    /// shown before the turn prompt and counted down while the player types.
+    clock: Option<(Clock, Color, Instant)>,
+    /// Whether standard input and output are a terminal, where the prompt reads keys in raw
+    /// mode to redraw the running clock; piped input is read line by line.
+    live_prompt: bool,
+    /// The origin and destination of the last move, highlighted under any other highlight,
+    /// and the move in algebraic notation, shown below the board.
+    last_move: Option<(Square, Square, String)>,
+    move_time: Option<String>,
+    status_line: Option<StatusLine>,
+    /// The pieces each side has taken, listed above the board for the side drawn at the top
+    /// and below it for the other.
+    captured: Option<CapturedPieces>,
+    /// The evaluation bar drawn beside the ranks, when it is turned on.
+    eval_bar: Option<EvalBar>,
+    /// Draw the board from Black's side, rank 1 at the top.
+    flipped: bool,
+    /// Draw the board from the side to move, for two players sharing the screen.
+    auto_flip: bool,
 }
 
-const FILE_NAMES_ROW: &'static str = "   A B C D E F G H";
-const MOVE_REGEX: &'static str = r"^move\s+([a-h][1-8])\s+([a-h][1-8])$";
-const SHOW_REGEX: &'static str = r"^show\s+([a-h][1-8])$";
+const FILE_NAMES_ROW: &str = "   A B C D E F G H";
+const FLIPPED_FILE_NAMES_ROW: &str = "   H G F E D C B A";
+/// Number of evaluations drawn in the engine match graph.
+const EVAL_GRAPH_WIDTH: usize = 40;
+/// How often the prompt checks whether the running clock must be redrawn.
+const CLOCK_TICK: Duration = Duration::from_millis(100);
+/// Reminder of the keys shown after the prompt in cursor mode.
+const CURSOR_KEYS: &str = "(arrows move, Enter picks up and drops, Esc cancels, : types a command, q quits)";
+// squares are matched loosely and parsed afterwards, so a typo such as `i9` gets a precise error
+const MOVE_REGEX: &str = r"^move\s+(\S+)\s+(\S+)$";
+const SHOW_REGEX: &str = r"^show\s+(\S+)$";
+const READ_REGEX: &str = r"^read\s+(\S+)$";
+const PERFT_REGEX: &str = r"^perft\s+(\d+)(\s+--by-piece)?$";
+const PREVIEW_REGEX: &str = r"^preview(?:\s+([1-9]))?$";
+const HASHSTATS_REGEX: &str = r"^hashstats(?:\s+([1-9]))?$";
+const SEARCHSTATS_REGEX: &str = r"^searchstats(?:\s+([1-9]))?$";
+const GOTO_REGEX: &str = r"^goto\s+(\d+)$";
+const MATE_REGEX: &str = r"^mate\s+([1-4])$";
+const LEVEL_REGEX: &str = r"^level\s+(\d{1,2}|off)$";
+const SET_REGEX: &str = r"^set\s+(\S+)\s+(\S+)$";
+const ANNOTATE_REGEX: &str = r"^annotate\s+(\d+)\s+(\S+)$";
 
 impl CommandPromptGUI{
-    pub fn render(&mut self, board: &[Option<(Piece, Color)>; 64], turn: Color) {
-        writeln!(self.writer, "{}", FILE_NAMES_ROW).unwrap();
-        for rank in  (0..8).rev(){
-            for file in 0..8{
+    /// Draws the board, the banner of the game status and the turn prompt.
+    ///
+    /// # Arguments
+    /// - `board`: The current position.
+    /// - `turn`: The side to move.
+    /// - `highlights`: Squares to highlight, e.g. the moves of a selected piece, each colored by
+    ///   the kind of move.
+    /// - `status`: Check or end of the game to announce; a checked king is highlighted.
+    /// - `options`: The pieces the player looking at the board may see.
+    pub fn render(
+        &mut self,
+        board: &Board,
+        turn: Color,
+        highlights: &[(Square, Highlight)],
+        status: GameStatus,
+        options: DisplayOptions,
+    ) -> io::Result<()> {
+        let cursor = self.cursor.map(|cursor| (cursor.square, Highlight::Cursor));
+        let last_move = self.last_move.iter()
+            .flat_map(|(from, to, _)| [(*from, Highlight::LastMove), (*to, Highlight::LastMove)]);
+        let highlights = cursor.iter().chain(highlights).copied().chain(last_move).collect::<Vec<_>>();
+        let checked_king = status.checked_king().map(usize::from);
+        let bottom = self.bottom_side(turn);
+        let (ranks, files) = orientation(bottom);
+        let file_names = if bottom == Color::White { FILE_NAMES_ROW } else { FLIPPED_FILE_NAMES_ROW };
+        if let Some(line) = self.captured_line(bottom.opposite()) {
+            writeln!(self.writer, "{}", line)?;
+        }
+        writeln!(self.writer, "{}", file_names)?;
+        for (row, rank) in ranks.into_iter().enumerate(){
+            for (column, file) in files.into_iter().enumerate(){
                 let idx = rank * 8 + file;
-                let piece = &board[idx];
-                let styled = Self::styled_symbol(piece);
-                if file == 0 {
-                    write!(self.writer, "{}|", rank+1).unwrap();
+                let piece = options.visible(board[idx]);
+                let styled = self.styled_symbol(&piece);
+                let highlight = highlights.iter().find(|(square, _)| usize::from(*square) == idx);
+                let colors = &self.theme.colors;
+                // a1 is a dark square
+                let square_color = if (rank + file) % 2 == 0 { colors.dark_square } else { colors.light_square };
+                let styled = if checked_king == Some(idx) && piece.is_some() {
+                    styled.on(colors.check)
+                } else if let Some((_, highlight)) = highlight {
+                    styled.on(colors.highlight(*highlight))
+                } else if let Some(color) = square_color {
+                    styled.on(color)
+                } else {
+                    styled
+                };
+                if column == 0 {
+                    write!(self.writer, "{}|", rank+1)?;
                 }
-                write!(self.writer, " {}", styled).unwrap();
-                if file == 7{
-                    writeln!(self.writer, "|{}", rank+1).unwrap();
+                write!(self.writer, " {}", styled)?;
+                if column == 7{
+                    write!(self.writer, "|{}", rank+1)?;
+                    if let Some(bar) = &self.eval_bar {
+                        let colors = &self.theme.colors;
+                        let (fill, rest) = match bottom {
+                            Color::White => (colors.white_piece, colors.black_piece),
+                            Color::Black => (colors.black_piece, colors.white_piece),
+                        };
+                        write!(self.writer, " {}", style(bar.cell(7 - row, bottom)).with(fill).on(rest))?;
+                    }
+                    writeln!(self.writer)?;
                 }
 
             }
         }
-        writeln!(self.writer, "{}", FILE_NAMES_ROW).unwrap();
-        write!(self.writer, "{:?} Turn:", turn).unwrap();
-        self.writer.flush().unwrap();
+        match &self.eval_bar {
+            Some(bar) => writeln!(self.writer, "{}   {}", file_names, bar.label())?,
+            None => writeln!(self.writer, "{}", file_names)?,
+        }
+        if let Some(line) = self.captured_line(bottom) {
+            writeln!(self.writer, "{}", line)?;
+        }
+        if let Some((_, _, san)) = &self.last_move {
+            writeln!(self.writer, "Last move: {}", san)?;
+        }
+        if let Some(move_time) = &self.move_time {
+            writeln!(self.writer, "{}", move_time)?;
+        }
+        if let Some(status_line) = &self.status_line {
+            writeln!(self.writer, "{}", status_line)?;
+        }
+        if status != GameStatus::InProgress {
+            writeln!(self.writer, "{}", style(status).bold())?;
+        }
+        if !status.is_over() {
+            write!(self.writer, "{}", self.turn_prompt(turn))?;
+        }
+        self.writer.flush()
+    }
+
+    /// Redraws the live view of an engine-vs-engine match: the board, the latest moves and
+    /// a graph of the evaluation after every move.
+    ///
+    /// # Arguments
+    /// - `board`: The current position.
+    /// - `turn`: The side to move.
+    /// - `last_moves`: The tail of the move list, already formatted.
+    /// - `evaluations`: The evaluation after each move, in centipawns from White's perspective.
+    /// - `depth`: The search depth of the last move, if the engine played it.
+    /// - `status`: Check or end of the game to announce.
+    pub fn render_match(
+        &mut self,
+        board: &Board,
+        turn: Color,
+        last_moves: &str,
+        evaluations: &[i32],
+        depth: Option<u8>,
+        status: GameStatus,
+    ) -> io::Result<()> {
+        clearscreen::clear().ok();
+        self.render(board, turn, &[], status, DisplayOptions::default())?;
+        writeln!(self.writer)?;
+        writeln!(self.writer, "Moves: {}", last_moves)?;
+        let latest = evaluations.last().copied().unwrap_or_default();
+        write!(self.writer, "Eval:  {} {:+}", sparkline(evaluations, EVAL_GRAPH_WIDTH), latest)?;
+        match depth {
+            Some(depth) => writeln!(self.writer, " (depth {})", depth)?,
+            None => writeln!(self.writer)?,
+        }
+        self.writer.flush()
+    }
+
+    /// Prints the position as plain sentences, one rank per line from rank 8 down, for
+    /// screen readers.
+    pub fn read_board(&mut self, board: &Board, turn: Color) -> io::Result<()> {
+        writeln!(self.writer)?;
+        for rank in (0..8).rev().filter_map(|rank| Rank::try_from(rank).ok()) {
+            writeln!(self.writer, "{}", speech::describe_rank(board, rank))?;
+        }
+        writeln!(self.writer, "{:?} to move.", turn)
+    }
+
+    /// Prints the content of a single square as a plain sentence, for screen readers.
+    pub fn read_square(&mut self, board: &Board, square: Square) -> io::Result<()> {
+        writeln!(self.writer)?;
+        writeln!(self.writer, "{}", speech::describe_square(board, square))
+    }
+
+    /// Prints how many pieces of each side attack or defend a square, and which ones.
+    pub fn show_square_control(&mut self, board: &Board, square: Square, attackers: &[Vec<(Piece, Square)>; 2]) -> io::Result<()> {
+        writeln!(self.writer, "{}", speech::describe_control(board, square, attackers))
     }
 
-    pub fn wait_and_process_event(&mut self) -> Option<(Square, Square)> {
+    pub fn wait_and_process_event(&mut self) -> Result<Option<Action>, GuiError> {
+        self.wait_for_action(None)
+    }
+
+    /// Waits for a valid command from the terminal or a [`CommandSender`], printing the help
+    /// after an invalid one.
+    ///
+    /// # Arguments
+    /// - `deadline`: When to stop waiting, e.g. when the flag of the player falls.
+    ///
+    /// # Returns
+    /// - `Ok(Some(Action))`: The action of the command.
+    /// - `Ok(None)`: If the deadline passed before a valid command came in.
+    /// - `Err(GuiError)`: If the input was closed or the terminal cannot be written to.
+    pub fn wait_for_action(&mut self, deadline: Option<Instant>) -> Result<Option<Action>, GuiError> {
         let move_regex = Regex::new(MOVE_REGEX).unwrap();
-        // let show_regex = Regex::new(SHOW_REGEX).unwrap();
+        let show_regex = Regex::new(SHOW_REGEX).unwrap();
+        let perft_regex = Regex::new(PERFT_REGEX).unwrap();
+        let read_regex = Regex::new(READ_REGEX).unwrap();
+        let preview_regex = Regex::new(PREVIEW_REGEX).unwrap();
+        let hashstats_regex = Regex::new(HASHSTATS_REGEX).unwrap();
+        let searchstats_regex = Regex::new(SEARCHSTATS_REGEX).unwrap();
+        let goto_regex = Regex::new(GOTO_REGEX).unwrap();
+        let level_regex = Regex::new(LEVEL_REGEX).unwrap();
+        let set_regex = Regex::new(SET_REGEX).unwrap();
+        let mate_regex = Regex::new(MATE_REGEX).unwrap();
+        let annotate_regex = Regex::new(ANNOTATE_REGEX).unwrap();
         loop {
-            let binding = self.receive_input();
+            // algebraic notation is case-sensitive (`bxc3` is not `Bxc3`), commands are not
+            let Some(typed) = self.receive_command(deadline)? else {
+                return Ok(None);
+            };
+            let raw_input = preferences::expand_aliases(&self.aliases, &typed);
+            let binding = raw_input.to_lowercase();
             let user_action = binding.as_str();
             match user_action {
                 "help" | "h" => {
-                    self.show_help_information();
+                    self.show_help_information()?;
                     continue;
                 },
-                "quit" | "q" => panic!(),
-                "draw" =>  panic!(),
-                "accept" =>  panic!(),
-                // s if show_regex.is_match(s) => return Self::extract_show(show_regex, s),
-                s if move_regex.is_match(s) => return Some(Self::extract_move(move_regex, s)),
+                "quit" | "q" => std::process::exit(0),
+                "draw" => return Ok(Some(Action::OfferDraw)),
+                "accept" => return Ok(Some(Action::AcceptDraw)),
+                "decline" => return Ok(Some(Action::DeclineDraw)),
+                "resign" => return Ok(Some(Action::Resign)),
+                "eval" => return Ok(Some(Action::Evaluate)),
+                "read" => return Ok(Some(Action::ReadBoard)),
+                "pack" => return Ok(Some(Action::Pack)),
+                "undo" | "prev" => return Ok(Some(Action::Undo)),
+                "redo" | "next" => return Ok(Some(Action::Redo)),
+                "history" => return Ok(Some(Action::History)),
+                "setup" => return Ok(Some(Action::Setup)),
+                "set" => return Ok(Some(Action::ShowOptions)),
+                "probe" => return Ok(Some(Action::Probe)),
+                "flip" => return Ok(Some(Action::Flip)),
+                "flip auto" => return Ok(Some(Action::AutoFlip)),
+                "blind on" => return Ok(Some(Action::Blind(PieceVisibility::Hidden))),
+                "blind own" => return Ok(Some(Action::Blind(PieceVisibility::Own))),
+                "blind off" => return Ok(Some(Action::Blind(PieceVisibility::All))),
+                "ponder" => return Ok(Some(Action::Ponder)),
+                "evalbar" => return Ok(Some(Action::EvalBar)),
+                "stop" => return Ok(Some(Action::StopSearch)),
+                s if s.starts_with("replay ") => {
+                    let (_, path) = raw_input.split_once(char::is_whitespace).unwrap();
+                    return Ok(Some(Action::ReplayLog(path.trim().to_string())));
+                },
+                s if s.starts_with("comment ") => {
+                    // comments keep their case
+                    let (_, text) = raw_input.split_once(char::is_whitespace).unwrap();
+                    return Ok(Some(Action::Comment(text.trim().to_string())));
+                },
+                s if annotate_regex.is_match(s) => {
+                    let captures = annotate_regex.captures(s).unwrap();
+                    match (captures[1].parse(), captures[2].parse()) {
+                        (Ok(ply), Ok(nag)) => return Ok(Some(Action::Annotate(ply, nag))),
+                        (Err(_), _) => writeln!(self.writer, "Invalid half-move, {}", &captures[1])?,
+                        (_, Err(err)) => writeln!(self.writer, "{}", err)?,
+                    }
+                    continue;
+                },
+                s if s.starts_with("theme ") => {
+                    let (_, name) = raw_input.split_once(char::is_whitespace).unwrap();
+                    return Ok(Some(Action::Theme(name.trim().to_string())));
+                },
+                s if s.starts_with("export ") => {
+                    let (_, path) = raw_input.split_once(char::is_whitespace).unwrap();
+                    let path = path.trim();
+                    let image = path.split_once(char::is_whitespace).and_then(|(format, file)| match format.to_lowercase().as_str() {
+                        "png" => Some((ImageFormat::Png, file)),
+                        "svg" => Some((ImageFormat::Svg, file)),
+                        _ => None,
+                    });
+                    return Ok(Some(match image {
+                        Some((format, file)) => Action::ExportImage(format, file.trim().to_string()),
+                        None => Action::Export(path.to_string()),
+                    }));
+                },
+                s if s.starts_with("save ") || s.starts_with("load ") => {
+                    // file names keep their case
+                    let (_, path) = raw_input.split_once(char::is_whitespace).unwrap();
+                    let path = path.trim().to_string();
+                    return Ok(Some(if s.starts_with("save ") { Action::Save(path) } else { Action::Load(path) }));
+                },
+                s if show_regex.is_match(s) => match Self::extract_square(&show_regex, s) {
+                    Ok(square) => return Ok(Some(Action::ShowMoves(square))),
+                    Err(err) => {
+                        writeln!(self.writer, "Invalid square, {}.", err)?;
+                        continue;
+                    }
+                },
+                s if read_regex.is_match(s) => match Self::extract_square(&read_regex, s) {
+                    Ok(square) => return Ok(Some(Action::ReadSquare(square))),
+                    Err(err) => {
+                        writeln!(self.writer, "Invalid square, {}.", err)?;
+                        continue;
+                    }
+                },
+                s if perft_regex.is_match(s) => {
+                    let captures = perft_regex.captures(s).unwrap();
+                    let depth = captures.get(1).unwrap().as_str();
+                    let by_piece = captures.get(2).is_some();
+                    match depth.parse() {
+                        Ok(depth) if by_piece => return Ok(Some(Action::PerftByPiece(depth))),
+                        Ok(depth) => return Ok(Some(Action::PerftDivide(depth))),
+                        Err(_) => {
+                            writeln!(self.writer, "Invalid perft depth, {}", depth)?;
+                            continue;
+                        }
+                    }
+                },
+                s if mate_regex.is_match(s) => {
+                    let moves = mate_regex.captures(s).unwrap()[1].parse().unwrap();
+                    return Ok(Some(Action::SolveMate(moves)));
+                },
+                s if level_regex.is_match(s) || set_regex.is_match(s) => {
+                    let option = match level_regex.captures(s) {
+                        Some(captures) => EngineOption::parse("skill", &captures[1]),
+                        None => {
+                            let captures = set_regex.captures(s).unwrap();
+                            EngineOption::parse(&captures[1], &captures[2])
+                        }
+                    };
+                    match option {
+                        Ok(option) => return Ok(Some(Action::SetOption(option))),
+                        Err(err) => {
+                            writeln!(self.writer, "{}", err)?;
+                            continue;
+                        }
+                    }
+                },
+                s if preview_regex.is_match(s) => {
+                    let depth = preview_regex.captures(s).unwrap().get(1)
+                        .map(|depth| depth.as_str().parse().unwrap());
+                    return Ok(Some(Action::Preview(depth)));
+                },
+                s if hashstats_regex.is_match(s) => {
+                    let depth = hashstats_regex.captures(s).unwrap().get(1)
+                        .map(|depth| depth.as_str().parse().unwrap());
+                    return Ok(Some(Action::HashStats(depth)));
+                },
+                s if searchstats_regex.is_match(s) => {
+                    let depth = searchstats_regex.captures(s).unwrap().get(1)
+                        .map(|depth| depth.as_str().parse().unwrap());
+                    return Ok(Some(Action::SearchStats(depth)));
+                },
+                s if goto_regex.is_match(s) => {
+                    let ply = goto_regex.captures(s).unwrap().get(1).unwrap().as_str();
+                    match ply.parse() {
+                        Ok(ply) => return Ok(Some(Action::Goto(ply))),
+                        Err(_) => {
+                            writeln!(self.writer, "Invalid half-move number, {}", ply)?;
+                            continue;
+                        }
+                    }
+                },
+                s if move_regex.is_match(s) => match Self::extract_move(&move_regex, s) {
+                    Ok((from, to)) => return Ok(Some(Action::Move(from, to))),
+                    Err(err) => {
+                        writeln!(self.writer, "Invalid square, {}.", err)?;
+                        continue;
+                    }
+                },
+                _ if san::parse(&raw_input).is_some() => return Ok(san::parse(&raw_input).map(Action::San)),
                 _ => {
-                    writeln!(self.writer, "Invalid command, {}", &user_action).unwrap();
-                    self.show_help_information();
+                    writeln!(self.writer, "Invalid command, {}", &user_action)?;
+                    self.show_help_information()?;
                     continue;
                 }
             };
@@ -69,52 +428,451 @@ impl CommandPromptGUI{
 impl CommandPromptGUI {
     pub fn new() -> Self {
         Self {
-            reader: io::stdin(),
+            input: InputQueue::new(),
+            aliases: BTreeMap::new(),
             writer: io::stdout(),
+            theme: Theme::default(),
+            cursor: None,
+            clock: None,
+            live_prompt: io::stdin().is_terminal() && io::stdout().is_terminal(),
+            last_move: None,
+            move_time: None,
+            status_line: None,
+            captured: None,
+            eval_bar: None,
+            flipped: false,
+            auto_flip: false,
+        }
+    }
+
+    /// Turns the board around, so that the other side is drawn at the bottom. Ends auto-flip.
+    pub fn flip(&mut self, turn: Color) {
+        self.flipped = self.bottom_side(turn) == Color::White;
+        self.auto_flip = false;
+    }
+
+    /// Sets the command aliases, each one word mapped to the command it stands for.
+    pub fn set_aliases(&mut self, aliases: BTreeMap<String, String>) {
+        self.aliases = aliases;
+    }
+
+    /// Draws the board from Black's side, rank 1 at the top, or from White's side.
+    pub fn set_flipped(&mut self, flipped: bool) {
+        self.flipped = flipped;
+    }
+
+    /// Turns drawing the board from the side to move on or off.
+    pub fn set_auto_flip(&mut self, enabled: bool) {
+        self.auto_flip = enabled;
+    }
+
+    /// Returns `true` if the board is drawn from the side to move.
+    pub fn is_auto_flip(&self) -> bool {
+        self.auto_flip
+    }
+
+    /// Returns the side drawn at the bottom of the board.
+    fn bottom_side(&self, turn: Color) -> Color {
+        match (self.auto_flip, self.flipped) {
+            (true, _) => turn,
+            (false, true) => Color::Black,
+            (false, false) => Color::White,
         }
     }
 
-    fn receive_input(&mut self) -> String {
-        let mut input = String::new();
-        self.reader
-            .read_line(&mut input)
-            .unwrap();
-        input.trim().to_lowercase()
+    /// Sets the clocks shown before the turn prompt, or hides them with `None`.
+    ///
+    /// # Arguments
+    /// - `clock`: The clocks, the side whose clock runs and when its turn started.
+    pub(crate) fn run_clock(&mut self, clock: Option<(Clock, Color, Instant)>) {
+        self.clock = clock;
+    }
+
+    /// Sets the last move, highlighted on the board and shown in algebraic notation below it,
+    /// or hides it with `None`.
+    pub fn show_last_move(&mut self, last_move: Option<(Square, Square, String)>) {
+        self.last_move = last_move;
+    }
+
+    /// Sets the time taken by the last move and by each player, shown below the last move, or
+    /// hides it with `None`.
+    pub fn show_move_time(&mut self, move_time: Option<String>) {
+        self.move_time = move_time;
+    }
+
+    /// Sets the line of facts about the position shown below the board, or hides it with `None`.
+    pub(crate) fn show_status_line(&mut self, status_line: Option<StatusLine>) {
+        self.status_line = status_line;
+    }
+
+    /// Sets the pieces each side has taken, shown beside the board, or hides them with `None`.
+    pub(crate) fn show_captured(&mut self, captured: Option<CapturedPieces>) {
+        self.captured = captured;
+    }
+
+    /// Sets the evaluation bar drawn beside the board, or hides it with `None`.
+    pub(crate) fn show_eval_bar(&mut self, eval_bar: Option<EvalBar>) {
+        self.eval_bar = eval_bar;
+    }
+
+    /// Switches to picking moves with a cursor moved by the arrow keys.
+    pub fn enable_cursor(&mut self) {
+        self.cursor = Some(Cursor::new());
     }
 
-    fn extract_move(regex: Regex, s: &str) -> (Square, Square) {
+    /// Returns `true` if moves are picked with the cursor rather than typed.
+    pub fn has_cursor(&self) -> bool {
+        self.cursor.is_some()
+    }
+
+    /// Lets the player pick a move with the cursor, redrawing the board after every key with
+    /// the legal targets of the piece picked up. Typing `:` falls back to a line command.
+    ///
+    /// # Arguments
+    /// - `board`: The current position.
+    /// - `turn`: The side to move.
+    /// - `status`: Check to announce, if any.
+    /// - `moves`: The legal moves of the side to move, by piece.
+    /// - `options`: The pieces the player may see.
+    /// - `deadline`: When to stop waiting, e.g. when the flag of the player falls.
+    ///
+    /// # Returns
+    /// - `Ok(Some(Action::Move))`: The picked move, or the action of a typed command.
+    /// - `Ok(None)`: If the deadline passed first.
+    /// - `Err(GuiError)`: If the input was closed or the terminal cannot be written to.
+    pub fn wait_for_cursor_action(
+        &mut self,
+        board: &Board,
+        turn: Color,
+        status: GameStatus,
+        moves: &MoveMap,
+        options: DisplayOptions,
+        deadline: Option<Instant>,
+    ) -> Result<Option<Action>, GuiError> {
+        loop {
+            write!(self.writer, " {}", CURSOR_KEYS)?;
+            self.writer.flush()?;
+            let key = match cursor::read_key(deadline) {
+                Ok(Some(key)) => key,
+                Ok(None) => return Ok(None),
+                Err(err) => {
+                    writeln!(self.writer, "The cursor is not available ({}), type commands instead.", err)?;
+                    self.cursor = None;
+                    return self.wait_for_action(deadline);
+                }
+            };
+            let is_flipped = self.bottom_side(turn) == Color::Black;
+            let cursor = self.cursor.as_mut().expect("cursor mode is enabled");
+            match key {
+                CursorKey::Quit => std::process::exit(0),
+                CursorKey::Command => {
+                    cursor.selected = None;
+                    write!(self.writer, "\nCommand:")?;
+                    self.writer.flush()?;
+                    return self.wait_for_action(deadline);
+                }
+                CursorKey::Cancel => cursor.selected = None,
+                CursorKey::Enter => {
+                    if let Some((from, to)) = cursor.press_enter(moves) {
+                        writeln!(self.writer, "{}{}", from, to)?;
+                        return Ok(Some(Action::Move(from, to)));
+                    }
+                }
+                // the arrows follow the board as drawn
+                _ if is_flipped => cursor.step(key.mirrored()),
+                _ => cursor.step(key),
+            }
+            let highlights = cursor.highlights(moves);
+            clearscreen::clear().ok();
+            self.render(board, turn, &highlights, status, options)?;
+        }
+    }
+
+    /// Asks which piece a pawn reaching the last rank becomes. In cursor mode the four pieces
+    /// are drawn side by side under the board and picked with the arrows and Enter, or with
+    /// their letter; otherwise the letter is typed.
+    ///
+    /// # Arguments
+    /// - `side`: The side promoting, whose pieces are shown.
+    /// - `timeout`: How long the player has to choose, in timed games.
+    ///
+    /// # Returns
+    /// - `Ok(Piece)`: The chosen piece, or a queen if the time ran out first.
+    /// - `Err(GuiError)`: If the input was closed or the terminal cannot be written to.
+    pub fn ask_promotion(&mut self, side: Color, timeout: Option<Duration>) -> Result<Piece, GuiError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        if self.cursor.is_some() {
+            let mut selector = PromotionSelector::default();
+            loop {
+                self.draw_promotion_selector(side, selector, deadline)?;
+                match cursor::read_key_code(deadline) {
+                    Ok(Some(key)) => if let Some(piece) = selector.press(key) {
+                        writeln!(self.writer)?;
+                        return Ok(piece);
+                    },
+                    Ok(None) => break,
+                    Err(err) => {
+                        writeln!(self.writer, "\nThe selector is not available ({}), type the piece instead.", err)?;
+                        self.cursor = None;
+                        return self.ask_promotion(side, deadline.map(|deadline| deadline.saturating_duration_since(Instant::now())));
+                    }
+                }
+            }
+        } else {
+            loop {
+                write!(self.writer, " Promote to (q, r, b or n; Enter for a queen):")?;
+                self.writer.flush()?;
+                let Some(input) = self.receive_input_until(deadline)? else {
+                    break;
+                };
+                match promotion::parse_choice(&input) {
+                    Some(piece) => return Ok(piece),
+                    None => writeln!(self.writer, "Invalid piece, {}", input)?,
+                }
+            }
+        }
+        writeln!(self.writer, "\nOut of time, promoting to a queen.")?;
+        Ok(Piece::Queen)
+    }
+
+    /// Redraws the promotion selector in place on the current line, the highlighted piece in
+    /// reverse video, with the seconds left if the choice is timed.
+    fn draw_promotion_selector(&mut self, side: Color, selector: PromotionSelector, deadline: Option<Instant>) -> io::Result<()> {
+        write!(self.writer, "\r")?;
+        crossterm::queue!(self.writer, Clear(ClearType::CurrentLine))?;
+        write!(self.writer, "Promote to:")?;
+        for (idx, piece) in promotion::CHOICES.into_iter().enumerate() {
+            let symbol = self.styled_symbol(&Some((piece, side)));
+            if idx == selector.selected {
+                write!(self.writer, " [{}]", symbol.reverse())?;
+            } else {
+                write!(self.writer, "  {} ", symbol)?;
+            }
+        }
+        write!(self.writer, "  ←/→ and Enter, or q/r/b/n")?;
+        if let Some(deadline) = deadline {
+            write!(self.writer, " ({}s)", deadline.saturating_duration_since(Instant::now()).as_secs())?;
+        }
+        self.writer.flush()
+    }
+
+    /// Replaces the theme used to draw the board.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// Waits for the player while stepping through a preview.
+    ///
+    /// # Returns
+    /// - `Ok(true)`: If the player pressed Enter to see the next move.
+    /// - `Ok(false)`: If the player typed `stop` to leave the preview.
+    /// - `Err(GuiError)`: If the input was closed or the terminal cannot be written to.
+    pub fn wait_for_step(&mut self) -> Result<bool, GuiError> {
+        write!(self.writer, " Press Enter for the next move, or type stop:")?;
+        self.writer.flush()?;
+        Ok(self.receive_input()?.to_lowercase() != "stop")
+    }
+
+    /// Asks the player a yes/no question.
+    ///
+    /// # Returns
+    /// - `Ok(true)`: If the answer starts with `y`.
+    /// - `Ok(false)`: Otherwise.
+    /// - `Err(GuiError)`: If the input was closed or the terminal cannot be written to.
+    pub fn confirm(&mut self, question: &str) -> Result<bool, GuiError> {
+        write!(self.writer, "{} (y/n):", question)?;
+        self.writer.flush()?;
+        Ok(self.receive_input()?.to_lowercase().starts_with('y'))
+    }
+
+    /// Waits for a valid board editor command, printing the editor help after an invalid one.
+    pub fn wait_for_setup_command(&mut self) -> Result<SetupCommand, GuiError> {
+        loop {
+            write!(self.writer, " Setup:")?;
+            self.writer.flush()?;
+            let input = self.receive_input()?;
+            match setup::parse(&input) {
+                Some(command) => return Ok(command),
+                None => {
+                    writeln!(self.writer, "Invalid setup command, {}", input)?;
+                    writeln!(self.writer, "Setup commands: put <piece><square> (e.g. put Ke1, put pa7; uppercase is White), remove <square>, clear, turn <white|black>, done, cancel")?;
+                }
+            }
+        }
+    }
+
+    fn receive_input(&mut self) -> Result<String, GuiError> {
+        Ok(self.receive_input_until(None)?.expect("waiting without a deadline always gets a line"))
+    }
+
+    /// Waits for the next line of input, until `deadline` if there is one.
+    fn receive_input_until(&mut self, deadline: Option<Instant>) -> Result<Option<String>, GuiError> {
+        let input = self.input.next(deadline)?;
+        if let Some(input) = &input {
+            crash::record_command(input);
+        }
+        Ok(input)
+    }
+
+    /// Waits for the next command at the turn prompt. While a clock runs at a terminal, the
+    /// line is read key by key so that the prompt counts down as the player types.
+    fn receive_command(&mut self, deadline: Option<Instant>) -> Result<Option<String>, GuiError> {
+        match self.clock {
+            Some((_, turn, _)) if self.live_prompt && self.input.terminal_is_free() => {
+                terminal::enable_raw_mode()?;
+                let line = self.read_line_with_clock(turn, deadline);
+                terminal::disable_raw_mode()?;
+                writeln!(self.writer)?;
+                let line = line?;
+                if let Some(line) = &line {
+                    crash::record_command(line);
+                }
+                Ok(line)
+            }
+            _ => self.receive_input_until(deadline),
+        }
+    }
+
+    /// Reads a line in raw mode, redrawing the prompt whenever the clock or the typed text
+    /// changes. Commands sent through a [`CommandSender`] are taken in between keys.
+    fn read_line_with_clock(&mut self, turn: Color, deadline: Option<Instant>) -> Result<Option<String>, GuiError> {
+        let mut editor = LineEditor::default();
+        let mut shown = String::new();
+        loop {
+            if let Some(line) = self.input.try_remote() {
+                return Ok(Some(line));
+            }
+            let prompt = format!("{}{}", self.turn_prompt(turn), editor.text());
+            if prompt != shown {
+                write!(self.writer, "\r{}{}", Clear(ClearType::CurrentLine), prompt)?;
+                self.writer.flush()?;
+                shown = prompt;
+            }
+            let left = deadline.map_or(CLOCK_TICK, |deadline| deadline.saturating_duration_since(Instant::now()));
+            if left.is_zero() {
+                return Ok(None);
+            }
+            if !event::poll(left.min(CLOCK_TICK))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match editor.press(key) {
+                LineEdit::Changed | LineEdit::Ignored => {}
+                LineEdit::Submitted(line) => return Ok(Some(line)),
+                LineEdit::Interrupted => return Ok(Some("quit".to_string())),
+                LineEdit::Closed => return Err(GuiError::EndOfInput),
+            }
+        }
+    }
+
+    /// Returns the prompt asking `turn` for a command, after the clocks in a timed game.
+    fn turn_prompt(&self, turn: Color) -> String {
+        match self.clock {
+            Some((clock, running, started)) => format!("{} {:?} Turn:", clock.display(running, started.elapsed()), turn),
+            None => format!("{:?} Turn:", turn),
+        }
+    }
+
+    /// Returns a handle other threads can send commands to this prompt with.
+    pub(crate) fn command_sender(&self) -> CommandSender {
+        self.input.sender()
+    }
+
+    fn extract_move(regex: &Regex, s: &str) -> Result<(Square, Square), SquareParseError> {
         let caps = regex.captures(s).unwrap();
-        let from = caps.get(1).unwrap().as_str().to_string();
-        let to = caps.get(2).unwrap().as_str().to_string();
-        let from = Square::try_from(from).unwrap();
-        let to = Square::try_from(to).unwrap();
-        (from, to)
+        let from = caps.get(1).unwrap().as_str().parse()?;
+        let to = caps.get(2).unwrap().as_str().parse()?;
+        Ok((from, to))
     }
 
-    fn styled_symbol(piece: &Option<(Piece, Color)>) -> StyledContent<&'static str> {
+    fn extract_square(regex: &Regex, s: &str) -> Result<Square, SquareParseError> {
+        let caps = regex.captures(s).unwrap();
+        caps.get(1).unwrap().as_str().parse()
+    }
+
+    /// Lists the pieces `side` has taken, the least valuable first, and its material lead,
+    /// e.g. `White captured: ♟♟♞ +4`; `None` if it has taken nothing.
+    fn captured_line(&self, side: Color) -> Option<String> {
+        let captured = self.captured.as_ref()?;
+        let mut pieces = captured.pieces[usize::from(side)].clone();
+        if pieces.is_empty() {
+            return None;
+        }
+        pieces.sort_by_key(|piece| eval::PIECE_VALUES[usize::from(*piece)]);
+        let glyphs = pieces.into_iter()
+            .map(|piece| self.theme.pieces.glyph(&Some((piece, side.opposite()))).to_string())
+            .collect::<String>();
+        let lead = captured.lead(side).map_or(String::new(), |lead| format!(" +{}", lead));
+        Some(format!("{:?} captured: {}{}", side, glyphs, lead))
+    }
+
+    fn styled_symbol(&self, piece: &Option<(Piece, Color)>) -> StyledContent<String> {
+        let glyph = style(self.theme.pieces.glyph(piece).to_string());
+        let colors = &self.theme.colors;
         match piece {
-            Some((Piece::King, Color::White)) => style::style("♔").with(style::Color::White),
-            Some((Piece::King, Color::Black)) => style::style("♚").with(style::Color::DarkGrey),
-            Some((Piece::Queen, Color::White)) => style::style("♕").with(style::Color::White),
-            Some((Piece::Queen, Color::Black)) => style::style("♛").with(style::Color::DarkGrey),
-            Some((Piece::Rock, Color::White)) => style::style("♖").with(style::Color::White),
-            Some((Piece::Rock, Color::Black)) => style::style("♜").with(style::Color::DarkGrey),
-            Some((Piece::Bishop, Color::White)) => style::style("♗").with(style::Color::White),
-            Some((Piece::Bishop, Color::Black)) => style::style("♝").with(style::Color::DarkGrey),
-            Some((Piece::Knight, Color::White)) => style::style("♘").with(style::Color::White),
-            Some((Piece::Knight, Color::Black)) => style::style("♞").with(style::Color::DarkGrey),
-            Some((Piece::Pawn, Color::White)) => style::style("♙").with(style::Color::White),
-            Some((Piece::Pawn, Color::Black)) => style::style("♟").with(style::Color::DarkGrey),
-            _ => style("□").with(style::Color::Grey),
-        }
-    }
-
-    fn show_help_information(&mut self) {
-        writeln!(self.writer, "=====================================").unwrap();
-        writeln!(self.writer, "       Available commands:").unwrap();
-        writeln!(self.writer, "       help, quit, draw, accept").unwrap();
-        writeln!(self.writer, "       move <from> <to>").unwrap();
-        writeln!(self.writer, "       show <from>").unwrap();
-        writeln!(self.writer, "=====================================").unwrap();
-    }
-}
\ No newline at end of file
+            Some((_, Color::White)) => glyph.with(colors.white_piece),
+            Some((_, Color::Black)) => glyph.with(colors.black_piece),
+            None => glyph.with(colors.empty),
+        }
+    }
+
+    fn show_help_information(&mut self) -> io::Result<()> {
+        writeln!(self.writer, "=====================================")?;
+        writeln!(self.writer, "       Available commands:")?;
+        writeln!(self.writer, "       help, quit, draw, accept, decline, resign, eval, probe, undo (prev), redo (next), history, pack, setup, ponder, stop, evalbar")?;
+        writeln!(self.writer, "       flip, flip auto, blind <on|own|off>")?;
+        writeln!(self.writer, "       move <from> <to>")?;
+        writeln!(self.writer, "       <move> in algebraic notation, e.g. Nf3, exd5, O-O, e8=N")?;
+        writeln!(self.writer, "       show <from>")?;
+        writeln!(self.writer, "       read [square]")?;
+        writeln!(self.writer, "       goto <half-moves>")?;
+        writeln!(self.writer, "       comment <text>, annotate <half-move> <!|?|!!|??|!?|?!>")?;
+        writeln!(self.writer, "       save <file>, load <file>, export <file.pgn>, export <png|svg> <file>, replay <event log>, theme <unicode|ascii|file>")?;
+        writeln!(self.writer, "       perft <depth> [--by-piece]")?;
+        writeln!(self.writer, "       preview [depth]")?;
+        writeln!(self.writer, "       level <1-10|off>, mate <1-4>")?;
+        writeln!(self.writer, "       set [hash <kilobytes> | skill <1-10|off> | contempt <centipawns>]")?;
+        writeln!(self.writer, "       hashstats [depth], searchstats [depth]")?;
+        if !self.aliases.is_empty() {
+            let aliases: Vec<String> = self.aliases.iter().map(|(alias, command)| format!("{} = {}", alias, command)).collect();
+            writeln!(self.writer, "       aliases: {}", aliases.join(", "))?;
+        }
+        writeln!(self.writer, "=====================================")
+    }
+}
+
+/// Returns the ranks from top to bottom and the files from left to right, as indices, of the
+/// board drawn with `bottom` at the bottom.
+fn orientation(bottom: Color) -> ([usize; 8], [usize; 8]) {
+    let ascending = [0, 1, 2, 3, 4, 5, 6, 7];
+    let descending = [7, 6, 5, 4, 3, 2, 1, 0];
+    match bottom {
+        Color::White => (descending, ascending),
+        Color::Black => (ascending, descending),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flipped_board_starts_at_h1() {
+        let (ranks, files) = orientation(Color::Black);
+        assert_eq!(Square::try_from(ranks[0] * 8 + files[0]).unwrap().to_string(), "h1");
+        let (ranks, files) = orientation(Color::White);
+        assert_eq!(Square::try_from(ranks[0] * 8 + files[0]).unwrap().to_string(), "a8");
+        let mut gui = CommandPromptGUI::new();
+        gui.flip(Color::White);
+        assert_eq!(gui.bottom_side(Color::White), Color::Black);
+        gui.set_auto_flip(true);
+        assert_eq!(gui.bottom_side(Color::White), Color::White);
+        gui.flip(Color::White);
+        assert_eq!((gui.bottom_side(Color::White), gui.is_auto_flip()), (Color::Black, false));
+    }
+}
@@ -0,0 +1,196 @@
+use std::io;
+use std::time::Instant;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::terminal;
+use crate::gui::highlight::Highlight;
+use crate::square::{File, Rank, Square};
+
+/// A key pressed while picking a move with the cursor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum CursorKey {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Select the piece under the cursor, or move the selected piece there.
+    Enter,
+    /// Drop the selected piece.
+    Cancel,
+    /// Leave the cursor to type a line command.
+    Command,
+    Quit,
+}
+
+impl CursorKey {
+    /// Returns the arrow pointing the other way, for a board drawn from Black's side; other
+    /// keys are unchanged.
+    pub(crate) fn mirrored(self) -> Self {
+        match self {
+            CursorKey::Up => CursorKey::Down,
+            CursorKey::Down => CursorKey::Up,
+            CursorKey::Left => CursorKey::Right,
+            CursorKey::Right => CursorKey::Left,
+            key => key,
+        }
+    }
+}
+
+/// The legal targets of every piece the side to move can move, keyed by the piece's square.
+pub(crate) type MoveMap = [(Square, Vec<(Square, Highlight)>)];
+
+/// Position of the cursor on the board and the piece picked up with it, if any.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Cursor {
+    pub(crate) square: Square,
+    pub(crate) selected: Option<Square>,
+}
+
+impl Cursor {
+    /// Creates a cursor on e2, with no piece selected.
+    pub(crate) fn new() -> Self {
+        Self { square: Square::new(File::E, Rank::Two), selected: None }
+    }
+
+    /// Moves the cursor one square in the direction of an arrow key, stopping at the edges of
+    /// the board. Other keys are ignored.
+    pub(crate) fn step(&mut self, key: CursorKey) {
+        let file = usize::from(self.square.file());
+        let rank = usize::from(self.square.rank());
+        let (file, rank) = match key {
+            CursorKey::Up => (file, (rank + 1).min(7)),
+            CursorKey::Down => (file, rank.saturating_sub(1)),
+            CursorKey::Left => (file.saturating_sub(1), rank),
+            CursorKey::Right => ((file + 1).min(7), rank),
+            _ => return,
+        };
+        if let (Ok(file), Ok(rank)) = (File::try_from(file), Rank::try_from(rank)) {
+            self.square = Square::new(file, rank);
+        }
+    }
+
+    /// Handles Enter: moves the selected piece if the cursor is on one of its targets,
+    /// otherwise selects the piece under the cursor, or drops the selection if it cannot move.
+    ///
+    /// # Arguments
+    /// - `moves`: The legal moves of the side to move.
+    ///
+    /// # Returns
+    /// - `Some((from, to))`: The move the player picked; the selection is dropped.
+    /// - `None`: If no move was picked yet.
+    pub(crate) fn press_enter(&mut self, moves: &MoveMap) -> Option<(Square, Square)> {
+        if let Some(from) = self.selected {
+            if Self::targets(moves, from).iter().any(|(to, _)| *to == self.square) {
+                self.selected = None;
+                return Some((from, self.square));
+            }
+        }
+        let can_move = moves.iter().any(|(from, targets)| *from == self.square && !targets.is_empty());
+        self.selected = can_move.then_some(self.square);
+        None
+    }
+
+    /// Returns the squares to highlight: the cursor first, then the selected piece and its
+    /// targets.
+    pub(crate) fn highlights(&self, moves: &MoveMap) -> Vec<(Square, Highlight)> {
+        let mut highlights = vec![(self.square, Highlight::Cursor)];
+        if let Some(from) = self.selected {
+            highlights.push((from, Highlight::Selected));
+            highlights.extend(Self::targets(moves, from));
+        }
+        highlights
+    }
+
+    fn targets(moves: &MoveMap, from: Square) -> Vec<(Square, Highlight)> {
+        moves.iter()
+            .find(|(square, _)| *square == from)
+            .map(|(_, targets)| targets.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Waits for the next key press that means something to the cursor, in raw mode so it does
+/// not wait for Enter.
+///
+/// # Returns
+/// - `Ok(Some(CursorKey))`: The key pressed.
+/// - `Ok(None)`: If `deadline` passed first.
+pub(crate) fn read_key(deadline: Option<Instant>) -> io::Result<Option<CursorKey>> {
+    loop {
+        let Some(code) = read_key_code(deadline)? else {
+            return Ok(None);
+        };
+        let key = match code {
+            KeyCode::Up | KeyCode::Char('k') => CursorKey::Up,
+            KeyCode::Down | KeyCode::Char('j') => CursorKey::Down,
+            KeyCode::Left | KeyCode::Char('h') => CursorKey::Left,
+            KeyCode::Right | KeyCode::Char('l') => CursorKey::Right,
+            KeyCode::Enter | KeyCode::Char(' ') => CursorKey::Enter,
+            KeyCode::Esc | KeyCode::Backspace => CursorKey::Cancel,
+            KeyCode::Char(':') => CursorKey::Command,
+            KeyCode::Char('q') => CursorKey::Quit,
+            _ => continue,
+        };
+        return Ok(Some(key));
+    }
+}
+
+/// Waits in raw mode for the next key press, until `deadline` if there is one.
+///
+/// # Returns
+/// - `Ok(Some(KeyCode))`: The key pressed.
+/// - `Ok(None)`: If the deadline passed first.
+pub(crate) fn read_key_code(deadline: Option<Instant>) -> io::Result<Option<KeyCode>> {
+    terminal::enable_raw_mode()?;
+    let key = loop {
+        if let Some(deadline) = deadline {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() || !event::poll(left)? {
+                break None;
+            }
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                break Some(key.code);
+            }
+        }
+    };
+    terminal::disable_raw_mode()?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_cursor_stops_at_the_edges() {
+        let mut cursor = Cursor { square: square("a1"), selected: None };
+        cursor.step(CursorKey::Left);
+        cursor.step(CursorKey::Down);
+        assert_eq!(cursor.square, square("a1"));
+        cursor.step(CursorKey::Up);
+        cursor.step(CursorKey::Right);
+        assert_eq!(cursor.square, square("b2"));
+    }
+
+    #[test]
+    fn test_enter_selects_then_moves() {
+        let moves = vec![
+            (square("e2"), vec![(square("e3"), Highlight::Move), (square("e4"), Highlight::Move)]),
+            (square("e1"), vec![]),
+        ];
+        let mut cursor = Cursor::new();
+        assert_eq!(cursor.press_enter(&moves), None);
+        assert_eq!(cursor.selected, Some(square("e2")));
+        assert_eq!(cursor.highlights(&moves).len(), 4);
+        cursor.step(CursorKey::Up);
+        cursor.step(CursorKey::Up);
+        assert_eq!(cursor.press_enter(&moves), Some((square("e2"), square("e4"))));
+        assert_eq!(cursor.selected, None);
+        // a piece without moves cannot be picked up
+        cursor.square = square("e1");
+        assert_eq!(cursor.press_enter(&moves), None);
+        assert_eq!(cursor.selected, None);
+    }
+}
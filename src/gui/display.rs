@@ -0,0 +1,56 @@
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+
+/// Which pieces the board shows, for blindfold training.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PieceVisibility {
+    #[default]
+    All,
+    /// Only the pieces of the player looking at the board.
+    Own,
+    /// No piece at all: the player follows the game from the move list.
+    Hidden,
+}
+
+/// How the board is drawn for the player looking at it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct DisplayOptions {
+    pub(crate) pieces: PieceVisibility,
+    /// The player looking at the board, whose pieces [`PieceVisibility::Own`] keeps.
+    pub(crate) viewer: Color,
+}
+
+/// Every piece shown, e.g. for the board editor or an engine match.
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self { pieces: PieceVisibility::All, viewer: Color::White }
+    }
+}
+
+impl DisplayOptions {
+    /// Returns the content of a square as the viewer may see it: the piece, or nothing if it
+    /// is hidden.
+    pub(crate) fn visible(&self, content: Option<(Piece, Color)>) -> Option<(Piece, Color)> {
+        match self.pieces {
+            PieceVisibility::All => content,
+            PieceVisibility::Own => content.filter(|(_, side)| *side == self.viewer),
+            PieceVisibility::Hidden => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_hides_pieces_by_owner() {
+        let white_king = Some((Piece::King, Color::White));
+        let black_king = Some((Piece::King, Color::Black));
+        let own = DisplayOptions { pieces: PieceVisibility::Own, viewer: Color::White };
+        assert_eq!((own.visible(white_king), own.visible(black_king)), (white_king, None));
+        let blind = DisplayOptions { pieces: PieceVisibility::Hidden, viewer: Color::White };
+        assert_eq!(blind.visible(white_king), None);
+        assert_eq!(DisplayOptions::default().visible(black_king), black_king);
+    }
+}
@@ -0,0 +1,37 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Why the command prompt could not read a command or draw the game.
+#[derive(Debug)]
+pub enum GuiError {
+    /// Standard input was closed, e.g. at the end of piped commands, and no command sent from
+    /// another thread is waiting.
+    EndOfInput,
+    /// Writing to the terminal failed, e.g. because the output pipe was closed.
+    Io(io::Error),
+}
+
+impl fmt::Display for GuiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuiError::EndOfInput => write!(f, "The input was closed."),
+            GuiError::Io(err) => write!(f, "Cannot write to the terminal: {}", err),
+        }
+    }
+}
+
+impl Error for GuiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GuiError::EndOfInput => None,
+            GuiError::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for GuiError {
+    fn from(err: io::Error) -> Self {
+        GuiError::Io(err)
+    }
+}
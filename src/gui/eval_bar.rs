@@ -0,0 +1,92 @@
+use crate::engine::search::Score;
+use crate::pieces::common::Color;
+
+/// Depth of the quick search the evaluation bar shows the score of.
+pub(crate) const EVAL_BAR_DEPTH: u8 = 3;
+
+/// Rows of the bar, one beside each rank.
+const ROWS: usize = 8;
+/// The blocks filling a row from the bottom, in eighths.
+const BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// The evaluation bar drawn beside the board: a column filled from the bottom with the share
+/// of the side drawn there, in eighths of a row, as the evaluation favours it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct EvalBar {
+    /// The score from the side to move's point of view.
+    score: Score,
+    turn: Color,
+}
+
+impl EvalBar {
+    /// Creates the bar of a searched position.
+    ///
+    /// # Arguments
+    /// - `score`: The score of the search, from the side to move's point of view.
+    /// - `turn`: The side to move.
+    pub(crate) fn new(score: Score, turn: Color) -> Self {
+        Self { score, turn }
+    }
+
+    /// Returns White's share of the bar, in eighths of a row: half of it for an even
+    /// position, all or nothing only for a forced mate.
+    fn white_share(&self) -> usize {
+        let total = (ROWS * 8) as f64;
+        let mover_share = match self.score {
+            // the usual conversion of centipawns to an expected score
+            Score::Centipawns(centipawns) => {
+                let expected = 1.0 / (1.0 + 10f64.powf(-centipawns as f64 / 400.0));
+                ((expected * total).round() as usize).clamp(1, ROWS * 8 - 1)
+            }
+            Score::Mate(moves) if moves > 0 => ROWS * 8,
+            Score::Mate(_) => 0,
+        };
+        match self.turn {
+            Color::White => mover_share,
+            Color::Black => ROWS * 8 - mover_share,
+        }
+    }
+
+    /// Returns the block drawn beside a rank.
+    ///
+    /// # Arguments
+    /// - `row`: The row of the bar, from `0` at the bottom of the board.
+    /// - `bottom`: The side drawn at the bottom, whose share fills the bar from there.
+    pub(crate) fn cell(&self, row: usize, bottom: Color) -> char {
+        let share = match bottom {
+            Color::White => self.white_share(),
+            Color::Black => ROWS * 8 - self.white_share(),
+        };
+        BLOCKS[share.saturating_sub(8 * row).min(8)]
+    }
+
+    /// Returns the score from White's point of view, e.g. `+0.35` or `#-2`, shown under the bar.
+    pub(crate) fn label(&self) -> String {
+        let score = match (self.turn, self.score) {
+            (Color::White, score) => score,
+            (Color::Black, Score::Centipawns(centipawns)) => Score::Centipawns(-centipawns),
+            (Color::Black, Score::Mate(moves)) => Score::Mate(-moves),
+        };
+        score.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_fills_from_the_side_drawn_at_the_bottom() {
+        let even = EvalBar::new(Score::Centipawns(0), Color::White);
+        let cells = (0..ROWS).map(|row| even.cell(row, Color::White)).collect::<String>();
+        assert_eq!(cells, "████    ");
+        // Black to move and a pawn up is a pawn down for White
+        let black_better = EvalBar::new(Score::Centipawns(100), Color::Black);
+        assert_eq!(black_better.label(), "-1.00");
+        assert!(black_better.cell(3, Color::White) < '█' && black_better.cell(3, Color::Black) == '█');
+        let mated = EvalBar::new(Score::Mate(2), Color::Black);
+        assert_eq!((mated.cell(7, Color::White), mated.cell(0, Color::White), mated.label().as_str()), (' ', ' ', "#-2"));
+        // a large advantage never empties the bar before the mate
+        assert_eq!(EvalBar::new(Score::Centipawns(-5000), Color::White).cell(0, Color::White), '▁');
+    }
+}
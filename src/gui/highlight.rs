@@ -0,0 +1,19 @@
+/// How a highlighted square is marked on the board, e.g. by `show <square>`. The background
+/// color of each kind comes from the theme.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Highlight {
+    /// A quiet move, or any square marked without a move kind.
+    Move,
+    /// A move capturing a piece, en passant included.
+    Capture,
+    /// The king's move when castling.
+    Castle,
+    /// A pawn move reaching the last rank.
+    Promotion,
+    /// The square under the cursor.
+    Cursor,
+    /// The piece picked up with the cursor.
+    Selected,
+    /// The origin and destination of the last move played.
+    LastMove,
+}
@@ -0,0 +1,254 @@
+use std::fmt::Write;
+use crate::engine::board::Board;
+use crate::square::Square;
+
+/// The formats the board can be exported to with `export png` and `export svg`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ImageFormat {
+    Png,
+    Svg,
+}
+
+/// The side of a square, in pixels or SVG units.
+const SQUARE_SIZE: usize = 64;
+/// How many pixels one cell of a piece sprite covers in a PNG.
+const SPRITE_SCALE: usize = 5;
+const LIGHT_SQUARE: [u8; 3] = [0xf0, 0xd9, 0xb5];
+const DARK_SQUARE: [u8; 3] = [0xb5, 0x88, 0x63];
+/// Tint of the origin and destination of the last move.
+const LAST_MOVE_LIGHT: [u8; 3] = [0xf6, 0xf6, 0x69];
+const LAST_MOVE_DARK: [u8; 3] = [0xba, 0xca, 0x2b];
+const PIECE_FILL: [[u8; 3]; 2] = [[0xfa, 0xfa, 0xfa], [0x22, 0x22, 0x22]];
+const PIECE_OUTLINE: [u8; 3] = [0x00, 0x00, 0x00];
+
+/// The 12×12 sprites the PNG draws the pieces with, in the order of [`crate::pieces::Piece`]:
+/// `#` is the body of the piece, and the cells around it are drawn as its outline.
+const SPRITES: [[&str; 12]; 6] = [
+    [
+        "............",
+        "............",
+        ".....##.....",
+        "....####....",
+        "....####....",
+        ".....##.....",
+        "....####....",
+        ".....##.....",
+        "....####....",
+        "...######...",
+        "..########..",
+        "............",
+    ],
+    [
+        "............",
+        ".....#.#....",
+        "....#####...",
+        "...######...",
+        "..###.####..",
+        "..##..####..",
+        ".....#####..",
+        "....#####...",
+        "...######...",
+        "...######...",
+        "..########..",
+        "............",
+    ],
+    [
+        "............",
+        "..##.##.##..",
+        "..########..",
+        "...######...",
+        "....####....",
+        "....####....",
+        "....####....",
+        "....####....",
+        "...######...",
+        "..########..",
+        "..########..",
+        "............",
+    ],
+    [
+        "............",
+        ".....##.....",
+        "....#.##....",
+        "....##.#....",
+        "....####....",
+        ".....##.....",
+        "....####....",
+        ".....##.....",
+        "....####....",
+        "...######...",
+        "..########..",
+        "............",
+    ],
+    [
+        "............",
+        "..#..##..#..",
+        "..#..##..#..",
+        "..##.##.##..",
+        "..########..",
+        "...######...",
+        "....####....",
+        "....####....",
+        "...######...",
+        "..########..",
+        "..########..",
+        "............",
+    ],
+    [
+        ".....##.....",
+        "....####....",
+        ".....##.....",
+        "...######...",
+        "..##.##.##..",
+        "..##.##.##..",
+        "...######...",
+        "....####....",
+        "...######...",
+        "..########..",
+        "..########..",
+        "............",
+    ],
+];
+
+/// Draws the board from White's side, with the last move tinted.
+///
+/// # Arguments
+/// - `board`: The position to draw.
+/// - `last_move`: The origin and destination of the last move, if any.
+/// - `format`: The image format.
+///
+/// # Returns
+/// - `Ok(Vec<u8>)`: The content of the image file.
+/// - `Err(String)`: If the PNG cannot be encoded.
+pub(crate) fn render(board: &Board, last_move: Option<(Square, Square)>, format: ImageFormat) -> Result<Vec<u8>, String> {
+    match format {
+        ImageFormat::Png => render_png(board, last_move),
+        ImageFormat::Svg => Ok(render_svg(board, last_move).into_bytes()),
+    }
+}
+
+/// Returns the color of a square, tinted if the last move left or reached it.
+fn square_color(square: usize, last_move: Option<(Square, Square)>) -> [u8; 3] {
+    let is_light = (square / 8 + square % 8) % 2 == 1;
+    let is_last_move = last_move.is_some_and(|(from, to)| usize::from(from) == square || usize::from(to) == square);
+    match (is_light, is_last_move) {
+        (true, false) => LIGHT_SQUARE,
+        (false, false) => DARK_SQUARE,
+        (true, true) => LAST_MOVE_LIGHT,
+        (false, true) => LAST_MOVE_DARK,
+    }
+}
+
+/// Returns the index of the square drawn at a row and column, the 8th rank at the top.
+fn square_at(row: usize, column: usize) -> usize {
+    (7 - row) * 8 + column
+}
+
+/// Draws the pieces with the built-in sprites, so that no font is needed.
+fn render_png(board: &Board, last_move: Option<(Square, Square)>) -> Result<Vec<u8>, String> {
+    let side = 8 * SQUARE_SIZE;
+    let mut pixels = vec![0u8; side * side * 3];
+    let margin = (SQUARE_SIZE - 12 * SPRITE_SCALE) / 2;
+    for y in 0..side {
+        for x in 0..side {
+            let square = square_at(y / SQUARE_SIZE, x / SQUARE_SIZE);
+            let mut color = square_color(square, last_move);
+            let (sprite_x, sprite_y) = ((x % SQUARE_SIZE).wrapping_sub(margin) / SPRITE_SCALE, (y % SQUARE_SIZE).wrapping_sub(margin) / SPRITE_SCALE);
+            if let Some((piece, owner)) = board[square] {
+                let sprite = &SPRITES[usize::from(piece)];
+                if is_body(sprite, sprite_x, sprite_y) {
+                    color = PIECE_FILL[usize::from(owner)];
+                } else if is_outline(sprite, sprite_x, sprite_y) {
+                    color = PIECE_OUTLINE;
+                }
+            }
+            let pixel = (y * side + x) * 3;
+            pixels[pixel..pixel + 3].copy_from_slice(&color);
+        }
+    }
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, side as u32, side as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(|err| format!("Cannot encode the PNG: {}", err))?;
+    Ok(bytes)
+}
+
+/// Whether the cell of a sprite belongs to the piece; cells outside the sprite do not.
+fn is_body(sprite: &[&str; 12], x: usize, y: usize) -> bool {
+    sprite.get(y).and_then(|row| row.as_bytes().get(x)) == Some(&b'#')
+}
+
+/// Whether the cell of a sprite borders the body of the piece without being part of it.
+fn is_outline(sprite: &[&str; 12], x: usize, y: usize) -> bool {
+    x < 12 && y < 12 && (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+        .any(|(dx, dy)| is_body(sprite, x.wrapping_add_signed(dx), y.wrapping_add_signed(dy)))
+}
+
+/// Draws the pieces as text glyphs over the squares, with the files and ranks named along
+/// the edges.
+fn render_svg(board: &Board, last_move: Option<(Square, Square)>) -> String {
+    let side = 8 * SQUARE_SIZE;
+    let hex = |[red, green, blue]: [u8; 3]| format!("#{:02x}{:02x}{:02x}", red, green, blue);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{side}\" height=\"{side}\" viewBox=\"0 0 {side} {side}\">\n"
+    );
+    for row in 0..8 {
+        for column in 0..8 {
+            let square = square_at(row, column);
+            let (x, y) = (column * SQUARE_SIZE, row * SQUARE_SIZE);
+            writeln!(svg, "<rect x=\"{}\" y=\"{}\" width=\"{size}\" height=\"{size}\" fill=\"{}\"/>", x, y, hex(square_color(square, last_move)), size = SQUARE_SIZE).unwrap();
+            if let Some((piece, owner)) = board[square] {
+                // the filled glyphs drawn in both colors, so that White's pieces are not hollow
+                let glyph = ["♟", "♞", "♜", "♝", "♛", "♚"][usize::from(piece)];
+                writeln!(
+                    svg,
+                    "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{}\" stroke=\"{}\">{}</text>",
+                    x + SQUARE_SIZE / 2, y + SQUARE_SIZE / 2, SQUARE_SIZE * 4 / 5, hex(PIECE_FILL[usize::from(owner)]), hex(PIECE_OUTLINE), glyph
+                ).unwrap();
+            }
+        }
+    }
+    for idx in 0..8 {
+        let (file, rank) = ((b'a' + idx as u8) as char, 8 - idx);
+        let (x, y) = (idx * SQUARE_SIZE, idx * SQUARE_SIZE);
+        writeln!(svg, "<text x=\"{}\" y=\"{}\" font-size=\"11\" fill=\"#555555\">{}</text>", x + SQUARE_SIZE - 9, side - 3, file).unwrap();
+        writeln!(svg, "<text x=\"2\" y=\"{}\" font-size=\"11\" fill=\"#555555\">{}</text>", y + 12, rank).unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::game::Game;
+
+    #[test]
+    fn test_svg_draws_every_square_and_piece() {
+        let board = Game::new().get_all_position();
+        let svg = render_svg(&board, None);
+        assert_eq!(svg.matches("<rect").count(), 64);
+        assert_eq!(svg.matches("♟").count(), 16);
+        assert_eq!(svg.matches("♚").count(), 2);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"512\""));
+    }
+
+    #[test]
+    fn test_png_is_a_valid_image_of_the_board() {
+        let board = Game::new().get_all_position();
+        let bytes = render(&board, None, ImageFormat::Png).unwrap();
+        let decoder = png::Decoder::new(bytes.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (512, 512));
+        // the corner of a8 is a light square without a piece drawn over it
+        assert_eq!(pixels[..3], LIGHT_SQUARE);
+        // the middle of the a1 rook is its body, in White's color
+        let middle = ((7 * SQUARE_SIZE + SQUARE_SIZE / 2) * 512 + SQUARE_SIZE / 2) * 3;
+        assert_eq!(pixels[middle..middle + 3], PIECE_FILL[0]);
+    }
+}
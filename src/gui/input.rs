@@ -0,0 +1,184 @@
+use std::io::{self, BufRead};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Instant;
+use crate::gui::error::GuiError;
+
+/// A line of input and where it came from.
+#[derive(Clone, Debug, PartialEq)]
+enum Input {
+    /// A line typed at the terminal.
+    Terminal(String),
+    /// Standard input was closed; the terminal thread has stopped.
+    Closed,
+    /// A command sent through a [`CommandSender`].
+    Remote(String),
+}
+
+/// Sends commands to a running game from another thread, e.g. a network listener, as if they
+/// had been typed at the prompt.
+#[derive(Clone, Debug)]
+pub struct CommandSender {
+    sender: Sender<Input>,
+}
+
+impl CommandSender {
+    /// Queues a command, such as `e4`, `resign` or `move e2 e4`, for the game to read the next
+    /// time it waits for input.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the command was queued.
+    /// - `Err(String)`: If the game has ended and reads no more input.
+    pub fn send(&self, command: &str) -> Result<(), String> {
+        self.sender
+            .send(Input::Remote(command.to_string()))
+            .map_err(|_| "The game no longer reads commands.".to_string())
+    }
+}
+
+/// Every source of commands merged into one queue: the terminal, read one line at a time by
+/// a thread of its own so that waiting for it can end at a deadline, and any number of
+/// [`CommandSender`]s.
+///
+/// The terminal thread only reads when a line is asked for, so that it does not take keys
+/// meant for the cursor in raw mode. A line asked for but not received before the deadline
+/// is kept for the next read.
+pub(crate) struct InputQueue {
+    sender: Sender<Input>,
+    receiver: Receiver<Input>,
+    /// Asks the terminal thread to read a line; `None` until the first line is asked for.
+    terminal: Option<Sender<()>>,
+    /// Whether the terminal thread was asked for a line that has not been received yet.
+    reading: bool,
+    /// Whether standard input was closed.
+    closed: bool,
+}
+
+impl InputQueue {
+    pub(crate) fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self { sender, receiver, terminal: None, reading: false, closed: false }
+    }
+
+    /// Returns a handle other threads can queue commands with.
+    pub(crate) fn sender(&self) -> CommandSender {
+        CommandSender { sender: self.sender.clone() }
+    }
+
+    /// Waits for the next line from any source.
+    ///
+    /// # Arguments
+    /// - `deadline`: When to stop waiting, e.g. when the flag of the player falls; `None` to
+    ///   wait as long as it takes.
+    ///
+    /// # Returns
+    /// - `Ok(Some(String))`: The line, trimmed.
+    /// - `Ok(None)`: If the deadline passed first.
+    /// - `Err(GuiError::EndOfInput)`: If standard input is closed and no sent command is waiting.
+    pub(crate) fn next(&mut self, deadline: Option<Instant>) -> Result<Option<String>, GuiError> {
+        if self.closed {
+            // commands sent before the end of the input are still read
+            return match self.receiver.try_recv() {
+                Ok(Input::Remote(line)) => Ok(Some(line.trim().to_string())),
+                _ => Err(GuiError::EndOfInput),
+            };
+        }
+        if !self.reading {
+            self.request_terminal_line();
+            self.reading = true;
+        }
+        let input = match deadline {
+            Some(deadline) => match self.receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(input) => input,
+                Err(RecvTimeoutError::Timeout) => return Ok(None),
+                Err(RecvTimeoutError::Disconnected) => unreachable!("the queue holds a sender"),
+            },
+            None => self.receiver.recv().expect("the queue holds a sender"),
+        };
+        match input {
+            Input::Terminal(line) => {
+                self.reading = false;
+                Ok(Some(line.trim().to_string()))
+            }
+            Input::Remote(line) => Ok(Some(line.trim().to_string())),
+            Input::Closed => {
+                self.closed = true;
+                Err(GuiError::EndOfInput)
+            }
+        }
+    }
+
+    /// Returns whether the terminal can be read directly, key by key: standard input is open
+    /// and the terminal thread is not waiting for a line, which would take the keys.
+    pub(crate) fn terminal_is_free(&self) -> bool {
+        !self.reading && !self.closed
+    }
+
+    /// Returns the next command sent through a [`CommandSender`], without waiting.
+    pub(crate) fn try_remote(&mut self) -> Option<String> {
+        match self.receiver.try_recv() {
+            Ok(Input::Remote(line)) => Some(line.trim().to_string()),
+            // only a terminal line that was asked for is sent besides commands
+            Ok(input) => unreachable!("{:?} while the terminal thread is idle", input),
+            Err(_) => None,
+        }
+    }
+
+    /// Asks the terminal thread for a line, starting the thread the first time. The thread
+    /// answers with the line, or with [`Input::Closed`] once standard input is closed.
+    fn request_terminal_line(&mut self) {
+        let terminal = self.terminal.get_or_insert_with(|| {
+            let (request, requests) = mpsc::channel::<()>();
+            let lines = self.sender.clone();
+            thread::spawn(move || {
+                let stdin = io::stdin();
+                for () in requests {
+                    let mut line = String::new();
+                    let input = match stdin.lock().read_line(&mut line) {
+                        Ok(0) | Err(_) => Input::Closed,
+                        Ok(_) => Input::Terminal(line),
+                    };
+                    let closed = input == Input::Closed;
+                    if lines.send(input).is_err() || closed {
+                        break;
+                    }
+                }
+            });
+            request
+        });
+        // the thread only stops once it has sent `Closed`, which ends the requests
+        let _ = terminal.send(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_commands_from_other_threads_and_deadlines() {
+        let mut queue = InputQueue::new();
+        // no terminal line is asked for while one is pending, so the test never blocks on it
+        queue.reading = true;
+        let sender = queue.sender();
+        thread::spawn(move || sender.send("  resign ").unwrap());
+        assert_eq!(queue.next(None).unwrap().as_deref(), Some("resign"));
+        assert_eq!(queue.next(Some(Instant::now() + Duration::from_millis(20))).unwrap(), None);
+        queue.sender().send("e4").unwrap();
+        assert_eq!(queue.next(Some(Instant::now())).unwrap().as_deref(), Some("e4"));
+    }
+
+    #[test]
+    fn test_closed_input_ends_after_sent_commands() {
+        let mut queue = InputQueue::new();
+        queue.reading = true;
+        queue.sender().send("resign").unwrap();
+        queue.sender.send(Input::Closed).unwrap();
+        assert_eq!(queue.next(None).unwrap().as_deref(), Some("resign"));
+        assert!(matches!(queue.next(None), Err(GuiError::EndOfInput)));
+        queue.sender().send("e4").unwrap();
+        assert_eq!(queue.next(None).unwrap().as_deref(), Some("e4"));
+        assert!(matches!(queue.next(Some(Instant::now())), Err(GuiError::EndOfInput)));
+    }
+}
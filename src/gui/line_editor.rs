@@ -0,0 +1,87 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// What a key did to the line being typed.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum LineEdit {
+    /// The text changed and the prompt is redrawn.
+    Changed,
+    /// The key has no effect, e.g. an arrow.
+    Ignored,
+    /// Enter was pressed: the line, trimmed, is read as a command.
+    Submitted(String),
+    /// Ctrl+C was pressed, which quits as it does outside raw mode.
+    Interrupted,
+    /// Ctrl+D was pressed on an empty line, which closes the input.
+    Closed,
+}
+
+/// The line typed at the prompt while the terminal is in raw mode, so that the prompt can be
+/// redrawn with the clocks running while the player types. Only the end of the line is
+/// edited: characters are appended and Backspace removes the last one.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LineEditor {
+    text: String,
+}
+
+impl LineEditor {
+    /// Returns the text typed so far.
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Applies a key pressed at the prompt.
+    ///
+    /// # Arguments
+    /// - `key`: The key, with its modifiers.
+    ///
+    /// # Returns
+    /// - `LineEdit`: What the key did; the text is emptied once submitted.
+    pub(crate) fn press(&mut self, key: KeyEvent) -> LineEdit {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if control => LineEdit::Interrupted,
+            KeyCode::Char('d') if control && self.text.is_empty() => LineEdit::Closed,
+            KeyCode::Char('u') if control => {
+                self.text.clear();
+                LineEdit::Changed
+            }
+            KeyCode::Char(_) if control => LineEdit::Ignored,
+            KeyCode::Char(symbol) => {
+                self.text.push(symbol);
+                LineEdit::Changed
+            }
+            KeyCode::Backspace => match self.text.pop() {
+                Some(_) => LineEdit::Changed,
+                None => LineEdit::Ignored,
+            },
+            KeyCode::Enter => LineEdit::Submitted(std::mem::take(&mut self.text).trim().to_string()),
+            _ => LineEdit::Ignored,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(editor: &mut LineEditor, code: KeyCode) -> LineEdit {
+        editor.press(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_typing_erasing_and_submitting() {
+        let mut editor = LineEditor::default();
+        for symbol in "Nf3x".chars() {
+            assert_eq!(press(&mut editor, KeyCode::Char(symbol)), LineEdit::Changed);
+        }
+        assert_eq!(press(&mut editor, KeyCode::Backspace), LineEdit::Changed);
+        assert_eq!(press(&mut editor, KeyCode::Left), LineEdit::Ignored);
+        assert_eq!(editor.text(), "Nf3");
+        assert_eq!(press(&mut editor, KeyCode::Enter), LineEdit::Submitted("Nf3".to_string()));
+        assert_eq!(press(&mut editor, KeyCode::Backspace), LineEdit::Ignored);
+        assert_eq!(editor.press(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL)), LineEdit::Closed);
+        press(&mut editor, KeyCode::Char('e'));
+        assert_eq!(editor.press(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL)), LineEdit::Ignored);
+        assert_eq!(editor.press(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)), LineEdit::Interrupted);
+    }
+}
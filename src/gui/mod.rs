@@ -1 +1,19 @@
-pub(crate) mod cmd;
\ No newline at end of file
+pub(crate) mod action;
+pub(crate) mod cmd;
+pub(crate) mod cursor;
+pub(crate) mod display;
+pub(crate) mod error;
+pub(crate) mod eval_bar;
+pub(crate) mod highlight;
+pub(crate) mod image;
+pub(crate) mod input;
+pub(crate) mod line_editor;
+pub(crate) mod preferences;
+pub(crate) mod promotion;
+pub(crate) mod san;
+pub(crate) mod search_info;
+pub(crate) mod setup;
+pub(crate) mod sparkline;
+pub(crate) mod speech;
+pub(crate) mod status;
+pub(crate) mod theme;
\ No newline at end of file
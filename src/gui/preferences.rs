@@ -0,0 +1,135 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use serde::Deserialize;
+use crate::engine::clock::TimeControl;
+use crate::gui::theme::Theme;
+
+/// Name of the preferences file, looked up in the home directory.
+pub const PREFERENCES_FILE: &str = ".chessgame.toml";
+
+/// How moves are written in the last move line and the move history.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Notation {
+    /// Standard algebraic notation, e.g. `Nf3` or `exd5`.
+    #[default]
+    San,
+    /// The squares the piece leaves and reaches, e.g. `g1f3` or `a7a8q`.
+    Coordinate,
+}
+
+/// The player's settings, read at startup from `~/.chessgame.toml`: command aliases, the
+/// notation moves are shown in, a color theme and a default time control.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Preferences {
+    aliases: BTreeMap<String, String>,
+    notation: Notation,
+    theme: Option<Theme>,
+    clock: Option<TimeControl>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct PreferencesFile {
+    #[serde(default)]
+    aliases: BTreeMap<String, String>,
+    notation: Option<String>,
+    theme: Option<String>,
+    clock: Option<String>,
+}
+
+impl Preferences {
+    /// Returns the path of the preferences file in the home directory, or `None` if the home
+    /// directory is unknown.
+    pub fn default_path() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join(PREFERENCES_FILE))
+    }
+
+    /// Loads the preferences from a TOML file.
+    ///
+    /// # Returns
+    /// - `Ok(Preferences)`: The preferences; defaults for the entries the file leaves out.
+    /// - `Err(String)`: If the file cannot be read or an entry is invalid.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|err| format!("Cannot read preferences {}: {}", path, err))?;
+        Self::parse(&content).map_err(|err| format!("Invalid preferences {}: {}", path, err))
+    }
+
+    /// Parses the preferences from the content of a TOML file: `notation` (`san` or
+    /// `coordinate`), `theme` (a built-in theme or a theme file), `clock` (a time control such
+    /// as `5+3`) and an `[aliases]` table of one-word commands, e.g. `m = "move"`.
+    ///
+    /// # Returns
+    /// - `Ok(Preferences)`: The preferences.
+    /// - `Err(String)`: If the content is not valid or an entry is unknown.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let file: PreferencesFile = toml::from_str(content).map_err(|err| err.message().to_string())?;
+        for (alias, command) in &file.aliases {
+            if alias.is_empty() || alias.contains(char::is_whitespace) || command.trim().is_empty() {
+                return Err(format!("Invalid alias '{}' = '{}': an alias is one word for a command.", alias, command));
+            }
+        }
+        let notation = match file.notation.as_deref() {
+            None | Some("san") => Notation::San,
+            Some("coordinate") => Notation::Coordinate,
+            Some(other) => return Err(format!("Unknown notation '{}', expected san or coordinate.", other)),
+        };
+        Ok(Self {
+            aliases: file.aliases.into_iter().map(|(alias, command)| (alias.to_lowercase(), command)).collect(),
+            notation,
+            theme: file.theme.as_deref().map(Theme::select).transpose()?,
+            clock: file.clock.as_deref().map(TimeControl::parse).transpose()?,
+        })
+    }
+
+    /// Returns the command aliases, each one word mapped to the command it stands for.
+    pub fn aliases(&self) -> &BTreeMap<String, String> {
+        &self.aliases
+    }
+
+    /// Returns the notation moves are shown in.
+    pub fn notation(&self) -> Notation {
+        self.notation
+    }
+
+    /// Returns the theme to draw the board with, if one is set.
+    pub fn theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    /// Returns the time control of new games, if one is set.
+    pub fn clock(&self) -> Option<TimeControl> {
+        self.clock
+    }
+}
+
+/// Replaces the first word of a command line with the command it is an alias for, keeping the
+/// rest of the line, e.g. `m e2 e4` becomes `move e2 e4` with the alias `m = "move"`.
+pub(crate) fn expand_aliases(aliases: &BTreeMap<String, String>, line: &str) -> String {
+    let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    match aliases.get(&word.to_lowercase()) {
+        Some(command) if rest.is_empty() => command.clone(),
+        Some(command) => format!("{} {}", command, rest),
+        None => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_preferences() {
+        let preferences = Preferences::parse("notation = \"coordinate\"\nclock = \"5+3\"\ntheme = \"ascii\"\n[aliases]\nm = \"move\"\nR = \"resign\"\n").unwrap();
+        assert_eq!(preferences.notation(), Notation::Coordinate);
+        assert_eq!(preferences.clock(), Some(TimeControl::parse("5+3").unwrap()));
+        assert_eq!(preferences.theme(), Some(&Theme::select("ascii").unwrap()));
+        assert_eq!(expand_aliases(preferences.aliases(), "m e2 e4"), "move e2 e4");
+        assert_eq!(expand_aliases(preferences.aliases(), "r"), "resign");
+        assert_eq!(expand_aliases(preferences.aliases(), "Nf3"), "Nf3");
+        assert_eq!(Preferences::parse("").unwrap(), Preferences::default());
+        assert!(Preferences::parse("notation = \"long\"").is_err());
+        assert!(Preferences::parse("[aliases]\n\"m x\" = \"move\"").is_err());
+        assert!(Preferences::parse("colour = \"red\"").is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use crossterm::event::KeyCode;
+use crate::pieces::Piece;
+
+/// The pieces a pawn may promote to, in the order the selector shows them.
+pub(crate) const CHOICES: [Piece; 4] = [Piece::Queen, Piece::Rock, Piece::Bishop, Piece::Knight];
+
+/// The inline selector shown when a pawn reaches the last rank: the four pieces side by side,
+/// one of them highlighted.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct PromotionSelector {
+    /// Index in [`CHOICES`] of the highlighted piece, the queen at first.
+    pub(crate) selected: usize,
+}
+
+impl PromotionSelector {
+    /// Handles a key: the arrows move the highlight, wrapping around, Enter picks the
+    /// highlighted piece and a piece letter picks that piece at once.
+    ///
+    /// # Returns
+    /// - `Some(Piece)`: The piece the player picked.
+    /// - `None`: If the player has not picked one yet.
+    pub(crate) fn press(&mut self, key: KeyCode) -> Option<Piece> {
+        match key {
+            KeyCode::Left | KeyCode::Char('h') => self.selected = (self.selected + CHOICES.len() - 1) % CHOICES.len(),
+            KeyCode::Right | KeyCode::Char('l') => self.selected = (self.selected + 1) % CHOICES.len(),
+            KeyCode::Enter | KeyCode::Char(' ') => return Some(CHOICES[self.selected]),
+            KeyCode::Char(letter) => return parse_choice(&letter.to_string()),
+            _ => {}
+        }
+        None
+    }
+}
+
+/// Reads a typed promotion choice: `q`, `r`, `b` or `n`, in either case or spelled out, or
+/// nothing for a queen.
+pub(crate) fn parse_choice(input: &str) -> Option<Piece> {
+    match input.trim().to_lowercase().as_str() {
+        "" | "q" | "queen" => Some(Piece::Queen),
+        "r" | "rook" => Some(Piece::Rock),
+        "b" | "bishop" => Some(Piece::Bishop),
+        "n" | "knight" => Some(Piece::Knight),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selector_wraps_and_picks() {
+        let mut selector = PromotionSelector::default();
+        assert_eq!(selector.press(KeyCode::Left), None);
+        assert_eq!(CHOICES[selector.selected], Piece::Knight);
+        selector.press(KeyCode::Right);
+        selector.press(KeyCode::Right);
+        assert_eq!(selector.press(KeyCode::Enter), Some(Piece::Rock));
+        assert_eq!(selector.press(KeyCode::Char('B')), Some(Piece::Bishop));
+        assert_eq!(selector.press(KeyCode::Char('x')), None);
+        assert_eq!((parse_choice(""), parse_choice(" Knight ")), (Some(Piece::Queen), Some(Piece::Knight)));
+    }
+}
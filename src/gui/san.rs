@@ -0,0 +1,137 @@
+use std::fmt;
+use regex::Regex;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::{File, Rank, Square};
+
+const SAN_REGEX: &str = r"^([NBRQK])?([a-h])?([1-8])?x?([a-h][1-8])(?:=?([NBRQ]))?[+#]?[!?]*$";
+const CASTLE_REGEX: &str = r"^(?:O-O|0-0)(-O|-0)?[+#]?[!?]*$";
+
+/// A move written in standard algebraic notation, before it is matched against the position.
+///
+/// Only the hints written by the player are kept; finding the moving piece (and rejecting
+/// ambiguous or illegal moves) is left to the game, which knows the legal moves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum SanMove {
+    /// `O-O` (king side) or `O-O-O` (queen side).
+    Castle { king_side: bool },
+    /// Any other move, e.g. `Nf3`, `exd5`, `R1a3` or `e8=Q`.
+    Normal {
+        piece: Piece,
+        from_file: Option<File>,
+        from_rank: Option<Rank>,
+        to: Square,
+        promotion: Option<Piece>,
+    },
+}
+
+impl SanMove {
+    /// Returns the piece a pawn promotes to, if the move names one.
+    pub(crate) fn promotion(&self) -> Option<Piece> {
+        match self {
+            SanMove::Normal { promotion, .. } => *promotion,
+            SanMove::Castle { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for SanMove {
+    /// Writes the move back with the hints it was given, e.g. `Nbd2` or `e8=N`; capture,
+    /// check and annotation marks are not kept.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SanMove::Castle { king_side: true } => write!(f, "O-O"),
+            SanMove::Castle { king_side: false } => write!(f, "O-O-O"),
+            SanMove::Normal { piece, from_file, from_rank, to, promotion } => {
+                if *piece != Piece::Pawn {
+                    write!(f, "{}", piece.fen_symbol(Color::White))?;
+                }
+                if let Some(file) = from_file {
+                    write!(f, "{}", file)?;
+                }
+                if let Some(rank) = from_rank {
+                    write!(f, "{}", rank)?;
+                }
+                write!(f, "{}", to)?;
+                if let Some(promotion) = promotion {
+                    write!(f, "={}", promotion.fen_symbol(Color::White))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parses a move in standard algebraic notation. Capture, check and annotation marks
+/// (`x`, `+`, `#`, `!`, `?`) are accepted but not checked.
+///
+/// # Arguments
+/// - `input`: The move as typed, e.g. `Nbd2`, `exd5`, `O-O-O` or `e8=Q+`.
+///
+/// # Returns
+/// - `Some(SanMove)`: If the text is well-formed algebraic notation.
+/// - `None`: Otherwise.
+pub(crate) fn parse(input: &str) -> Option<SanMove> {
+    let input = input.trim();
+    if let Some(caps) = Regex::new(CASTLE_REGEX).unwrap().captures(input) {
+        return Some(SanMove::Castle { king_side: caps.get(1).is_none() });
+    }
+    let caps = Regex::new(SAN_REGEX).unwrap().captures(input)?;
+    let letter = |idx: usize| caps.get(idx).and_then(|symbol| symbol.as_str().chars().next());
+    let piece = letter(1).map_or(Some(Piece::Pawn), |symbol| Piece::from_fen_symbol(symbol).map(|(piece, _)| piece))?;
+    let promotion = match letter(5) {
+        Some(symbol) => Some(Piece::from_fen_symbol(symbol)?.0),
+        None => None,
+    };
+    if promotion.is_some() && piece != Piece::Pawn {
+        return None;
+    }
+    Some(SanMove::Normal {
+        piece,
+        from_file: letter(2).and_then(|symbol| File::try_from(symbol).ok()),
+        from_rank: letter(3).and_then(|symbol| Rank::try_from(symbol).ok()),
+        to: Square::try_from(caps.get(4)?.as_str().to_string()).ok()?,
+        promotion,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_parse_piece_and_pawn_moves() {
+        assert_eq!(parse("Nf3"), Some(SanMove::Normal {
+            piece: Piece::Knight, from_file: None, from_rank: None, to: square("f3"), promotion: None,
+        }));
+        assert_eq!(parse("exd5"), Some(SanMove::Normal {
+            piece: Piece::Pawn, from_file: Some(File::E), from_rank: None, to: square("d5"), promotion: None,
+        }));
+        assert_eq!(parse("e4"), Some(SanMove::Normal {
+            piece: Piece::Pawn, from_file: None, from_rank: None, to: square("e4"), promotion: None,
+        }));
+    }
+
+    #[test]
+    fn test_parse_disambiguation_and_promotion() {
+        assert_eq!(parse("R1a3"), Some(SanMove::Normal {
+            piece: Piece::Rock, from_file: None, from_rank: Some(Rank::One), to: square("a3"), promotion: None,
+        }));
+        assert_eq!(parse("e8=Q+"), Some(SanMove::Normal {
+            piece: Piece::Pawn, from_file: None, from_rank: None, to: square("e8"), promotion: Some(Piece::Queen),
+        }));
+        for san in ["R1a3", "Nbd2", "ed5", "e8=N", "O-O-O"] {
+            assert_eq!(parse(san).unwrap().to_string(), san);
+        }
+    }
+
+    #[test]
+    fn test_parse_castling_and_garbage() {
+        assert_eq!(parse("O-O"), Some(SanMove::Castle { king_side: true }));
+        assert_eq!(parse("O-O-O+"), Some(SanMove::Castle { king_side: false }));
+        assert_eq!(parse("Zf3"), None);
+        assert_eq!(parse("Nf3=Q"), None);
+        assert_eq!(parse("help"), None);
+    }
+}
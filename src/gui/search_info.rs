@@ -0,0 +1,67 @@
+use std::io::Write;
+use crate::engine::game::Move;
+use crate::engine::search::{Score, SearchObserver};
+
+/// Prints the progress of the engine's search while it thinks: one line per completed depth
+/// with its score and principal variation, and a running node count in between.
+pub(crate) struct SearchInfoPrinter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> SearchInfoPrinter<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+impl<W: Write> SearchObserver for SearchInfoPrinter<W> {
+    fn on_depth_complete(&mut self, depth: u8, score: Score, pv: &[Move]) {
+        let line = pv.iter().map(Move::to_string).collect::<Vec<_>>().join(" ");
+        // the carriage return and the padding overwrite the running node count; the search
+        // goes on if the terminal is gone
+        let _ = writeln!(self.writer, "\r depth {}  score {:<6}  pv {:<20}", depth, score, line);
+    }
+
+    fn on_node_count(&mut self, nodes: u64) {
+        let _ = write!(self.writer, "\r nodes {}", nodes).and_then(|_| self.writer.flush());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::search::principal_variation;
+    use crate::engine::game::Game;
+
+    #[test]
+    fn test_prints_depth_lines() {
+        let pv = principal_variation(&Game::from_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap(), 2);
+        let promotion = principal_variation(&Game::from_fen("5bn1/6Pk/7p/8/8/8/8/B1K5 w - - 0 1").unwrap(), 2);
+        let mut printer = SearchInfoPrinter::new(Vec::new());
+        printer.on_node_count(4096);
+        printer.on_depth_complete(2, Score::Centipawns(35), &pv);
+        printer.on_depth_complete(3, Score::Mate(-2), &promotion);
+        let output = String::from_utf8(printer.writer).unwrap();
+        assert_eq!(output, "\r nodes 4096\r depth 2  score +0.35   pv d2d5 e8e7           \n\r depth 3  score #-2     pv g7f8n               \n");
+    }
+
+    /// A terminal that has been closed, as when the output is piped into a program that quit.
+    struct ClosedPipe;
+
+    impl Write for ClosedPipe {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Err(std::io::ErrorKind::BrokenPipe.into())
+        }
+    }
+
+    #[test]
+    fn test_closed_output_does_not_stop_the_search() {
+        let mut printer = SearchInfoPrinter::new(ClosedPipe);
+        printer.on_node_count(4096);
+        printer.on_depth_complete(1, Score::Centipawns(0), &[]);
+    }
+}
@@ -0,0 +1,75 @@
+use regex::Regex;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::Square;
+
+const PUT_REGEX: &str = r"^put\s+([PNBRQKpnbrqk])([a-h][1-8])$";
+const REMOVE_REGEX: &str = r"^remove\s+([a-h][1-8])$";
+const TURN_REGEX: &str = r"^turn\s+(white|black)$";
+
+/// A command of the board editor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum SetupCommand {
+    /// Place a piece on a square, replacing whatever stands there.
+    Put(Piece, Color, Square),
+    /// Empty a square.
+    Remove(Square),
+    /// Empty the whole board.
+    Clear,
+    /// Choose the side to move.
+    Turn(Color),
+    /// Check the position and start playing from it.
+    Done,
+    /// Leave the editor and keep the current game.
+    Cancel,
+}
+
+/// Parses a board editor command such as `put Ke1` (uppercase for White, lowercase for
+/// Black, as in FEN), `remove a2`, `clear`, `turn black`, `done` or `cancel`.
+///
+/// # Returns
+/// - `Some(SetupCommand)`: If the text is a well-formed command.
+/// - `None`: Otherwise.
+pub(crate) fn parse(input: &str) -> Option<SetupCommand> {
+    let input = input.trim();
+    match input.to_lowercase().as_str() {
+        "clear" => return Some(SetupCommand::Clear),
+        "done" => return Some(SetupCommand::Done),
+        "cancel" => return Some(SetupCommand::Cancel),
+        _ => {}
+    }
+    // the piece letter is case-sensitive, as it gives the color
+    if let Some(caps) = Regex::new(PUT_REGEX).unwrap().captures(input) {
+        let symbol = caps[1].chars().next()?;
+        let (piece, color) = Piece::from_fen_symbol(symbol)?;
+        let square = Square::try_from(caps[2].to_string()).ok()?;
+        return Some(SetupCommand::Put(piece, color, square));
+    }
+    let lowercase = input.to_lowercase();
+    if let Some(caps) = Regex::new(REMOVE_REGEX).unwrap().captures(&lowercase) {
+        return Square::try_from(caps[1].to_string()).ok().map(SetupCommand::Remove);
+    }
+    let caps = Regex::new(TURN_REGEX).unwrap().captures(&lowercase)?;
+    match &caps[1] {
+        "white" => Some(SetupCommand::Turn(Color::White)),
+        _ => Some(SetupCommand::Turn(Color::Black)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_parse_setup_commands() {
+        assert_eq!(parse("put Ke1"), Some(SetupCommand::Put(Piece::King, Color::White, square("e1"))));
+        assert_eq!(parse("put  nb8"), Some(SetupCommand::Put(Piece::Knight, Color::Black, square("b8"))));
+        assert_eq!(parse("Remove A2"), Some(SetupCommand::Remove(square("a2"))));
+        assert_eq!(parse("turn black"), Some(SetupCommand::Turn(Color::Black)));
+        assert_eq!(parse("DONE"), Some(SetupCommand::Done));
+        assert_eq!(parse("put Xe1"), None);
+        assert_eq!(parse("put Ke9"), None);
+        assert_eq!(parse("remove"), None);
+    }
+}
@@ -0,0 +1,50 @@
+/// Block characters from the lowest to the highest bar.
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Smallest half-range of the graph in centipawns, so small swings around an equal
+/// position do not fill the whole height.
+const MIN_RANGE: i32 = 100;
+
+/// Draws a series of evaluations as a one-line graph of unicode block characters.
+///
+/// The scale is symmetric around `0`, so the middle bar always means an equal position and
+/// the graph keeps its meaning as the values grow.
+///
+/// # Arguments
+/// - `values`: The evaluations in centipawns, oldest first.
+/// - `width`: The maximum number of bars; only the latest `width` values are drawn.
+///
+/// # Returns
+/// - A `String` with one bar per drawn value.
+pub(crate) fn sparkline(values: &[i32], width: usize) -> String {
+    let values = &values[values.len().saturating_sub(width)..];
+    let range = values.iter().map(|value| value.saturating_abs()).max().unwrap_or(0).max(MIN_RANGE);
+    values.iter()
+        .map(|value| {
+            let clamped = (*value).clamp(-range, range) as i64;
+            let level = (clamped + range as i64) * (BARS.len() as i64 - 1) / (2 * range as i64);
+            BARS[level as usize]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline_scales_symmetrically() {
+        assert_eq!(sparkline(&[-300, 0, 300], 10), "▁▄█");
+    }
+
+    #[test]
+    fn test_sparkline_keeps_small_values_near_the_middle() {
+        assert_eq!(sparkline(&[0, 50, -50], 10), "▄▆▂");
+    }
+
+    #[test]
+    fn test_sparkline_only_draws_the_latest_values() {
+        assert_eq!(sparkline(&[-500, 100, 100], 2).chars().count(), 2);
+        assert_eq!(sparkline(&[], 5), "");
+    }
+}
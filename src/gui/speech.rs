@@ -0,0 +1,124 @@
+use crate::engine::board::Board;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::{File, Rank, Square};
+
+/// Returns the spoken name of a side, `white` or `black`.
+fn spoken_color(color: Color) -> &'static str {
+    match color {
+        Color::White => "white",
+        Color::Black => "black",
+    }
+}
+
+/// Returns the spoken name of a piece type, e.g. `knight`.
+fn spoken_name(piece: Piece) -> &'static str {
+    match piece {
+        Piece::Pawn => "pawn",
+        Piece::Knight => "knight",
+        Piece::Rock => "rook",
+        Piece::Bishop => "bishop",
+        Piece::Queen => "queen",
+        Piece::King => "king",
+    }
+}
+
+/// Returns the spoken name of a piece, e.g. `white knight`, or `empty` for an empty square.
+fn spoken_piece(piece: &Option<(Piece, Color)>) -> String {
+    match piece {
+        Some((piece, color)) => format!("{} {}", spoken_color(*color), spoken_name(*piece)),
+        None => "empty".to_string(),
+    }
+}
+
+/// Describes one rank from the a-file to the h-file, for screen readers.
+///
+/// # Arguments
+/// - `board`: The position, indexed like [`Square`].
+/// - `rank`: The rank to read.
+///
+/// # Returns
+/// - A line such as `Rank 8: black rook a8, empty b8, ...`.
+pub(crate) fn describe_rank(board: &Board, rank: Rank) -> String {
+    let squares = (0..8)
+        .filter_map(|file| File::try_from(file).ok())
+        .map(|file| {
+            let square = Square::new(file, rank);
+            format!("{} {}", spoken_piece(&board[square]), square)
+        })
+        .collect::<Vec<String>>();
+    format!("Rank {}: {}", rank, squares.join(", "))
+}
+
+/// Describes a single square, for screen readers.
+///
+/// # Returns
+/// - A line such as `e4: white pawn` or `e5: empty`.
+pub(crate) fn describe_square(board: &Board, square: Square) -> String {
+    format!("{}: {}", square, spoken_piece(&board[square]))
+}
+
+/// Describes who attacks and defends a square: the pieces of the side standing on it defend
+/// it, the others attack it; both sides attack an empty square.
+///
+/// # Arguments
+/// - `board`: The position, indexed like [`Square`].
+/// - `square`: The square described.
+/// - `attackers`: The pieces of each side reaching the square, indexed by side.
+///
+/// # Returns
+/// - A line such as `e5: black pawn. Attacked by 1 white piece: knight f3. Defended by 2 black
+///   pieces: pawn d6, queen e7.`
+pub(crate) fn describe_control(board: &Board, square: Square, attackers: &[Vec<(Piece, Square)>; 2]) -> String {
+    let occupant = board[square];
+    let mut line = describe_square(board, square);
+    line.push('.');
+    for side in [Color::White, Color::Black] {
+        let pieces = &attackers[usize::from(side)];
+        let verb = if occupant.is_some_and(|(_, owner)| owner == side) { "Defended" } else { "Attacked" };
+        let plural = if pieces.len() == 1 { "" } else { "s" };
+        line.push_str(&format!(" {} by {} {} piece{}", verb, pieces.len(), spoken_color(side), plural));
+        let names = pieces.iter()
+            .map(|(piece, from)| format!("{} {}", spoken_name(*piece), from))
+            .collect::<Vec<String>>();
+        if !names.is_empty() {
+            line.push_str(&format!(": {}", names.join(", ")));
+        }
+        line.push('.');
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board() -> Board {
+        let mut board = Board::empty();
+        board.set(Square::new(File::A, Rank::Eight), Some((Piece::Rock, Color::Black)));
+        board.set(Square::new(File::E, Rank::Four), Some((Piece::Pawn, Color::White)));
+        board
+    }
+
+    #[test]
+    fn test_describe_rank() {
+        let line = describe_rank(&board(), Rank::Eight);
+        assert!(line.starts_with("Rank 8: black rook a8, empty b8, empty c8"));
+        assert!(line.ends_with("empty h8"));
+    }
+
+    #[test]
+    fn test_describe_square() {
+        assert_eq!(describe_square(&board(), Square::new(File::E, Rank::Four)), "e4: white pawn");
+        assert_eq!(describe_square(&board(), Square::new(File::E, Rank::Five)), "e5: empty");
+    }
+
+    #[test]
+    fn test_describe_control() {
+        let attackers = [vec![], vec![(Piece::Rock, Square::new(File::E, Rank::Eight)), (Piece::Pawn, Square::new(File::D, Rank::Five))]];
+        assert_eq!(
+            describe_control(&board(), Square::new(File::E, Rank::Four), &attackers),
+            "e4: white pawn. Defended by 0 white pieces. Attacked by 2 black pieces: rook e8, pawn d5."
+        );
+    }
+}
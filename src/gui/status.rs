@@ -0,0 +1,132 @@
+use std::fmt;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+use crate::square::Square;
+
+/// What the board display announces about the position below the board.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum GameStatus {
+    /// The game goes on and the side to move is not in check.
+    InProgress,
+    /// The side to move is in check; its king stands on the square.
+    Check(Square),
+    /// The side to move is checkmated; its king stands on the square.
+    Checkmate { king: Square, winner: Color },
+    /// The side to move has no legal move but is not in check.
+    Stalemate,
+    /// The players agreed to a draw.
+    Draw,
+    /// The given side resigned.
+    Resignation(Color),
+    /// The flag of the given side fell.
+    Timeout(Color),
+    /// The flag of the given side fell, but its opponent cannot checkmate.
+    TimeoutDraw(Color),
+    /// The king of the given side was blown up in an Atomic game.
+    KingExploded(Color),
+}
+
+/// The compact line of facts about the position printed under the board.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct StatusLine {
+    /// The material balance in pawns from White's point of view, e.g. `2` when White is a
+    /// knight up and `-1` when it is a pawn down.
+    pub(crate) material: i32,
+    /// The number of legal moves of the side to move.
+    pub(crate) legal_moves: usize,
+    pub(crate) check: bool,
+    /// Half-moves since the last capture or pawn move, for the fifty-move rule.
+    pub(crate) halfmove_clock: u32,
+    /// The square a pawn may capture en passant on this move.
+    pub(crate) en_passant: Option<Square>,
+}
+
+/// The pieces each side has taken, shown beside the board.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CapturedPieces {
+    /// The pieces taken by each side, indexed by the color of the capturing side.
+    pub(crate) pieces: [Vec<Piece>; 2],
+    /// The material balance in pawns from White's point of view, as in [`StatusLine`].
+    pub(crate) material: i32,
+}
+
+impl CapturedPieces {
+    /// Returns how many pawns `side` is ahead in material, or `None` if it is not ahead.
+    pub(crate) fn lead(&self, side: Color) -> Option<i32> {
+        let lead = if side == Color::White { self.material } else { -self.material };
+        (lead > 0).then_some(lead)
+    }
+}
+
+impl fmt::Display for StatusLine {
+    /// Formats the line, e.g. `Material +2 | 31 legal moves | Check | Halfmove clock 4`, with
+    /// `| En passant e3` at the end after a double pawn step.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.material {
+            0 => write!(f, "Material =")?,
+            material => write!(f, "Material {:+}", material)?,
+        }
+        write!(f, " | {} legal move{}", self.legal_moves, if self.legal_moves == 1 { "" } else { "s" })?;
+        if self.check {
+            write!(f, " | Check")?;
+        }
+        write!(f, " | Halfmove clock {}", self.halfmove_clock)?;
+        if let Some(square) = self.en_passant {
+            write!(f, " | En passant {}", square)?;
+        }
+        Ok(())
+    }
+}
+
+impl GameStatus {
+    /// Returns the square of the king in check, which the board highlights.
+    pub(crate) fn checked_king(&self) -> Option<Square> {
+        match self {
+            GameStatus::Check(king) | GameStatus::Checkmate { king, .. } => Some(*king),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the game has ended.
+    pub(crate) fn is_over(&self) -> bool {
+        !matches!(self, GameStatus::InProgress | GameStatus::Check(_))
+    }
+}
+
+impl fmt::Display for GameStatus {
+    /// Formats the banner shown below the board, empty while the game simply goes on.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameStatus::InProgress => Ok(()),
+            GameStatus::Check(_) => write!(f, "Check!"),
+            GameStatus::Checkmate { winner, .. } => write!(f, "Checkmate — {:?} wins", winner),
+            GameStatus::Stalemate => write!(f, "Stalemate"),
+            GameStatus::Draw => write!(f, "Draw"),
+            GameStatus::Resignation(loser) => write!(f, "{:?} resigns — {:?} wins", loser, loser.opposite()),
+            GameStatus::Timeout(loser) => write!(f, "{:?} lost on time — {:?} wins", loser, loser.opposite()),
+            GameStatus::TimeoutDraw(loser) => write!(f, "{:?} ran out of time — draw by insufficient material", loser),
+            GameStatus::KingExploded(loser) => write!(f, "{:?}'s king exploded — {:?} wins", loser, loser.opposite()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_captured_pieces_lead() {
+        let captured = CapturedPieces { pieces: [vec![Piece::Knight], vec![Piece::Pawn]], material: 2 };
+        assert_eq!(captured.lead(Color::White), Some(2));
+        assert_eq!(captured.lead(Color::Black), None);
+    }
+
+    #[test]
+    fn test_status_line_format() {
+        let line = StatusLine { material: 2, legal_moves: 1, check: true, halfmove_clock: 4, en_passant: None };
+        assert_eq!(line.to_string(), "Material +2 | 1 legal move | Check | Halfmove clock 4");
+        let e3 = Square::try_from("e3".to_string()).ok();
+        let line = StatusLine { material: 0, legal_moves: 20, check: false, halfmove_clock: 0, en_passant: e3 };
+        assert_eq!(line.to_string(), "Material = | 20 legal moves | Halfmove clock 0 | En passant e3");
+    }
+}
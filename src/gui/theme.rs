@@ -0,0 +1,324 @@
+use std::fs;
+use crossterm::style;
+use serde::Deserialize;
+use unicode_width::UnicodeWidthStr;
+use crate::gui::highlight::Highlight;
+use crate::pieces::common::Color;
+use crate::pieces::Piece;
+
+/// Environment variable naming the theme used when `--theme` is not given, e.g. `ascii`.
+pub const THEME_VARIABLE: &str = "CHESS_THEME";
+
+/// The themes built into the game, by name.
+const BUILTIN_THEMES: [(&str, &str); 2] = [
+    ("unicode", ""),
+    ("ascii", include_str!("../../themes/letters.toml")),
+];
+
+/// The glyphs used to draw the board, one per piece and color plus the empty square.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PieceSet {
+    glyphs: [[String; 6]; 2],
+    empty: String,
+}
+
+impl Default for PieceSet {
+    /// The unicode chess glyphs.
+    fn default() -> Self {
+        let glyphs = |symbols: [&str; 6]| symbols.map(String::from);
+        Self {
+            glyphs: [
+                glyphs(["♙", "♘", "♖", "♗", "♕", "♔"]),
+                glyphs(["♟", "♞", "♜", "♝", "♛", "♚"]),
+            ],
+            empty: "□".to_string(),
+        }
+    }
+}
+
+impl PieceSet {
+    /// Returns the glyph of a piece, or of the empty square for `None`.
+    pub fn glyph(&self, piece: &Option<(Piece, Color)>) -> &str {
+        match piece {
+            Some((piece, color)) => &self.glyphs[usize::from(*color)][usize::from(*piece)],
+            None => &self.empty,
+        }
+    }
+}
+
+/// The colors the board is drawn with.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Palette {
+    pub(crate) white_piece: style::Color,
+    pub(crate) black_piece: style::Color,
+    pub(crate) empty: style::Color,
+    /// Background of the light squares; `None` keeps the terminal's background.
+    pub(crate) light_square: Option<style::Color>,
+    /// Background of the dark squares; `None` keeps the terminal's background.
+    pub(crate) dark_square: Option<style::Color>,
+    /// Background of a king in check.
+    pub(crate) check: style::Color,
+    /// Background of each kind of highlighted square, in the order of [`Highlight`].
+    highlights: [style::Color; 7],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            white_piece: style::Color::White,
+            black_piece: style::Color::DarkGrey,
+            empty: style::Color::Grey,
+            light_square: None,
+            dark_square: None,
+            check: style::Color::DarkRed,
+            highlights: [
+                style::Color::DarkGreen,
+                style::Color::DarkRed,
+                style::Color::DarkBlue,
+                style::Color::DarkYellow,
+                style::Color::DarkCyan,
+                style::Color::DarkMagenta,
+                style::Color::DarkGrey,
+            ],
+        }
+    }
+}
+
+impl Palette {
+    /// Returns the background color of a highlighted square.
+    pub(crate) fn highlight(&self, highlight: Highlight) -> style::Color {
+        self.highlights[highlight_index(highlight)]
+    }
+}
+
+/// Returns the position of a highlight kind in [`Palette::highlights`].
+fn highlight_index(highlight: Highlight) -> usize {
+    match highlight {
+        Highlight::Move => 0,
+        Highlight::Capture => 1,
+        Highlight::Castle => 2,
+        Highlight::Promotion => 3,
+        Highlight::Cursor => 4,
+        Highlight::Selected => 5,
+        Highlight::LastMove => 6,
+    }
+}
+
+/// Rendering settings of the command prompt GUI.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Theme {
+    pub(crate) pieces: PieceSet,
+    pub(crate) colors: Palette,
+}
+
+/// The theme file layout; every entry is optional and falls back to the default theme.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    #[serde(default)]
+    pieces: PieceSetFile,
+    #[serde(default)]
+    colors: ColorsFile,
+    #[serde(default)]
+    highlights: HighlightsFile,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ColorsFile {
+    white_piece: Option<String>,
+    black_piece: Option<String>,
+    empty: Option<String>,
+    light_square: Option<String>,
+    dark_square: Option<String>,
+    check: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct HighlightsFile {
+    #[serde(rename = "move")]
+    quiet_move: Option<String>,
+    capture: Option<String>,
+    castle: Option<String>,
+    promotion: Option<String>,
+    cursor: Option<String>,
+    selected: Option<String>,
+    last_move: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct PieceSetFile {
+    white_pawn: Option<String>,
+    white_knight: Option<String>,
+    white_rook: Option<String>,
+    white_bishop: Option<String>,
+    white_queen: Option<String>,
+    white_king: Option<String>,
+    black_pawn: Option<String>,
+    black_knight: Option<String>,
+    black_rook: Option<String>,
+    black_bishop: Option<String>,
+    black_queen: Option<String>,
+    black_king: Option<String>,
+    empty: Option<String>,
+}
+
+impl Theme {
+    /// Returns a built-in theme, or loads a theme file.
+    ///
+    /// # Arguments
+    /// - `name`: `unicode` for the chess glyphs, `ascii` for letters, or the path of a theme file.
+    ///
+    /// # Returns
+    /// - `Ok(Theme)`: The theme.
+    /// - `Err(String)`: If `name` is not a built-in theme and the file cannot be loaded.
+    pub fn select(name: &str) -> Result<Self, String> {
+        match BUILTIN_THEMES.iter().find(|(builtin, _)| builtin.eq_ignore_ascii_case(name)) {
+            Some((_, content)) => Self::parse(content),
+            None => Self::load(name),
+        }
+    }
+
+    /// Loads a theme from a TOML file.
+    ///
+    /// # Arguments
+    /// - `path`: The theme file, e.g. `themes/letters.toml`.
+    ///
+    /// # Returns
+    /// - `Ok(Theme)`: The default theme with the entries of the file applied.
+    /// - `Err(String)`: If the file cannot be read or parsed, or a glyph is not one column wide.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|err| format!("Cannot read theme {}: {}", path, err))?;
+        Self::parse(&content).map_err(|err| format!("Invalid theme {}: {}", path, err))
+    }
+
+    /// Parses a theme from the content of a TOML file: glyphs under `[pieces]`, piece and square
+    /// colors under `[colors]` and highlight backgrounds under `[highlights]`. A color is a name
+    /// such as `dark_green` or a `#rrggbb` value.
+    ///
+    /// # Returns
+    /// - `Ok(Theme)`: The default theme with the given entries applied.
+    /// - `Err(String)`: If the content is not valid, a glyph is not one column wide or a color
+    ///   is unknown.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let file: ThemeFile = toml::from_str(content).map_err(|err| err.message().to_string())?;
+        let pieces = file.pieces;
+        let mut theme = Theme::default();
+        let entries = [
+            (Color::White, [pieces.white_pawn, pieces.white_knight, pieces.white_rook,
+                pieces.white_bishop, pieces.white_queen, pieces.white_king]),
+            (Color::Black, [pieces.black_pawn, pieces.black_knight, pieces.black_rook,
+                pieces.black_bishop, pieces.black_queen, pieces.black_king]),
+        ];
+        for (color, glyphs) in entries {
+            for (piece_idx, glyph) in glyphs.into_iter().enumerate() {
+                if let Some(glyph) = glyph {
+                    theme.pieces.glyphs[usize::from(color)][piece_idx] = Self::validate_glyph(glyph)?;
+                }
+            }
+        }
+        if let Some(glyph) = pieces.empty {
+            theme.pieces.empty = Self::validate_glyph(glyph)?;
+        }
+        let colors = file.colors;
+        let palette = &mut theme.colors;
+        for (color, value) in [
+            (&mut palette.white_piece, colors.white_piece),
+            (&mut palette.black_piece, colors.black_piece),
+            (&mut palette.empty, colors.empty),
+            (&mut palette.check, colors.check),
+        ] {
+            if let Some(value) = value {
+                *color = parse_color(&value)?;
+            }
+        }
+        for (color, value) in [(&mut palette.light_square, colors.light_square), (&mut palette.dark_square, colors.dark_square)] {
+            if let Some(value) = value {
+                *color = Some(parse_color(&value)?);
+            }
+        }
+        let highlights = file.highlights;
+        let entries = [
+            (Highlight::Move, highlights.quiet_move),
+            (Highlight::Capture, highlights.capture),
+            (Highlight::Castle, highlights.castle),
+            (Highlight::Promotion, highlights.promotion),
+            (Highlight::Cursor, highlights.cursor),
+            (Highlight::Selected, highlights.selected),
+            (Highlight::LastMove, highlights.last_move),
+        ];
+        for (highlight, value) in entries {
+            if let Some(value) = value {
+                palette.highlights[highlight_index(highlight)] = parse_color(&value)?;
+            }
+        }
+        Ok(theme)
+    }
+
+    /// Checks that a glyph takes exactly one terminal column, so the board stays aligned.
+    fn validate_glyph(glyph: String) -> Result<String, String> {
+        match glyph.width() {
+            1 => Ok(glyph),
+            width => Err(format!("glyph '{}' is {} columns wide, expected exactly 1.", glyph, width)),
+        }
+    }
+}
+
+/// Parses a color name such as `dark_green`, or a `#rrggbb` value for terminals with true color.
+fn parse_color(value: &str) -> Result<style::Color, String> {
+    let unknown = || format!("unknown color '{}', expected a name such as dark_green or #rrggbb.", value);
+    match value.strip_prefix('#') {
+        Some(hex) if hex.len() == 6 => {
+            let channel = |idx: usize| u8::from_str_radix(hex.get(idx..idx + 2).ok_or_else(unknown)?, 16).map_err(|_| unknown());
+            Ok(style::Color::Rgb { r: channel(0)?, g: channel(2)?, b: channel(4)? })
+        }
+        Some(_) => Err(unknown()),
+        None => style::Color::try_from(value).map_err(|_| unknown()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_overrides_only_given_glyphs() {
+        let theme = Theme::parse("[pieces]\nwhite_king = \"K\"\nempty = \".\"\n").unwrap();
+        assert_eq!(theme.pieces.glyph(&Some((Piece::King, Color::White))), "K");
+        assert_eq!(theme.pieces.glyph(&None), ".");
+        assert_eq!(theme.pieces.glyph(&Some((Piece::King, Color::Black))), "♚");
+    }
+
+    #[test]
+    fn test_parse_rejects_wide_or_empty_glyphs() {
+        assert!(Theme::parse("[pieces]\nwhite_king = \"KK\"\n").is_err());
+        assert!(Theme::parse("[pieces]\nwhite_king = \"\"\n").is_err());
+        assert!(Theme::parse("[pieces]\nwhite_king = \"王\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_keys() {
+        assert!(Theme::parse("[pieces]\nwhite_kong = \"K\"\n").is_err());
+    }
+
+    #[test]
+    fn test_bundled_themes_are_valid() {
+        assert!(Theme::parse(include_str!("../../themes/letters.toml")).is_ok());
+        assert!(Theme::parse(include_str!("../../themes/contrast.toml")).is_ok());
+        let ascii = Theme::select("ASCII").unwrap();
+        assert_eq!(ascii.pieces.glyph(&Some((Piece::Queen, Color::Black))), "q");
+        assert_eq!(Theme::select("unicode").unwrap(), Theme::default());
+    }
+
+    #[test]
+    fn test_parse_colors() {
+        let theme = Theme::parse("[colors]\nlight_square = \"#f0d9b5\"\n\n[highlights]\nmove = \"green\"\n").unwrap();
+        assert_eq!(theme.colors.light_square, Some(style::Color::Rgb { r: 0xf0, g: 0xd9, b: 0xb5 }));
+        assert_eq!(theme.colors.highlight(Highlight::Move), style::Color::Green);
+        assert_eq!(theme.colors.highlight(Highlight::Capture), style::Color::DarkRed);
+        assert!(Theme::parse("[colors]\ncheck = \"pink\"\n").is_err());
+        assert!(Theme::parse("[colors]\ncheck = \"#12345\"\n").is_err());
+    }
+}
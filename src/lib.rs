@@ -0,0 +1,55 @@
+//! A chess engine with a command line interface.
+//!
+//! The [`Game`] type holds a position and everything needed to play on from it: legal move
+//! generation, FEN and packed encodings, undo/redo, perft and the search used by the computer
+//! player.
+//!
+//! ```
+//! use chess_game::{Game, Square};
+//!
+//! let mut game = Game::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+//! assert_eq!(game.legal_move_list().len(), 6);
+//! let e2: Square = "e2".parse().unwrap();
+//! let e4: Square = "e4".parse().unwrap();
+//! game.play(e2, e4).unwrap();
+//! assert_eq!(game.fen(), "4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1");
+//! ```
+
+mod bitboard;
+mod square;
+mod pieces;
+mod engine;
+mod gui;
+mod crash;
+mod logging;
+mod net;
+
+pub use crash::install as install_crash_reporter;
+pub use logging::install as install_logging;
+pub use engine::clock::{ClockBonus, TimeControl};
+pub use engine::coach::TimeCoach;
+pub use engine::driver::{DriverAction, DriverEvent, GameDriver};
+pub use engine::eval::{evaluate, EvalConfig, GamePhase};
+pub use engine::game::{parse_perft_suite, parse_puzzles, CastlingRights, Direction, EventLog, Game, GameEvent, GameSnapshot, GameResult, IllegalReason, Move, MoveError, MoveFlag, MoveList, Nag, PackedMove, PackedPosition, PerftCase, PerftMismatch, PlayerKind, Puzzle, Variant, MAX_MATE_MOVES, MAX_MOVES};
+pub use engine::match_runner::{EngineConfig, MatchGame, MatchOutcome, MatchRunner, MatchStats};
+pub use engine::observer::{CsvMoveLogger, MoveEvent, MoveObserver};
+pub use engine::options::{EngineOption, OPTION_NAMES};
+pub use engine::pgn::{parse_pgn, PgnError, PgnGame};
+pub use engine::rating::RatingTracker;
+pub use engine::script::{run_script, ScriptError};
+pub use engine::search::{analyze, best_move, search_with_observer, spawn_search, Score, SearchHandle, SearchObserver};
+pub use engine::skill::{SkillLevel, MAX_LEVEL as MAX_SKILL_LEVEL};
+pub use engine::tablebase::{prober as tablebase, set_prober as set_tablebase, BuiltinTablebase, TablebaseProbe, TablebaseProber, Wdl};
+#[cfg(feature = "syzygy")]
+pub use engine::tablebase::SyzygyTablebase;
+pub use engine::tuner::{parse_tuning_positions, self_play_positions, Tuner, TuningPosition};
+pub use gui::display::PieceVisibility;
+pub use gui::input::CommandSender;
+pub use gui::preferences::{Notation, Preferences, PREFERENCES_FILE};
+pub use gui::theme::{Theme, THEME_VARIABLE};
+pub use net::peer::{PeerConnection, PeerMessage};
+#[cfg(feature = "spectator")]
+pub use net::spectator::SpectatorServer;
+pub use pieces::common::Color;
+pub use pieces::Piece;
+pub use square::{File, Rank, Square, SquareParseError};
@@ -0,0 +1,51 @@
+use tracing_subscriber::EnvFilter;
+
+/// Installs a subscriber that writes the engine's logs to standard error, filtered by the
+/// `RUST_LOG` environment variable (e.g. `RUST_LOG=chess_game=debug`). Without it only errors
+/// are written, so the board drawn on standard output stays clean.
+///
+/// Does nothing if a subscriber is already installed.
+pub fn install() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::{Arc, Mutex};
+    use crate::engine::game::Game;
+    use crate::square::square;
+
+    /// Collects everything the subscriber writes.
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Buffer {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(bytes)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_rejected_moves_are_logged_at_debug_level() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_env_filter("chess_game=debug")
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            assert!(Game::new().play(square("e2"), square("e5")).is_err());
+        });
+        let logs = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains("DEBUG") && logs.contains("validate_move"), "{}", logs);
+    }
+}
@@ -1,13 +1,597 @@
-use crate::bitboard::BitBoard;
-use crate::engine::game;
+use std::io::IsTerminal;
+use std::time::Duration;
+use chess_game::{parse_perft_suite, parse_pgn, parse_puzzles, run_script, parse_tuning_positions, self_play_positions, Color, EngineConfig, EvalConfig, EventLog, MatchRunner, Tuner, TimeControl, CsvMoveLogger, EngineOption, Game, GameDriver, PackedPosition, PeerConnection, PeerMessage, PlayerKind, Preferences, RatingTracker, Theme, TimeCoach, Variant, THEME_VARIABLE};
+#[cfg(feature = "spectator")]
+use chess_game::SpectatorServer;
+#[cfg(feature = "syzygy")]
+use chess_game::{set_tablebase, SyzygyTablebase, TablebaseProber};
 
-mod bitboard;
-mod square;
-mod pieces;
-mod engine;
-mod gui;
+const USAGE: &str = "Usage: ChessGame [--fen <FEN> | --packed <hex> | --replay <file.pgn> [--game <N>]] [--strict] [--variant <standard|atomic>] [--computer <white|black|both> | --watch <depth>,<depth> [--delay <ms>]] [--depth <N> | --level <1-10>] [--eval <file>] [--contempt <centipawns>] [--search-info] [--ponder] [--eval-bar] [--flip | --auto-flip] [--alerts <on|off>] [--theme <unicode|ascii|file>] [--config <file.toml>] [--csv-log <file>] [--event-log <file>] [--coach] [--clock <minutes[+|d|b seconds]> | --clock ask] [--promotion-timeout <seconds>] [--stats <file>] [--line] [--tutorial | --puzzles <file> | --script <file|-> | --perft-suite <file>] [--host <port> [--side <white|black>] [--websocket] | --connect <address|ws://address>] [--spectate <port>] [--syzygy <directory>]
+       ChessGame --match <depth>,<depth> [--eval <file>] [--games <N>] [--max-plies <N>] [--pgn <file>]
+       ChessGame --tune <positions.epd|selfplay> [--eval <file>] [--tune-output <file.toml>] [--games <N>] [--depth <N>] [--max-plies <N>]";
+
+/// Number of games of an engine match when `--games` is not given.
+const DEFAULT_MATCH_GAMES: usize = 10;
+
+/// Pause before each move of `--watch` when `--delay` is not given, in milliseconds.
+const DEFAULT_WATCH_DELAY_MS: u64 = 800;
+
+/// Search depth used by the computer player when `--depth` is not given.
+const DEFAULT_DEPTH: u8 = 3;
+
+/// Highest computer depth counted as a beginner level, where tactic alerts are on by default.
+const BEGINNER_DEPTH: u8 = 2;
 
 fn main() {
-    let mut game = game::Game::new();
+    chess_game::install_crash_reporter();
+    chess_game::install_logging();
+    let mut fen = None;
+    let mut packed = None;
+    let mut strict = false;
+    let mut computer: Vec<Color> = Vec::new();
+    let mut depth = DEFAULT_DEPTH;
+    let mut level = None;
+    let mut alerts = None;
+    let mut theme = None;
+    let mut config = None;
+    let mut script = None;
+    let mut eval_config = None;
+    let mut csv_log = None;
+    let mut event_log = None;
+    let mut variant = Variant::Standard;
+    let mut coach = false;
+    let mut search_info = false;
+    let mut pondering = false;
+    let mut eval_bar = false;
+    let mut flipped = false;
+    let mut auto_flip = false;
+    let mut stats = None;
+    let mut clock = None;
+    let mut promotion_timeout = None;
+    let mut contempt = None;
+    let mut engine_match = None;
+    let mut tune = None;
+    let mut tune_output = None;
+    let mut watch = None;
+    let mut delay = None;
+    let mut games = DEFAULT_MATCH_GAMES;
+    let mut max_plies = None;
+    let mut pgn = None;
+    let mut replay = None;
+    let mut replay_game = 1;
+    let mut tutorial = false;
+    let mut puzzles = None;
+    let mut perft_suite = None;
+    let mut line_mode = false;
+    let mut host = None;
+    let mut connect = None;
+    let mut host_side = Color::White;
+    let mut websocket = false;
+    let mut spectate = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fen" => fen = args.next(),
+            "--packed" => packed = args.next(),
+            "--strict" => strict = true,
+            "--coach" => coach = true,
+            "--search-info" => search_info = true,
+            "--ponder" => pondering = true,
+            "--eval-bar" => eval_bar = true,
+            "--flip" => flipped = true,
+            "--auto-flip" => auto_flip = true,
+            "--tutorial" => tutorial = true,
+            "--line" => line_mode = true,
+            "--computer" => computer = match args.next().as_deref() {
+                Some("white") => vec![Color::White],
+                Some("black") => vec![Color::Black],
+                Some("both") => vec![Color::White, Color::Black],
+                _ => exit_with_usage("--computer expects white, black or both."),
+            },
+            "--variant" => variant = match args.next().as_deref() {
+                Some("standard") => Variant::Standard,
+                Some("atomic") => Variant::Atomic,
+                _ => exit_with_usage("--variant expects standard or atomic."),
+            },
+            "--depth" => depth = match args.next().and_then(|value| value.parse().ok()) {
+                Some(value) if value > 0 => value,
+                _ => exit_with_usage("--depth expects a positive number."),
+            },
+            "--level" => level = match args.next().map(|value| EngineOption::parse("skill", &value)) {
+                Some(Ok(EngineOption::Skill(Some(value)))) => Some(value),
+                _ => exit_with_usage("--level expects a skill level from 1 to 10."),
+            },
+            "--alerts" => alerts = match args.next().as_deref() {
+                Some("on") => Some(true),
+                Some("off") => Some(false),
+                _ => exit_with_usage("--alerts expects on or off."),
+            },
+            "--host" => host = match args.next().and_then(|value| value.parse::<u16>().ok()) {
+                Some(port) => Some(port),
+                None => exit_with_usage("--host expects a port number."),
+            },
+            "--side" => host_side = match args.next().as_deref() {
+                Some("white") => Color::White,
+                Some("black") => Color::Black,
+                _ => exit_with_usage("--side expects white or black."),
+            },
+            "--websocket" => websocket = true,
+            "--spectate" => spectate = match args.next().and_then(|value| value.parse::<u16>().ok()) {
+                Some(port) => Some(port),
+                None => exit_with_usage("--spectate expects a port number."),
+            },
+            "--connect" => connect = match args.next() {
+                Some(address) => Some(address),
+                None => exit_with_usage("--connect expects an address such as 127.0.0.1:7878."),
+            },
+            "--syzygy" => match args.next() {
+                Some(directory) => load_syzygy_tables(&directory),
+                None => exit_with_usage("--syzygy expects a directory of Syzygy tables."),
+            },
+            "--eval" => eval_config = match args.next() {
+                Some(path) => Some(EvalConfig::load(&path).unwrap_or_else(|err| exit_with_error(&err))),
+                None => exit_with_usage("--eval expects a file."),
+            },
+            "--theme" => theme = match args.next() {
+                Some(name) => Some(Theme::select(&name).unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    std::process::exit(2);
+                })),
+                None => exit_with_usage("--theme expects unicode, ascii or a file."),
+            },
+            "--script" => script = match args.next() {
+                Some(path) => Some(path),
+                None => exit_with_usage("--script expects a file, or - for standard input."),
+            },
+            "--config" => config = match args.next() {
+                Some(path) => Some(Preferences::load(&path).unwrap_or_else(|err| exit_with_error(&err))),
+                None => exit_with_usage("--config expects a file."),
+            },
+            "--clock" => clock = match args.next().as_deref() {
+                // an empty answer means no clock, even if the preferences set one
+                Some("ask") => Some(prompt_time_control()),
+                Some(spec) => Some(Some(TimeControl::parse(spec).unwrap_or_else(|err| exit_with_usage(&err)))),
+                None => exit_with_usage("--clock expects a time control such as 5+3, or ask."),
+            },
+            "--promotion-timeout" => promotion_timeout = match args.next().and_then(|value| value.parse().ok()) {
+                Some(seconds) => Some(Duration::from_secs(seconds)),
+                None => exit_with_usage("--promotion-timeout expects a number of seconds."),
+            },
+            "--contempt" => contempt = match args.next().map(|value| EngineOption::parse("contempt", &value)) {
+                Some(Ok(option)) => Some(option),
+                Some(Err(err)) => exit_with_usage(&err),
+                None => exit_with_usage("--contempt expects a number of centipawns."),
+            },
+            "--watch" => watch = match args.next().as_deref().and_then(parse_depth_pair) {
+                Some(depths) => Some(depths),
+                None => exit_with_usage("--watch expects two positive depths, such as 2,3."),
+            },
+            "--delay" => delay = match args.next().and_then(|value| value.parse().ok()) {
+                Some(millis) => Some(Duration::from_millis(millis)),
+                None => exit_with_usage("--delay expects a number of milliseconds."),
+            },
+            "--match" => engine_match = match args.next().as_deref().and_then(parse_depth_pair) {
+                Some(depths) => Some(depths),
+                None => exit_with_usage("--match expects two positive depths, such as 2,3."),
+            },
+            "--tune" => tune = match args.next() {
+                Some(source) => Some(source),
+                None => exit_with_usage("--tune expects a file of positions, or selfplay."),
+            },
+            "--tune-output" => tune_output = match args.next() {
+                Some(path) => Some(path),
+                None => exit_with_usage("--tune-output expects a file."),
+            },
+            "--games" => games = match args.next().and_then(|value| value.parse().ok()) {
+                Some(value) if value > 0 => value,
+                _ => exit_with_usage("--games expects a positive number."),
+            },
+            "--max-plies" => max_plies = match args.next().and_then(|value| value.parse().ok()) {
+                Some(value) if value > 0 => Some(value),
+                _ => exit_with_usage("--max-plies expects a positive number."),
+            },
+            "--pgn" => pgn = match args.next() {
+                Some(path) => Some(path),
+                None => exit_with_usage("--pgn expects a file."),
+            },
+            "--perft-suite" => perft_suite = match args.next() {
+                Some(path) => Some(path),
+                None => exit_with_usage("--perft-suite expects a file."),
+            },
+            "--puzzles" => puzzles = match args.next() {
+                Some(path) => Some(path),
+                None => exit_with_usage("--puzzles expects a file."),
+            },
+            "--replay" => replay = match args.next() {
+                Some(path) => Some(path),
+                None => exit_with_usage("--replay expects a PGN file."),
+            },
+            "--game" => replay_game = match args.next().and_then(|value| value.parse().ok()).filter(|number| *number > 0) {
+                Some(number) => number,
+                None => exit_with_usage("--game expects a game number, starting at 1."),
+            },
+            "--stats" => stats = match args.next() {
+                Some(path) => Some(path),
+                None => exit_with_usage("--stats expects a file."),
+            },
+            "--csv-log" => csv_log = match args.next() {
+                Some(path) => Some(CsvMoveLogger::open(&path).unwrap_or_else(|err| exit_with_error(&err))),
+                None => exit_with_usage("--csv-log expects a file."),
+            },
+            "--event-log" => event_log = match args.next() {
+                Some(path) => Some(EventLog::open(&path).unwrap_or_else(|err| exit_with_error(&err))),
+                None => exit_with_usage("--event-log expects a file."),
+            },
+            _ => exit_with_usage(&format!("Unknown argument '{}'.", arg)),
+        }
+    }
+    // the preferences file in the home directory is optional, one named with --config is not
+    let preferences = config.unwrap_or_else(|| match Preferences::default_path().filter(|path| path.exists()) {
+        Some(path) => Preferences::load(&path.to_string_lossy()).unwrap_or_else(|err| exit_with_error(&err)),
+        None => Preferences::default(),
+    });
+    // the environment picks the theme for terminals where the glyphs always render poorly
+    let theme = theme.or_else(|| {
+        let name = std::env::var(THEME_VARIABLE).ok()?;
+        Some(Theme::select(&name).unwrap_or_else(|err| exit_with_error(&format!("{}: {}", THEME_VARIABLE, err))))
+    }).or_else(|| preferences.theme().cloned());
+    let mut clock = clock.unwrap_or(preferences.clock());
+    // a skill level searches at a depth of its own
+    if let Some(level) = level {
+        depth = level.depth();
+    }
+    if variant != Variant::Standard && engine_match.is_some() {
+        exit_with_usage("--variant cannot be combined with --match.");
+    }
+    if let Some((first, second)) = engine_match {
+        run_match(first, second, eval_config, games, max_plies, pgn);
+        return;
+    }
+    if let Some(source) = tune {
+        run_tuning(&source, eval_config.unwrap_or_default(), games, depth, max_plies, tune_output);
+        return;
+    }
+    if let Some(path) = perft_suite {
+        run_perft_suite(&path);
+        return;
+    }
+    if tutorial {
+        let mut game = Game::new();
+        if let Some(theme) = theme {
+            game.set_theme(theme);
+        }
+        apply_preferences(&mut game, &preferences);
+        game.start_tutorial();
+        return;
+    }
+    if let Some(path) = puzzles {
+        let text = std::fs::read_to_string(&path).unwrap_or_else(|err| exit_with_error(&format!("Cannot read {}: {}", path, err)));
+        let puzzles = parse_puzzles(&text).unwrap_or_else(|err| exit_with_error(&err));
+        let mut game = Game::new();
+        if let Some(theme) = theme {
+            game.set_theme(theme);
+        }
+        apply_preferences(&mut game, &preferences);
+        game.start_puzzles(&puzzles);
+        return;
+    }
+    if replay.is_some() && (fen.is_some() || packed.is_some()) {
+        exit_with_usage("--replay cannot be combined with --fen or --packed.");
+    }
+    let mut game = match (fen, packed) {
+        (None, None) if replay.is_some() => replay_pgn(replay.as_deref().unwrap(), replay_game),
+        (None, None) => Game::new(),
+        (Some(fen), None) => Game::from_fen(&fen).unwrap_or_else(|err| {
+            eprintln!("Invalid FEN: {}", err);
+            std::process::exit(2);
+        }),
+        (None, Some(hex)) => PackedPosition::from_hex(&hex)
+            .and_then(|packed| Game::unpack(&packed))
+            .unwrap_or_else(|err| {
+                eprintln!("Invalid packed position: {}", err);
+                std::process::exit(2);
+            }),
+        (Some(_), Some(_)) => exit_with_usage("--fen and --packed cannot be combined."),
+    };
+    if watch.is_some() && (!computer.is_empty() || host.is_some() || connect.is_some()) {
+        exit_with_usage("--watch cannot be combined with --computer, --host or --connect.");
+    }
+    if replay.is_some() && (host.is_some() || connect.is_some()) {
+        exit_with_usage("--replay cannot be combined with --host or --connect.");
+    }
+    // the peer protocol does not carry the rules, both sides play standard chess
+    if variant != Variant::Standard && (host.is_some() || connect.is_some()) {
+        exit_with_usage("--variant cannot be combined with --host or --connect.");
+    }
+    game.set_variant(variant);
+    if let Some(path) = script {
+        if host.is_some() || connect.is_some() || watch.is_some() {
+            exit_with_usage("--script cannot be combined with --host, --connect or --watch.");
+        }
+        run_script_file(game, &path);
+        return;
+    }
+    match (host, connect) {
+        (Some(port), None) => {
+            println!("Waiting for a peer on port {}...", port);
+            let peer = if websocket { PeerConnection::host_websocket(port) } else { PeerConnection::host(port) };
+            let mut peer = peer.unwrap_or_else(|err| exit_with_error(&err));
+            let start = PeerMessage::Start { fen: game.fen(), guest_side: host_side.opposite(), clock };
+            peer.send(&start).unwrap_or_else(|err| exit_with_error(&err));
+            game.set_peer(peer, host_side.opposite());
+        }
+        (None, Some(address)) => {
+            let peer = if address.starts_with("ws://") {
+                PeerConnection::connect_websocket(&address)
+            } else {
+                PeerConnection::connect(&address)
+            };
+            let mut peer = peer.unwrap_or_else(|err| exit_with_error(&err));
+            let guest_side;
+            (game, guest_side, clock) = match peer.receive() {
+                Ok(PeerMessage::Start { fen, guest_side, clock }) => {
+                    (Game::from_fen(&fen).unwrap_or_else(|err| exit_with_error(&err)), guest_side, clock)
+                }
+                Ok(_) => exit_with_error("The host did not start the game."),
+                Err(err) => exit_with_error(&err),
+            };
+            println!("Connected, you play {:?}.", guest_side);
+            game.set_peer(peer, guest_side.opposite());
+        }
+        (Some(_), Some(_)) => exit_with_usage("--host and --connect cannot be combined."),
+        (None, None) => {}
+    }
+    if let Some(theme) = theme {
+        game.set_theme(theme);
+    }
+    apply_preferences(&mut game, &preferences);
+    // the cursor needs a terminal; piped input keeps working line by line
+    if !line_mode && std::io::stdin().is_terminal() && std::io::stdout().is_terminal() {
+        game.enable_cursor();
+    }
+    if let Some(logger) = csv_log {
+        game.add_observer(Box::new(logger));
+    }
+    if let Some(log) = event_log {
+        game.set_event_log(log);
+    }
+    if coach {
+        let humans = [Color::White, Color::Black].into_iter().filter(|side| !computer.contains(side)).collect();
+        game.add_observer(Box::new(TimeCoach::new(humans)));
+    }
+    if let Some(path) = stats {
+        let [engine] = computer[..] else {
+            exit_with_usage("--stats rates games against the computer, use it with --computer white or black.");
+        };
+        let tracker = RatingTracker::open(&path, engine.opposite(), depth).unwrap_or_else(|err| exit_with_error(&err));
+        game.add_observer(Box::new(tracker));
+    }
+    if let Some(control) = clock {
+        game.set_clock(control);
+    }
+    if let Some(timeout) = promotion_timeout {
+        game.set_promotion_timeout(timeout);
+    }
+    if let Some(port) = spectate {
+        start_spectator_server(&mut game, port);
+    }
+    if let Some(config) = eval_config {
+        game.set_eval_config(config);
+    }
+    if let Some(contempt) = contempt {
+        game.set_option(contempt);
+    }
+    game.set_search_info(search_info);
+    game.set_pondering(pondering);
+    game.set_eval_bar(eval_bar);
+    game.set_flipped(flipped);
+    game.set_auto_flip(auto_flip);
+    let is_beginner_level = computer.len() == 1 && depth <= BEGINNER_DEPTH;
+    game.set_tactic_alerts(alerts.unwrap_or(is_beginner_level));
+    for side in computer {
+        game.set_player(side, PlayerKind::Engine { depth });
+    }
+    game.set_skill_level(level);
+    if let Some((white, black)) = watch {
+        game.set_player(Color::White, PlayerKind::Engine { depth: white });
+        game.set_player(Color::Black, PlayerKind::Engine { depth: black });
+        game.set_move_delay(delay.unwrap_or(Duration::from_millis(DEFAULT_WATCH_DELAY_MS)));
+    } else if let Some(delay) = delay {
+        game.set_move_delay(delay);
+    }
+    if strict {
+        for warning in game.plausibility_warnings() {
+            println!("Warning: {}", warning);
+        }
+    }
     game.start();
-}
\ No newline at end of file
+}
+
+/// Reads a game from a PGN file and replays it, then rewinds to the start position so that the
+/// user can step through it with `next` and `prev`.
+///
+/// # Arguments
+/// - `path`: The PGN file.
+/// - `number`: Which game of the file to replay, starting at `1`.
+fn replay_pgn(path: &str, number: usize) -> Game {
+    let text = std::fs::read_to_string(path).unwrap_or_else(|err| exit_with_error(&format!("Cannot read {}: {}", path, err)));
+    let mut games = parse_pgn(&text).unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    if number > games.len() {
+        exit_with_error(&format!("{} has {} game(s), there is no game {}.", path, games.len(), number));
+    }
+    let pgn = games.swap_remove(number - 1);
+    let mut game = pgn.replay().unwrap_or_else(|err| exit_with_error(&err.to_string()));
+    for (tag, value) in &pgn.tags {
+        println!("{}: {}", tag, value);
+    }
+    println!("{} moves loaded ({}), type next and prev to step through them.", pgn.moves.len(), pgn.result);
+    game.goto(0).unwrap_or_else(|err| exit_with_error(&err));
+    game
+}
+
+/// Parses the two search depths of `--match` and `--watch`, e.g. `2,3`.
+fn parse_depth_pair(value: &str) -> Option<(u8, u8)> {
+    let (first, second) = value.split_once(',')?;
+    let depth = |value: &str| value.trim().parse::<u8>().ok().filter(|depth| *depth > 0);
+    Some((depth(first)?, depth(second)?))
+}
+
+/// Plays an engine match between two search depths, printing each result as it comes in and
+/// writing the games to a PGN file if one is given. The first engine uses `eval` if given,
+/// so that a set of weights can be measured against the default one.
+fn run_match(first: u8, second: u8, eval: Option<EvalConfig>, games: usize, max_plies: Option<usize>, pgn: Option<String>) {
+    let mut pgn_file = pgn.map(|path| {
+        std::fs::File::create(&path).unwrap_or_else(|err| exit_with_error(&format!("Cannot create {}: {}", path, err)))
+    });
+    let (mut first, second) = (EngineConfig::with_depth(first), EngineConfig::with_depth(second));
+    if let Some(eval) = eval {
+        first.name.push_str(" (custom eval)");
+        first.eval = eval;
+    }
+    println!("Match: {} vs {}, {} games", first.name, second.name, games);
+    let mut runner = MatchRunner::new(first.clone(), second, games);
+    if let Some(max_plies) = max_plies {
+        runner.set_max_plies(max_plies);
+    }
+    let stats = runner.run(|game, stats| {
+        println!(
+            "Game {}: {} - {} {} ({}, {} plies), {} {}",
+            game.round, game.white, game.black, game.outcome.pgn(), game.termination, game.moves.len(), first.name, stats
+        );
+        if let Some(file) = pgn_file.as_mut() {
+            if let Err(err) = std::io::Write::write_all(file, game.pgn().as_bytes()) {
+                exit_with_error(&format!("Cannot write the PGN file: {}", err));
+            }
+        }
+    });
+    println!("Final: {} {}", first.name, stats);
+}
+
+/// Tunes the evaluation weights on a file of positions with known results, or on the
+/// positions of self-play games at `depth`, and writes the tuned weights as an evaluation
+/// config to `output`, or prints them.
+fn run_tuning(source: &str, start: EvalConfig, games: usize, depth: u8, max_plies: Option<usize>, output: Option<String>) {
+    let positions = if source == "selfplay" {
+        let engine = EngineConfig { eval: start, ..EngineConfig::with_depth(depth) };
+        let mut runner = MatchRunner::new(engine.clone(), engine, games);
+        if let Some(max_plies) = max_plies {
+            runner.set_max_plies(max_plies);
+        }
+        let mut positions = Vec::new();
+        runner.run(|game, _| {
+            println!("Self-play game {}: {} ({} plies)", game.round, game.outcome.pgn(), game.moves.len());
+            positions.extend(self_play_positions(game));
+        });
+        positions
+    } else {
+        let text = std::fs::read_to_string(source).unwrap_or_else(|err| exit_with_error(&format!("Cannot read {}: {}", source, err)));
+        parse_tuning_positions(&text).unwrap_or_else(|err| exit_with_error(&format!("Invalid positions in {}: {}", source, err)))
+    };
+    println!("Tuning on {} positions...", positions.len());
+    let mut tuner = Tuner::new(positions);
+    let tuned = tuner.tune(start, |step, error| println!("Step {:>2}: error {:.6}", step, error));
+    match output {
+        Some(path) => {
+            std::fs::write(&path, tuned.to_toml()).unwrap_or_else(|err| exit_with_error(&format!("Cannot write {}: {}", path, err)));
+            println!("Tuned weights written to {}, play with them using --eval {}.", path, path);
+        }
+        None => print!("{}", tuned.to_toml()),
+    }
+}
+
+/// Plays a script of moves and commands without a terminal, then prints the moves, the final
+/// position and the result. Exits with status `1` at the first line that cannot be played.
+fn run_script_file(game: Game, path: &str) {
+    let script = if path == "-" {
+        std::io::read_to_string(std::io::stdin()).unwrap_or_else(|err| exit_with_error(&format!("Cannot read standard input: {}", err)))
+    } else {
+        std::fs::read_to_string(path).unwrap_or_else(|err| exit_with_error(&format!("Cannot read {}: {}", path, err)))
+    };
+    let mut driver = GameDriver::new(game);
+    let outcome = run_script(&mut driver, &script);
+    let game = driver.game();
+    println!("Moves: {}", game.move_list());
+    println!("FEN: {}", game.fen());
+    match game.game_result() {
+        Some(result) => println!("Result: {}", result),
+        None => println!("Result: in progress, {:?} to move", game.turn()),
+    }
+    if let Err(err) = outcome {
+        exit_with_error(&err.to_string());
+    }
+}
+
+/// Runs every case of a perft suite file in order and stops at the first count that differs,
+/// printing its divide breakdown and exiting with status `1`.
+fn run_perft_suite(path: &str) {
+    let text = std::fs::read_to_string(path).unwrap_or_else(|err| exit_with_error(&format!("Cannot read {}: {}", path, err)));
+    let cases = parse_perft_suite(&text).unwrap_or_else(|err| exit_with_error(&err));
+    for case in &cases {
+        let started = std::time::Instant::now();
+        match case.run() {
+            Ok(nodes) => println!("ok   line {}: depth {}, {} nodes in {:.2}s", case.line, case.depth, nodes, started.elapsed().as_secs_f64()),
+            Err(mismatch) => exit_with_error(&mismatch.to_string()),
+        }
+    }
+    println!("All {} cases passed.", cases.len());
+}
+
+/// Serves the game to spectators over HTTP on the given port.
+#[cfg(feature = "spectator")]
+fn start_spectator_server(game: &mut Game, port: u16) {
+    let server = SpectatorServer::start(port).unwrap_or_else(|err| exit_with_error(&err));
+    println!("Spectators can watch at http://localhost:{}/", server.address().port());
+    game.publish_to(server.snapshot());
+}
+
+#[cfg(not(feature = "spectator"))]
+fn start_spectator_server(_game: &mut Game, _port: u16) {
+    exit_with_usage("--spectate needs a build with the spectator feature.");
+}
+
+/// Replaces the built-in tablebase with the Syzygy tables of a directory.
+#[cfg(feature = "syzygy")]
+fn load_syzygy_tables(directory: &str) {
+    let tables = SyzygyTablebase::open(directory).unwrap_or_else(|err| exit_with_error(&err));
+    println!("Syzygy tables loaded, up to {} pieces.", tables.max_pieces());
+    set_tablebase(tables).unwrap_or_else(|err| exit_with_error(&err));
+}
+
+#[cfg(not(feature = "syzygy"))]
+fn load_syzygy_tables(_directory: &str) {
+    exit_with_usage("--syzygy needs a build with the syzygy feature.");
+}
+
+/// Applies the command aliases and the move notation of the preferences to the game.
+fn apply_preferences(game: &mut Game, preferences: &Preferences) {
+    game.set_command_aliases(preferences.aliases().clone());
+    game.set_notation(preferences.notation());
+}
+
+/// Asks for the time control at startup until a valid one, or an empty line for no clock,
+/// is entered.
+fn prompt_time_control() -> Option<TimeControl> {
+    loop {
+        println!("Time control in minutes, with an optional bonus in seconds (5, 5+3 increment, 5d3 delay, 5b3 Bronstein), or empty for no clock:");
+        let mut line = String::new();
+        if std::io::stdin().read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+            return None;
+        }
+        match TimeControl::parse(&line) {
+            Ok(control) => return Some(control),
+            Err(err) => println!("{}", err),
+        }
+    }
+}
+
+/// Prints an error and exits with status `1`.
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+/// Prints an error with the usage line and exits with status `2`.
+fn exit_with_usage(message: &str) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    std::process::exit(2);
+}
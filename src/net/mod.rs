@@ -0,0 +1,3 @@
+pub(crate) mod peer;
+#[cfg(feature = "spectator")]
+pub(crate) mod spectator;
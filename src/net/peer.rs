@@ -0,0 +1,175 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use serde::{Deserialize, Serialize};
+use tungstenite::{Message, WebSocket};
+use crate::engine::clock::TimeControl;
+use crate::engine::game::PackedMove;
+use crate::pieces::common::Color;
+
+/// A message exchanged between two networked games, sent as one JSON object per line or per
+/// WebSocket frame.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PeerMessage {
+    /// Sent by the host once the peer connects: the position both games start from, the side
+    /// the connecting player plays and the time control, if the game is played with clocks.
+    Start { fen: String, guest_side: Color, clock: Option<TimeControl> },
+    /// A move and the Zobrist hash of the sender's position after playing it.
+    Move { packed: PackedMove, hash: u64 },
+    /// Sent when the hashes do not match: asks the peer for its position.
+    ResyncRequest,
+    /// The sender's position, replacing the receiver's.
+    Resync { fen: String },
+}
+
+/// How the messages travel: one JSON line per message over plain TCP, or one JSON text frame
+/// per message over a WebSocket, which passes through web proxies and firewalls.
+enum Transport {
+    Tcp { reader: BufReader<TcpStream>, writer: TcpStream },
+    WebSocket(Box<WebSocket<TcpStream>>),
+}
+
+/// A connection to the other player's game.
+pub struct PeerConnection {
+    transport: Transport,
+}
+
+impl PeerConnection {
+    /// Waits on the given port until one peer connects.
+    ///
+    /// # Returns
+    /// - `Ok(PeerConnection)`: The accepted connection.
+    /// - `Err(String)`: If the port cannot be bound or the connection fails.
+    pub fn host(port: u16) -> Result<Self, String> {
+        Self::from_stream(Self::accept(port)?)
+    }
+
+    /// Connects to a game hosted with [`PeerConnection::host`].
+    ///
+    /// # Arguments
+    /// - `address`: The host and port, e.g. `192.168.1.10:7878`.
+    pub fn connect(address: &str) -> Result<Self, String> {
+        let stream = TcpStream::connect(address).map_err(|err| format!("Cannot connect to {}: {}", address, err))?;
+        Self::from_stream(stream)
+    }
+
+    /// Waits on the given port until one peer connects and completes the WebSocket handshake.
+    ///
+    /// # Returns
+    /// - `Ok(PeerConnection)`: The accepted connection.
+    /// - `Err(String)`: If the port cannot be bound, the connection fails or the peer does not
+    ///   speak the WebSocket protocol.
+    pub fn host_websocket(port: u16) -> Result<Self, String> {
+        let socket = tungstenite::accept(Self::accept(port)?).map_err(|err| format!("WebSocket handshake failed: {}", err))?;
+        Ok(Self { transport: Transport::WebSocket(Box::new(socket)) })
+    }
+
+    /// Connects to a game hosted with [`PeerConnection::host_websocket`].
+    ///
+    /// # Arguments
+    /// - `url`: The WebSocket URL of the host, e.g. `ws://192.168.1.10:7878`.
+    pub fn connect_websocket(url: &str) -> Result<Self, String> {
+        let address = url.strip_prefix("ws://")
+            .map(|rest| rest.split('/').next().unwrap_or(rest))
+            .ok_or_else(|| format!("Invalid WebSocket URL {}, expected ws://<host>:<port>.", url))?;
+        let stream = TcpStream::connect(address).map_err(|err| format!("Cannot connect to {}: {}", address, err))?;
+        let (socket, _) = tungstenite::client(url, stream).map_err(|err| format!("WebSocket handshake failed: {}", err))?;
+        Ok(Self { transport: Transport::WebSocket(Box::new(socket)) })
+    }
+
+    fn accept(port: u16) -> Result<TcpStream, String> {
+        let listener = TcpListener::bind(("0.0.0.0", port)).map_err(|err| format!("Cannot listen on port {}: {}", port, err))?;
+        let (stream, _) = listener.accept().map_err(|err| format!("Cannot accept a peer: {}", err))?;
+        Ok(stream)
+    }
+
+    fn from_stream(stream: TcpStream) -> Result<Self, String> {
+        let writer = stream.try_clone().map_err(|err| err.to_string())?;
+        Ok(Self { transport: Transport::Tcp { reader: BufReader::new(stream), writer } })
+    }
+
+    /// Sends a message to the peer.
+    pub fn send(&mut self, message: &PeerMessage) -> Result<(), String> {
+        let line = serde_json::to_string(message).map_err(|err| err.to_string())?;
+        let sent = match &mut self.transport {
+            Transport::Tcp { writer, .. } => writeln!(writer, "{}", line).map_err(|err| err.to_string()),
+            Transport::WebSocket(socket) => socket.send(Message::text(line)).map_err(|err| err.to_string()),
+        };
+        sent.map_err(|err| format!("Cannot send to the peer: {}", err))
+    }
+
+    /// Blocks until the peer sends a message.
+    ///
+    /// # Returns
+    /// - `Ok(PeerMessage)`: The next message.
+    /// - `Err(String)`: If the connection is closed or the message is malformed.
+    pub fn receive(&mut self) -> Result<PeerMessage, String> {
+        let line = match &mut self.transport {
+            Transport::Tcp { reader, .. } => {
+                let mut line = String::new();
+                match reader.read_line(&mut line) {
+                    Ok(0) => return Err("The peer closed the connection.".to_string()),
+                    Ok(_) => line,
+                    Err(err) => return Err(format!("Cannot receive from the peer: {}", err)),
+                }
+            }
+            Transport::WebSocket(socket) => loop {
+                match socket.read() {
+                    Ok(Message::Text(text)) => break text,
+                    // pings are answered by the socket itself
+                    Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_)) => continue,
+                    Ok(Message::Binary(_)) => return Err("Invalid message from the peer: binary frame".to_string()),
+                    Ok(Message::Close(_)) | Err(tungstenite::Error::ConnectionClosed) => {
+                        return Err("The peer closed the connection.".to_string());
+                    }
+                    Err(err) => return Err(format!("Cannot receive from the peer: {}", err)),
+                }
+            },
+        };
+        serde_json::from_str(&line).map_err(|err| format!("Invalid message from the peer: {}", err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_messages_travel_over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let client = std::thread::spawn(move || PeerConnection::connect(&address).unwrap());
+        let mut host = PeerConnection::from_stream(listener.accept().unwrap().0).unwrap();
+        let mut client = client.join().unwrap();
+
+        let from = square("e2");
+        let to = square("e4");
+        let message = PeerMessage::Move { packed: PackedMove::new(from, to, None), hash: 42 };
+        host.send(&message).unwrap();
+        assert_eq!(client.receive(), Ok(message));
+        drop(host);
+        assert!(client.receive().is_err());
+    }
+
+    #[test]
+    fn test_handshake_travels_over_websocket() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}/", listener.local_addr().unwrap());
+        let client = std::thread::spawn(move || PeerConnection::connect_websocket(&url).unwrap());
+        let socket = tungstenite::accept(listener.accept().unwrap().0).unwrap();
+        let mut host = PeerConnection { transport: Transport::WebSocket(Box::new(socket)) };
+        let mut client = client.join().unwrap();
+
+        let start = PeerMessage::Start {
+            fen: "4k3/8/8/8/8/8/8/4K3 w - - 0 1".to_string(),
+            guest_side: Color::White,
+            clock: Some(TimeControl::parse("5+3").unwrap()),
+        };
+        host.send(&start).unwrap();
+        assert_eq!(client.receive(), Ok(start));
+        client.send(&PeerMessage::ResyncRequest).unwrap();
+        assert_eq!(host.receive(), Ok(PeerMessage::ResyncRequest));
+        drop(host);
+        assert!(client.receive().is_err());
+    }
+}
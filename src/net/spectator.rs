@@ -0,0 +1,178 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::Duration;
+use crate::engine::game::GameSnapshot;
+
+/// How often the spectator page reloads itself, in seconds.
+const REFRESH_SECONDS: u32 = 1;
+/// How long a spectator may take to send its request before the connection is dropped.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A tiny HTTP server that lets a game in progress be watched from a browser. `/` serves a
+/// page showing the board that reloads itself every second, `/state.json` the
+/// [`GameSnapshot`] as JSON.
+///
+/// Requests are answered one at a time on a thread of the server's own, from the snapshot
+/// the game publishes after every move with [`crate::Game::publish_to`].
+pub struct SpectatorServer {
+    address: SocketAddr,
+    snapshot: Arc<RwLock<GameSnapshot>>,
+}
+
+impl SpectatorServer {
+    /// Starts serving on the given port, on every interface.
+    ///
+    /// # Arguments
+    /// - `port`: The port to listen on; `0` picks a free one, see [`SpectatorServer::address`].
+    ///
+    /// # Returns
+    /// - `Ok(SpectatorServer)`: The running server.
+    /// - `Err(String)`: If the port cannot be bound.
+    pub fn start(port: u16) -> Result<Self, String> {
+        let listener = TcpListener::bind(("0.0.0.0", port)).map_err(|err| format!("Cannot listen on port {}: {}", port, err))?;
+        let address = listener.local_addr().map_err(|err| err.to_string())?;
+        let snapshot = Arc::new(RwLock::new(GameSnapshot::default()));
+        let shared = Arc::clone(&snapshot);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Err(err) = serve(stream, &shared) {
+                    tracing::debug!(%err, "spectator request failed");
+                }
+            }
+        });
+        Ok(Self { address, snapshot })
+    }
+
+    /// Returns the address the server listens on.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Returns the snapshot the server shows, for the game to publish to.
+    pub fn snapshot(&self) -> Arc<RwLock<GameSnapshot>> {
+        Arc::clone(&self.snapshot)
+    }
+}
+
+/// Reads one request and answers it, closing the connection.
+fn serve(stream: TcpStream, snapshot: &RwLock<GameSnapshot>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // the headers are not used, but are read so that the client sees the whole request taken
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+        header.clear();
+    }
+    let snapshot = snapshot.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+    let (status, content_type, body) = respond(&request, &snapshot);
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status, content_type, body.len(), body
+    )?;
+    stream.flush()
+}
+
+/// Answers the request line of a spectator.
+///
+/// # Arguments
+/// - `request`: The first line of the request, e.g. `GET / HTTP/1.1`.
+/// - `snapshot`: The state of the game to show.
+///
+/// # Returns
+/// - The status, the content type and the body of the response.
+fn respond(request: &str, snapshot: &GameSnapshot) -> (&'static str, &'static str, String) {
+    let mut words = request.split_whitespace();
+    let (method, path) = (words.next(), words.next().map(|path| path.split('?').next().unwrap_or_default()));
+    match (method, path) {
+        (Some("GET"), Some("/")) => ("200 OK", "text/html; charset=utf-8", page(snapshot)),
+        (Some("GET"), Some("/state.json")) => {
+            let json = serde_json::to_string(snapshot).expect("a snapshot always serializes");
+            ("200 OK", "application/json", json)
+        }
+        (Some("GET"), _) => ("404 Not Found", "text/plain; charset=utf-8", "Not found\n".to_string()),
+        _ => ("405 Method Not Allowed", "text/plain; charset=utf-8", "Only GET is supported\n".to_string()),
+    }
+}
+
+/// Writes the spectator page: the board with White at the bottom, the side to move, the
+/// clocks, the moves and the result.
+fn page(snapshot: &GameSnapshot) -> String {
+    let board = snapshot.board.iter().zip((1..=8).rev())
+        .map(|(rank, number)| format!("{} {}", number, rank.chars().map(glyph).map(String::from).collect::<Vec<_>>().join(" ")))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let status = match &snapshot.result {
+        Some(result) => result.clone(),
+        None => format!("{:?} to move", snapshot.turn),
+    };
+    let clock = snapshot.clock.as_deref().map(|clock| format!("<p>{}</p>", clock)).unwrap_or_default();
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><meta http-equiv=\"refresh\" content=\"{}\">\
+        <title>ChessGame</title></head>\n<body style=\"font-family: sans-serif\">\n\
+        <pre style=\"font-size: 2em; line-height: 1.2\">{}\n  a b c d e f g h</pre>\n\
+        <p><b>{}</b></p>{}\n<p>{}</p>\n<p><small>{}</small></p>\n</body></html>\n",
+        REFRESH_SECONDS, board, status, clock, snapshot.moves, snapshot.fen
+    )
+}
+
+/// Returns the chess glyph of a FEN letter, or a dot for an empty square.
+fn glyph(symbol: char) -> char {
+    match symbol {
+        'P' => '♙',
+        'N' => '♘',
+        'B' => '♗',
+        'R' => '♖',
+        'Q' => '♕',
+        'K' => '♔',
+        'p' => '♟',
+        'n' => '♞',
+        'b' => '♝',
+        'r' => '♜',
+        'q' => '♛',
+        'k' => '♚',
+        _ => '·',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use crate::engine::game::Game;
+
+    #[test]
+    fn test_routes_answer_the_page_and_the_state() {
+        let snapshot = GameSnapshot::default();
+        let (status, _, page) = respond("GET / HTTP/1.1\r\n", &snapshot);
+        assert_eq!(status, "200 OK");
+        assert!(page.contains("http-equiv=\"refresh\""));
+        assert!(page.contains("1 ♖ ♘ ♗ ♕ ♔ ♗ ♘ ♖"), "{}", page);
+        assert!(page.contains("White to move"));
+        assert_eq!(respond("GET /missing HTTP/1.1", &snapshot).0, "404 Not Found");
+        assert_eq!(respond("POST / HTTP/1.1", &snapshot).0, "405 Method Not Allowed");
+    }
+
+    #[test]
+    fn test_server_serves_the_published_snapshot() {
+        let server = SpectatorServer::start(0).unwrap();
+        let mut game = Game::new();
+        game.play_san("d4").unwrap();
+        game.publish_to(server.snapshot());
+        let mut stream = TcpStream::connect(("127.0.0.1", server.address().port())).unwrap();
+        stream.write_all(b"GET /state.json HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let state: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(state["moves"], "1. d4");
+        assert_eq!(state["turn"], "Black");
+        assert_eq!(state["version"], 1);
+    }
+}
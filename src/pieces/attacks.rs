@@ -0,0 +1,325 @@
+use std::sync::OnceLock;
+use crate::bitboard::BitBoard;
+use crate::pieces::bishop::Bishop;
+use crate::pieces::common::{Color, PossibleMoves};
+use crate::pieces::king::King;
+use crate::pieces::knight::Knight;
+use crate::pieces::pawn::Pawn;
+use crate::pieces::rock::Rock;
+use crate::square::{File, Rank, Square};
+
+// Magic multipliers found offline with a sparse random search, one per square from `a1` to `h8`.
+// Multiplying the relevant blockers by the magic maps every blocker configuration of a square to
+// a distinct slot (or to a slot sharing the same attack set) in the top bits of the product.
+
+const ROOK_MAGICS: [u64; 64] = [
+    0x1080004008801020, 0x0840092002c03000, 0x1900200010400900, 0x0880100008000480,
+    0x4200100420080200, 0x8100020100080400, 0x0200040110886200, 0x0200008040220411,
+    0x0404800084400220, 0x0000401000402000, 0x0086001081220440, 0x0408800800100280,
+    0x000a001201040820, 0x8848800200840080, 0x4001000100040200, 0x0442000102105084,
+    0x9080010020804100, 0x0040404000201009, 0x0000808010002009, 0x2200090021d00100,
+    0x0008008008040080, 0x0004004002010040, 0x0011040008015042, 0x00000a0001768104,
+    0x0000800080204009, 0x2010004140002001, 0x9800200280100080, 0x1000100080080080,
+    0x0442000a00049020, 0x2100040080020080, 0x0800120400900148, 0x0010040a00128541,
+    0x2800804000800030, 0x1010002000400041, 0x4000200011004100, 0x0610008410800800,
+    0x0400802402800800, 0xc100020080800400, 0x0002000802000401, 0x0182085882000401,
+    0x0220204000808000, 0x2860100040024022, 0x0001002004110040, 0x99101042000a0020,
+    0x0004080004008080, 0x0010040002008080, 0x2012004881020004, 0x8300842444820011,
+    0x0088403882010200, 0x0820400080210100, 0x0110910040a00300, 0x0801100280080480,
+    0x0242009008200600, 0x1002000489500200, 0x0040800200010080, 0x0091800041000080,
+    0x0000209300488001, 0x04c1002414824001, 0x020020000b001041, 0x7000100004200901,
+    0x8002002004100802, 0x30010002084c0007, 0x0888221800813004, 0x4000002840840112,
+];
+
+const BISHOP_MAGICS: [u64; 64] = [
+    0xa010041108003100, 0x006082020a002900, 0x6810010619200000, 0x08281a0520000408,
+    0x0001104001000400, 0x0018901008048400, 0x00040a0210245280, 0x000200210808a402,
+    0x9140048410821200, 0x0800091010820041, 0x20504804832202c0, 0x0100091401081000,
+    0x8021011140000012, 0x0810020804450400, 0x208b0542109008a2, 0x0080084a08040204,
+    0x0040e2a80811244c, 0x2505022008008108, 0x0430220100420040, 0x010a040420220040,
+    0x1105000290400000, 0x0093001200822120, 0x4000a62048043004, 0x280120048a015004,
+    0x006090002a020814, 0x44042000240800d0, 0x01102800040a4400, 0x1004080080220040,
+    0x0001001011004024, 0x0010044000805040, 0x0914041200820100, 0x0004821012821480,
+    0x0024040500c05021, 0x0088611002080200, 0x0116080a00040020, 0x4000020080080080,
+    0x2450450140840040, 0x0000880201484100, 0x0222020404020092, 0x8081110600002e00,
+    0x2842101105000801, 0x1100809008001025, 0x00020202221c0400, 0x0422014022009020,
+    0x0210046102100c00, 0xc004008082029102, 0x00aa461801101200, 0x0404080080201108,
+    0x020542108c205002, 0x0410544804100100, 0x0040910841100000, 0x0400200042021100,
+    0x00004204850400c0, 0x0200100410a42102, 0x1040020801210102, 0x0805040410420000,
+    0x2884804130100200, 0x800c262201242000, 0x1058000194108800, 0x0014221054420204,
+    0x0104000012a02200, 0x0200881003300100, 0x0140400202840100, 0x0402020801010201,
+];
+
+/// Lookup parameters of a single square.
+struct Magic {
+    /// The squares whose occupancy changes the attacks, board edges excluded.
+    mask: BitBoard,
+    magic: u64,
+    /// `64` minus the number of squares in `mask`.
+    shift: u32,
+    /// Start of this square's slots in the shared attack table.
+    offset: usize,
+}
+
+impl Magic {
+    /// Returns the index of the attack set for the given occupancy.
+    #[inline]
+    fn index(&self, occupancy: BitBoard) -> usize {
+        self.offset + (u64::from((occupancy & self.mask) * self.magic) >> self.shift) as usize
+    }
+}
+
+/// Precomputed rook and bishop attacks for every square and blocker configuration.
+struct SlidingTables {
+    rook: Vec<Magic>,
+    bishop: Vec<Magic>,
+    attacks: Vec<BitBoard>,
+}
+
+static TABLES: OnceLock<SlidingTables> = OnceLock::new();
+
+/// Returns the squares a rook on `square` attacks, up to and including the first blocker in
+/// each direction, whatever its color.
+///
+/// The lookup tables are built on first use and shared for the rest of the program.
+///
+/// # Arguments
+/// - `square`: The `Square` of the rook.
+/// - `occupancy`: Every occupied square on the board; the rook's own square is ignored.
+pub(crate) fn rook(square: Square, occupancy: BitBoard) -> BitBoard {
+    let tables = tables();
+    tables.attacks[tables.rook[usize::from(square)].index(occupancy)]
+}
+
+/// Returns the squares a bishop on `square` attacks, up to and including the first blocker in
+/// each direction, whatever its color.
+///
+/// # Arguments
+/// - `square`: The `Square` of the bishop.
+/// - `occupancy`: Every occupied square on the board; the bishop's own square is ignored.
+pub(crate) fn bishop(square: Square, occupancy: BitBoard) -> BitBoard {
+    let tables = tables();
+    tables.attacks[tables.bishop[usize::from(square)].index(occupancy)]
+}
+
+/// Returns the squares a queen on `square` attacks: the union of the rook and bishop attacks.
+pub(crate) fn queen(square: Square, occupancy: BitBoard) -> BitBoard {
+    rook(square, occupancy) | bishop(square, occupancy)
+}
+
+/// Precomputed attacks of the pieces that do not slide, for every square.
+struct LeaperTables {
+    knight: [BitBoard; 64],
+    king: [BitBoard; 64],
+    /// The two capture squares of a pawn, indexed by the pawn's color.
+    pawn: [[BitBoard; 64]; 2],
+}
+
+static LEAPERS: OnceLock<LeaperTables> = OnceLock::new();
+
+/// Returns the squares a knight on `square` attacks.
+pub(crate) fn knight(square: Square) -> BitBoard {
+    leapers().knight[usize::from(square)]
+}
+
+/// Returns the squares a king on `square` attacks, castling aside.
+pub(crate) fn king(square: Square) -> BitBoard {
+    leapers().king[usize::from(square)]
+}
+
+/// Returns the squares a pawn of `color` on `square` attacks diagonally.
+pub(crate) fn pawn(square: Square, color: Color) -> BitBoard {
+    leapers().pawn[usize::from(color)][usize::from(square)]
+}
+
+/// Precomputed lines between every pair of squares sharing a rank, file or diagonal.
+struct LineTables {
+    /// The squares strictly between two squares, indexed by both squares.
+    between: Vec<[BitBoard; 64]>,
+    /// The whole line through two squares, from board edge to board edge.
+    line: Vec<[BitBoard; 64]>,
+}
+
+static LINES: OnceLock<LineTables> = OnceLock::new();
+
+/// Returns the squares strictly between `a` and `b` when they share a rank, file or diagonal,
+/// e.g. the squares a piece may block a check or a pin on.
+///
+/// # Returns
+/// - `BitBoard`: The squares between, empty if the squares are not aligned or are neighbours.
+pub(crate) fn ray_between(a: Square, b: Square) -> BitBoard {
+    lines().between[usize::from(a)][usize::from(b)]
+}
+
+/// Returns the whole rank, file or diagonal through `a` and `b`, both included, e.g. the line
+/// a pinned piece may still move along.
+///
+/// # Returns
+/// - `BitBoard`: The line from edge to edge, empty if the squares are not aligned or the same.
+pub(crate) fn line_through(a: Square, b: Square) -> BitBoard {
+    lines().line[usize::from(a)][usize::from(b)]
+}
+
+/// Returns the pieces standing strictly between `a` and `b` on their line, so that a slider on
+/// one square reaches the other when there are none.
+///
+/// # Arguments
+/// - `occupancy`: Every occupied square on the board.
+pub(crate) fn blockers_between(a: Square, b: Square, occupancy: BitBoard) -> BitBoard {
+    ray_between(a, b) & occupancy
+}
+
+fn lines() -> &'static LineTables {
+    LINES.get_or_init(|| {
+        let mut tables = LineTables { between: vec![[BitBoard::empty(); 64]; 64], line: vec![[BitBoard::empty(); 64]; 64] };
+        let squares = || (0..64).filter_map(|idx| Square::try_from(idx).ok());
+        for a in squares() {
+            for b in squares().filter(|b| *b != a) {
+                let (a_bit, b_bit) = (BitBoard::from(a), BitBoard::from(b));
+                for ray_attacks in [Rock::ray_attacks, Bishop::ray_attacks] {
+                    if (ray_attacks(a, BitBoard::empty()) & b_bit).is_empty() {
+                        continue;
+                    }
+                    // each square blocks the other's rays, leaving only the shared stretch
+                    tables.between[usize::from(a)][usize::from(b)] = ray_attacks(a, b_bit) & ray_attacks(b, a_bit);
+                    tables.line[usize::from(a)][usize::from(b)] = (ray_attacks(a, BitBoard::empty()) & ray_attacks(b, BitBoard::empty())) | a_bit | b_bit;
+                }
+            }
+        }
+        tables
+    })
+}
+
+fn leapers() -> &'static LeaperTables {
+    LEAPERS.get_or_init(|| {
+        let mut tables = LeaperTables { knight: [BitBoard::empty(); 64], king: [BitBoard::empty(); 64], pawn: [[BitBoard::empty(); 64]; 2] };
+        for square in (0..64).filter_map(|idx| Square::try_from(idx).ok()) {
+            let idx = usize::from(square);
+            let piece = BitBoard::from(square);
+            let empty = BitBoard::empty();
+            tables.knight[idx] = Knight::get_moves(&piece, square, &empty, &empty, &Color::White);
+            // the king's own square is in its neighbourhood mask unless it counts as occupied
+            tables.king[idx] = King::get_moves(&piece, square, &piece, &empty, &Color::White);
+            for color in [Color::White, Color::Black] {
+                tables.pawn[usize::from(color)][idx] = Pawn::get_capture(&piece, square, &empty, &!piece, &color);
+            }
+        }
+        tables
+    })
+}
+
+fn tables() -> &'static SlidingTables {
+    TABLES.get_or_init(|| {
+        let mut attacks = Vec::new();
+        let rook = build(&ROOK_MAGICS, rook_mask, Rock::ray_attacks, &mut attacks);
+        let bishop = build(&BISHOP_MAGICS, bishop_mask, Bishop::ray_attacks, &mut attacks);
+        SlidingTables { rook, bishop, attacks }
+    })
+}
+
+/// Fills the attack table of one piece type for every square, using the slow ray computation
+/// once per blocker configuration.
+///
+/// # Panics
+/// If a magic maps two configurations with different attacks to the same slot.
+fn build(
+    magics: &[u64; 64],
+    mask_of: fn(Square) -> BitBoard,
+    ray_attacks: fn(Square, BitBoard) -> BitBoard,
+    attacks: &mut Vec<BitBoard>,
+) -> Vec<Magic> {
+    (0..64)
+        .map(|idx| {
+            let square = Square::try_from(idx).expect("index is on the board");
+            let mask = mask_of(square);
+            let magic = Magic { mask, magic: magics[idx], shift: 64 - mask.count(), offset: attacks.len() };
+            attacks.resize(attacks.len() + (1 << mask.count()), BitBoard::empty());
+            let mut filled = vec![false; 1 << mask.count()];
+            // enumerate every subset of the mask (Carry-Rippler)
+            let mut blockers = BitBoard::empty();
+            loop {
+                let slot = magic.index(blockers);
+                let expected = ray_attacks(square, blockers);
+                assert!(
+                    !filled[slot - magic.offset] || attacks[slot] == expected,
+                    "magic collision on {}", square
+                );
+                filled[slot - magic.offset] = true;
+                attacks[slot] = expected;
+                blockers = (blockers - mask) & mask;
+                if blockers.is_empty() {
+                    break;
+                }
+            }
+            magic
+        })
+        .collect()
+}
+
+/// The rook's relevant blockers: its rank and file, without the board edges and its own square.
+fn rook_mask(square: Square) -> BitBoard {
+    let empty = Rock::ray_attacks(square, BitBoard::empty());
+    let file_edges = BitBoard::from(File::A) | BitBoard::from(File::H);
+    let rank_edges = BitBoard::from(Rank::One) | BitBoard::from(Rank::Eight);
+    (empty & BitBoard::from(square.rank()) & !file_edges) | (empty & BitBoard::from(square.file()) & !rank_edges)
+}
+
+/// The bishop's relevant blockers: its diagonals, without the board edges and its own square.
+fn bishop_mask(square: Square) -> BitBoard {
+    let edges = BitBoard::from(File::A) | BitBoard::from(File::H) | BitBoard::from(Rank::One) | BitBoard::from(Rank::Eight);
+    Bishop::ray_attacks(square, BitBoard::empty()) & !edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::square;
+
+    #[test]
+    fn test_lookups_match_ray_attacks() {
+        // a few pseudo-random occupancies per square
+        let mut state = 0x2545F4914F6CDD1Du64;
+        for idx in 0..64 {
+            let square = Square::try_from(idx).unwrap();
+            for _ in 0..16 {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let occupancy = BitBoard::new(state & state.rotate_left(23));
+                assert_eq!(rook(square, occupancy), Rock::ray_attacks(square, occupancy));
+                assert_eq!(bishop(square, occupancy), Bishop::ray_attacks(square, occupancy));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rook_attacks_stop_at_blockers() {
+        let d4 = Square::new(File::D, Rank::Four);
+        let occupancy = BitBoard::from(Square::new(File::D, Rank::Six)) | BitBoard::from(Square::new(File::B, Rank::Four));
+        let expected = [
+            "d1", "d2", "d3", "d5", "d6", "b4", "c4", "e4", "f4", "g4", "h4",
+        ].iter().fold(BitBoard::empty(), |board, name| board | BitBoard::from(square(name)));
+        assert_eq!(rook(d4, occupancy), expected);
+        assert_eq!(queen(d4, occupancy), expected | bishop(d4, occupancy));
+    }
+
+    #[test]
+    fn test_lines_between_and_through_squares() {
+        let squares = |names: &[&str]| names.iter().fold(BitBoard::empty(), |board, name| board | BitBoard::from(square(name)));
+        assert_eq!(ray_between(square("a1"), square("d4")), squares(&["b2", "c3"]));
+        assert_eq!(ray_between(square("e8"), square("e5")), squares(&["e7", "e6"]));
+        assert_eq!(ray_between(square("e1"), square("f3")), BitBoard::empty());
+        assert_eq!(line_through(square("c3"), square("d4")), squares(&["a1", "b2", "c3", "d4", "e5", "f6", "g7", "h8"]));
+        assert_eq!(line_through(square("b1"), square("c1")), BitBoard::from(Rank::One));
+        assert_eq!(line_through(square("e1"), square("f3")), BitBoard::empty());
+        assert_eq!(blockers_between(square("e1"), square("h1"), squares(&["f1", "d1"])), squares(&["f1"]));
+    }
+
+    #[test]
+    fn test_leaper_attacks() {
+        assert_eq!(knight(square("a1")), BitBoard::from(square("b3")) | BitBoard::from(square("c2")));
+        assert_eq!(king(square("e4")).count(), 8);
+        assert_eq!(pawn(square("e4"), Color::White), BitBoard::from(square("d5")) | BitBoard::from(square("f5")));
+        assert_eq!(pawn(square("a7"), Color::Black), BitBoard::from(square("b6")));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::bitboard::BitBoard;
+use crate::pieces::attacks;
 use crate::pieces::common::{Color, PossibleMoves};
 use crate::square::Square;
 
@@ -47,13 +48,28 @@ pub(crate) struct Bishop;
 
 
 impl PossibleMoves for Bishop {
-    fn get_moves(piece: &BitBoard, square: Square, own_pieces: &BitBoard, opponent_pieces: &BitBoard, color: &Color) -> BitBoard {
-       Self::get_diagonal_moves(piece, square, own_pieces, opponent_pieces, color)
-        | Self::get_anti_diagonal_moves(piece, square, own_pieces, opponent_pieces, color)
+    fn get_moves(_piece: &BitBoard, square: Square, own_pieces: &BitBoard, opponent_pieces: &BitBoard, _color: &Color) -> BitBoard {
+        attacks::bishop(square, Self::occupied(own_pieces, opponent_pieces)) & !own_pieces
     }
 }
 
 impl Bishop {
+    /// Computes the bishop attacks with the subtract-and-reverse trick, diagonal by diagonal.
+    /// Used to fill the [`attacks`] lookup tables.
+    ///
+    /// # Parameters
+    /// - `square`: The [`Square`] of the bishop.
+    /// - `occupancy`: A [`BitBoard`] of every occupied square.
+    ///
+    /// # Returns
+    /// A [`BitBoard`] of the attacked squares, up to and including the first blocker of each ray.
+    pub(super) fn ray_attacks(square: Square, occupancy: BitBoard) -> BitBoard {
+        let piece = BitBoard::from(square);
+        let blockers = occupancy & !piece;
+        Self::get_diagonal_moves(&piece, square, &piece, &blockers, &Color::White)
+        | Self::get_anti_diagonal_moves(&piece, square, &piece, &blockers, &Color::White)
+    }
+
 
     /// Computes the diagonal mask for the given square.
     /// # Parameters
@@ -140,7 +156,7 @@ mod tests {
         let opponent_pieces = BitBoard::new(0);
 
         let result = Bishop::get_diagonal_moves(&piece, d4, &own_pieces, &opponent_pieces, &Color::White);
-        println!("{:?}", result);
+        tracing::debug!(?result, "diagonal moves");
         let expected = BitBoard::from(a1)
             | BitBoard::from(b2)
             | BitBoard::from(c3)
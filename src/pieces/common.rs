@@ -1,9 +1,10 @@
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 use crate::bitboard::BitBoard;
 use crate::square::Square;
 
 /// Represents the color of a chess piece or player.
-#[derive(Clone, Copy, Debug, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, EnumIter)]
 pub enum Color {
     White,
     Black,
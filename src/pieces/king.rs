@@ -1,12 +1,14 @@
 use crate::bitboard::BitBoard;
 use crate::pieces::common::{Color, PossibleMoves};
-use crate::square::{Square};
+use crate::square::{File, Square};
 
 pub(crate) struct King;
 
 impl PossibleMoves for King{
     fn get_moves(piece: &BitBoard, _square: Square, own_pieces: &BitBoard, _opponent_pieces: &BitBoard, _color: &Color) -> BitBoard {
-        let horizontal_movement = (piece << 1) | (piece >> 1) | *piece;
+        let right = (*piece & !BitBoard::from(File::H)) << 1;
+        let left = (*piece & !BitBoard::from(File::A)) >> 1;
+        let horizontal_movement = right | left | *piece;
         let movement = horizontal_movement | horizontal_movement << 8 | horizontal_movement >> 8;
         movement & !own_pieces
     }
@@ -41,6 +43,24 @@ mod tests {
         assert_eq!(king_moves, expected);
     }
 
+    #[test]
+    fn test_king_moves_edge_files_do_not_wrap() {
+        let h4 = Square::new(File::H, Rank::Four);
+        let a5 = Square::new(File::A, Rank::Five);
+
+        let king_moves = King::get_moves(&BitBoard::from(h4), h4, &BitBoard::from(h4), &BitBoard::empty(), &Color::White);
+        let expected = BitBoard::from(Square::new(File::G, Rank::Three)) | BitBoard::from(Square::new(File::H, Rank::Three))
+            | BitBoard::from(Square::new(File::G, Rank::Four))
+            | BitBoard::from(Square::new(File::G, Rank::Five)) | BitBoard::from(Square::new(File::H, Rank::Five));
+        assert_eq!(king_moves, expected);
+
+        let king_moves = King::get_moves(&BitBoard::from(a5), a5, &BitBoard::from(a5), &BitBoard::empty(), &Color::White);
+        let expected = BitBoard::from(Square::new(File::A, Rank::Four)) | BitBoard::from(Square::new(File::B, Rank::Four))
+            | BitBoard::from(Square::new(File::B, Rank::Five))
+            | BitBoard::from(Square::new(File::A, Rank::Six)) | BitBoard::from(Square::new(File::B, Rank::Six));
+        assert_eq!(king_moves, expected);
+    }
+
     #[test]
     fn test_king_moves_left_corner_blocked() {
         let a1 = Square::new(File::A, Rank::One);
@@ -76,7 +76,7 @@ mod tests {
         let c2 = BitBoard::from(Square::new(File::C, Rank::Two));
 
 
-        let expected = BitBoard::from(b3) | BitBoard::from(c2);
+        let expected = b3 | c2;
 
         let moves = Knight::get_moves(&a1, Square::new(File::A, Rank::One), &own_pieces, &opponent_pieces, &Color::White);
 
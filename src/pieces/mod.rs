@@ -5,7 +5,9 @@ pub(crate) mod rock;
 pub(crate) mod bishop;
 pub(crate) mod queen;
 pub(crate) mod king;
+pub(crate) mod attacks;
 
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 use rock::Rock;
 use bishop::Bishop;
@@ -14,8 +16,8 @@ use crate::pieces::common::{Color, PossibleMoves};
 use crate::square::Square;
 
 // TODO: create enum for pieces
-#[derive(EnumIter, Clone, Debug, Copy, PartialEq)]
-pub(crate) enum Piece{
+#[derive(EnumIter, Clone, Debug, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Piece{
     Pawn,
     Knight,
     Rock,
@@ -29,7 +31,7 @@ type CaculateFn = fn(&BitBoard, Square, &BitBoard, &BitBoard, &Color) -> BitBoar
 
 impl Piece{
 
-    pub fn moves_function(&self) -> CaculateFn {
+    pub(crate) fn moves_function(&self) -> CaculateFn {
         match self {
             Piece::Pawn => pawn::Pawn::get_moves,
             Piece::Knight => knight::Knight::get_moves,
@@ -40,7 +42,7 @@ impl Piece{
         }
     }
 
-    pub fn capture_function(&self) -> CaculateFn {
+    pub(crate) fn capture_function(&self) -> CaculateFn {
         match self {
             Piece::Pawn => pawn::Pawn::get_capture,
             Piece::Knight => knight::Knight::get_capture,
@@ -50,6 +52,41 @@ impl Piece{
             Piece::King => king::King::get_capture,
         }
     }
+
+    /// Returns the FEN letter of the piece, uppercase for White and lowercase for Black.
+    pub fn fen_symbol(&self, color: Color) -> char {
+        let symbol = match self {
+            Piece::Pawn => 'p',
+            Piece::Knight => 'n',
+            Piece::Rock => 'r',
+            Piece::Bishop => 'b',
+            Piece::Queen => 'q',
+            Piece::King => 'k',
+        };
+        match color {
+            Color::White => symbol.to_ascii_uppercase(),
+            Color::Black => symbol,
+        }
+    }
+
+    /// Parses a FEN letter into the piece and its color (uppercase is White).
+    ///
+    /// # Returns
+    /// - `Some((Piece, Color))`: If the letter is one of `pnbrqk` in either case.
+    /// - `None`: Otherwise.
+    pub fn from_fen_symbol(symbol: char) -> Option<(Piece, Color)> {
+        let piece = match symbol.to_ascii_lowercase() {
+            'p' => Piece::Pawn,
+            'n' => Piece::Knight,
+            'r' => Piece::Rock,
+            'b' => Piece::Bishop,
+            'q' => Piece::Queen,
+            'k' => Piece::King,
+            _ => return None,
+        };
+        let color = if symbol.is_ascii_uppercase() { Color::White } else { Color::Black };
+        Some((piece, color))
+    }
 }
 
 impl From<Piece> for usize{
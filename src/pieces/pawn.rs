@@ -21,8 +21,8 @@ impl PossibleMoves for Pawn {
 impl Pawn {
     /// Calculates the possible single-step moves for pawns of the given color.
     /// Determines the squares to which a pawn can move forward by one rank.
-    /// A pawn can move forward if the square is empty and it is not located on the 1/8'th rank
-    /// (since pawns cannot move forward once they reach the promotion rank).
+    /// A pawn can move forward if the square is empty and it is not located on the promotion rank
+    /// (`Rank::Eight` for white pawns, `Rank::One` for black pawns); moving onto it is a promotion.
     /// # Parameters
     /// - `piece`: A &[`BitBoard`]  representing the positions of pawns to evaluate.
     /// - `own_pieces`: A &[`BitBoard`]  representing the positions of all friendly pieces.
@@ -35,8 +35,8 @@ impl Pawn {
     fn possible_single_step(piece: &BitBoard, own_pieces: &BitBoard, opponent_pieces: &BitBoard, color: &Color) -> BitBoard {
         let empty = Self::empty(own_pieces, opponent_pieces);
         match color {
-            Color::White => (piece << 8) & empty & !BitBoard::from(Rank::Eight),
-            Color::Black => (piece >> 8) & empty & !BitBoard::from(Rank::One)
+            Color::White => ((*piece & !BitBoard::from(Rank::Eight)) << 8) & empty,
+            Color::Black => ((*piece & !BitBoard::from(Rank::One)) >> 8) & empty
         }
     }
 
@@ -122,9 +122,7 @@ mod tests {
         let piece = BitBoard::new(0x400000000000);
         let own_pieces = BitBoard::new(0x408000000000);
         let opponent_pieces = BitBoard::new(0xff9f000000000000);
-        println!("own {:?}", own_pieces);
-        println!("opponent pieces {:?}", opponent_pieces);
-        println!("Location {:?}", piece);
+        tracing::debug!(?own_pieces, ?opponent_pieces, ?piece, "pawn capture setup");
         let result = Pawn::possible_capture_step(&piece, &own_pieces, &opponent_pieces, &Color::White);
         let expected = BitBoard::from(Square::new(File::H, Rank::Seven));
         assert_eq!(result, expected);
@@ -135,9 +133,7 @@ mod tests {
         let piece = BitBoard::new(0x80000000000000);
         let own_pieces = BitBoard::new(0xff9f000000000000);
         let opponent_pieces = BitBoard::new(0x400000000000);
-        println!("own {:?}", own_pieces);
-        println!("opponent pieces {:?}", opponent_pieces);
-        println!("Location {:?}", piece);
+        tracing::debug!(?own_pieces, ?opponent_pieces, ?piece, "pawn capture setup");
         let result = Pawn::possible_capture_step(&piece, &own_pieces, &opponent_pieces, &Color::Black);
         let expected = BitBoard::from(Square::new(File::G, Rank::Six));
         assert_eq!(result, expected);
@@ -1,16 +1,15 @@
 use crate::bitboard::BitBoard;
 use crate::pieces::common::{Color, PossibleMoves};
 use crate::square::Square;
-use super::{Rock, Bishop};
+use super::attacks;
 /// Description
 /// Combination of both bishop and rock (can or between each movement map)
 pub(crate) struct Queen;
 
 
 impl PossibleMoves for Queen {
-    fn get_moves(piece: &BitBoard, square: Square, own_pieces: &BitBoard, opponent_pieces: &BitBoard, color: &Color) -> BitBoard {
-        Rock::get_moves(piece, square, own_pieces, opponent_pieces, color)
-        | Bishop::get_moves(piece, square, own_pieces, opponent_pieces, color)
+    fn get_moves(_piece: &BitBoard, square: Square, own_pieces: &BitBoard, opponent_pieces: &BitBoard, _color: &Color) -> BitBoard {
+        attacks::queen(square, Self::occupied(own_pieces, opponent_pieces)) & !own_pieces
     }
 }
 
@@ -30,8 +29,8 @@ mod tests {
         let moves = Queen::get_moves(&queen, Square::new(File::D, Rank::Four), &own_pieces, &opponent_pieces, &Color::White);
 
         let mut expected = BitBoard::empty();
-        expected = expected | (BitBoard::from(File::D) & !queen);
-        expected = expected | (BitBoard::from(Rank::Four) & !queen);
+        expected |= BitBoard::from(File::D) & !queen;
+        expected |= BitBoard::from(Rank::Four) & !queen;
         let diagonals = vec![
             Square::new(File::C, Rank::Three),
             Square::new(File::B, Rank::Two),
@@ -49,7 +48,7 @@ mod tests {
             Square::new(File::G, Rank::One),
         ];
         for square in diagonals {
-            expected = expected | BitBoard::from(square);
+            expected |= BitBoard::from(square);
         }
 
         assert_eq!(moves, expected);
@@ -1,5 +1,6 @@
-use crate::{BitBoard};
+use crate::bitboard::BitBoard;
 use crate::square::{Square};
+use super::attacks;
 use super::common::{Color, PossibleMoves};
 
 /// Description
@@ -8,14 +9,29 @@ use super::common::{Color, PossibleMoves};
 pub(crate) struct Rock;
 
 impl PossibleMoves for Rock {
-    fn get_moves(piece: &BitBoard, square: Square, own_pieces: &BitBoard, opponent_pieces: &BitBoard, color: &Color) -> BitBoard {
-        Self::get_vertical_moves(piece, square, own_pieces, opponent_pieces, color)
-        | Self::get_horizontal_moves(piece, square, own_pieces, opponent_pieces, color)
+    fn get_moves(_piece: &BitBoard, square: Square, own_pieces: &BitBoard, opponent_pieces: &BitBoard, _color: &Color) -> BitBoard {
+        attacks::rook(square, Self::occupied(own_pieces, opponent_pieces)) & !own_pieces
     }
 }
 
 
 impl Rock{
+    /// Computes the rook attacks with the subtract-and-reverse trick, ray by ray. Used to fill
+    /// the [`attacks`] lookup tables, which are much faster for repeated queries.
+    ///
+    /// # Parameters
+    /// - `square`: The [`Square`] of the rook.
+    /// - `occupancy`: A [`BitBoard`] of every occupied square.
+    ///
+    /// # Returns
+    /// A [`BitBoard`] of the attacked squares, up to and including the first blocker of each ray.
+    pub(super) fn ray_attacks(square: Square, occupancy: BitBoard) -> BitBoard {
+        let piece = BitBoard::from(square);
+        let blockers = occupancy & !piece;
+        Self::get_vertical_moves(&piece, square, &piece, &blockers, &Color::White)
+        | Self::get_horizontal_moves(&piece, square, &piece, &blockers, &Color::White)
+    }
+
     /// Calculates all possible horizontal moves for a piece located at the given square.
     /// # Parameters
     /// - `piece`: A [`BitBoard`] representing the single position of the piece whose horizontal moves are being calculated.
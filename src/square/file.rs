@@ -1,4 +1,6 @@
+use std::fmt;
 use crate::bitboard::BitBoard;
+use crate::square::SquareParseError;
 
 /// Represents the files (columns) on a chessboard.
 ///
@@ -33,7 +35,7 @@ impl From<File> for BitBoard {
 }
 
 impl TryFrom<char> for File {
-    type Error = ();
+    type Error = SquareParseError;
 
     fn try_from(file: char) -> Result<Self, Self::Error> {
         match file {
@@ -45,6 +47,24 @@ impl TryFrom<char> for File {
             'f'|'F' => Ok(File::F),
             'g'|'G' => Ok(File::G),
             'h'|'H' => Ok(File::H),
+            _ => Err(SquareParseError::File(file))
+        }
+    }
+}
+
+impl TryFrom<usize> for File {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(File::A),
+            1 => Ok(File::B),
+            2 => Ok(File::C),
+            3 => Ok(File::D),
+            4 => Ok(File::E),
+            5 => Ok(File::F),
+            6 => Ok(File::G),
+            7 => Ok(File::H),
             _ => Err(())
         }
     }
@@ -63,4 +83,12 @@ impl From<File> for usize{
             File::H => 7,
         }
     }
+}
+
+impl fmt::Display for File {
+    /// Formats the file as its lowercase algebraic letter (`a`-`h`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letter = (b'a' + usize::from(*self) as u8) as char;
+        write!(f, "{}", letter)
+    }
 }
\ No newline at end of file
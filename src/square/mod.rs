@@ -1,8 +1,11 @@
 pub(crate) mod file;
 pub(crate) mod rank;
 
-pub(crate) use file::File;
-pub(crate) use rank::Rank;
+pub use file::File;
+pub use rank::Rank;
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use crate::bitboard::BitBoard;
 
 
@@ -12,7 +15,7 @@ use crate::bitboard::BitBoard;
 /// chessboard position. It is useful for mapping board positions to bitboards or other
 /// representations.
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub(crate) struct Square {
+pub struct Square {
     /// The file (column) of the square, such as `File::A` or `File::H`.
     file: File,
     /// The rank (row) of the square, such as `Rank::One` or `Rank::Eight`.
@@ -28,17 +31,17 @@ impl Square{
     ///
     /// # Returns
     /// A new instance of `Square`.
-    pub(crate) fn new(file: File, rank: Rank) -> Self {
+    pub fn new(file: File, rank: Rank) -> Self {
         Self { file, rank }
     }
 
     /// Return square file (copy)
-    pub(crate) fn file(&self) -> File{
+    pub fn file(&self) -> File{
         self.file
     }
 
     /// Return square rank (copy)
-    pub(crate) fn rank(&self) -> Rank{
+    pub fn rank(&self) -> Rank{
         self.rank
     }
 }
@@ -50,18 +53,62 @@ impl From<Square> for BitBoard {
     }
 }
 
+/// Why a square name such as `i9` or `e` could not be parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SquareParseError {
+    /// The name is not one file letter followed by one rank digit.
+    Length(String),
+    /// The first character is not a file letter.
+    File(char),
+    /// The second character is not a rank digit.
+    Rank(char),
+}
+
+impl fmt::Display for SquareParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SquareParseError::Length(name) if name.is_empty() => write!(f, "a square is a file a-h followed by a rank 1-8, e.g. e4"),
+            SquareParseError::Length(name) => write!(f, "'{}' is not a square: expected a file a-h followed by a rank 1-8, e.g. e4", name),
+            SquareParseError::File(file) => write!(f, "'{}' is not a file: file must be a-h", file),
+            SquareParseError::Rank(rank) => write!(f, "'{}' is not a rank: rank must be 1-8", rank),
+        }
+    }
+}
+
+impl std::error::Error for SquareParseError {}
+
+/// Parses a square in algebraic notation (e.g. `e4`, or `E4`). Any other text, of any length or
+/// encoding, is an error rather than a panic.
+impl FromStr for Square {
+    type Err = SquareParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let mut chars = name.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(file), Some(rank), None) => Ok(Self::new(File::try_from(file)?, Rank::try_from(rank)?)),
+            // a lone character that is not even a file gets the more precise error
+            (Some(file), None, None) => File::try_from(file).and(Err(SquareParseError::Length(name.to_string()))),
+            _ => Err(SquareParseError::Length(name.to_string())),
+        }
+    }
+}
+
 impl TryFrom<String> for Square {
-    type Error = ();
+    type Error = SquareParseError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        match value.chars().collect::<Vec<char>>().as_slice(){
-            [first, second] => {
-                let file = File::try_from(*first)?;
-                let rank = Rank::try_from(*second)?;
-                Ok(Self::new(file, rank))
-            }
-            _ => Err(())
-        }
+        value.parse()
+    }
+}
+
+impl TryFrom<usize> for Square {
+    type Error = ();
+
+    /// Converts a board index (0 for a1, 63 for h8) into a `Square`.
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        let file = File::try_from(value % 8)?;
+        let rank = Rank::try_from(value / 8)?;
+        Ok(Self::new(file, rank))
     }
 }
 
@@ -70,4 +117,48 @@ impl From<Square> for usize{
         let [file, rank] = [usize::from(value.file), usize::from(value.rank)];
         rank * 8 + file
     }
-}
\ No newline at end of file
+}
+
+impl fmt::Display for Square {
+    /// Formats the square in algebraic notation (e.g. `e4`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.file, self.rank)
+    }
+}
+
+/// Parses a square name that is known to be valid, such as `"e4"`, for the tests of every module.
+#[cfg(test)]
+pub(crate) fn square(name: &str) -> Square {
+    name.parse().expect("a valid square name")
+}
+
+/// Squares are saved in algebraic notation (e.g. `"e4"`), which keeps saved games readable.
+impl Serialize for Square {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Square {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Square::try_from(name.clone())
+            .map_err(|_| serde::de::Error::custom(format!("invalid square '{}'", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reports_what_is_wrong() {
+        assert_eq!("E4".parse::<Square>(), Ok(Square::new(File::E, Rank::Four)));
+        assert_eq!("i9".parse::<Square>(), Err(SquareParseError::File('i')));
+        assert_eq!("a9".parse::<Square>(), Err(SquareParseError::Rank('9')));
+        assert_eq!("e".parse::<Square>(), Err(SquareParseError::Length("e".to_string())));
+        assert_eq!("e44".parse::<Square>(), Err(SquareParseError::Length("e44".to_string())));
+        assert!("é4".parse::<Square>().unwrap_err().to_string().contains("file must be a-h"));
+        assert!("".parse::<Square>().is_err());
+    }
+}
@@ -1,4 +1,6 @@
+use std::fmt;
 use crate::bitboard::BitBoard;
+use crate::square::SquareParseError;
 
 
 /// Represents the ranks (rows) on a chessboard.
@@ -35,7 +37,7 @@ impl From<Rank> for BitBoard {
 
 
 impl TryFrom<char> for Rank {
-    type Error = ();
+    type Error = SquareParseError;
 
     fn try_from(rank: char) -> Result<Self, Self::Error> {
         match rank.to_digit(10) {
@@ -47,6 +49,24 @@ impl TryFrom<char> for Rank {
             Some(6) => Ok(Rank::Six),
             Some(7) => Ok(Rank::Seven),
             Some(8) => Ok(Rank::Eight),
+            _ => Err(SquareParseError::Rank(rank))
+        }
+    }
+}
+
+impl TryFrom<usize> for Rank {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Rank::One),
+            1 => Ok(Rank::Two),
+            2 => Ok(Rank::Three),
+            3 => Ok(Rank::Four),
+            4 => Ok(Rank::Five),
+            5 => Ok(Rank::Six),
+            6 => Ok(Rank::Seven),
+            7 => Ok(Rank::Eight),
             _ => Err(())
         }
     }
@@ -65,4 +85,11 @@ impl From<Rank> for usize {
             Rank::Eight => 7,
         }
     }
+}
+
+impl fmt::Display for Rank {
+    /// Formats the rank as its digit (`1`-`8`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", usize::from(*self) + 1)
+    }
 }
\ No newline at end of file
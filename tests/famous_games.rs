@@ -0,0 +1,74 @@
+use chess_game::{Color, Game, GameResult};
+
+/// Morphy vs. Duke of Brunswick and Count Isouard, Paris 1858.
+const OPERA_GAME: &str = "e4 e5 Nf3 d6 d4 Bg4 dxe5 Bxf3 Qxf3 dxe5 Bc4 Nf6 Qb3 Qe7 Nc3 c6 Bg5 b5 Nxb5 cxb5 \
+    Bxb5+ Nbd7 O-O-O Rd8 Rxd7 Rxd7 Rd1 Qe6 Bxd7+ Nxd7 Qb8+ Nxb8 Rd8#";
+
+/// Anderssen vs. Kieseritzky, London 1851.
+const IMMORTAL_GAME: &str = "e4 e5 f4 exf4 Bc4 Qh4+ Kf1 b5 Bxb5 Nf6 Nf3 Qh6 d3 Nh5 Nh4 Qg5 Nf5 c6 g4 Nf6 \
+    Rg1 cxb5 h4 Qg6 h5 Qg5 Qf3 Ng8 Bxf4 Qf6 Nc3 Bc5 Nd5 Qxb2 Bd6 Bxg1 e5 Qxa1+ Ke2 Na6 Nxg7+ Kd8 Qf6+ Nxf6 Be7#";
+
+/// Plays a game from the start position, one algebraic move at a time, and checks after
+/// every move that the game sees a check exactly where the score shows one.
+///
+/// # Arguments
+/// - `moves`: The moves, separated by whitespace.
+/// - `fens`: The expected FEN after some of the moves, keyed by ply (`1` for White's first move).
+///
+/// # Returns
+/// - The game after the last move.
+fn play_through(moves: &str, fens: &[(usize, &str)]) -> Game {
+    let mut game = Game::new();
+    for (idx, san) in moves.split_whitespace().enumerate() {
+        let ply = idx + 1;
+        assert_eq!(game.game_result(), None, "the game ended before ply {}", ply);
+        game.play_san(san).unwrap_or_else(|err| panic!("ply {} ({}): {}", ply, san, err));
+        let gives_check = san.ends_with('+') || san.ends_with('#');
+        assert_eq!(game.is_checked(), gives_check, "check after ply {} ({})", ply, san);
+        if let Some((_, fen)) = fens.iter().find(|(at, _)| *at == ply) {
+            assert_eq!(game.fen(), *fen, "position after ply {} ({})", ply, san);
+        }
+    }
+    game
+}
+
+#[test]
+fn test_opera_game() {
+    let game = play_through(OPERA_GAME, &[
+        (9, "rn1qkbnr/ppp2ppp/3p4/4P3/4P3/5Q2/PPP2PPP/RNB1KB1R b KQkq - 0 5"),
+        (21, "rn2kb1r/p3qppp/5n2/1B2p1B1/4P3/1Q6/PPP2PPP/R3K2R b KQkq - 0 11"),
+        // queen-side castling moves the rook to d1 and drops White's rights
+        (23, "r3kb1r/p2nqppp/5n2/1B2p1B1/4P3/1Q6/PPP2PPP/2KR3R b kq - 2 12"),
+        // the a8 rook leaves, so Black can only castle king-side
+        (24, "3rkb1r/p2nqppp/5n2/1B2p1B1/4P3/1Q6/PPP2PPP/2KR3R w k - 3 13"),
+        (32, "1n2kb1r/p4ppp/4q3/4p1B1/4P3/8/PPP2PPP/2KR4 w k - 0 17"),
+        (33, "1n1Rkb1r/p4ppp/4q3/4p1B1/4P3/8/PPP2PPP/2K5 b k - 1 17"),
+    ]);
+    assert_eq!(game.game_result(), Some(GameResult::Checkmate(Color::Black)));
+}
+
+#[test]
+fn test_immortal_game() {
+    let game = play_through(IMMORTAL_GAME, &[
+        // the king steps out of check and loses both castling rights
+        (7, "rnb1kbnr/pppp1ppp/8/8/2B1Pp1q/8/PPPP2PP/RNBQ1KNR b kq - 3 4"),
+        (22, "rnb1kb1r/p2p1ppp/5n2/1p3Nq1/4PpP1/3P4/PPP4P/RNBQ1KR1 w kq - 0 12"),
+        (36, "rnb1k1nr/p2p1ppp/3B4/1p1N1N1P/4P1P1/3P1Q2/PqP5/R4Kb1 w kq - 0 19"),
+        (38, "rnb1k1nr/p2p1ppp/3B4/1p1NPN1P/6P1/3P1Q2/P1P5/q4Kb1 w kq - 0 20"),
+        (42, "r1bk2nr/p2p1pNp/n2B4/1p1NP2P/6P1/3P1Q2/P1P1K3/q5b1 w - - 1 22"),
+        (45, "r1bk3r/p2pBpNp/n4n2/1p1NP2P/6P1/3P4/P1P1K3/q5b1 b - - 1 23"),
+    ]);
+    assert_eq!(game.game_result(), Some(GameResult::Checkmate(Color::Black)));
+    assert!(game.legal_move_list().is_empty());
+}
+
+#[test]
+fn test_illegal_move_is_refused_mid_game() {
+    let mut game = play_through("e4 e5 f4 exf4 Bc4 Qh4+", &[]);
+    let fen = game.fen();
+    // White is in check and cannot ignore it
+    assert!(game.play_san("Nf3").is_err());
+    assert!(game.play_san("O-O").is_err());
+    assert_eq!(game.fen(), fen);
+    game.play_san("Kf1").unwrap();
+}
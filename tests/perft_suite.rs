@@ -0,0 +1,16 @@
+use chess_game::parse_perft_suite;
+
+/// The suite shipped with the project; every count must match, as a wrong count means a
+/// castling, en passant or promotion rule is broken.
+const STANDARD_SUITE: &str = include_str!("../perft/standard.txt");
+
+#[test]
+fn test_standard_perft_suite() {
+    let cases = parse_perft_suite(STANDARD_SUITE).unwrap();
+    assert!(cases.len() >= 10);
+    for case in &cases {
+        if let Err(mismatch) = case.run() {
+            panic!("{}", mismatch);
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs the binary with `--script -` and the script on standard input.
+///
+/// # Arguments
+/// - `args`: Arguments passed before `--script -`, e.g. a start position.
+/// - `script`: The moves and commands to play.
+///
+/// # Returns
+/// - The exit status and everything the binary printed.
+fn run(args: &[&str], script: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ChessGame"))
+        .args(args)
+        .args(["--script", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(script.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_script_prints_the_final_position_and_result() {
+    let output = run(&[], "1. e4 e5 2. Qh5 Nc6\n# the defence fails\nBc4 Nf6\nQxf7#\n");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("FEN: r1bqkb1r/pppp1Qpp/2n2n2/4p3/2B1P3/8/PPPP1PPP/RNB1K1NR b KQkq - 0 4"), "{}", stdout);
+    assert!(stdout.contains("Result: Checkmate, White wins"), "{}", stdout);
+}
+
+#[test]
+fn test_script_exits_with_an_error_on_an_illegal_move() {
+    let output = run(&["--fen", "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"], "e4 Kd7\ne6\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("Moves: 1. e4 Kd7"));
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with("Script line 2: Illegal move e6"));
+}